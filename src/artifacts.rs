@@ -0,0 +1,261 @@
+//! Content-addressed artifact store
+//!
+//! `Task.artifacts` are paths, so a later task that overwrites a file silently
+//! changes what an earlier task appears to have produced. `wg artifact add
+//! --snapshot` copies the file into `.workgraph/artifacts/<sha256>` and records
+//! an [`ArtifactSnapshot`] on the task. `wg artifact verify` compares files
+//! against their snapshots, and `wg artifact checkout` restores missing ones
+//! (and, with `--force`, modified ones).
+//!
+//! Relative artifact paths are resolved against the project root (the parent
+//! of the `.workgraph` directory).
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What a task produced at an artifact path when it was snapshotted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactSnapshot {
+    /// Hex SHA-256 of the contents, also the name of the stored copy
+    pub sha256: String,
+    pub size: u64,
+    /// Modification time of the file when snapshotted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<String>,
+}
+
+impl ArtifactSnapshot {
+    /// First 12 hex digits of the hash, for display
+    pub fn short_hash(&self) -> &str {
+        self.sha256.get(..12).unwrap_or(&self.sha256)
+    }
+}
+
+/// Whether `sha256` is a hex SHA-256 digest. Snapshots come from the graph
+/// file, so the hash is checked before it is used as a store path.
+pub fn is_valid_hash(sha256: &str) -> bool {
+    sha256.len() == 64 && sha256.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// State of an artifact compared to its snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum Drift {
+    /// The file matches the snapshot
+    Intact,
+    /// The file's contents changed since the snapshot
+    Modified { sha256: String },
+    /// The file no longer exists
+    Missing,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Drift::Intact => write!(f, "intact"),
+            Drift::Modified { sha256 } => {
+                write!(f, "modified (now {})", &sha256[..sha256.len().min(12)])
+            }
+            Drift::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+/// Directory holding stored artifact contents, by hash
+pub fn store_dir(dir: &Path) -> PathBuf {
+    dir.join("artifacts")
+}
+
+/// Where the stored copy of `sha256` lives
+pub fn blob_path(dir: &Path, sha256: &str) -> PathBuf {
+    store_dir(dir).join(sha256)
+}
+
+/// Resolve an artifact path: absolute paths as-is, relative ones against the
+/// project root
+pub fn resolve(dir: &Path, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        dir.parent().unwrap_or(dir).join(path)
+    }
+}
+
+fn hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Copy the file at `path` into the store and return its snapshot. Contents
+/// already stored are not written again.
+pub fn snapshot(dir: &Path, path: &str) -> Result<ArtifactSnapshot> {
+    let file = resolve(dir, path);
+    let bytes =
+        fs::read(&file).with_context(|| format!("Failed to read artifact {}", file.display()))?;
+    let sha256 = hash(&bytes);
+
+    let blob = blob_path(dir, &sha256);
+    if !blob.exists() {
+        fs::create_dir_all(store_dir(dir)).context("Failed to create artifact store")?;
+        // Write then rename, so an interrupted copy never sits under the hash
+        let tmp = store_dir(dir).join(format!(".{}.tmp", sha256));
+        fs::write(&tmp, &bytes).context("Failed to write artifact to store")?;
+        fs::rename(&tmp, &blob).context("Failed to write artifact to store")?;
+    }
+
+    let mtime = fs::metadata(&file)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(|t| DateTime::<Utc>::from(t).to_rfc3339());
+
+    Ok(ArtifactSnapshot {
+        sha256,
+        size: bytes.len() as u64,
+        mtime,
+    })
+}
+
+/// Compare the file at `path` with its snapshot
+pub fn check(dir: &Path, path: &str, snapshot: &ArtifactSnapshot) -> Drift {
+    match fs::read(resolve(dir, path)) {
+        Ok(bytes) => {
+            let sha256 = hash(&bytes);
+            if sha256 == snapshot.sha256 {
+                Drift::Intact
+            } else {
+                Drift::Modified { sha256 }
+            }
+        }
+        Err(_) => Drift::Missing,
+    }
+}
+
+/// Read the stored copy of a snapshot, failing if it is missing or its
+/// contents no longer match the hash
+pub fn read_stored(dir: &Path, snapshot: &ArtifactSnapshot) -> Result<Vec<u8>> {
+    if !is_valid_hash(&snapshot.sha256) {
+        anyhow::bail!(
+            "Snapshot hash '{}' is not a SHA-256 digest",
+            snapshot.sha256
+        );
+    }
+    let blob = blob_path(dir, &snapshot.sha256);
+    let bytes = fs::read(&blob)
+        .with_context(|| format!("Stored copy {} is missing", snapshot.short_hash()))?;
+    if hash(&bytes) != snapshot.sha256 {
+        anyhow::bail!(
+            "Stored copy {} is corrupt (contents don't match the hash)",
+            snapshot.short_hash()
+        );
+    }
+    Ok(bytes)
+}
+
+/// What [`checkout`] did with a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checkout {
+    /// The file already matched its snapshot
+    Unchanged,
+    /// The file was written from the store
+    Restored,
+    /// The file was modified since the snapshot and left alone, since
+    /// overwriting it wasn't forced; `sha256` is its current hash
+    Kept { sha256: String },
+}
+
+/// Restore the file at `path` to its snapshot. A missing file is always
+/// restored; a modified one only if `force` is set, since its changes would
+/// be lost.
+pub fn checkout(
+    dir: &Path,
+    path: &str,
+    snapshot: &ArtifactSnapshot,
+    force: bool,
+) -> Result<Checkout> {
+    match check(dir, path, snapshot) {
+        Drift::Intact => return Ok(Checkout::Unchanged),
+        Drift::Modified { sha256 } if !force => return Ok(Checkout::Kept { sha256 }),
+        Drift::Modified { .. } | Drift::Missing => {}
+    }
+    let bytes = read_stored(dir, snapshot)?;
+    let file = resolve(dir, path);
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&file, bytes).with_context(|| format!("Failed to write {}", file.display()))?;
+    Ok(Checkout::Restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_check_and_checkout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join(".workgraph");
+        fs::create_dir_all(&dir).unwrap();
+        fs::create_dir_all(tmp.path().join("out")).unwrap();
+        fs::write(tmp.path().join("out/report.md"), "v1").unwrap();
+
+        let snap = snapshot(&dir, "out/report.md").unwrap();
+        assert_eq!(snap.size, 2);
+        assert!(snap.mtime.is_some());
+        assert!(blob_path(&dir, &snap.sha256).exists());
+        assert_eq!(check(&dir, "out/report.md", &snap), Drift::Intact);
+
+        // A later task overwrites the file
+        fs::write(tmp.path().join("out/report.md"), "v2").unwrap();
+        assert!(matches!(
+            check(&dir, "out/report.md", &snap),
+            Drift::Modified { .. }
+        ));
+        // Modified files are only overwritten when forced
+        assert!(matches!(
+            checkout(&dir, "out/report.md", &snap, false).unwrap(),
+            Checkout::Kept { .. }
+        ));
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("out/report.md")).unwrap(),
+            "v2"
+        );
+        assert_eq!(
+            checkout(&dir, "out/report.md", &snap, true).unwrap(),
+            Checkout::Restored
+        );
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("out/report.md")).unwrap(),
+            "v1"
+        );
+        assert_eq!(
+            checkout(&dir, "out/report.md", &snap, false).unwrap(),
+            Checkout::Unchanged
+        );
+
+        fs::remove_file(tmp.path().join("out/report.md")).unwrap();
+        assert_eq!(check(&dir, "out/report.md", &snap), Drift::Missing);
+        assert_eq!(
+            checkout(&dir, "out/report.md", &snap, false).unwrap(),
+            Checkout::Restored
+        );
+
+        // A tampered store copy is refused
+        fs::write(blob_path(&dir, &snap.sha256), "tampered").unwrap();
+        fs::remove_file(tmp.path().join("out/report.md")).unwrap();
+        assert!(checkout(&dir, "out/report.md", &snap, false).is_err());
+
+        // So is a hash that isn't one, before it's used as a path
+        let escape = ArtifactSnapshot {
+            sha256: "../../graph.jsonl".to_string(),
+            ..snap
+        };
+        let err = checkout(&dir, "out/report.md", &escape, false).unwrap_err();
+        assert!(err.to_string().contains("not a SHA-256 digest"));
+    }
+}
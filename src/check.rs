@@ -1,4 +1,5 @@
-use crate::graph::{LoopGuard, WorkGraph};
+use crate::graph::{DepCondition, Status, WorkGraph, unreachable_condition};
+use crate::loop_guard;
 use serde::Serialize;
 use std::collections::HashSet;
 
@@ -10,6 +11,8 @@ pub struct CheckResult {
     pub loop_edge_issues: Vec<LoopEdgeIssue>,
     pub stale_assignments: Vec<StaleAssignment>,
     pub stuck_blocked: Vec<StuckBlocked>,
+    pub dep_condition_issues: Vec<DepConditionIssue>,
+    pub over_capacity: Vec<OverCapacity>,
     pub ok: bool,
 }
 
@@ -36,6 +39,44 @@ pub struct StuckBlocked {
     pub blocked_by_ids: Vec<String>,
 }
 
+/// A pending task that requires more of a resource than the resource's total
+/// capacity, so it can never be dispatched
+#[derive(Debug, Clone, Serialize)]
+pub struct OverCapacity {
+    pub task_id: String,
+    pub resource: String,
+    pub needed: f64,
+    pub available: f64,
+}
+
+/// An issue with a conditional dependency (`on_success` / `on_failure`)
+#[derive(Debug, Clone, Serialize)]
+pub struct DepConditionIssue {
+    pub task_id: String,
+    pub blocker: String,
+    pub condition: DepCondition,
+    pub kind: DepConditionIssueKind,
+}
+
+/// Types of conditional dependency issues
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum DepConditionIssueKind {
+    /// The condition names a task that isn't in `blocked_by`
+    NotABlocker,
+    /// The blocker is in a dependency group, where conditions don't apply
+    InGroup,
+    /// The blocker ended without meeting the condition, but the task is still
+    /// pending instead of skipped
+    Unreachable(Status),
+}
+
+impl DepConditionIssueKind {
+    /// Unreachable conditions are warnings; the rest are errors
+    pub fn is_error(&self) -> bool {
+        !matches!(self, DepConditionIssueKind::Unreachable(_))
+    }
+}
+
 /// An issue with a loop edge
 #[derive(Debug, Clone, Serialize)]
 pub struct LoopEdgeIssue {
@@ -53,6 +94,8 @@ pub enum LoopEdgeIssueKind {
     ZeroMaxIterations,
     /// Guard references a task that does not exist
     GuardTaskNotFound(String),
+    /// Guard is malformed (e.g. an artifact path outside the project)
+    InvalidGuard(String),
     /// Self-loop: a task loops_to itself (immediate re-open on done)
     SelfLoop,
 }
@@ -151,15 +194,22 @@ pub fn check_loop_edges(graph: &WorkGraph) -> Vec<LoopEdgeIssue> {
             }
 
             // Guard task references must exist
-            if let Some(LoopGuard::TaskStatus {
-                task: guard_task, ..
-            }) = &edge.guard
+            if let Some(guard_task) = edge.guard.as_ref().and_then(loop_guard::referenced_task)
                 && graph.get_task(guard_task).is_none()
             {
                 issues.push(LoopEdgeIssue {
                     from: task.id.clone(),
                     target: edge.target.clone(),
-                    kind: LoopEdgeIssueKind::GuardTaskNotFound(guard_task.clone()),
+                    kind: LoopEdgeIssueKind::GuardTaskNotFound(guard_task.to_string()),
+                });
+            }
+
+            // Guards must be well-formed
+            if let Some(problem) = edge.guard.as_ref().and_then(loop_guard::validate) {
+                issues.push(LoopEdgeIssue {
+                    from: task.id.clone(),
+                    target: edge.target.clone(),
+                    kind: LoopEdgeIssueKind::InvalidGuard(problem),
                 });
             }
         }
@@ -214,6 +264,62 @@ pub fn check_stuck_blocked(graph: &WorkGraph) -> Vec<StuckBlocked> {
     stuck
 }
 
+/// Check conditional dependencies: they must name ungrouped blockers, and a
+/// task whose condition can no longer be met should have been skipped
+pub fn check_dep_conditions(graph: &WorkGraph) -> Vec<DepConditionIssue> {
+    let mut issues = Vec::new();
+
+    for task in graph.tasks() {
+        for (blocker, &condition) in &task.dep_conditions {
+            let kind = if !task.blocked_by.contains(blocker) {
+                DepConditionIssueKind::NotABlocker
+            } else if task.dep_groups.iter().any(|g| g.tasks.contains(blocker)) {
+                DepConditionIssueKind::InGroup
+            } else {
+                continue;
+            };
+            issues.push(DepConditionIssue {
+                task_id: task.id.clone(),
+                blocker: blocker.clone(),
+                condition,
+                kind,
+            });
+        }
+
+        if matches!(task.status, Status::Open | Status::Blocked)
+            && let Some((blocker, condition, status)) =
+                unreachable_condition(task, |id| graph.get_task(id).map(|t| t.status))
+        {
+            issues.push(DepConditionIssue {
+                task_id: task.id.clone(),
+                blocker: blocker.to_string(),
+                condition,
+                kind: DepConditionIssueKind::Unreachable(status),
+            });
+        }
+    }
+
+    issues
+}
+
+/// Check for unfinished tasks whose resource requirements exceed capacity
+pub fn check_resource_capacity(graph: &WorkGraph) -> Vec<OverCapacity> {
+    graph
+        .tasks()
+        .filter(|t| !t.status.is_terminal())
+        .flat_map(|task| {
+            crate::query::resource_shortfalls(graph, task)
+                .into_iter()
+                .map(|s| OverCapacity {
+                    task_id: task.id.clone(),
+                    resource: s.resource,
+                    needed: s.needed,
+                    available: s.available,
+                })
+        })
+        .collect()
+}
+
 /// Check for references to non-existent nodes
 pub fn check_orphans(graph: &WorkGraph) -> Vec<OrphanRef> {
     let mut orphans = Vec::new();
@@ -260,10 +366,16 @@ pub fn check_all(graph: &WorkGraph) -> CheckResult {
     let loop_edge_issues = check_loop_edges(graph);
     let stale_assignments = check_stale_assignments(graph);
     let stuck_blocked = check_stuck_blocked(graph);
+    let dep_condition_issues = check_dep_conditions(graph);
+    let over_capacity = check_resource_capacity(graph);
 
-    // Cycles, stale assignments, and stuck blocked are warnings, not errors —
-    // only orphan refs and loop edge issues make the graph invalid
-    let ok = orphan_refs.is_empty() && loop_edge_issues.is_empty();
+    // Cycles, stale assignments, stuck blocked and unreachable conditions are
+    // warnings, not errors — only orphan refs, loop edge issues, misplaced
+    // conditions and requirements beyond capacity make the graph invalid
+    let ok = orphan_refs.is_empty()
+        && loop_edge_issues.is_empty()
+        && !dep_condition_issues.iter().any(|i| i.kind.is_error())
+        && over_capacity.is_empty();
 
     CheckResult {
         cycles,
@@ -271,6 +383,8 @@ pub fn check_all(graph: &WorkGraph) -> CheckResult {
         loop_edge_issues,
         stale_assignments,
         stuck_blocked,
+        dep_condition_issues,
+        over_capacity,
         ok,
     }
 }
@@ -497,6 +611,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_loop_guard_reward_task_and_artifact_path() {
+        let mut graph = WorkGraph::new();
+        let t1 = make_task("t1", "Task 1");
+        let mut t2 = make_task("t2", "Task 2");
+        t2.loops_to = vec![
+            LoopEdge {
+                target: "t1".to_string(),
+                guard: Some("reward:missing<0.8".parse().unwrap()),
+                max_iterations: 3,
+                delay: None,
+            },
+            LoopEdge {
+                target: "t1".to_string(),
+                guard: Some("artifact-changed:/etc/passwd".parse().unwrap()),
+                max_iterations: 3,
+                delay: None,
+            },
+        ];
+
+        graph.add_node(Node::Task(t1));
+        graph.add_node(Node::Task(t2));
+
+        let issues = check_loop_edges(&graph);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(
+            issues[0].kind,
+            LoopEdgeIssueKind::GuardTaskNotFound("missing".to_string())
+        );
+        assert!(matches!(issues[1].kind, LoopEdgeIssueKind::InvalidGuard(_)));
+    }
+
     #[test]
     fn test_loop_self_loop_no_delay() {
         let mut graph = WorkGraph::new();
@@ -991,4 +1137,67 @@ mod tests {
         // Stuck blocked should not make the graph invalid
         assert!(result.ok);
     }
+
+    #[test]
+    fn test_dep_conditions() {
+        let mut graph = WorkGraph::new();
+        let mut migrate = make_task("migrate", "Run migrations");
+        migrate.status = Status::Failed;
+        let mut deploy = make_task("deploy", "Deploy");
+        deploy.blocked_by = vec!["migrate".to_string()];
+        deploy
+            .dep_conditions
+            .insert("migrate".to_string(), DepCondition::OnSuccess);
+        graph.add_node(Node::Task(migrate));
+        graph.add_node(Node::Task(deploy));
+
+        // Pending although it can never run: a warning
+        let result = check_all(&graph);
+        assert_eq!(result.dep_condition_issues.len(), 1);
+        assert_eq!(
+            result.dep_condition_issues[0].kind,
+            DepConditionIssueKind::Unreachable(Status::Failed)
+        );
+        assert!(result.ok);
+
+        // A condition on something that isn't a blocker: an error
+        let mut rollback = make_task("rollback", "Roll back");
+        rollback
+            .dep_conditions
+            .insert("deploy".to_string(), DepCondition::OnFailure);
+        graph.add_node(Node::Task(rollback));
+        let issues = check_dep_conditions(&graph);
+        assert!(
+            issues
+                .iter()
+                .any(|i| i.task_id == "rollback" && i.kind == DepConditionIssueKind::NotABlocker)
+        );
+        assert!(!check_all(&graph).ok);
+    }
+
+    #[test]
+    fn test_requirement_beyond_capacity() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Resource(crate::graph::Resource {
+            id: "gpu-box".to_string(),
+            name: None,
+            resource_type: None,
+            available: Some(2.0),
+            unit: None,
+        }));
+        let mut train = make_task("train", "Train model");
+        train.requires = vec!["gpu-box".to_string()];
+        train.resource_amounts.insert("gpu-box".to_string(), 4.0);
+        graph.add_node(Node::Task(train));
+
+        let result = check_all(&graph);
+        assert_eq!(result.over_capacity.len(), 1);
+        assert_eq!(result.over_capacity[0].task_id, "train");
+        assert_eq!(result.over_capacity[0].needed, 4.0);
+        assert!(!result.ok);
+
+        // Finished tasks no longer matter
+        graph.get_task_mut("train").unwrap().status = Status::Abandoned;
+        assert!(check_resource_capacity(&graph).is_empty());
+    }
 }
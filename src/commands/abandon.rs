@@ -1,8 +1,7 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::Utc;
 use std::path::Path;
-use workgraph::graph::{LogEntry, Status};
-use workgraph::parser::save_graph;
+use workgraph::graph::{LogEntry, Status, complete_parents, skip_unreachable};
 
 #[cfg(test)]
 use super::graph_path;
@@ -10,34 +9,41 @@ use super::graph_path;
 use workgraph::parser::load_graph;
 
 pub fn run(dir: &Path, id: &str, reason: Option<&str>) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
+    let (prev_assigned, changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
 
-    let task = graph.get_task_mut_or_err(id)?;
+        if task.status == Status::Done {
+            anyhow::bail!("Task '{}' is already done and cannot be abandoned", id);
+        }
 
-    if task.status == Status::Done {
-        anyhow::bail!("Task '{}' is already done and cannot be abandoned", id);
-    }
+        if task.status == Status::Abandoned {
+            return Ok(None);
+        }
 
-    if task.status == Status::Abandoned {
+        let prev_assigned = task.assigned.clone();
+        task.status = Status::Abandoned;
+        task.failure_reason = reason.map(String::from);
+
+        let log_message = match reason {
+            Some(r) => format!("Task abandoned: {}", r),
+            None => "Task abandoned".to_string(),
+        };
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: task.assigned.clone(),
+            message: log_message,
+        });
+
+        let completed_parents = complete_parents(graph, id);
+        let skipped = skip_unreachable(graph);
+        Ok(Some((prev_assigned, completed_parents, skipped)))
+    })?;
+
+    let Some((prev_assigned, completed_parents, skipped)) = prev_assigned else {
         println!("Task '{}' is already abandoned", id);
         return Ok(());
-    }
-
-    let prev_assigned = task.assigned.clone();
-    task.status = Status::Abandoned;
-    task.failure_reason = reason.map(String::from);
-
-    let log_message = match reason {
-        Some(r) => format!("Task abandoned: {}", r),
-        None => "Task abandoned".to_string(),
     };
-    task.log.push(LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: task.assigned.clone(),
-        message: log_message,
-    });
 
-    save_graph(&graph, &path).context("Failed to save graph")?;
     super::notify_graph_changed(dir);
 
     // Record operation
@@ -47,12 +53,24 @@ pub fn run(dir: &Path, id: &str, reason: Option<&str>) -> Result<()> {
         "abandon",
         Some(id),
         prev_assigned.as_deref(),
-        serde_json::json!({ "reason": reason, "prev_assigned": prev_assigned }),
+        workgraph::undo::with_changes(
+            serde_json::json!({ "reason": reason, "prev_assigned": prev_assigned }),
+            &changes,
+        ),
         config.log.rotation_threshold,
     );
 
     let reason_msg = reason.map(|r| format!(" ({})", r)).unwrap_or_default();
     println!("Marked '{}' as abandoned{}", id, reason_msg);
+    for parent_id in &completed_parents {
+        println!("  Parent: auto-completed '{}'", parent_id);
+    }
+    for skipped_id in &skipped {
+        println!(
+            "  Skipped '{}' (its dependency conditions can't be met)",
+            skipped_id
+        );
+    }
 
     Ok(())
 }
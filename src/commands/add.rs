@@ -1,44 +1,20 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::Utc;
 use std::path::Path;
-use workgraph::graph::{Estimate, LoopEdge, Node, Status, Task, parse_delay};
-use workgraph::parser::{load_graph, save_graph};
-
-use super::graph_path;
+use std::collections::BTreeMap;
+use workgraph::graph::{
+    DepCondition, Estimate, LoopEdge, Node, Priority, Status, Task, parse_delay, parse_dep_group,
+    parse_resource_requirement,
+};
+use workgraph::fan_out::FanOut;
+use workgraph::retry::RetryPolicy;
 
 /// Parse a guard expression string into a LoopGuard.
-/// Formats: 'task:<id>=<status>' or 'always'
+/// Formats: 'always', 'iteration<N', 'task:<id>=<status>', 'reward:<id><op><value>',
+/// 'artifact-exists:<path>', 'artifact-missing:<path>', 'artifact-changed:<path>',
+/// 'shell:<command>' or 'shell=<code>:<command>' (see `workgraph::loop_guard`)
 pub fn parse_guard_expr(expr: &str) -> Result<workgraph::graph::LoopGuard> {
-    let expr = expr.trim();
-    if expr.eq_ignore_ascii_case("always") {
-        return Ok(workgraph::graph::LoopGuard::Always);
-    }
-    if let Some(rest) = expr.strip_prefix("task:") {
-        if let Some((task_id, status_str)) = rest.split_once('=') {
-            let status = match status_str.to_lowercase().as_str() {
-                "open" => Status::Open,
-                "in-progress" => Status::InProgress,
-                "done" => Status::Done,
-                "blocked" => Status::Blocked,
-                "failed" => Status::Failed,
-                "abandoned" => Status::Abandoned,
-                "pending-review" => Status::Done, // pending-review is deprecated, maps to done
-                _ => anyhow::bail!("Unknown status '{}' in guard expression", status_str),
-            };
-            return Ok(workgraph::graph::LoopGuard::TaskStatus {
-                task: task_id.to_string(),
-                status,
-            });
-        }
-        anyhow::bail!(
-            "Invalid guard format. Expected 'task:<id>=<status>', got '{}'",
-            expr
-        );
-    }
-    anyhow::bail!(
-        "Invalid guard expression '{}'. Expected 'task:<id>=<status>' or 'always'",
-        expr
-    );
+    expr.parse()
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -62,157 +38,340 @@ pub fn run(
     loop_max: Option<u32>,
     loop_guard: Option<&str>,
     loop_delay: Option<&str>,
+    priority: Option<&str>,
+    value: Option<f64>,
+    requires: &[String],
+    parent: Option<&str>,
+    auto_complete: bool,
+    fields: &[String],
+    deadline: Option<&str>,
+    schedule: Option<&str>,
+    any_of: &[String],
+    quorum: &[String],
+    abandon_rest: bool,
+    on_success: &[String],
+    on_failure: &[String],
+    retry_policy: Option<&str>,
+    fan_out: Option<&str>,
+    fan_out_description: Option<&str>,
+    fan_out_items: Option<&str>,
+    join: Option<&str>,
 ) -> Result<()> {
     if title.trim().is_empty() {
         anyhow::bail!("Task title cannot be empty");
     }
 
-    let path = graph_path(dir);
+    let config = workgraph::config::Config::load_or_default(dir);
+    let fields = workgraph::fields::parse_all(&config.fields, fields)?;
+    workgraph::fields::check_required(&config.fields, &fields)?;
 
-    // Load existing graph to check for ID conflicts
-    let mut graph = if path.exists() {
-        load_graph(&path).context("Failed to load graph")?
-    } else {
-        anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
+    let priority: Priority = match priority {
+        Some(p) => p.parse()?,
+        None => Priority::default(),
     };
+    let deadline = deadline
+        .map(|d| workgraph::history::parse_timestamp(d).map(|t| t.to_rfc3339()))
+        .transpose()?;
+    let schedule = schedule
+        .map(|cron| workgraph::schedule::Schedule::new(cron, false, None, Utc::now()))
+        .transpose()?;
+    let retry_policy: Option<RetryPolicy> = retry_policy.map(str::parse).transpose()?;
+    // Catch a bad check (e.g. an invalid regex) now rather than at 'wg done'
+    if let Some(verify) = verify {
+        workgraph::verify::parse(verify)?;
+    }
+    if fan_out.is_none()
+        && (fan_out_description.is_some() || fan_out_items.is_some() || join.is_some())
+    {
+        anyhow::bail!("--fan-out-description, --fan-out-items and --join require --fan-out");
+    }
 
-    // Generate ID if not provided
-    let task_id = match id {
-        Some(id) => {
-            if graph.get_node(id).is_some() {
-                anyhow::bail!("Task with ID '{}' already exists", id);
-            }
-            id.to_string()
+    // Dependency groups; their members are blockers like any other
+    let mut dep_groups = Vec::new();
+    for ids in any_of {
+        let group = parse_dep_group(ids, abandon_rest)?;
+        if group.need != 1 {
+            anyhow::bail!("--any-of takes task IDs only; use --quorum for '{}'", ids);
         }
-        None => generate_id(title, &graph),
-    };
-
-    // Validate blocked_by references (supports cross-repo peer:task-id syntax)
-    for blocker_id in blocked_by {
-        if blocker_id == &task_id {
-            anyhow::bail!("Task '{}' cannot block itself", task_id);
+        dep_groups.push(group);
+    }
+    for spec in quorum {
+        if !spec.contains(':') {
+            anyhow::bail!("Invalid quorum '{}'. Use format: <k>:<id>,<id>,...", spec);
         }
-        if workgraph::federation::parse_remote_ref(blocker_id).is_some() {
-            // Cross-repo dependency — validated at resolution time, not here
-        } else if graph.get_node(blocker_id).is_none() {
-            eprintln!(
-                "Warning: blocker '{}' does not exist in the graph",
-                blocker_id
-            );
+        dep_groups.push(parse_dep_group(spec, abandon_rest)?);
+    }
+    if abandon_rest && dep_groups.is_empty() {
+        anyhow::bail!("--abandon-rest requires --any-of or --quorum");
+    }
+    let mut all_blockers = blocked_by.to_vec();
+    for member in dep_groups.iter().flat_map(|g| &g.tasks) {
+        if !all_blockers.contains(member) {
+            all_blockers.push(member.clone());
         }
     }
 
-    let estimate = if hours.is_some() || cost.is_some() {
-        Some(Estimate { hours, cost })
-    } else {
-        None
-    };
+    // Conditional dependencies; blockers too, but not group members
+    let mut dep_conditions = BTreeMap::new();
+    for (ids, condition) in [
+        (on_success, DepCondition::OnSuccess),
+        (on_failure, DepCondition::OnFailure),
+    ] {
+        for id in ids {
+            if dep_groups.iter().any(|g| g.tasks.contains(id)) {
+                anyhow::bail!(
+                    "'{}' is in a dependency group and can't also be conditional",
+                    id
+                );
+            }
+            if let Some(other) = dep_conditions.insert(id.clone(), condition)
+                && other != condition
+            {
+                anyhow::bail!("'{}' can't be both --on-success and --on-failure", id);
+            }
+            if !all_blockers.contains(id) {
+                all_blockers.push(id.clone());
+            }
+        }
+    }
+    let blocked_by: &[String] = &all_blockers;
+
+    // ID conflict checks and the insert share one locked load-modify-save
+    let ((task_id, join_id), changes) = super::with_workgraph_changes(dir, |graph| {
+        // Generate ID if not provided
+        let task_id = match id {
+            Some(id) => {
+                if graph.get_node(id).is_some() {
+                    anyhow::bail!("Task with ID '{}' already exists", id);
+                }
+                id.to_string()
+            }
+            None => generate_id(title, graph),
+        };
 
-    // Build loop edges if --loops-to specified
-    let loops_to_edges = if let Some(target) = loops_to {
-        if graph.get_node(target).is_none() {
-            eprintln!(
-                "Warning: loop target '{}' does not exist in the graph",
-                target
-            );
+        // Validate blocked_by references (supports cross-repo peer:task-id syntax)
+        for blocker_id in blocked_by {
+            if blocker_id == &task_id {
+                anyhow::bail!("Task '{}' cannot block itself", task_id);
+            }
+            if workgraph::federation::parse_remote_ref(blocker_id).is_some() {
+                // Cross-repo dependency — validated at resolution time, not here
+            } else if graph.get_node(blocker_id).is_none() {
+                eprintln!(
+                    "Warning: blocker '{}' does not exist in the graph",
+                    blocker_id
+                );
+            }
+        }
+
+        // The parent must exist (hierarchy is structural, unlike blocker references)
+        if let Some(parent_id) = parent {
+            graph.get_task_or_err(parent_id)?;
         }
-        let max_iterations = loop_max
-            .ok_or_else(|| anyhow::anyhow!("--loop-max is required when using --loops-to"))?;
-        let guard = match loop_guard {
-            Some(expr) => Some(parse_guard_expr(expr)?),
-            None => None,
+
+        // Parse resource requirements ("gpu-box" or "gpu-box:2")
+        let mut required_resources: Vec<String> = Vec::new();
+        let mut resource_amounts: BTreeMap<String, f64> = BTreeMap::new();
+        for requirement in requires {
+            let (resource_id, amount) = parse_resource_requirement(requirement)?;
+            if graph.get_resource(&resource_id).is_none() {
+                eprintln!(
+                    "Warning: resource '{}' does not exist in the graph",
+                    resource_id
+                );
+            }
+            if amount == 1.0 {
+                resource_amounts.remove(&resource_id);
+            } else {
+                resource_amounts.insert(resource_id.clone(), amount);
+            }
+            if !required_resources.contains(&resource_id) {
+                required_resources.push(resource_id);
+            }
+        }
+
+        let estimate = if hours.is_some() || cost.is_some() {
+            Some(Estimate { hours, cost })
+        } else {
+            None
         };
-        let delay = match loop_delay {
-            Some(d) => {
-                // Validate the delay parses correctly
-                parse_delay(d).ok_or_else(|| {
-                    anyhow::anyhow!("Invalid delay '{}'. Use format: 30s, 5m, 1h, 24h, 7d", d)
-                })?;
-                Some(d.to_string())
+
+        // Build loop edges if --loops-to specified
+        let loops_to_edges = if let Some(target) = loops_to {
+            if graph.get_node(target).is_none() {
+                eprintln!(
+                    "Warning: loop target '{}' does not exist in the graph",
+                    target
+                );
             }
-            None => None,
+            let max_iterations = loop_max
+                .ok_or_else(|| anyhow::anyhow!("--loop-max is required when using --loops-to"))?;
+            let guard = match loop_guard {
+                Some(expr) => Some(parse_guard_expr(expr)?),
+                None => None,
+            };
+            let delay = match loop_delay {
+                Some(d) => {
+                    // Validate the delay parses correctly
+                    parse_delay(d).ok_or_else(|| {
+                        anyhow::anyhow!("Invalid delay '{}'. Use format: 30s, 5m, 1h, 24h, 7d", d)
+                    })?;
+                    Some(d.to_string())
+                }
+                None => None,
+            };
+            vec![LoopEdge {
+                target: target.to_string(),
+                guard,
+                max_iterations,
+                delay,
+            }]
+        } else {
+            if loop_max.is_some() || loop_guard.is_some() || loop_delay.is_some() {
+                anyhow::bail!("--loop-max, --loop-guard, and --loop-delay require --loops-to");
+            }
+            vec![]
         };
-        vec![LoopEdge {
-            target: target.to_string(),
-            guard,
-            max_iterations,
-            delay,
-        }]
-    } else {
-        if loop_max.is_some() || loop_guard.is_some() || loop_delay.is_some() {
-            anyhow::bail!("--loop-max, --loop-guard, and --loop-delay require --loops-to");
+
+        let mut task = Task {
+            id: task_id.clone(),
+            title: title.to_string(),
+            description: description.map(String::from),
+            status: Status::Open,
+            assigned: assign.map(String::from),
+            estimate,
+            blocks: vec![],
+            blocked_by: blocked_by.to_vec(),
+            requires: required_resources,
+            tags: tags.to_vec(),
+            skills: skills.to_vec(),
+            inputs: inputs.to_vec(),
+            deliverables: deliverables.to_vec(),
+            artifacts: vec![],
+            exec: None,
+            not_before: None,
+            created_at: Some(Utc::now().to_rfc3339()),
+            started_at: None,
+            completed_at: None,
+            log: vec![],
+            retry_count: 0,
+            max_retries,
+            failure_reason: None,
+            model: model.map(String::from),
+            verify: verify.map(String::from),
+            agent: None,
+            loops_to: loops_to_edges,
+            loop_iteration: 0,
+            ready_after: None,
+            paused: false,
+            priority,
+            value,
+            resource_amounts,
+            parent: parent.map(String::from),
+            auto_complete,
+            fields: fields.clone(),
+            deadline: deadline.clone(),
+            schedule: None,
+            dep_groups: dep_groups.clone(),
+            dep_conditions: dep_conditions.clone(),
+            retry_policy: retry_policy.clone(),
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
+        };
+        if let Some(schedule) = schedule.clone() {
+            workgraph::schedule::apply(&mut task, schedule);
         }
-        vec![]
-    };
 
-    let task = Task {
-        id: task_id.clone(),
-        title: title.to_string(),
-        description: description.map(String::from),
-        status: Status::Open,
-        assigned: assign.map(String::from),
-        estimate,
-        blocks: vec![],
-        blocked_by: blocked_by.to_vec(),
-        requires: vec![],
-        tags: tags.to_vec(),
-        skills: skills.to_vec(),
-        inputs: inputs.to_vec(),
-        deliverables: deliverables.to_vec(),
-        artifacts: vec![],
-        exec: None,
-        not_before: None,
-        created_at: Some(Utc::now().to_rfc3339()),
-        started_at: None,
-        completed_at: None,
-        log: vec![],
-        retry_count: 0,
-        max_retries,
-        failure_reason: None,
-        model: model.map(String::from),
-        verify: verify.map(String::from),
-        agent: None,
-        loops_to: loops_to_edges,
-        loop_iteration: 0,
-        ready_after: None,
-        paused: false,
-    };
+        // A fan-out task comes with its join task, which waits for the children
+        let join_id = if let Some(child_title) = fan_out {
+            let join_id = workgraph::fan_out::unique_id(graph, &format!("{}-join", task_id));
+            task.fan_out = Some(FanOut::new(
+                &task_id,
+                child_title,
+                fan_out_description,
+                fan_out_items,
+                &join_id,
+            ));
+            task.blocks.push(join_id.clone());
+            graph.add_node(Node::Task(Task {
+                id: join_id.clone(),
+                title: join
+                    .map(String::from)
+                    .unwrap_or_else(|| format!("Join: {}", title)),
+                blocked_by: vec![task_id.clone()],
+                parent: parent.map(String::from),
+                created_at: Some(Utc::now().to_rfc3339()),
+                ..Task::default()
+            }));
+            Some(join_id)
+        } else {
+            None
+        };
 
-    // Add task to graph
-    graph.add_node(Node::Task(task));
+        // Add task to graph
+        graph.add_node(Node::Task(task));
 
-    // Maintain bidirectional consistency: update `blocks` on referenced blocker tasks
-    // (skip cross-repo refs — those live in a different graph)
-    for dep in blocked_by {
-        if workgraph::federation::parse_remote_ref(dep).is_some() {
-            continue; // Cross-repo dep; can't update remote graph's blocks field
-        }
-        if let Some(blocker) = graph.get_task_mut(dep)
-            && !blocker.blocks.contains(&task_id)
-        {
-            blocker.blocks.push(task_id.clone());
+        // Maintain bidirectional consistency: update `blocks` on referenced blocker tasks
+        // (skip cross-repo refs — those live in a different graph)
+        for dep in blocked_by {
+            if workgraph::federation::parse_remote_ref(dep).is_some() {
+                continue; // Cross-repo dep; can't update remote graph's blocks field
+            }
+            if let Some(blocker) = graph.get_task_mut(dep)
+                && !blocker.blocks.contains(&task_id)
+            {
+                blocker.blocks.push(task_id.clone());
+            }
         }
-    }
 
-    // Save atomically (temp file + rename)
-    save_graph(&graph, &path).context("Failed to save graph")?;
+        Ok((task_id, join_id))
+    })?;
     super::notify_graph_changed(dir);
 
     // Record operation
-    let config = workgraph::config::Config::load_or_default(dir);
     let _ = workgraph::provenance::record(
         dir,
         "add_task",
         Some(&task_id),
         assign,
-        serde_json::json!({ "title": title }),
+        workgraph::undo::with_changes(serde_json::json!({ "title": title }), &changes),
         config.log.rotation_threshold,
     );
 
     println!("Added task: {} ({})", title, task_id);
+    if priority != Priority::Normal {
+        println!("  Priority: {}", priority);
+    }
+    if let Some(parent_id) = parent {
+        println!("  Parent: {}", parent_id);
+    }
+    if !fields.is_empty() {
+        println!("  Fields: {}", workgraph::fields::display_all(&fields));
+    }
+    for group in &dep_groups {
+        println!("  Waits for: {}", group);
+    }
+    for (blocker, condition) in &dep_conditions {
+        let outcome = match condition {
+            DepCondition::OnFailure => "fails",
+            _ => "succeeds",
+        };
+        println!("  Runs only if '{}' {}", blocker, outcome);
+    }
     if let (Some(target), Some(max)) = (&loops_to, &loop_max) {
         println!("  Loop edge: → {} (max {} iterations)", target, max);
     }
+    if let Some(policy) = &retry_policy {
+        println!("  Retry policy: {}", policy);
+    }
+    if let Some(join_id) = &join_id {
+        println!(
+            "  Fans out into one task per item when done, joined by '{}'",
+            join_id
+        );
+    }
     super::print_service_hint(dir);
     Ok(())
 }
@@ -327,74 +486,89 @@ fn add_task_directly(
     verify: Option<&str>,
     origin: &str,
 ) -> Result<String> {
-    use workgraph::graph::{Node, Status, Task};
-    use workgraph::parser::{load_graph, save_graph};
+    use workgraph::graph::{Node, Priority, Status, Task};
+    use workgraph::undo::with_graph_changes;
 
     let graph_path = super::graph_path(peer_workgraph_dir);
-    let mut graph = if graph_path.exists() {
-        load_graph(&graph_path).context("Failed to load peer graph")?
-    } else {
+    if !graph_path.exists() {
         anyhow::bail!(
             "No graph.jsonl at '{}'. Is this a workgraph project?",
             peer_workgraph_dir.display()
         );
-    };
+    }
 
-    let task_id = match id {
-        Some(id) => {
-            if graph.get_node(id).is_some() {
-                anyhow::bail!("Task with ID '{}' already exists in peer", id);
+    let (task_id, changes) = with_graph_changes(&graph_path, |graph| -> Result<String> {
+        let task_id = match id {
+            Some(id) => {
+                if graph.get_node(id).is_some() {
+                    anyhow::bail!("Task with ID '{}' already exists in peer", id);
+                }
+                id.to_string()
             }
-            id.to_string()
-        }
-        None => generate_id(title, &graph),
-    };
+            None => generate_id(title, graph),
+        };
 
-    let task = Task {
-        id: task_id.clone(),
-        title: title.to_string(),
-        description: description.map(String::from),
-        status: Status::Open,
-        assigned: None,
-        estimate: None,
-        blocks: vec![],
-        blocked_by: blocked_by.to_vec(),
-        requires: vec![],
-        tags: tags.to_vec(),
-        skills: skills.to_vec(),
-        inputs: vec![],
-        deliverables: deliverables.to_vec(),
-        artifacts: vec![],
-        exec: None,
-        not_before: None,
-        created_at: Some(chrono::Utc::now().to_rfc3339()),
-        started_at: None,
-        completed_at: None,
-        log: vec![],
-        retry_count: 0,
-        max_retries: None,
-        failure_reason: None,
-        model: model.map(String::from),
-        verify: verify.map(String::from),
-        agent: None,
-        loops_to: vec![],
-        loop_iteration: 0,
-        ready_after: None,
-        paused: false,
-    };
+        let task = Task {
+            id: task_id.clone(),
+            title: title.to_string(),
+            description: description.map(String::from),
+            status: Status::Open,
+            assigned: None,
+            estimate: None,
+            blocks: vec![],
+            blocked_by: blocked_by.to_vec(),
+            requires: vec![],
+            tags: tags.to_vec(),
+            skills: skills.to_vec(),
+            inputs: vec![],
+            deliverables: deliverables.to_vec(),
+            artifacts: vec![],
+            exec: None,
+            not_before: None,
+            created_at: Some(chrono::Utc::now().to_rfc3339()),
+            started_at: None,
+            completed_at: None,
+            log: vec![],
+            retry_count: 0,
+            max_retries: None,
+            failure_reason: None,
+            model: model.map(String::from),
+            verify: verify.map(String::from),
+            agent: None,
+            loops_to: vec![],
+            loop_iteration: 0,
+            ready_after: None,
+            paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: BTreeMap::new(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: BTreeMap::new(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
+        };
 
-    graph.add_node(Node::Task(task));
+        graph.add_node(Node::Task(task));
 
-    // Maintain bidirectional blocked_by/blocks consistency
-    for dep in blocked_by {
-        if let Some(blocker) = graph.get_task_mut(dep)
-            && !blocker.blocks.contains(&task_id)
-        {
-            blocker.blocks.push(task_id.clone());
+        // Maintain bidirectional blocked_by/blocks consistency
+        for dep in blocked_by {
+            if let Some(blocker) = graph.get_task_mut(dep)
+                && !blocker.blocks.contains(&task_id)
+            {
+                blocker.blocks.push(task_id.clone());
+            }
         }
-    }
 
-    save_graph(&graph, &graph_path).context("Failed to save peer graph")?;
+        Ok(task_id)
+    })?;
 
     // Record provenance in the peer's workgraph
     let config = workgraph::config::Config::load_or_default(peer_workgraph_dir);
@@ -403,7 +577,10 @@ fn add_task_directly(
         "add_task",
         Some(&task_id),
         None,
-        serde_json::json!({ "title": title, "origin": origin, "remote": true }),
+        workgraph::undo::with_changes(
+            serde_json::json!({ "title": title, "origin": origin, "remote": true }),
+            &changes,
+        ),
         config.log.rotation_threshold,
     );
 
@@ -456,6 +633,7 @@ mod tests {
     use super::*;
     use workgraph::WorkGraph;
     use workgraph::graph::{LoopGuard, Node, Status, Task};
+    use workgraph::parser::load_graph;
 
     /// Helper: create a minimal task with the given ID for inserting into a WorkGraph.
     fn stub_task(id: &str) -> Task {
@@ -726,7 +904,7 @@ mod tests {
         let dir_path = dir.path();
         // Initialize a workgraph
         std::fs::create_dir_all(dir_path).unwrap();
-        let path = super::graph_path(dir_path);
+        let path = super::super::graph_path(dir_path);
         let graph = WorkGraph::new();
         workgraph::parser::save_graph(&graph, &path).unwrap();
 
@@ -750,6 +928,24 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            None,
+            false,
+            &[],
+            None,
+            None,
+            &[],
+            &[],
+            false,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
         );
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("cannot be empty"));
@@ -760,7 +956,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let dir_path = dir.path();
         std::fs::create_dir_all(dir_path).unwrap();
-        let path = super::graph_path(dir_path);
+        let path = super::super::graph_path(dir_path);
         let graph = WorkGraph::new();
         workgraph::parser::save_graph(&graph, &path).unwrap();
 
@@ -784,6 +980,24 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            None,
+            false,
+            &[],
+            None,
+            None,
+            &[],
+            &[],
+            false,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
         );
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("cannot be empty"));
@@ -794,7 +1008,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let dir_path = dir.path();
         std::fs::create_dir_all(dir_path).unwrap();
-        let path = super::graph_path(dir_path);
+        let path = super::super::graph_path(dir_path);
         let graph = WorkGraph::new();
         workgraph::parser::save_graph(&graph, &path).unwrap();
 
@@ -818,6 +1032,24 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            None,
+            false,
+            &[],
+            None,
+            None,
+            &[],
+            &[],
+            false,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
         );
         assert!(result.is_err());
         assert!(
@@ -834,7 +1066,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let dir_path = dir.path();
         std::fs::create_dir_all(dir_path).unwrap();
-        let path = super::graph_path(dir_path);
+        let path = super::super::graph_path(dir_path);
         let graph = WorkGraph::new();
         workgraph::parser::save_graph(&graph, &path).unwrap();
 
@@ -859,6 +1091,24 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            None,
+            false,
+            &[],
+            None,
+            None,
+            &[],
+            &[],
+            false,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
         );
         assert!(result.is_ok());
     }
@@ -868,7 +1118,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let dir_path = dir.path();
         std::fs::create_dir_all(dir_path).unwrap();
-        let path = super::graph_path(dir_path);
+        let path = super::super::graph_path(dir_path);
 
         // Create a graph with an existing blocker task
         let mut graph = WorkGraph::new();
@@ -897,6 +1147,24 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            None,
+            false,
+            &[],
+            None,
+            None,
+            &[],
+            &[],
+            false,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
         );
         assert!(result.is_ok());
 
@@ -923,4 +1191,38 @@ mod tests {
             b.blocks
         );
     }
+
+    #[test]
+    fn fields_validated_against_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path();
+        std::fs::create_dir_all(dir_path).unwrap();
+        let path = super::super::graph_path(dir_path);
+        workgraph::parser::save_graph(&WorkGraph::new(), &path).unwrap();
+        std::fs::write(
+            dir_path.join("config.toml"),
+            "[fields.customer]\ntype = \"string\"\nrequired = true\n\n\
+             [fields.points]\ntype = \"number\"\n",
+        )
+        .unwrap();
+
+        let add = |id: &str, fields: &[&str]| {
+            let fields: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+            run(
+                dir_path, id, Some(id), None, &[], None, None, None, &[], &[], &[], &[], None,
+                None, None, None, None, None, None, None, None, &[], None, false, &fields, None, None,
+                &[], &[], false, &[], &[], None, None, None, None, None,
+            )
+        };
+
+        assert!(add("no-customer", &["points=3"]).is_err());
+        assert!(add("bad-points", &["customer=acme", "points=many"]).is_err());
+        add("ok", &["customer=acme", "points=3"]).unwrap();
+
+        let graph = load_graph(&path).unwrap();
+        let task = graph.get_task("ok").unwrap();
+        assert_eq!(task.fields["customer"], serde_json::json!("acme"));
+        assert_eq!(task.fields["points"], serde_json::json!(3));
+        assert!(graph.get_task("no-customer").is_none());
+    }
 }
@@ -16,10 +16,12 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::thread;
 use std::time::Duration;
+use workgraph::completion::{check_completable, complete, read_guards};
 use workgraph::config::Config;
-use workgraph::graph::{LogEntry, Status, reward_loop_edges};
-use workgraph::parser::{load_graph, save_graph};
+use workgraph::graph::{LogEntry, Status, skip_unreachable};
+use workgraph::parser::load_graph;
 use workgraph::query::ready_tasks;
+use workgraph::verify;
 
 use super::graph_path;
 
@@ -415,8 +417,7 @@ fn run_iteration(dir: &Path, actor_id: &str, json: bool) -> Result<IterationResu
         }
 
         if success {
-            complete_task(dir, &task_id, actor_id)?;
-            Ok(IterationResult::Completed(task_id))
+            complete_task(dir, &task_id, actor_id)
         } else {
             let exit_code = output.status.code().unwrap_or(-1);
             let reason = format!("Exit code {}", exit_code);
@@ -437,65 +438,93 @@ fn run_iteration(dir: &Path, actor_id: &str, json: bool) -> Result<IterationResu
 
 /// Claim a task for the actor
 fn claim_task(dir: &Path, task_id: &str, actor_id: &str) -> Result<()> {
-    let path = graph_path(dir);
-    let mut graph = load_graph(&path).context("Failed to load graph")?;
-
-    let task = graph.get_task_mut_or_err(task_id)?;
-
-    task.status = Status::InProgress;
-    task.assigned = Some(actor_id.to_string());
-    task.started_at = Some(Utc::now().to_rfc3339());
-    task.log.push(LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: Some(actor_id.to_string()),
-        message: "Claimed by autonomous agent".to_string(),
-    });
+    super::with_workgraph_mut(dir, |graph| {
+        let task = graph.get_task_mut_or_err(task_id)?;
 
-    save_graph(&graph, &path).context("Failed to save graph")?;
+        task.status = Status::InProgress;
+        task.assigned = Some(actor_id.to_string());
+        task.started_at = Some(Utc::now().to_rfc3339());
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: Some(actor_id.to_string()),
+            message: "Claimed by autonomous agent".to_string(),
+        });
+        Ok(())
+    })?;
     super::notify_graph_changed(dir);
     Ok(())
 }
 
-/// Mark task as completed
-fn complete_task(dir: &Path, task_id: &str, actor_id: &str) -> Result<()> {
-    let path = graph_path(dir);
-    let mut graph = load_graph(&path).context("Failed to load graph")?;
-
-    let task = graph.get_task_mut_or_err(task_id)?;
-
-    task.status = Status::Done;
-    task.completed_at = Some(Utc::now().to_rfc3339());
-    task.log.push(LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: Some(actor_id.to_string()),
-        message: "Completed by autonomous agent".to_string(),
-    });
-
-    // Reward loop edges: re-activate upstream tasks if conditions are met
-    reward_loop_edges(&mut graph, task_id);
+/// Mark task as completed (or submitted for review when run by an agent),
+/// or as failed if its verification checks fail
+fn complete_task(dir: &Path, task_id: &str, actor_id: &str) -> Result<IterationResult> {
+    // Verification checks and loop guards can run commands, so they're run
+    // against a snapshot before the graph lock
+    let guards = {
+        let (graph, _path) = super::load_workgraph(dir)?;
+        let task = graph.get_task_or_err(task_id)?;
+        let failures = match task.status {
+            Status::Done | Status::InReview => vec![],
+            _ => verify::run(task, dir)?,
+        };
+        if !failures.is_empty() {
+            let reason = format!("Verification failed: {}", verify::summary(&failures));
+            fail_task(dir, task_id, actor_id, &reason)?;
+            return Ok(IterationResult::Failed(task_id.to_string(), reason));
+        }
+        read_guards(&graph, dir, task_id)
+    };
+    super::with_workgraph_mut(dir, |graph| {
+        // The exec command may have finished the task itself with 'wg done'
+        let status = graph.get_task_or_err(task_id)?.status;
+        if matches!(status, Status::Done | Status::InReview) {
+            return Ok(());
+        }
+        check_completable(graph, task_id)?;
+
+        let task = graph.get_task_mut_or_err(task_id)?;
+
+        // As with 'wg done', an agent's work on a task with verify criteria
+        // waits for a human to sign off
+        if task.verify.is_some() && super::done::run_by_agent() {
+            task.status = Status::InReview;
+            task.log.push(LogEntry {
+                timestamp: Utc::now().to_rfc3339(),
+                actor: Some(actor_id.to_string()),
+                message: "Task submitted for review".to_string(),
+            });
+            return Ok(());
+        }
 
-    save_graph(&graph, &path).context("Failed to save graph")?;
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: Some(actor_id.to_string()),
+            message: "Completed by autonomous agent".to_string(),
+        });
+        complete(graph, dir, task_id, &guards)?;
+        Ok(())
+    })?;
     super::notify_graph_changed(dir);
-    Ok(())
+    Ok(IterationResult::Completed(task_id.to_string()))
 }
 
 /// Mark task as failed
 fn fail_task(dir: &Path, task_id: &str, actor_id: &str, reason: &str) -> Result<()> {
-    let path = graph_path(dir);
-    let mut graph = load_graph(&path).context("Failed to load graph")?;
-
-    let task = graph.get_task_mut_or_err(task_id)?;
-
-    task.status = Status::Failed;
-    task.retry_count += 1;
-    task.failure_reason = Some(reason.to_string());
-    task.log.push(LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: Some(actor_id.to_string()),
-        message: format!("Failed: {}", reason),
-    });
-
-    save_graph(&graph, &path).context("Failed to save graph")?;
+    super::with_workgraph_mut(dir, |graph| {
+        let task = graph.get_task_mut_or_err(task_id)?;
+
+        task.status = Status::Failed;
+        task.retry_count += 1;
+        task.failure_reason = Some(reason.to_string());
+        workgraph::retry::record_failure(task, None, None);
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: Some(actor_id.to_string()),
+            message: format!("Failed: {}", reason),
+        });
+        skip_unreachable(graph);
+        Ok(())
+    })?;
     super::notify_graph_changed(dir);
     Ok(())
 }
@@ -660,6 +689,27 @@ mod tests {
         assert!(task.completed_at.is_some());
     }
 
+    #[test]
+    fn test_complete_task_fails_verification() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("graph.jsonl");
+
+        let mut graph = WorkGraph::new();
+        let mut task = make_task("t1", "Unverified Task");
+        task.verify = Some("shell:exit 1".to_string());
+        graph.add_node(Node::Task(task));
+        save_graph(&graph, &path).unwrap();
+
+        claim_task(temp_dir.path(), "t1", "test-agent").unwrap();
+        let result = complete_task(temp_dir.path(), "t1", "test-agent").unwrap();
+        assert!(matches!(result, IterationResult::Failed(..)));
+
+        let graph = load_graph(graph_path(temp_dir.path())).unwrap();
+        let task = graph.get_task("t1").unwrap();
+        assert_eq!(task.status, Status::Failed);
+        assert!(task.completed_at.is_none());
+    }
+
     #[test]
     fn test_fail_task() {
         let temp_dir = setup_graph();
@@ -173,7 +173,7 @@ fn compute_summary(graph: &WorkGraph) -> Summary {
                     estimated_cost += est.cost.unwrap_or(0.0);
                 }
             }
-            Status::InProgress => {
+            Status::InProgress | Status::InReview => {
                 in_progress += 1;
                 // Include in-progress tasks in remaining estimates
                 if let Some(ref est) = task.estimate {
@@ -359,6 +359,12 @@ fn compute_structural_health(graph: &WorkGraph) -> StructuralHealth {
                             issue.from, issue.target, guard_task
                         )
                     }
+                    LoopEdgeIssueKind::InvalidGuard(problem) => {
+                        format!(
+                            "{} -> {} (invalid guard: {})",
+                            issue.from, issue.target, problem
+                        )
+                    }
                     LoopEdgeIssueKind::SelfLoop => {
                         format!("{} -> {} (self-loop)", issue.from, issue.target)
                     }
@@ -787,6 +793,7 @@ fn print_human_readable(output: &AnalysisOutput) {
                 Status::Blocked => "blocked".to_string(),
                 Status::Failed => "failed".to_string(),
                 Status::Abandoned => "abandoned".to_string(),
+                Status::InReview => "in-review".to_string(),
             };
 
             let assigned_str = bottleneck
@@ -3,8 +3,9 @@ use chrono::{DateTime, Duration, Utc};
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
-use workgraph::graph::{Node, Status, Task};
-use workgraph::parser::{load_graph, save_graph};
+use workgraph::graph::{Node, Status, Task, WorkGraph};
+use workgraph::parser::load_graph;
+use workgraph::query::filter::{Filter, FilterContext};
 
 use super::graph_path;
 
@@ -42,6 +43,21 @@ fn parse_duration(s: &str) -> Result<Duration> {
     }
 }
 
+/// Tasks in the graph eligible for archiving
+fn archivable_tasks(
+    graph: &WorkGraph,
+    older_than: Option<&Duration>,
+    filter: Option<&Filter>,
+) -> Vec<Task> {
+    let ctx = FilterContext::new(graph);
+    graph
+        .tasks()
+        .filter(|t| should_archive(t, older_than))
+        .filter(|t| filter.is_none_or(|f| f.matches(t, &ctx)))
+        .cloned()
+        .collect()
+}
+
 /// Check if a task should be archived based on the --older filter
 fn should_archive(task: &Task, older_than: Option<&Duration>) -> bool {
     if task.status != Status::Done {
@@ -108,17 +124,32 @@ fn load_archive(archive_path: &Path) -> Result<Vec<Task>> {
     Ok(tasks)
 }
 
-pub fn run(dir: &Path, dry_run: bool, older: Option<&str>, list: bool, json: bool) -> Result<()> {
+pub fn run(
+    dir: &Path,
+    dry_run: bool,
+    older: Option<&str>,
+    list: bool,
+    filter: Option<&str>,
+    json: bool,
+) -> Result<()> {
     let path = graph_path(dir);
     let arch_path = archive_path(dir);
 
     if !path.exists() {
         anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
     }
+    let filter = super::parse_filter(dir, filter)?;
 
     // Handle --list: show archived tasks
     if list {
-        let tasks = load_archive(&arch_path)?;
+        let mut tasks = load_archive(&arch_path)?;
+        if let Some(filter) = &filter {
+            // Archived tasks are matched against the live graph for
+            // relations such as `is:ready` or `blocks:`
+            let graph = load_graph(&path).context("Failed to load graph")?;
+            let ctx = FilterContext::new(&graph);
+            tasks.retain(|t| filter.matches(t, &ctx));
+        }
         if json {
             let items: Vec<serde_json::Value> = tasks
                 .iter()
@@ -150,41 +181,44 @@ pub fn run(dir: &Path, dry_run: bool, older: Option<&str>, list: bool, json: boo
         None
     };
 
-    let graph = load_graph(&path).context("Failed to load graph")?;
-
-    // Find tasks to archive
-    let tasks_to_archive: Vec<Task> = graph
-        .tasks()
-        .filter(|t| should_archive(t, older_duration.as_ref()))
-        .cloned()
-        .collect();
-
-    if tasks_to_archive.is_empty() {
-        println!("No tasks to archive.");
-        return Ok(());
-    }
-
     if dry_run {
-        println!("Would archive {} tasks:", tasks_to_archive.len());
-        for task in &tasks_to_archive {
-            let completed = task.completed_at.as_deref().unwrap_or("unknown");
-            println!("  {} - {} (completed: {})", task.id, task.title, completed);
+        let graph = load_graph(&path).context("Failed to load graph")?;
+        let tasks_to_archive = archivable_tasks(&graph, older_duration.as_ref(), filter.as_ref());
+        if tasks_to_archive.is_empty() {
+            println!("No tasks to archive.");
+        } else {
+            println!("Would archive {} tasks:", tasks_to_archive.len());
+            for task in &tasks_to_archive {
+                let completed = task.completed_at.as_deref().unwrap_or("unknown");
+                println!("  {} - {} (completed: {})", task.id, task.title, completed);
+            }
         }
         return Ok(());
     }
 
-    // Perform the archive operation
-    // 1. Append tasks to archive file
-    append_to_archive(&tasks_to_archive, &arch_path)?;
+    // Perform the archive operation under the graph lock so no task
+    // completed concurrently is lost between selection and save
+    let tasks_to_archive = super::with_workgraph_mut(dir, |graph| {
+        let tasks_to_archive = archivable_tasks(graph, older_duration.as_ref(), filter.as_ref());
+        if tasks_to_archive.is_empty() {
+            return Ok(tasks_to_archive);
+        }
+
+        // 1. Append tasks to archive file
+        append_to_archive(&tasks_to_archive, &arch_path)?;
 
-    // 2. Remove archived tasks from the main graph
-    let mut modified_graph = graph;
-    for task in &tasks_to_archive {
-        modified_graph.remove_node(&task.id);
+        // 2. Remove archived tasks from the main graph (saved on return)
+        for task in &tasks_to_archive {
+            graph.remove_node(&task.id);
+        }
+        Ok(tasks_to_archive)
+    })?;
+
+    if tasks_to_archive.is_empty() {
+        println!("No tasks to archive.");
+        return Ok(());
     }
 
-    // 3. Save the modified graph
-    save_graph(&modified_graph, &path).context("Failed to save graph")?;
     super::notify_graph_changed(dir);
 
     // Record operation
@@ -213,6 +247,7 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
     use workgraph::graph::WorkGraph;
+    use workgraph::parser::save_graph;
 
     fn make_task(id: &str, title: &str, status: Status, completed_at: Option<&str>) -> Task {
         Task {
@@ -317,7 +352,7 @@ mod tests {
         save_graph(&graph, &graph_file).unwrap();
 
         // Run in dry-run mode
-        run(wg_dir, true, None, false, false).unwrap();
+        run(wg_dir, true, None, false, None, false).unwrap();
 
         // Verify graph is unchanged
         let loaded = load_graph(&graph_file).unwrap();
@@ -349,7 +384,7 @@ mod tests {
         save_graph(&graph, &graph_file).unwrap();
 
         // Run archive
-        run(wg_dir, false, None, false, false).unwrap();
+        run(wg_dir, false, None, false, None, false).unwrap();
 
         // Verify done task removed from graph
         let loaded = load_graph(&graph_file).unwrap();
@@ -388,7 +423,7 @@ mod tests {
         append_to_archive(&tasks, &arch_path).unwrap();
 
         // Run list - should not error
-        run(wg_dir, false, None, true, false).unwrap();
+        run(wg_dir, false, None, true, None, false).unwrap();
     }
 
     #[test]
@@ -423,6 +458,6 @@ mod tests {
         append_to_archive(&tasks, &arch_path).unwrap();
 
         // Run list with json=true (output goes to stdout, just verify no error)
-        run(wg_dir, false, None, true, true).unwrap();
+        run(wg_dir, false, None, true, None, true).unwrap();
     }
 }
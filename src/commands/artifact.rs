@@ -1,30 +1,49 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
+use serde::Serialize;
 use std::path::Path;
-use workgraph::parser::save_graph;
+use workgraph::artifacts::{self, ArtifactSnapshot, Checkout, Drift};
 
 #[cfg(test)]
 use super::graph_path;
 #[cfg(test)]
 use workgraph::parser::load_graph;
 
-/// Register an artifact (produced output) for a task
-pub fn run_add(dir: &Path, task_id: &str, artifact_path: &str) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
+/// Register an artifact (produced output) for a task. With `snapshot`, the
+/// file is also copied into the artifact store and its hash recorded, so it
+/// can be verified and restored later.
+pub fn run_add(dir: &Path, task_id: &str, artifact_path: &str, snapshot: bool) -> Result<()> {
+    let snapshot = if snapshot {
+        Some(artifacts::snapshot(dir, artifact_path)?)
+    } else {
+        None
+    };
+
+    let (added, changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(task_id)?;
+        let mut added = false;
 
-    let task = graph.get_task_mut_or_err(task_id)?;
+        // Check if artifact already registered
+        if !task.artifacts.contains(&artifact_path.to_string()) {
+            task.artifacts.push(artifact_path.to_string());
+            added = true;
+        }
+        if let Some(ref snapshot) = snapshot
+            && task.artifact_snapshots.get(artifact_path) != Some(snapshot)
+        {
+            task.artifact_snapshots
+                .insert(artifact_path.to_string(), snapshot.clone());
+            added = true;
+        }
+        Ok(added)
+    })?;
 
-    // Check if artifact already registered
-    if task.artifacts.contains(&artifact_path.to_string()) {
+    if !added {
         println!(
             "Artifact '{}' already registered for task '{}'",
             artifact_path, task_id
         );
         return Ok(());
     }
-
-    task.artifacts.push(artifact_path.to_string());
-
-    save_graph(&graph, &path).context("Failed to save graph")?;
     super::notify_graph_changed(dir);
 
     // Record operation
@@ -34,7 +53,10 @@ pub fn run_add(dir: &Path, task_id: &str, artifact_path: &str) -> Result<()> {
         "artifact_add",
         Some(task_id),
         None,
-        serde_json::json!({ "path": artifact_path }),
+        workgraph::undo::with_changes(
+            serde_json::json!({ "path": artifact_path, "snapshot": snapshot }),
+            &changes,
+        ),
         config.log.rotation_threshold,
     );
 
@@ -42,27 +64,34 @@ pub fn run_add(dir: &Path, task_id: &str, artifact_path: &str) -> Result<()> {
         "Registered artifact '{}' for task '{}'",
         artifact_path, task_id
     );
+    if let Some(snapshot) = snapshot {
+        println!(
+            "  Snapshot {} ({} bytes)",
+            snapshot.short_hash(),
+            snapshot.size
+        );
+    }
     Ok(())
 }
 
 /// Remove an artifact from a task
 pub fn run_remove(dir: &Path, task_id: &str, artifact_path: &str) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
-
-    let task = graph.get_task_mut_or_err(task_id)?;
-
-    let original_len = task.artifacts.len();
-    task.artifacts.retain(|a| a != artifact_path);
-
-    if task.artifacts.len() == original_len {
-        anyhow::bail!(
-            "Artifact '{}' not found on task '{}'",
-            artifact_path,
-            task_id
-        );
-    }
-
-    save_graph(&graph, &path).context("Failed to save graph")?;
+    let ((), changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(task_id)?;
+
+        let original_len = task.artifacts.len();
+        task.artifacts.retain(|a| a != artifact_path);
+        task.artifact_snapshots.remove(artifact_path);
+
+        if task.artifacts.len() == original_len {
+            anyhow::bail!(
+                "Artifact '{}' not found on task '{}'",
+                artifact_path,
+                task_id
+            );
+        }
+        Ok(())
+    })?;
     super::notify_graph_changed(dir);
 
     // Record operation
@@ -72,7 +101,7 @@ pub fn run_remove(dir: &Path, task_id: &str, artifact_path: &str) -> Result<()>
         "artifact_rm",
         Some(task_id),
         None,
-        serde_json::json!({ "path": artifact_path }),
+        workgraph::undo::with_changes(serde_json::json!({ "path": artifact_path }), &changes),
         config.log.rotation_threshold,
     );
 
@@ -94,6 +123,7 @@ pub fn run_list(dir: &Path, task_id: &str, json: bool) -> Result<()> {
             "task_id": task_id,
             "deliverables": task.deliverables,
             "artifacts": task.artifacts,
+            "snapshots": task.artifact_snapshots,
         });
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
@@ -121,7 +151,15 @@ pub fn run_list(dir: &Path, task_id: &str, json: bool) -> Result<()> {
                 } else {
                     " [extra]"
                 };
-                println!("  {}{}", a, expected);
+                let snapshot = match task.artifact_snapshots.get(a) {
+                    Some(snapshot) => format!(
+                        " (snapshot {}, {} bytes)",
+                        snapshot.short_hash(),
+                        snapshot.size
+                    ),
+                    None => String::new(),
+                };
+                println!("  {}{}{}", a, expected, snapshot);
             }
         } else {
             println!("No artifacts produced yet.");
@@ -131,6 +169,144 @@ pub fn run_list(dir: &Path, task_id: &str, json: bool) -> Result<()> {
     Ok(())
 }
 
+/// A snapshotted artifact checked against the file and the stored copy
+#[derive(Debug, Serialize)]
+struct Verification {
+    task_id: String,
+    path: String,
+    sha256: String,
+    #[serde(flatten)]
+    drift: Drift,
+    /// Why the stored copy can't be used for checkout, if it can't
+    #[serde(skip_serializing_if = "Option::is_none")]
+    store_error: Option<String>,
+}
+
+/// Check snapshotted artifacts (of one task, or all) for drift. Fails if any
+/// file or stored copy no longer matches.
+pub fn run_verify(dir: &Path, task_id: Option<&str>, json: bool) -> Result<()> {
+    let (graph, _path) = super::load_workgraph(dir)?;
+
+    let tasks = match task_id {
+        Some(id) => vec![graph.get_task_or_err(id)?],
+        None => graph.tasks().collect(),
+    };
+
+    let mut results = Vec::new();
+    for task in tasks {
+        for (path, snapshot) in &task.artifact_snapshots {
+            results.push(Verification {
+                task_id: task.id.clone(),
+                path: path.clone(),
+                sha256: snapshot.sha256.clone(),
+                drift: artifacts::check(dir, path, snapshot),
+                store_error: artifacts::read_stored(dir, snapshot)
+                    .err()
+                    .map(|e| e.to_string()),
+            });
+        }
+    }
+    let bad = results
+        .iter()
+        .filter(|r| r.drift != Drift::Intact || r.store_error.is_some())
+        .count();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else if results.is_empty() {
+        println!("No snapshotted artifacts to verify.");
+    } else {
+        for r in &results {
+            let store = match r.store_error {
+                Some(ref e) => format!(" [{}]", e),
+                None => String::new(),
+            };
+            println!(
+                "  {:<10} {} ({}) {}{}",
+                r.drift.to_string(),
+                r.path,
+                r.task_id,
+                r.sha256.get(..12).unwrap_or(&r.sha256),
+                store
+            );
+        }
+        println!();
+        println!(
+            "{} artifact(s) checked, {} drifted or unrecoverable",
+            results.len(),
+            bad
+        );
+    }
+
+    if bad > 0 {
+        anyhow::bail!("{} artifact(s) no longer match their snapshots", bad);
+    }
+    Ok(())
+}
+
+/// Restore a task's snapshotted artifacts (all, or `paths`) to the contents
+/// the task produced. Files modified since the snapshot are only overwritten
+/// with `force`; otherwise they are listed.
+pub fn run_checkout(dir: &Path, task_id: &str, paths: &[String], force: bool) -> Result<()> {
+    let (graph, _path) = super::load_workgraph(dir)?;
+    let task = graph.get_task_or_err(task_id)?;
+
+    let selected: Vec<(&String, &ArtifactSnapshot)> = if paths.is_empty() {
+        task.artifact_snapshots.iter().collect()
+    } else {
+        paths
+            .iter()
+            .map(|path| {
+                task.artifact_snapshots.get_key_value(path).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Task '{}' has no snapshot of '{}'. Use 'wg artifact add --snapshot'",
+                        task_id,
+                        path
+                    )
+                })
+            })
+            .collect::<Result<_>>()?
+    };
+    if selected.is_empty() {
+        anyhow::bail!(
+            "Task '{}' has no snapshotted artifacts. Use 'wg artifact add --snapshot'",
+            task_id
+        );
+    }
+
+    let mut restored = 0;
+    let mut kept = 0;
+    for (path, snapshot) in selected {
+        match artifacts::checkout(dir, path, snapshot, force)? {
+            Checkout::Restored => {
+                println!("Restored {} ({})", path, snapshot.short_hash());
+                restored += 1;
+            }
+            Checkout::Unchanged => println!("Unchanged {} ({})", path, snapshot.short_hash()),
+            Checkout::Kept { sha256 } => {
+                println!(
+                    "Would overwrite {} (modified, now {}; snapshot {})",
+                    path,
+                    &sha256[..12],
+                    snapshot.short_hash()
+                );
+                kept += 1;
+            }
+        }
+    }
+    println!(
+        "{} artifact(s) restored to what '{}' produced",
+        restored, task_id
+    );
+    if kept > 0 {
+        println!(
+            "{} modified artifact(s) left alone; rerun with --force to overwrite them",
+            kept
+        );
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,7 +339,7 @@ mod tests {
     fn test_add_artifact() {
         let temp_dir = setup_graph();
 
-        let result = run_add(temp_dir.path(), "t1", "output.txt");
+        let result = run_add(temp_dir.path(), "t1", "output.txt", false);
         assert!(result.is_ok());
 
         let graph = load_graph(graph_path(temp_dir.path())).unwrap();
@@ -175,8 +351,8 @@ mod tests {
     fn test_add_artifact_duplicate() {
         let temp_dir = setup_graph();
 
-        run_add(temp_dir.path(), "t1", "output.txt").unwrap();
-        let result = run_add(temp_dir.path(), "t1", "output.txt");
+        run_add(temp_dir.path(), "t1", "output.txt", false).unwrap();
+        let result = run_add(temp_dir.path(), "t1", "output.txt", false);
         assert!(result.is_ok()); // Should succeed but not duplicate
 
         let graph = load_graph(graph_path(temp_dir.path())).unwrap();
@@ -188,7 +364,7 @@ mod tests {
     fn test_remove_artifact() {
         let temp_dir = setup_graph();
 
-        run_add(temp_dir.path(), "t1", "output.txt").unwrap();
+        run_add(temp_dir.path(), "t1", "output.txt", false).unwrap();
         let result = run_remove(temp_dir.path(), "t1", "output.txt");
         assert!(result.is_ok());
 
@@ -209,8 +385,46 @@ mod tests {
     fn test_list_artifacts() {
         let temp_dir = setup_graph();
 
-        run_add(temp_dir.path(), "t1", "output.txt").unwrap();
+        run_add(temp_dir.path(), "t1", "output.txt", false).unwrap();
         let result = run_list(temp_dir.path(), "t1", false);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_snapshot_verify_checkout() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join(".workgraph");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("t1", "Producer")));
+        save_graph(&graph, graph_path(&dir)).unwrap();
+        let file = temp_dir.path().join("report.md");
+        std::fs::write(&file, "first draft").unwrap();
+
+        run_add(&dir, "t1", "report.md", true).unwrap();
+        let graph = load_graph(graph_path(&dir)).unwrap();
+        let snapshot = &graph.get_task("t1").unwrap().artifact_snapshots["report.md"];
+        assert_eq!(snapshot.size, 11);
+        assert!(run_verify(&dir, None, false).is_ok());
+
+        // Overwritten by a later task: verify fails, and checkout only
+        // restores it with --force
+        std::fs::write(&file, "rewritten").unwrap();
+        assert!(run_verify(&dir, Some("t1"), true).is_err());
+        run_checkout(&dir, "t1", &[], false).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "rewritten");
+        run_checkout(&dir, "t1", &[], true).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "first draft");
+        assert!(run_verify(&dir, None, false).is_ok());
+
+        // Missing files are restored without --force
+        std::fs::remove_file(&file).unwrap();
+        run_checkout(&dir, "t1", &[], false).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "first draft");
+
+        assert!(run_checkout(&dir, "t1", &["other.md".to_string()], false).is_err());
+        run_remove(&dir, "t1", "report.md").unwrap();
+        let graph = load_graph(graph_path(&dir)).unwrap();
+        assert!(graph.get_task("t1").unwrap().artifact_snapshots.is_empty());
+    }
 }
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use std::path::Path;
 use workgraph::identity;
-use workgraph::parser::{load_graph, save_graph};
+use workgraph::undo::with_graph_changes;
 
 use super::graph_path;
 
@@ -45,12 +45,11 @@ fn run_explicit_assign(dir: &Path, path: &Path, task_id: &str, agent_hash: &str)
         format!("No agent matching '{}'. {}", agent_hash, hint)
     })?;
 
-    let mut graph = load_graph(path).context("Failed to load graph")?;
-
-    let task = graph.get_task_mut_or_err(task_id)?;
-
-    task.agent = Some(agent.id.clone());
-    save_graph(&graph, path).context("Failed to save graph")?;
+    let ((), changes) = with_graph_changes(path, |graph| -> Result<()> {
+        let task = graph.get_task_mut_or_err(task_id)?;
+        task.agent = Some(agent.id.clone());
+        Ok(())
+    })?;
     super::notify_graph_changed(dir);
 
     // Record operation
@@ -60,7 +59,10 @@ fn run_explicit_assign(dir: &Path, path: &Path, task_id: &str, agent_hash: &str)
         "assign",
         Some(task_id),
         None,
-        serde_json::json!({ "agent_hash": agent.id, "role_id": agent.role_id }),
+        workgraph::undo::with_changes(
+            serde_json::json!({ "agent_hash": agent.id, "role_id": agent.role_id }),
+            &changes,
+        ),
         config.log.rotation_threshold,
     );
 
@@ -97,13 +99,10 @@ fn run_explicit_assign(dir: &Path, path: &Path, task_id: &str, agent_hash: &str)
 
 /// Clear the agent assignment from a task.
 fn run_clear(dir: &Path, path: &Path, task_id: &str) -> Result<()> {
-    let mut graph = load_graph(path).context("Failed to load graph")?;
-
-    let task = graph.get_task_mut_or_err(task_id)?;
-
-    let prev_agent = task.agent.clone();
-    task.agent = None;
-    save_graph(&graph, path).context("Failed to save graph")?;
+    let (prev_agent, changes) = with_graph_changes(path, |graph| -> Result<_> {
+        let task = graph.get_task_mut_or_err(task_id)?;
+        Ok(task.agent.take())
+    })?;
     super::notify_graph_changed(dir);
 
     // Record operation
@@ -113,7 +112,10 @@ fn run_clear(dir: &Path, path: &Path, task_id: &str) -> Result<()> {
         "assign",
         Some(task_id),
         None,
-        serde_json::json!({ "action": "clear", "prev_agent": prev_agent }),
+        workgraph::undo::with_changes(
+            serde_json::json!({ "action": "clear", "prev_agent": prev_agent }),
+            &changes,
+        ),
         config.log.rotation_threshold,
     );
 
@@ -149,6 +151,7 @@ mod tests {
     use tempfile::tempdir;
     use workgraph::identity::{Lineage, RewardHistory, SkillRef};
     use workgraph::graph::{Node, Task, WorkGraph};
+    use workgraph::parser::{save_graph, load_graph};
 
     fn make_task(id: &str, title: &str) -> Task {
         Task {
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+use workgraph::format_hours;
+use workgraph::graph::{Status, WorkGraph};
+use workgraph::query::compute_slack;
+
+/// One task whose slack is under the threshold
+#[derive(Debug, Serialize)]
+struct AtRiskTask {
+    id: String,
+    title: String,
+    status: Status,
+    slack_hours: f64,
+    latest_start: DateTime<Utc>,
+    earliest_finish: DateTime<Utc>,
+    deadline: DateTime<Utc>,
+    deadline_task: String,
+}
+
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// Tasks with less than `threshold` hours of slack, least slack first
+fn at_risk_tasks(graph: &WorkGraph, now: DateTime<Utc>, threshold: f64) -> Vec<AtRiskTask> {
+    let slack = compute_slack(graph, now);
+    let mut at_risk: Vec<AtRiskTask> = slack
+        .into_iter()
+        .filter(|(_, s)| s.slack_hours < threshold)
+        .filter_map(|(id, s)| {
+            let task = graph.get_task(&id)?;
+            Some(AtRiskTask {
+                id,
+                title: task.title.clone(),
+                status: task.status,
+                slack_hours: s.slack_hours,
+                latest_start: s.latest_start,
+                earliest_finish: s.earliest_finish,
+                deadline: s.latest_finish,
+                deadline_task: s.deadline_task,
+            })
+        })
+        .collect();
+    at_risk.sort_by(|a, b| {
+        a.slack_hours
+            .partial_cmp(&b.slack_hours)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    at_risk
+}
+
+/// List tasks whose deadline slack is below `within` hours (default 0, i.e.
+/// tasks that will miss a deadline at current estimates), least slack first.
+pub fn run(dir: &Path, within: Option<f64>, json: bool) -> Result<()> {
+    let (graph, _path) = super::load_workgraph(dir)?;
+    let threshold = within.unwrap_or(0.0);
+    let at_risk = at_risk_tasks(&graph, Utc::now(), threshold);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&at_risk)?);
+        return Ok(());
+    }
+
+    if at_risk.is_empty() {
+        if within.is_some() {
+            println!(
+                "No tasks with less than {}h of slack.",
+                format_hours(threshold)
+            );
+        } else {
+            println!("No tasks at risk of missing a deadline.");
+        }
+        return Ok(());
+    }
+
+    println!(
+        "At risk ({} task{} with less than {}h of slack):\n",
+        at_risk.len(),
+        if at_risk.len() == 1 { "" } else { "s" },
+        format_hours(threshold)
+    );
+    let id_width = at_risk.iter().map(|t| t.id.len()).max().unwrap_or(0);
+    for task in &at_risk {
+        let due = if task.deadline_task == task.id {
+            format!("due {}", format_time(&task.deadline))
+        } else {
+            format!(
+                "finish by {} for {}",
+                format_time(&task.deadline),
+                task.deadline_task
+            )
+        };
+        println!(
+            "  {:<width$}  {:>6}h  [{}]  {}, start by {}, earliest finish {}",
+            task.id,
+            format_hours(task.slack_hours),
+            task.status,
+            due,
+            format_time(&task.latest_start),
+            format_time(&task.earliest_finish),
+            width = id_width
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use workgraph::graph::{Node, Task};
+    use workgraph::parser::save_graph;
+
+    fn task_due_in(id: &str, hours: i64) -> Node {
+        Node::Task(Task {
+            id: id.to_string(),
+            title: id.to_string(),
+            deadline: Some((Utc::now() + chrono::Duration::hours(hours)).to_rfc3339()),
+            ..Task::default()
+        })
+    }
+
+    #[test]
+    fn test_at_risk_threshold() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(task_due_in("overdue", -1));
+        graph.add_node(task_due_in("tight", 2));
+        graph.add_node(task_due_in("relaxed", 48));
+        graph.add_node(Node::Task(Task {
+            id: "no-deadline".to_string(),
+            ..Task::default()
+        }));
+
+        let ids = |threshold| -> Vec<String> {
+            at_risk_tasks(&graph, Utc::now(), threshold)
+                .into_iter()
+                .map(|t| t.id)
+                .collect()
+        };
+        assert_eq!(ids(0.0), vec!["overdue"]);
+        assert_eq!(ids(24.0), vec!["overdue", "tight"]);
+
+        let tmp = tempfile::tempdir().unwrap();
+        save_graph(&graph, super::super::graph_path(tmp.path())).unwrap();
+        assert!(run(tmp.path(), None, false).is_ok());
+        assert!(run(tmp.path(), Some(24.0), true).is_ok());
+    }
+}
@@ -97,6 +97,7 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
                 Status::Blocked => "blocked".to_string(),
                 Status::Failed => "FAILED (needs retry!)".to_string(),
                 Status::Abandoned => "abandoned".to_string(),
+                Status::InReview => "in-review (awaiting approval)".to_string(),
             };
             print!("   Status: {}", status_str);
 
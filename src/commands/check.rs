@@ -1,7 +1,7 @@
 use anyhow::Result;
 use serde::Serialize;
 use std::path::Path;
-use workgraph::check::{LoopEdgeIssueKind, check_all};
+use workgraph::check::{DepConditionIssue, DepConditionIssueKind, LoopEdgeIssueKind, check_all};
 
 #[derive(Serialize)]
 struct CheckJsonOutput {
@@ -11,6 +11,8 @@ struct CheckJsonOutput {
     loop_edge_issues: Vec<workgraph::check::LoopEdgeIssue>,
     stale_assignments: Vec<workgraph::check::StaleAssignment>,
     stuck_blocked: Vec<workgraph::check::StuckBlocked>,
+    dep_condition_issues: Vec<workgraph::check::DepConditionIssue>,
+    over_capacity: Vec<workgraph::check::OverCapacity>,
     node_count: usize,
     loop_edge_count: usize,
     warnings: usize,
@@ -21,9 +23,18 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
     let (graph, _path) = super::load_workgraph(dir)?;
     let result = check_all(&graph);
 
-    let warnings =
-        result.cycles.len() + result.stale_assignments.len() + result.stuck_blocked.len();
-    let errors = result.orphan_refs.len() + result.loop_edge_issues.len();
+    let (condition_errors, condition_warnings): (Vec<&DepConditionIssue>, Vec<_>) = result
+        .dep_condition_issues
+        .iter()
+        .partition(|i| i.kind.is_error());
+    let warnings = result.cycles.len()
+        + result.stale_assignments.len()
+        + result.stuck_blocked.len()
+        + condition_warnings.len();
+    let errors = result.orphan_refs.len()
+        + result.loop_edge_issues.len()
+        + condition_errors.len()
+        + result.over_capacity.len();
     let loop_edge_count: usize = graph.tasks().map(|t| t.loops_to.len()).sum();
 
     if json {
@@ -34,6 +45,8 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
             loop_edge_issues: result.loop_edge_issues,
             stale_assignments: result.stale_assignments,
             stuck_blocked: result.stuck_blocked,
+            dep_condition_issues: result.dep_condition_issues,
+            over_capacity: result.over_capacity,
             node_count: graph.len(),
             loop_edge_count,
             warnings,
@@ -75,6 +88,21 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
         }
     }
 
+    // Conditional dependencies that can no longer be met are warnings
+    if !condition_warnings.is_empty() {
+        eprintln!(
+            "Warning: Tasks that should have been skipped (a conditional dependency can no longer be met):"
+        );
+        for issue in &condition_warnings {
+            if let DepConditionIssueKind::Unreachable(status) = &issue.kind {
+                eprintln!(
+                    "  {} ({} on '{}', which is {})",
+                    issue.task_id, issue.condition, issue.blocker, status
+                );
+            }
+        }
+    }
+
     // Orphan references are errors
     if !result.orphan_refs.is_empty() {
         eprintln!("Error: Orphan references:");
@@ -109,6 +137,12 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
                         issue.from, issue.target, guard_task
                     )
                 }
+                LoopEdgeIssueKind::InvalidGuard(problem) => {
+                    format!(
+                        "{} --[loops_to]--> {} (invalid guard: {})",
+                        issue.from, issue.target, problem
+                    )
+                }
                 LoopEdgeIssueKind::SelfLoop => {
                     format!(
                         "{} --[loops_to]--> {} (self-loop: task would immediately re-open on completion)",
@@ -120,6 +154,32 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
         }
     }
 
+    // Misplaced dependency conditions are errors
+    if !condition_errors.is_empty() {
+        eprintln!("Error: Dependency condition issues:");
+        for issue in &condition_errors {
+            let problem = match issue.kind {
+                DepConditionIssueKind::InGroup => "blocker is in a dependency group",
+                _ => "not a blocker",
+            };
+            eprintln!(
+                "  {} --[{}]--> {} ({})",
+                issue.task_id, issue.condition, issue.blocker, problem
+            );
+        }
+    }
+
+    // Requirements no resource can ever satisfy are errors
+    if !result.over_capacity.is_empty() {
+        eprintln!("Error: Resource requirements that exceed capacity (task can never start):");
+        for over in &result.over_capacity {
+            eprintln!(
+                "  {} --[requires]--> {} (needs {}, capacity is {})",
+                over.task_id, over.resource, over.needed, over.available
+            );
+        }
+    }
+
     // Count loop edges for info
     if loop_edge_count > 0 && result.loop_edge_issues.is_empty() {
         println!("Loop edges: {} edge(s), all valid", loop_edge_count);
@@ -85,6 +85,24 @@ pub fn show(dir: &Path, scope: Option<ConfigScope>, json: bool) -> Result<()> {
                 println!("  description = \"{}\"", desc);
             }
         }
+        for (name, spec) in &config.fields {
+            println!();
+            println!("[fields.{}]", name);
+            println!("  type = \"{}\"", spec.kind);
+            if spec.required {
+                println!("  required = true");
+            }
+            if !spec.values.is_empty() {
+                println!("  values = {:?}", spec.values);
+            }
+        }
+        if !config.queries.is_empty() {
+            println!();
+            println!("[queries]");
+            for (name, query) in &config.queries {
+                println!("  {} = {:?}", name, query);
+            }
+        }
     }
 
     Ok(())
@@ -1,8 +1,9 @@
 use anyhow::Result;
 use serde::Serialize;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 use workgraph::graph::Status;
+use workgraph::outputs;
 
 /// Source of context (which dependency produced it)
 #[derive(Debug, Serialize)]
@@ -11,6 +12,8 @@ struct ContextSource {
     task_title: String,
     status: Status,
     artifacts: Vec<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    outputs: BTreeMap<String, serde_json::Value>,
 }
 
 /// Context available for a task
@@ -21,6 +24,9 @@ struct TaskContext {
     declared_inputs: Vec<String>,
     available_context: Vec<ContextSource>,
     missing_inputs: Vec<String>,
+    /// `{{deps.<task>.<key>}}` references in the description with no value yet
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unresolved_outputs: Vec<String>,
 }
 
 /// Show available context for a task from its dependencies
@@ -38,12 +44,13 @@ pub fn run(dir: &Path, task_id: &str, json: bool) -> Result<()> {
             for artifact in &dep_task.artifacts {
                 all_artifacts.insert(artifact.clone());
             }
-            if !dep_task.artifacts.is_empty() {
+            if !dep_task.artifacts.is_empty() || !dep_task.outputs.is_empty() {
                 available_context.push(ContextSource {
                     task_id: dep_task.id.clone(),
                     task_title: dep_task.title.clone(),
                     status: dep_task.status,
                     artifacts: dep_task.artifacts.clone(),
+                    outputs: dep_task.outputs.clone(),
                 });
             }
         }
@@ -57,12 +64,28 @@ pub fn run(dir: &Path, task_id: &str, json: bool) -> Result<()> {
         .cloned()
         .collect();
 
+    let dep_outputs = outputs::dep_outputs(&graph, task);
+    let unresolved_outputs: Vec<String> = task
+        .description
+        .as_deref()
+        .map(outputs::references)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|reference| {
+            let (dep, key) = reference.split_once('.').unwrap_or_default();
+            !dep_outputs
+                .get(dep)
+                .is_some_and(|values| values.contains_key(key))
+        })
+        .collect();
+
     let context = TaskContext {
         task_id: task.id.clone(),
         task_title: task.title.clone(),
         declared_inputs: task.inputs.clone(),
         available_context,
         missing_inputs,
+        unresolved_outputs,
     };
 
     if json {
@@ -96,9 +119,12 @@ pub fn run(dir: &Path, task_id: &str, json: bool) -> Result<()> {
                 for artifact in &source.artifacts {
                     println!("    - {}", artifact);
                 }
+                for (key, value) in &source.outputs {
+                    println!("    {} = {}", key, outputs::render(value));
+                }
             }
         } else {
-            println!("No artifacts available from dependencies yet.");
+            println!("No artifacts or outputs available from dependencies yet.");
         }
 
         if !context.missing_inputs.is_empty() {
@@ -108,6 +134,14 @@ pub fn run(dir: &Path, task_id: &str, json: bool) -> Result<()> {
                 println!("  {}", input);
             }
         }
+
+        if !context.unresolved_outputs.is_empty() {
+            println!();
+            println!("Unresolved output references (no value yet):");
+            for reference in &context.unresolved_outputs {
+                println!("  {{{{deps.{}}}}}", reference);
+            }
+        }
     }
 
     Ok(())
@@ -585,4 +619,31 @@ mod tests {
         let result = run_dependents(temp_dir.path(), "t1", false);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_context_shows_dep_outputs() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("graph.jsonl");
+
+        let mut graph = WorkGraph::new();
+
+        let mut t1 = make_task("t1", "Producer");
+        t1.status = Status::Done;
+        t1.outputs
+            .insert("version".to_string(), serde_json::json!("1.2.0"));
+
+        let mut t2 = make_task("t2", "Consumer");
+        t2.blocked_by = vec!["t1".to_string()];
+        t2.description = Some("Ship {{deps.t1.version}} at {{deps.t1.url}}".to_string());
+
+        graph.add_node(Node::Task(t1));
+        graph.add_node(Node::Task(t2));
+        save_graph(&graph, &path).unwrap();
+
+        // The producer is listed for its outputs alone; deps.t1.url is unresolved
+        let result = run(temp_dir.path(), "t2", false);
+        assert!(result.is_ok());
+        let result = run(temp_dir.path(), "t2", true);
+        assert!(result.is_ok());
+    }
 }
@@ -80,11 +80,13 @@ pub fn get_coordination_status(graph: &workgraph::graph::WorkGraph) -> Coordinat
         .filter(|t| {
             t.status == Status::Open
                 && !t.blocked_by.is_empty()
-                && t.blocked_by.iter().any(|blocker_id| {
-                    graph
-                        .get_task(blocker_id)
-                        .map(|b| !b.status.is_terminal())
-                        .unwrap_or(true) // Missing blocker = unresolved (consistent with query.rs)
+                && !workgraph::query::dependencies_met(t, |blocker_id| {
+                    // Missing blocker = unresolved (consistent with query.rs)
+                    Some(
+                        graph
+                            .get_task(blocker_id)
+                            .map_or(Status::Open, |b| b.status),
+                    )
                 })
         })
         .map(BlockedTaskSummary::from_task)
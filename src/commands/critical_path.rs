@@ -182,6 +182,7 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
                     Status::Done => "done",
                     Status::Failed => "failed",
                     Status::Abandoned => "abandoned",
+                    Status::InReview => "in-review",
                 };
 
                 let hours_str = task
@@ -232,12 +233,18 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
 }
 
 /// Build forward index: task_id -> tasks that it blocks (among active non-cycle tasks)
+///
+/// Members of a dependency group that the task won't wait for (those expected
+/// to finish after the quorum is reached) are left out.
 fn build_forward_index<'a>(
     graph: &'a WorkGraph,
     active_ids: &HashSet<&str>,
     cycle_nodes: &HashSet<&str>,
 ) -> HashMap<&'a str, Vec<&'a str>> {
     let mut index: HashMap<&str, Vec<&str>> = HashMap::new();
+    let finish = workgraph::query::finish_hours(graph, |t| {
+        active_ids.contains(t.id.as_str()) && !cycle_nodes.contains(t.id.as_str())
+    });
 
     for task in graph.tasks() {
         if !active_ids.contains(task.id.as_str()) || cycle_nodes.contains(task.id.as_str()) {
@@ -245,14 +252,12 @@ fn build_forward_index<'a>(
         }
 
         // For each blocker, add this task to its forward list
-        for blocker_id in &task.blocked_by {
-            if active_ids.contains(blocker_id.as_str())
-                && !cycle_nodes.contains(blocker_id.as_str())
-            {
-                index
-                    .entry(blocker_id.as_str())
-                    .or_default()
-                    .push(task.id.as_str());
+        let gating = workgraph::query::gating_blockers(task, |blocker| {
+            finish.get(blocker).copied().unwrap_or(0.0)
+        });
+        for (blocker_id, _) in gating {
+            if active_ids.contains(blocker_id) && !cycle_nodes.contains(blocker_id) {
+                index.entry(blocker_id).or_default().push(task.id.as_str());
             }
         }
     }
@@ -375,7 +380,7 @@ fn find_cycles_dfs(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use workgraph::graph::{Estimate, Node, Task};
+    use workgraph::graph::{Estimate, Node, Priority, Task};
 
     fn make_task(id: &str, title: &str) -> Task {
         Task {
@@ -420,6 +425,21 @@ mod tests {
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         }
     }
 
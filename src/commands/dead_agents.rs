@@ -12,12 +12,12 @@
 //!   wg dead-agents --purge           # Remove dead/done/failed entries from registry
 //!   wg dead-agents --purge --delete-dirs  # Also delete agent work directories
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::Utc;
 use std::path::Path;
 use workgraph::config::Config;
 use workgraph::graph::{LogEntry, Status};
-use workgraph::parser::{load_graph, save_graph};
+use workgraph::parser::with_graph_mut;
 use workgraph::service::{AgentRegistry, AgentStatus};
 
 use super::graph_path;
@@ -130,44 +130,42 @@ pub fn run_cleanup(
     locked_registry.save_ref()?;
 
     // Now unclaim tasks from dead agents
-    let mut graph = load_graph(&path).context("Failed to load graph")?;
-    let mut tasks_unclaimed = Vec::new();
-    let mut errors = Vec::new();
-
-    for dead_agent in &dead_info {
-        if let Some(task) = graph.get_task_mut(&dead_agent.task_id) {
-            // Only unclaim if task is still in progress
-            if task.status == Status::InProgress {
-                task.status = Status::Open;
-                task.assigned = None;
-                // Don't clear started_at - keep the history
-
-                // Add log entry
-                task.log.push(LogEntry {
-                    timestamp: Utc::now().to_rfc3339(),
-                    actor: None,
-                    message: format!(
-                        "Task unclaimed: agent '{}' (PID {}) detected as dead (no heartbeat for {} seconds)",
-                        dead_agent.agent_id,
-                        dead_agent.pid,
-                        dead_agent.seconds_since_heartbeat
-                    ),
-                });
-
-                tasks_unclaimed.push(dead_agent.task_id.clone());
+    let (tasks_unclaimed, errors) = with_graph_mut(&path, |graph| -> Result<_> {
+        let mut tasks_unclaimed = Vec::new();
+        let mut errors = Vec::new();
+
+        for dead_agent in &dead_info {
+            if let Some(task) = graph.get_task_mut(&dead_agent.task_id) {
+                // Only unclaim if task is still in progress
+                if task.status == Status::InProgress {
+                    task.status = Status::Open;
+                    task.assigned = None;
+                    // Don't clear started_at - keep the history
+
+                    // Add log entry
+                    task.log.push(LogEntry {
+                        timestamp: Utc::now().to_rfc3339(),
+                        actor: None,
+                        message: format!(
+                            "Task unclaimed: agent '{}' (PID {}) detected as dead (no heartbeat for {} seconds)",
+                            dead_agent.agent_id,
+                            dead_agent.pid,
+                            dead_agent.seconds_since_heartbeat
+                        ),
+                    });
+
+                    tasks_unclaimed.push(dead_agent.task_id.clone());
+                }
+            } else {
+                errors.push(format!(
+                    "Task '{}' not found for dead agent '{}'",
+                    dead_agent.task_id, dead_agent.agent_id
+                ));
             }
-        } else {
-            errors.push(format!(
-                "Task '{}' not found for dead agent '{}'",
-                dead_agent.task_id, dead_agent.agent_id
-            ));
         }
-    }
 
-    // Save graph
-    if !tasks_unclaimed.is_empty() {
-        save_graph(&graph, &path).context("Failed to save graph")?;
-    }
+        Ok((tasks_unclaimed, errors))
+    })?;
 
     let result = DetectionResult {
         dead_agents: dead_info,
@@ -442,7 +440,7 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
     use workgraph::graph::{Node, Task, WorkGraph};
-    use workgraph::parser::save_graph;
+    use workgraph::parser::{load_graph, save_graph};
 
     fn make_task(id: &str, title: &str, status: Status) -> Task {
         Task {
@@ -1,91 +1,155 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::Utc;
 use std::path::Path;
+use workgraph::completion::{Completed, check_completable, complete, read_guards};
+use workgraph::graph::{LogEntry, Status, Task};
 use workgraph::identity::capture_task_output;
-use workgraph::graph::{LogEntry, Status, reward_loop_edges};
-use workgraph::parser::save_graph;
-use workgraph::query;
+use workgraph::loop_guard::Readings;
+use workgraph::{query, verify};
 
 #[cfg(test)]
 use super::graph_path;
 #[cfg(test)]
 use workgraph::parser::load_graph;
 
-pub fn run(dir: &Path, id: &str, converged: bool) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
-
-    let task = graph.get_task_mut_or_err(id)?;
-
-    if task.status == Status::Done {
-        println!("Task '{}' is already done", id);
-        return Ok(());
-    }
+pub fn run(
+    dir: &Path,
+    id: &str,
+    converged: bool,
+    skip_verify: bool,
+    actor: Option<&str>,
+) -> Result<()> {
+    // Run verification checks and read loop guards before taking the graph
+    // lock, since they can take as long as a build. A blocked task fails
+    // below without running them.
+    let (skipped_verify, guards) = {
+        let (graph, _path) = super::load_workgraph(dir)?;
+        let task = graph.get_task_or_err(id)?;
+        let checks = match task.verify {
+            Some(ref verify) if !matches!(task.status, Status::Done | Status::InReview) => {
+                verify::parse(verify)?
+            }
+            _ => vec![],
+        };
+        let blocked = !query::blocked_by(&graph, id).is_empty();
+        let skipped_verify = if checks.is_empty() || blocked {
+            false
+        } else if skip_verify {
+            if run_by_agent() {
+                anyhow::bail!(
+                    "Agents can't skip verification of '{}'. Fix the failing checks and run 'wg done' again",
+                    id
+                );
+            }
+            true
+        } else {
+            let failures = verify::run(task, dir)?;
+            if !failures.is_empty() {
+                return verification_failed(dir, id, &failures, actor);
+            }
+            false
+        };
+        let guards = if blocked || converged {
+            Readings::default()
+        } else {
+            read_guards(&graph, dir, id)
+        };
+        (skipped_verify, guards)
+    };
 
-    // Check for unresolved blockers
-    let blockers = query::blocked_by(&graph, id);
-    if !blockers.is_empty() {
-        let blocker_list: Vec<String> = blockers
-            .iter()
-            .map(|t| format!("  - {} ({}): {:?}", t.id, t.title, t.status))
-            .collect();
-        anyhow::bail!(
-            "Cannot mark '{}' as done: blocked by {} unresolved task(s):\n{}",
-            id,
-            blockers.len(),
-            blocker_list.join("\n")
-        );
-    }
+    let (done, changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
 
-    // Re-acquire mutable reference after immutable borrow
-    let task = graph
-        .get_task_mut(id)
-        .ok_or_else(|| anyhow::anyhow!("Task '{}' disappeared from graph", id))?;
+        if task.status == Status::Done {
+            return Ok(None);
+        }
+        if task.status == Status::InReview {
+            anyhow::bail!(
+                "Task '{}' is awaiting review. Use 'wg approve {}' or 'wg reject {} --reason ...'",
+                id,
+                id,
+                id
+            );
+        }
 
-    task.status = Status::Done;
-    task.completed_at = Some(Utc::now().to_rfc3339());
+        check_completable(graph, id)?;
 
-    if converged && !task.tags.contains(&"converged".to_string()) {
-        task.tags.push("converged".to_string());
-    }
+        let task = graph.get_task_mut_or_err(id)?;
+        if converged && !task.tags.contains(&"converged".to_string()) {
+            task.tags.push("converged".to_string());
+        }
+        if skipped_verify {
+            task.log.push(LogEntry {
+                timestamp: Utc::now().to_rfc3339(),
+                actor: actor.map(String::from),
+                message: "Verification checks skipped (--skip-verify)".to_string(),
+            });
+        }
 
-    task.log.push(LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: task.assigned.clone(),
-        message: if converged {
-            "Task marked as done (converged)".to_string()
-        } else {
-            "Task marked as done".to_string()
-        },
-    });
+        // An agent's work on a task with verify criteria waits for a human to
+        // sign off; dependents stay blocked until 'wg approve'
+        if task.verify.is_some() && run_by_agent() {
+            task.status = Status::InReview;
+            task.log.push(LogEntry {
+                timestamp: Utc::now().to_rfc3339(),
+                actor: task.assigned.clone(),
+                message: "Task submitted for review".to_string(),
+            });
+            return Ok(Some((task.clone(), None)));
+        }
 
-    // Reward loop edges: re-activate upstream tasks if conditions are met
-    let id_owned = id.to_string();
-    let reactivated = reward_loop_edges(&mut graph, &id_owned);
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: task.assigned.clone(),
+            message: if converged {
+                "Task marked as done (converged)".to_string()
+            } else {
+                "Task marked as done".to_string()
+            },
+        });
+        let completed = complete(graph, dir, id, &guards)?;
+        Ok(Some((graph.get_task_or_err(id)?.clone(), Some(completed))))
+    })?;
+
+    let Some((task, completed)) = done else {
+        println!("Task '{}' is already done", id);
+        return Ok(());
+    };
 
-    save_graph(&graph, &path).context("Failed to save graph")?;
     super::notify_graph_changed(dir);
 
     // Record operation
     let config = workgraph::config::Config::load_or_default(dir);
+    let op = if completed.is_some() { "done" } else { "submit" };
     let _ = workgraph::provenance::record(
         dir,
-        "done",
+        op,
         Some(id),
-        None,
-        serde_json::Value::Null,
+        actor,
+        workgraph::undo::with_changes(
+            if skipped_verify {
+                serde_json::json!({ "skip_verify": true })
+            } else {
+                serde_json::Value::Null
+            },
+            &changes,
+        ),
         config.log.rotation_threshold,
     );
 
-    println!("Marked '{}' as done", id);
-
-    for task_id in &reactivated {
-        println!("  Loop: re-activated '{}'", task_id);
+    match completed {
+        Some(completed) => {
+            println!("Marked '{}' as done", id);
+            print_completed(&completed, &task);
+        }
+        None => println!(
+            "Submitted '{}' for review. Dependents stay blocked until 'wg approve {}'",
+            id, id
+        ),
     }
 
     // Archive agent conversation (prompt + output) for provenance
-    if let Some(task) = graph.get_task(id)
-        && let Some(ref agent_id) = task.assigned
-    {
+    if let Some(ref agent_id) = task.assigned {
         match super::log::archive_agent(dir, id, agent_id) {
             Ok(archive_dir) => {
                 eprintln!("Agent archived to {}", archive_dir.display());
@@ -100,24 +164,103 @@ pub fn run(dir: &Path, id: &str, converged: bool) -> Result<()> {
     // When auto_reward is enabled, the coordinator creates a reward task
     // in the graph that becomes ready once this task is done; the captured output
     // feeds that evaluator.
-    if let Some(task) = graph.get_task(id) {
-        match capture_task_output(dir, task) {
-            Ok(output_dir) => {
-                eprintln!("Output captured to {}", output_dir.display());
-            }
-            Err(e) => {
-                eprintln!("Warning: output capture failed: {}", e);
-            }
+    match capture_task_output(dir, &task) {
+        Ok(output_dir) => {
+            eprintln!("Output captured to {}", output_dir.display());
+        }
+        Err(e) => {
+            eprintln!("Warning: output capture failed: {}", e);
         }
     }
 
     Ok(())
 }
 
+/// Print what finishing `task` set off elsewhere in the graph
+pub(super) fn print_completed(completed: &Completed, task: &Task) {
+    if let (Some(children), Some(fan_out)) = (&completed.fanned_out, &task.fan_out) {
+        println!(
+            "  Fanned out into {} task(s), joined by '{}'",
+            children.len(),
+            fan_out.join
+        );
+    }
+
+    for task_id in &completed.reactivated {
+        println!("  Loop: re-activated '{}'", task_id);
+    }
+    for parent_id in &completed.completed_parents {
+        println!("  Parent: auto-completed '{}'", parent_id);
+    }
+    for sibling_id in &completed.abandoned {
+        println!("  Quorum reached: abandoned '{}'", sibling_id);
+    }
+    for skipped_id in &completed.skipped {
+        println!(
+            "  Skipped '{}' (its dependency conditions can't be met)",
+            skipped_id
+        );
+    }
+}
+
+/// Whether this process was started for a spawned agent
+pub(super) fn run_by_agent() -> bool {
+    std::env::var_os("WG_AGENT_ID").is_some()
+}
+
+/// Print each failed verification check with the tail of its output
+pub(super) fn print_failures(failures: &[verify::Failure]) {
+    for failure in failures {
+        eprintln!("  ✗ {}: {}", failure.check, failure.reason);
+        for line in failure.output.lines() {
+            eprintln!("      {}", line);
+        }
+    }
+}
+
+/// Log failed verification checks on the task, which stays open or
+/// in-progress, and fail with a report of them
+fn verification_failed(
+    dir: &Path,
+    id: &str,
+    failures: &[verify::Failure],
+    actor: Option<&str>,
+) -> Result<()> {
+    let summary = verify::summary(failures);
+    let ((), changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: actor.map(String::from).or_else(|| task.assigned.clone()),
+            message: format!("Verification failed: {}", summary),
+        });
+        Ok(())
+    })?;
+    super::notify_graph_changed(dir);
+
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = workgraph::provenance::record(
+        dir,
+        "verify_failed",
+        Some(id),
+        actor,
+        workgraph::undo::with_changes(serde_json::json!({ "failed": summary }), &changes),
+        config.log.rotation_threshold,
+    );
+
+    print_failures(failures);
+    anyhow::bail!(
+        "Cannot mark '{}' as done: {} verification check(s) failed. Fix them and run 'wg done' again",
+        id,
+        failures.len()
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::tempdir;
+    use workgraph::fan_out;
     use workgraph::graph::LoopEdge;
     use workgraph::test_helpers::{make_task_with_status as make_task, setup_workgraph};
 
@@ -127,7 +270,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Open)]);
 
-        let result = run(dir_path, "t1", false);
+        let result = run(dir_path, "t1", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -145,7 +288,7 @@ mod tests {
             vec![make_task("t1", "Test task", Status::InProgress)],
         );
 
-        let result = run(dir_path, "t1", false);
+        let result = run(dir_path, "t1", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -161,7 +304,7 @@ mod tests {
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Done)]);
 
         // Should return Ok (idempotent) rather than error
-        let result = run(dir_path, "t1", false);
+        let result = run(dir_path, "t1", false, false, None);
         assert!(result.is_ok());
     }
 
@@ -176,13 +319,48 @@ mod tests {
 
         setup_workgraph(dir_path, vec![blocker, blocked]);
 
-        let result = run(dir_path, "blocked", false);
+        let result = run(dir_path, "blocked", false, false, None);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.to_string().contains("blocked by"));
         assert!(err.to_string().contains("unresolved"));
     }
 
+    #[test]
+    fn test_done_parent_with_open_children_fails() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let parent = make_task("epic", "Epic", Status::Open);
+        let mut child = make_task("child", "Child", Status::InProgress);
+        child.parent = Some("epic".to_string());
+        setup_workgraph(dir_path, vec![parent, child]);
+
+        let err = run(dir_path, "epic", false, false, None).unwrap_err();
+        assert!(err.to_string().contains("child task(s) not finished"));
+    }
+
+    #[test]
+    fn test_done_last_child_auto_completes_parent() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let mut parent = make_task("epic", "Epic", Status::Open);
+        parent.auto_complete = true;
+        let mut a = make_task("a", "A", Status::Done);
+        a.parent = Some("epic".to_string());
+        let mut b = make_task("b", "B", Status::InProgress);
+        b.parent = Some("epic".to_string());
+        setup_workgraph(dir_path, vec![parent, a, b]);
+
+        run(dir_path, "b", false, false, None).unwrap();
+
+        let graph = load_graph(graph_path(dir_path)).unwrap();
+        let epic = graph.get_task("epic").unwrap();
+        assert_eq!(epic.status, Status::Done);
+        assert!(epic.completed_at.is_some());
+    }
+
     #[test]
     fn test_done_with_resolved_blockers_succeeds() {
         let dir = tempdir().unwrap();
@@ -194,7 +372,7 @@ mod tests {
 
         setup_workgraph(dir_path, vec![blocker, blocked]);
 
-        let result = run(dir_path, "blocked", false);
+        let result = run(dir_path, "blocked", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -215,7 +393,7 @@ mod tests {
 
         setup_workgraph(dir_path, vec![blocker, blocked]);
 
-        let result = run(dir_path, "blocked", false);
+        let result = run(dir_path, "blocked", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -236,7 +414,7 @@ mod tests {
 
         setup_workgraph(dir_path, vec![blocker, blocked]);
 
-        let result = run(dir_path, "blocked", false);
+        let result = run(dir_path, "blocked", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -256,7 +434,7 @@ mod tests {
         setup_workgraph(dir_path, vec![task]);
 
         // Verified tasks can now use wg done directly (submit is deprecated)
-        let result = run(dir_path, "t1", false);
+        let result = run(dir_path, "t1", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -265,6 +443,36 @@ mod tests {
         assert_eq!(task.status, Status::Done);
     }
 
+    #[test]
+    fn test_done_runs_verify_checks() {
+        let tmp = tempdir().unwrap();
+        let dir_path = tmp.path().join(".workgraph");
+        let mut task = make_task("t1", "Checked task", Status::InProgress);
+        task.verify = Some("Output looks right\nexists:out.txt\nshell:grep -q ok out.txt".to_string());
+        let mut other = make_task("t2", "Skipped task", Status::InProgress);
+        other.verify = Some("shell:false".to_string());
+        setup_workgraph(&dir_path, vec![task, other]);
+
+        let err = run(&dir_path, "t1", false, false, None).unwrap_err();
+        assert!(err.to_string().contains("2 verification check(s) failed"));
+        let graph = load_graph(graph_path(&dir_path)).unwrap();
+        let task = graph.get_task("t1").unwrap();
+        assert_eq!(task.status, Status::InProgress);
+        assert!(task.log.last().unwrap().message.starts_with("Verification failed: exists:out.txt"));
+
+        std::fs::write(tmp.path().join("out.txt"), "ok").unwrap();
+        run(&dir_path, "t1", false, false, None).unwrap();
+        let graph = load_graph(graph_path(&dir_path)).unwrap();
+        assert_eq!(graph.get_task("t1").unwrap().status, Status::Done);
+
+        run(&dir_path, "t2", false, true, Some("erik")).unwrap();
+        let graph = load_graph(graph_path(&dir_path)).unwrap();
+        let task = graph.get_task("t2").unwrap();
+        assert_eq!(task.status, Status::Done);
+        assert!(task.log.iter().any(|e| e.actor.as_deref() == Some("erik")
+            && e.message.contains("--skip-verify")));
+    }
+
     #[test]
     fn test_done_sets_completed_at_timestamp() {
         let dir = tempdir().unwrap();
@@ -272,7 +480,7 @@ mod tests {
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Open)]);
 
         let before = Utc::now();
-        let result = run(dir_path, "t1", false);
+        let result = run(dir_path, "t1", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -295,7 +503,7 @@ mod tests {
         task.assigned = Some("agent-1".to_string());
         setup_workgraph(dir_path, vec![task]);
 
-        let result = run(dir_path, "t1", false);
+        let result = run(dir_path, "t1", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -327,7 +535,7 @@ mod tests {
 
         setup_workgraph(dir_path, vec![source, target]);
 
-        let result = run(dir_path, "source", false);
+        let result = run(dir_path, "source", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -350,7 +558,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![]);
 
-        let result = run(dir_path, "nonexistent", false);
+        let result = run(dir_path, "nonexistent", false, false, None);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.to_string().contains("not found"));
@@ -362,7 +570,7 @@ mod tests {
         let dir_path = dir.path();
         // Don't initialize workgraph
 
-        let result = run(dir_path, "t1", false);
+        let result = run(dir_path, "t1", false, false, None);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.to_string().contains("not initialized"));
@@ -374,7 +582,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Open)]);
 
-        let result = run(dir_path, "t1", false);
+        let result = run(dir_path, "t1", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -404,7 +612,7 @@ mod tests {
 
         setup_workgraph(dir_path, vec![source, target]);
 
-        let result = run(dir_path, "source", false);
+        let result = run(dir_path, "source", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -445,7 +653,7 @@ mod tests {
 
         setup_workgraph(dir_path, vec![task_a, task_b, task_c]);
 
-        let result = run(dir_path, "c", false);
+        let result = run(dir_path, "c", false, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -466,6 +674,29 @@ mod tests {
         assert_eq!(c.loop_iteration, 1);
     }
 
+    #[test]
+    fn test_done_fires_shell_guarded_loop() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let mut source = make_task("source", "Source task", Status::InProgress);
+        source.loops_to = vec![LoopEdge {
+            target: "source".to_string(),
+            guard: Some("shell:false".parse().unwrap()),
+            max_iterations: 3,
+            delay: None,
+        }];
+        setup_workgraph(dir_path, vec![source]);
+
+        // The guard is read before the graph lock and applied under it
+        run(dir_path, "source", false, false, None).unwrap();
+
+        let graph = load_graph(graph_path(dir_path)).unwrap();
+        let source = graph.get_task("source").unwrap();
+        assert_eq!(source.status, Status::Open);
+        assert_eq!(source.loop_iteration, 1);
+    }
+
     #[test]
     fn test_done_converged_adds_tag_and_stops_loop() {
         let dir = tempdir().unwrap();
@@ -484,7 +715,7 @@ mod tests {
 
         setup_workgraph(dir_path, vec![source, target]);
 
-        let result = run(dir_path, "source", true);
+        let result = run(dir_path, "source", true, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -509,7 +740,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Open)]);
 
-        let result = run(dir_path, "t1", true);
+        let result = run(dir_path, "t1", true, false, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -519,4 +750,37 @@ mod tests {
         let last_log = task.log.last().unwrap();
         assert_eq!(last_log.message, "Task marked as done (converged)");
     }
+
+    #[test]
+    fn test_done_fans_out() {
+        let tmp = tempdir().unwrap();
+        let dir_path = tmp.path().join(".workgraph");
+        let mut list = make_task("list", "List files", Status::InProgress);
+        list.fan_out = Some(fan_out::FanOut::new(
+            "list",
+            "Review {{input.item}}",
+            None,
+            None,
+            "list-join",
+        ));
+        list.blocks = vec!["list-join".to_string()];
+        let mut join = make_task("list-join", "Join", Status::Open);
+        join.blocked_by = vec!["list".to_string()];
+        setup_workgraph(&dir_path, vec![list, join]);
+
+        // No items yet: the task stays unfinished
+        assert!(run(&dir_path, "list", false, false, None).is_err());
+
+        std::fs::write(tmp.path().join("files.json"), r#"["a.rs", "b.rs"]"#).unwrap();
+        super::super::artifact::run_add(&dir_path, "list", "files.json", false).unwrap();
+        run(&dir_path, "list", false, false, None).unwrap();
+
+        let graph = load_graph(graph_path(&dir_path)).unwrap();
+        assert_eq!(graph.get_task("list").unwrap().status, Status::Done);
+        assert_eq!(graph.get_task("list-1").unwrap().title, "Review a.rs");
+        assert_eq!(
+            graph.get_task("list-join").unwrap().blocked_by,
+            vec!["list", "list-1", "list-2"]
+        );
+    }
 }
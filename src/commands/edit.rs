@@ -1,11 +1,9 @@
 //! Edit command for modifying existing tasks
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use std::path::Path;
-use workgraph::graph::{LoopEdge, parse_delay};
-use workgraph::parser::{load_graph, save_graph};
-
-use super::graph_path;
+use workgraph::graph::{LoopEdge, Priority, parse_delay, parse_resource_requirement};
+use workgraph::retry::RetryPolicy;
 
 /// Edit a task's fields
 #[allow(clippy::too_many_arguments)]
@@ -27,216 +25,433 @@ pub fn run(
     loop_delay: Option<&str>,
     remove_loops_to: Option<&str>,
     loop_iteration: Option<u32>,
+    priority: Option<&str>,
+    value: Option<f64>,
+    add_requires: &[String],
+    remove_requires: &[String],
+    parent: Option<&str>,
+    no_parent: bool,
+    auto_complete: Option<bool>,
+    set_field: &[String],
+    remove_field: &[String],
+    deadline: Option<&str>,
+    no_deadline: bool,
+    retry_policy: Option<&str>,
+    no_retry_policy: bool,
 ) -> Result<()> {
-    let path = graph_path(dir);
-
-    if !path.exists() {
-        anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
-    }
-
-    // Load the graph
-    let mut graph = load_graph(&path).context("Failed to load graph")?;
-
-    // Validate task exists
-    graph.get_task_or_err(task_id)?;
-
-    // Validate self-blocking
-    for dep in add_blocked_by {
-        if dep == task_id {
-            anyhow::bail!("Task '{}' cannot block itself", task_id);
+    let config = workgraph::config::Config::load_or_default(dir);
+    let set_field = workgraph::fields::parse_all(&config.fields, set_field)?;
+    let deadline = deadline
+        .map(|d| workgraph::history::parse_timestamp(d).map(|t| t.to_rfc3339()))
+        .transpose()?;
+    let retry_policy: Option<RetryPolicy> = retry_policy.map(str::parse).transpose()?;
+    for key in remove_field {
+        if config.fields.get(key).is_some_and(|spec| spec.required) {
+            anyhow::bail!("Field '{}' is required and cannot be removed", key);
         }
     }
 
-    // Validate self-loop
-    if let Some(target) = add_loops_to
-        && target == task_id
-    {
-        anyhow::bail!("Task '{}' cannot loop to itself", task_id);
-    }
+    // Validate and apply the edits under the graph lock
+    let ((changed, field_changes), changes) = super::with_workgraph_changes(dir, |graph| {
+        // Validate task exists
+        graph.get_task_or_err(task_id)?;
 
-    let mut changed = false;
-    let mut field_changes: Vec<serde_json::Value> = Vec::new();
+        // Validate self-blocking
+        for dep in add_blocked_by {
+            if dep == task_id {
+                anyhow::bail!("Task '{}' cannot block itself", task_id);
+            }
+        }
 
-    // Modify the task in a block so the mutable borrow is released afterwards
-    {
-        let task = graph.get_task_mut_or_err(task_id)?;
+        // Validate self-loop
+        if let Some(target) = add_loops_to
+            && target == task_id
+        {
+            anyhow::bail!("Task '{}' cannot loop to itself", task_id);
+        }
 
-        // Update title
-        if let Some(new_title) = title {
-            let old = task.title.clone();
-            task.title = new_title.to_string();
-            field_changes.push(serde_json::json!({"field": "title", "old": old, "new": new_title}));
-            println!("Updated title: {}", new_title);
-            changed = true;
+        let priority: Option<Priority> = priority.map(str::parse).transpose()?;
+        let add_requires = add_requires
+            .iter()
+            .map(|r| parse_resource_requirement(r))
+            .collect::<Result<Vec<_>>>()?;
+        for (resource_id, _) in &add_requires {
+            if graph.get_resource(resource_id).is_none() {
+                eprintln!(
+                    "Warning: resource '{}' does not exist in the graph",
+                    resource_id
+                );
+            }
         }
 
-        // Update description
-        if let Some(new_description) = description {
-            let old = task.description.clone();
-            task.description = Some(new_description.to_string());
-            field_changes.push(serde_json::json!({"field": "description", "old": old, "new": new_description}));
-            println!("Updated description");
-            changed = true;
+        // Validate the new parent: it must exist and not be this task or below it
+        if let Some(parent_id) = parent {
+            graph.get_task_or_err(parent_id)?;
+            if graph.would_create_parent_cycle(task_id, parent_id) {
+                anyhow::bail!(
+                    "Cannot make '{}' the parent of '{}': it would create a parent cycle",
+                    parent_id,
+                    task_id
+                );
+            }
         }
 
-        // Add blocked_by dependencies
-        for dep in add_blocked_by {
-            if !task.blocked_by.contains(dep) {
-                task.blocked_by.push(dep.clone());
-                println!("Added blocked_by: {}", dep);
+        let mut changed = false;
+        let mut field_changes: Vec<serde_json::Value> = Vec::new();
+
+        // Modify the task in a block so the mutable borrow is released afterwards
+        {
+            let task = graph.get_task_mut_or_err(task_id)?;
+
+            // Update title
+            if let Some(new_title) = title {
+                let old = task.title.clone();
+                task.title = new_title.to_string();
+                field_changes
+                    .push(serde_json::json!({"field": "title", "old": old, "new": new_title}));
+                println!("Updated title: {}", new_title);
                 changed = true;
-            } else {
-                println!("Already blocked by: {}", dep);
             }
-        }
 
-        // Remove blocked_by dependencies
-        for dep in remove_blocked_by {
-            if let Some(pos) = task.blocked_by.iter().position(|x| x == dep) {
-                task.blocked_by.remove(pos);
-                println!("Removed blocked_by: {}", dep);
+            // Update description
+            if let Some(new_description) = description {
+                let old = task.description.clone();
+                task.description = Some(new_description.to_string());
+                field_changes.push(
+                    serde_json::json!({"field": "description", "old": old, "new": new_description}),
+                );
+                println!("Updated description");
                 changed = true;
-            } else {
-                println!("Not blocked by: {}", dep);
             }
-        }
 
-        // Add tags
-        for tag in add_tag {
-            if !task.tags.contains(tag) {
-                task.tags.push(tag.clone());
-                println!("Added tag: {}", tag);
+            // Add blocked_by dependencies
+            for dep in add_blocked_by {
+                if !task.blocked_by.contains(dep) {
+                    task.blocked_by.push(dep.clone());
+                    println!("Added blocked_by: {}", dep);
+                    changed = true;
+                } else {
+                    println!("Already blocked by: {}", dep);
+                }
+            }
+
+            // Remove blocked_by dependencies
+            for dep in remove_blocked_by {
+                if let Some(pos) = task.blocked_by.iter().position(|x| x == dep) {
+                    task.blocked_by.remove(pos);
+                    task.dep_conditions.remove(dep);
+                    for group in &mut task.dep_groups {
+                        group.tasks.retain(|t| t != dep);
+                    }
+                    task.dep_groups.retain(|g| !g.tasks.is_empty());
+                    println!("Removed blocked_by: {}", dep);
+                    changed = true;
+                } else {
+                    println!("Not blocked by: {}", dep);
+                }
+            }
+
+            // Add tags
+            for tag in add_tag {
+                if !task.tags.contains(tag) {
+                    task.tags.push(tag.clone());
+                    println!("Added tag: {}", tag);
+                    changed = true;
+                } else {
+                    println!("Already has tag: {}", tag);
+                }
+            }
+
+            // Remove tags
+            for tag in remove_tag {
+                if let Some(pos) = task.tags.iter().position(|x| x == tag) {
+                    task.tags.remove(pos);
+                    println!("Removed tag: {}", tag);
+                    changed = true;
+                } else {
+                    println!("Does not have tag: {}", tag);
+                }
+            }
+
+            // Update model
+            if let Some(new_model) = model {
+                task.model = Some(new_model.to_string());
+                println!("Updated model: {}", new_model);
                 changed = true;
-            } else {
-                println!("Already has tag: {}", tag);
             }
-        }
 
-        // Remove tags
-        for tag in remove_tag {
-            if let Some(pos) = task.tags.iter().position(|x| x == tag) {
-                task.tags.remove(pos);
-                println!("Removed tag: {}", tag);
+            // Update priority
+            if let Some(new_priority) = priority {
+                let old = task.priority;
+                task.priority = new_priority;
+                field_changes.push(
+                    serde_json::json!({"field": "priority", "old": old, "new": new_priority}),
+                );
+                println!("Updated priority: {}", new_priority);
                 changed = true;
-            } else {
-                println!("Does not have tag: {}", tag);
             }
-        }
 
-        // Update model
-        if let Some(new_model) = model {
-            task.model = Some(new_model.to_string());
-            println!("Updated model: {}", new_model);
-            changed = true;
-        }
+            // Update value
+            if let Some(new_value) = value {
+                let old = task.value;
+                task.value = Some(new_value);
+                field_changes
+                    .push(serde_json::json!({"field": "value", "old": old, "new": new_value}));
+                println!("Updated value: {}", new_value);
+                changed = true;
+            }
 
-        // Add skills
-        for skill in add_skill {
-            if !task.skills.contains(skill) {
-                task.skills.push(skill.clone());
-                println!("Added skill: {}", skill);
+            // Update parent
+            let new_parent = if no_parent { Some(None) } else { parent.map(Some) };
+            if let Some(new_parent) = new_parent
+                && task.parent.as_deref() != new_parent
+            {
+                let old = task.parent.take();
+                task.parent = new_parent.map(String::from);
+                field_changes
+                    .push(serde_json::json!({"field": "parent", "old": old, "new": new_parent}));
+                match new_parent {
+                    Some(p) => println!("Updated parent: {}", p),
+                    None => println!("Removed parent"),
+                }
                 changed = true;
-            } else {
-                println!("Already has skill: {}", skill);
             }
-        }
 
-        // Remove skills
-        for skill in remove_skill {
-            if let Some(pos) = task.skills.iter().position(|x| x == skill) {
-                task.skills.remove(pos);
-                println!("Removed skill: {}", skill);
+            // Update auto-complete
+            if let Some(flag) = auto_complete
+                && task.auto_complete != flag
+            {
+                task.auto_complete = flag;
+                field_changes.push(
+                    serde_json::json!({"field": "auto_complete", "old": !flag, "new": flag}),
+                );
+                println!("Updated auto-complete: {}", flag);
                 changed = true;
-            } else {
-                println!("Does not have skill: {}", skill);
             }
-        }
 
-        // Add loops_to edge
-        if let Some(target) = add_loops_to {
-            let max_iterations = loop_max.ok_or_else(|| {
-                anyhow::anyhow!("--loop-max is required when using --add-loops-to")
-            })?;
-            let guard = match loop_guard {
-                Some(expr) => Some(crate::commands::add::parse_guard_expr(expr)?),
-                None => None,
+            // Update deadline
+            let new_deadline = if no_deadline {
+                Some(None)
+            } else {
+                deadline.as_ref().map(Some)
             };
-            let delay = match loop_delay {
-                Some(d) => {
-                    parse_delay(d).ok_or_else(|| {
-                        anyhow::anyhow!("Invalid delay '{}'. Use format: 30s, 5m, 1h, 24h, 7d", d)
-                    })?;
-                    Some(d.to_string())
+            if let Some(new_deadline) = new_deadline
+                && task.deadline.as_ref() != new_deadline
+            {
+                let old = task.deadline.take();
+                task.deadline = new_deadline.cloned();
+                field_changes.push(
+                    serde_json::json!({"field": "deadline", "old": old, "new": new_deadline}),
+                );
+                match new_deadline {
+                    Some(d) => println!("Updated deadline: {}", d),
+                    None => println!("Removed deadline"),
                 }
-                None => None,
-            };
-            // Check for duplicate target
-            if task.loops_to.iter().any(|e| e.target == target) {
-                println!("Already has loops_to edge targeting: {}", target);
+                changed = true;
+            }
+
+            // Update retry policy
+            let new_policy = if no_retry_policy {
+                Some(None)
             } else {
-                task.loops_to.push(LoopEdge {
-                    target: target.to_string(),
-                    guard,
-                    max_iterations,
-                    delay,
-                });
-                println!(
-                    "Added loops_to: {} (max_iterations: {})",
-                    target, max_iterations
+                retry_policy.as_ref().map(Some)
+            };
+            if let Some(new_policy) = new_policy
+                && task.retry_policy.as_ref() != new_policy
+            {
+                let old = task.retry_policy.take();
+                task.retry_policy = new_policy.cloned();
+                field_changes.push(
+                    serde_json::json!({"field": "retry_policy", "old": old, "new": new_policy}),
                 );
+                match new_policy {
+                    Some(p) => println!("Updated retry policy: {}", p),
+                    None => println!("Removed retry policy"),
+                }
                 changed = true;
             }
-        } else if loop_max.is_some() || loop_guard.is_some() || loop_delay.is_some() {
-            anyhow::bail!("--loop-max, --loop-guard, and --loop-delay require --add-loops-to");
-        }
 
-        // Remove loops_to edge
-        if let Some(target) = remove_loops_to {
-            if let Some(pos) = task.loops_to.iter().position(|e| e.target == target) {
-                task.loops_to.remove(pos);
-                println!("Removed loops_to: {}", target);
+            // Set custom fields
+            for (key, value) in &set_field {
+                let old = task.fields.insert(key.clone(), value.clone());
+                if old.as_ref() != Some(value) {
+                    field_changes.push(serde_json::json!({
+                        "field": format!("fields.{}", key),
+                        "old": old,
+                        "new": value,
+                    }));
+                    println!(
+                        "Set field: {}={}",
+                        key,
+                        workgraph::fields::display(value)
+                    );
+                    changed = true;
+                }
+            }
+
+            // Remove custom fields
+            for key in remove_field {
+                if let Some(old) = task.fields.remove(key) {
+                    field_changes.push(serde_json::json!({
+                        "field": format!("fields.{}", key),
+                        "old": old,
+                        "new": null,
+                    }));
+                    println!("Removed field: {}", key);
+                    changed = true;
+                } else {
+                    println!("No field: {}", key);
+                }
+            }
+
+            // Add skills
+            for skill in add_skill {
+                if !task.skills.contains(skill) {
+                    task.skills.push(skill.clone());
+                    println!("Added skill: {}", skill);
+                    changed = true;
+                } else {
+                    println!("Already has skill: {}", skill);
+                }
+            }
+
+            // Remove skills
+            for skill in remove_skill {
+                if let Some(pos) = task.skills.iter().position(|x| x == skill) {
+                    task.skills.remove(pos);
+                    println!("Removed skill: {}", skill);
+                    changed = true;
+                } else {
+                    println!("Does not have skill: {}", skill);
+                }
+            }
+
+            // Add resource requirements (or update the amount of an existing one)
+            for (resource_id, amount) in &add_requires {
+                let old_amount = task
+                    .requires
+                    .contains(resource_id)
+                    .then(|| workgraph::query::resource_amount(task, resource_id));
+                if old_amount == Some(*amount) {
+                    println!("Already requires: {}", resource_id);
+                    continue;
+                }
+                if old_amount.is_none() {
+                    task.requires.push(resource_id.clone());
+                }
+                if *amount == 1.0 {
+                    task.resource_amounts.remove(resource_id);
+                } else {
+                    task.resource_amounts.insert(resource_id.clone(), *amount);
+                }
+                field_changes.push(serde_json::json!({"field": "requires", "resource": resource_id, "old": old_amount, "new": amount}));
+                println!("Requires: {} ({})", resource_id, amount);
                 changed = true;
-            } else {
-                println!("No loops_to edge targeting: {}", target);
             }
-        }
 
-        // Set loop_iteration directly
-        if let Some(iter) = loop_iteration {
-            task.loop_iteration = iter;
-            println!("Set loop_iteration: {}", iter);
-            changed = true;
-        }
-    } // task borrow released here
+            // Remove resource requirements
+            for resource_id in remove_requires {
+                if let Some(pos) = task.requires.iter().position(|x| x == resource_id) {
+                    task.requires.remove(pos);
+                    task.resource_amounts.remove(resource_id);
+                    println!("Removed requires: {}", resource_id);
+                    changed = true;
+                } else {
+                    println!("Does not require: {}", resource_id);
+                }
+            }
 
-    // Maintain bidirectional consistency: update `blocks` on referenced tasks
-    let task_id_owned = task_id.to_string();
-    for dep in add_blocked_by {
-        if let Some(blocker) = graph.get_task_mut(dep)
-            && !blocker.blocks.contains(&task_id_owned)
-        {
-            blocker.blocks.push(task_id_owned.clone());
+            // Add loops_to edge
+            if let Some(target) = add_loops_to {
+                let max_iterations = loop_max.ok_or_else(|| {
+                    anyhow::anyhow!("--loop-max is required when using --add-loops-to")
+                })?;
+                let guard = match loop_guard {
+                    Some(expr) => Some(crate::commands::add::parse_guard_expr(expr)?),
+                    None => None,
+                };
+                let delay = match loop_delay {
+                    Some(d) => {
+                        parse_delay(d).ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "Invalid delay '{}'. Use format: 30s, 5m, 1h, 24h, 7d",
+                                d
+                            )
+                        })?;
+                        Some(d.to_string())
+                    }
+                    None => None,
+                };
+                // Check for duplicate target
+                if task.loops_to.iter().any(|e| e.target == target) {
+                    println!("Already has loops_to edge targeting: {}", target);
+                } else {
+                    task.loops_to.push(LoopEdge {
+                        target: target.to_string(),
+                        guard,
+                        max_iterations,
+                        delay,
+                    });
+                    println!(
+                        "Added loops_to: {} (max_iterations: {})",
+                        target, max_iterations
+                    );
+                    changed = true;
+                }
+            } else if loop_max.is_some() || loop_guard.is_some() || loop_delay.is_some() {
+                anyhow::bail!("--loop-max, --loop-guard, and --loop-delay require --add-loops-to");
+            }
+
+            // Remove loops_to edge
+            if let Some(target) = remove_loops_to {
+                if let Some(pos) = task.loops_to.iter().position(|e| e.target == target) {
+                    task.loops_to.remove(pos);
+                    println!("Removed loops_to: {}", target);
+                    changed = true;
+                } else {
+                    println!("No loops_to edge targeting: {}", target);
+                }
+            }
+
+            // Set loop_iteration directly
+            if let Some(iter) = loop_iteration {
+                task.loop_iteration = iter;
+                println!("Set loop_iteration: {}", iter);
+                changed = true;
+            }
+        } // task borrow released here
+
+        // Maintain bidirectional consistency: update `blocks` on referenced tasks
+        let task_id_owned = task_id.to_string();
+        for dep in add_blocked_by {
+            if let Some(blocker) = graph.get_task_mut(dep)
+                && !blocker.blocks.contains(&task_id_owned)
+            {
+                blocker.blocks.push(task_id_owned.clone());
+            }
         }
-    }
-    for dep in remove_blocked_by {
-        if let Some(blocker) = graph.get_task_mut(dep) {
-            blocker.blocks.retain(|b| b != &task_id_owned);
+        for dep in remove_blocked_by {
+            if let Some(blocker) = graph.get_task_mut(dep) {
+                blocker.blocks.retain(|b| b != &task_id_owned);
+            }
         }
-    }
 
-    // Save if changes were made
+        Ok((changed, field_changes))
+    })?;
+
     if changed {
-        save_graph(&graph, &path).context("Failed to save graph")?;
         super::notify_graph_changed(dir);
 
         // Record operation
-        let config = workgraph::config::Config::load_or_default(dir);
         let _ = workgraph::provenance::record(
             dir,
             "edit",
             Some(task_id),
             None,
-            serde_json::json!({ "fields": field_changes }),
+            workgraph::undo::with_changes(
+                serde_json::json!({ "fields": field_changes }),
+                &changes,
+            ),
             config.log.rotation_threshold,
         );
 
@@ -250,9 +465,11 @@ pub fn run(
 
 #[cfg(test)]
 mod tests {
+    use super::super::graph_path;
     use super::*;
     use std::fs;
     use tempfile::TempDir;
+    use workgraph::parser::load_graph;
 
     fn create_test_graph(dir: &Path) -> Result<()> {
         // Create the workgraph directory if it doesn't exist
@@ -283,6 +500,24 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            None,
+            false,
+            &[],
+            None,
+            None,
+            &[],
+            &[],
+            false,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
         )?;
 
         Ok(())
@@ -314,6 +549,24 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            None,
+            false,
+            &[],
+            None,
+            None,
+            &[],
+            &[],
+            false,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
         )?;
 
         crate::commands::add::run(
@@ -336,6 +589,24 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            None,
+            false,
+            &[],
+            None,
+            None,
+            &[],
+            &[],
+            false,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
         )?;
 
         Ok(())
@@ -364,6 +635,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -396,6 +680,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -428,6 +725,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -461,6 +771,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -493,6 +816,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -526,6 +862,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -558,6 +907,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -590,6 +952,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -623,6 +998,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -655,6 +1043,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
@@ -683,6 +1084,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
     }
@@ -710,6 +1124,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_err());
         assert!(
@@ -743,6 +1170,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_err());
         assert!(
@@ -777,6 +1217,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -815,6 +1268,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         )
         .unwrap();
 
@@ -837,6 +1303,19 @@ mod tests {
             None,
             None,
             None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
         );
         assert!(result.is_ok());
 
@@ -850,4 +1329,200 @@ mod tests {
             "blocker-task.blocks should NOT contain test-task after removal"
         );
     }
+
+    #[test]
+    fn test_edit_priority_and_value() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_graph(temp_dir.path()).unwrap();
+
+        let result = run(
+            temp_dir.path(),
+            "test-task",
+            None,
+            None,
+            &[],
+            &[],
+            &[],
+            &[],
+            None,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some("high"),
+            Some(3.0),
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
+        );
+        assert!(result.is_ok());
+
+        let path = graph_path(temp_dir.path());
+        let graph = load_graph(&path).unwrap();
+        let task = graph.get_task("test-task").unwrap();
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.value, Some(3.0));
+    }
+
+    #[test]
+    fn test_edit_invalid_priority_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_graph(temp_dir.path()).unwrap();
+
+        let result = run(
+            temp_dir.path(),
+            "test-task",
+            None,
+            None,
+            &[],
+            &[],
+            &[],
+            &[],
+            None,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some("asap"),
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_edit_add_and_remove_requires() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_graph(temp_dir.path()).unwrap();
+
+        let result = run(
+            temp_dir.path(),
+            "test-task",
+            None,
+            None,
+            &[],
+            &[],
+            &[],
+            &[],
+            None,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &["gpu-box:2".to_string(), "api-quota".to_string()],
+            &[],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
+        );
+        assert!(result.is_ok());
+
+        let path = graph_path(temp_dir.path());
+        let graph = load_graph(&path).unwrap();
+        let task = graph.get_task("test-task").unwrap();
+        assert_eq!(task.requires, vec!["gpu-box", "api-quota"]);
+        assert_eq!(task.resource_amounts.get("gpu-box"), Some(&2.0));
+        assert!(!task.resource_amounts.contains_key("api-quota"));
+
+        let result = run(
+            temp_dir.path(),
+            "test-task",
+            None,
+            None,
+            &[],
+            &[],
+            &[],
+            &[],
+            None,
+            &[],
+            &[],
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &[],
+            &["gpu-box".to_string()],
+            None,
+            false,
+            None,
+            &[],
+            &[],
+            None,
+            false,
+            None,
+            false,
+        );
+        assert!(result.is_ok());
+
+        let graph = load_graph(&path).unwrap();
+        let task = graph.get_task("test-task").unwrap();
+        assert_eq!(task.requires, vec!["api-quota"]);
+        assert!(task.resource_amounts.is_empty());
+    }
+
+    #[test]
+    fn test_edit_set_and_remove_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_graph(temp_dir.path()).unwrap();
+        let edit = |set: &[&str], remove: &[&str]| {
+            let set: Vec<String> = set.iter().map(|f| f.to_string()).collect();
+            let remove: Vec<String> = remove.iter().map(|f| f.to_string()).collect();
+            run(
+                temp_dir.path(), "test-task", None, None, &[], &[], &[], &[], None, &[], &[],
+                None, None, None, None, None, None, None, None, &[], &[], None, false, None,
+                &set, &remove, None, false, None, false,
+            )
+        };
+
+        edit(&["customer=acme", "points=3"], &[]).unwrap();
+        edit(&[], &["points"]).unwrap();
+        assert!(edit(&["broken"], &[]).is_err());
+
+        let graph = load_graph(graph_path(temp_dir.path())).unwrap();
+        let task = graph.get_task("test-task").unwrap();
+        assert_eq!(task.fields.len(), 1);
+        assert_eq!(task.fields["customer"], serde_json::json!("acme"));
+    }
 }
@@ -8,8 +8,8 @@ use std::process::Command;
 
 use workgraph::identity::{self, Reward, Lineage, Objective, RewardHistory, Role, SkillRef};
 use workgraph::config::Config;
-use workgraph::graph::{Node, Status, Task};
-use workgraph::{load_graph, save_graph};
+use workgraph::graph::{Node, Priority, Status, Task};
+use workgraph::with_graph_mut;
 
 /// Strategies the evolver can use.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -1107,74 +1107,90 @@ fn extract_json(raw: &str) -> Option<String> {
 /// The task requires human approval before the mutation can be applied.
 fn defer_self_mutation(op: &EvolverOperation, dir: &Path, run_id: &str) -> Result<String> {
     let graph_path = super::graph_path(dir);
-    let mut graph =
-        load_graph(&graph_path).context("Failed to load graph for self-mutation deferral")?;
-
     let task_id = format!(
         "evolve-review-{}-{}",
         op.op,
         op.target_id.as_deref().unwrap_or("unknown"),
     );
 
-    // Don't create duplicate review tasks
-    if graph.get_task(&task_id).is_some() {
-        return Ok(task_id);
-    }
-
-    let op_json = serde_json::to_string_pretty(op).unwrap_or_else(|_| format!("{:?}", op.op));
+    let created = with_graph_mut(&graph_path, |graph| -> Result<bool> {
+        // Don't create duplicate review tasks
+        if graph.get_task(&task_id).is_some() {
+            return Ok(false);
+        }
 
-    let desc = format!(
-        "The evolver (run {run_id}) proposed a mutation targeting its own identity. \
-         This requires human review before applying.\n\n\
-         ## Proposed Operation\n\n\
-         ```json\n{op_json}\n```\n\n\
-         ## Instructions\n\n\
-         Review the proposed change. If acceptable, apply it manually with \
-         `wg evolve` or by editing the role/objective YAML directly, then \
-         `wg approve {task_id}`.",
-    );
+        let op_json = serde_json::to_string_pretty(op).unwrap_or_else(|_| format!("{:?}", op.op));
+
+        let desc = format!(
+            "The evolver (run {run_id}) proposed a mutation targeting its own identity. \
+             This requires human review before applying.\n\n\
+             ## Proposed Operation\n\n\
+             ```json\n{op_json}\n```\n\n\
+             ## Instructions\n\n\
+             Review the proposed change. If acceptable, apply it manually with \
+             `wg evolve` or by editing the role/objective YAML directly, then \
+             `wg approve {task_id}`.",
+        );
 
-    let task = Task {
-        id: task_id.clone(),
-        title: format!(
-            "Review evolver self-mutation: {} on {}",
-            op.op,
-            op.target_id.as_deref().unwrap_or("?")
-        ),
-        description: Some(desc),
-        status: Status::Open,
-        assigned: None,
-        estimate: None,
-        blocks: vec![],
-        blocked_by: vec![],
-        requires: vec![],
-        tags: vec!["evolution".to_string(), "identity".to_string()],
-        skills: vec![],
-        inputs: vec![],
-        deliverables: vec![],
-        artifacts: vec![],
-        exec: None,
-        not_before: None,
-        created_at: Some(Utc::now().to_rfc3339()),
-        started_at: None,
-        completed_at: None,
-        log: vec![],
-        retry_count: 0,
-        max_retries: None,
-        failure_reason: None,
-        model: None,
-        verify: Some("Human must approve evolver self-mutation before applying.".to_string()),
-        agent: None,
-        loops_to: vec![],
-        loop_iteration: 0,
-        ready_after: None,
-        paused: false,
-    };
+        let task = Task {
+            id: task_id.clone(),
+            title: format!(
+                "Review evolver self-mutation: {} on {}",
+                op.op,
+                op.target_id.as_deref().unwrap_or("?")
+            ),
+            description: Some(desc),
+            status: Status::Open,
+            assigned: None,
+            estimate: None,
+            blocks: vec![],
+            blocked_by: vec![],
+            requires: vec![],
+            tags: vec!["evolution".to_string(), "identity".to_string()],
+            skills: vec![],
+            inputs: vec![],
+            deliverables: vec![],
+            artifacts: vec![],
+            exec: None,
+            not_before: None,
+            created_at: Some(Utc::now().to_rfc3339()),
+            started_at: None,
+            completed_at: None,
+            log: vec![],
+            retry_count: 0,
+            max_retries: None,
+            failure_reason: None,
+            model: None,
+            verify: Some("Human must approve evolver self-mutation before applying.".to_string()),
+            agent: None,
+            loops_to: vec![],
+            loop_iteration: 0,
+            ready_after: None,
+            paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
+        };
 
-    graph.add_node(Node::Task(task));
-    save_graph(&graph, &graph_path)
-        .context("Failed to save graph with self-mutation review task")?;
-    super::notify_graph_changed(dir);
+        graph.add_node(Node::Task(task));
+        Ok(true)
+    })
+    .context("Failed to add self-mutation review task to graph")?;
+    if created {
+        super::notify_graph_changed(dir);
+    }
 
     Ok(task_id)
 }
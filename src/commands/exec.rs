@@ -2,8 +2,9 @@ use anyhow::{Context, Result};
 use chrono::Utc;
 use std::path::Path;
 use std::process::Command;
-use workgraph::graph::{LogEntry, Status, reward_loop_edges};
-use workgraph::parser::{load_graph, save_graph};
+use workgraph::completion::{check_completable, complete, read_guards};
+use workgraph::graph::{LogEntry, Status, Task, skip_unreachable};
+use workgraph::verify;
 
 #[cfg(test)]
 use super::graph_path;
@@ -15,22 +16,10 @@ use super::graph_path;
 /// - Runs the task's exec command
 /// - Marks done on success (exit 0), fail on error
 pub fn run(dir: &Path, task_id: &str, actor: Option<&str>, dry_run: bool) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
-
-    let task = graph.get_task_or_err(task_id)?;
-
-    // Check task has an exec command
-    let exec_cmd = task
-        .exec
-        .clone()
-        .ok_or_else(|| anyhow::anyhow!("Task '{}' has no exec command defined", task_id))?;
-
-    // Check task status
-    if task.status == Status::Done {
-        anyhow::bail!("Task '{}' is already done", task_id);
-    }
-
     if dry_run {
+        let (graph, _path) = super::load_workgraph(dir)?;
+        let task = graph.get_task_or_err(task_id)?;
+        let exec_cmd = exec_command(task)?;
         println!("Would execute for task '{}':", task_id);
         println!("  Command: {}", exec_cmd);
         println!("  Status: {:?} -> InProgress -> Done/Failed", task.status);
@@ -38,22 +27,27 @@ pub fn run(dir: &Path, task_id: &str, actor: Option<&str>, dry_run: bool) -> Res
     }
 
     // Claim the task if not already in progress
-    // Re-acquire mutable reference after immutable borrow above
-    let task = graph.get_task_mut_or_err(task_id)?;
-    let was_open = task.status == Status::Open;
+    let (exec_cmd, was_open) = super::with_workgraph_mut(dir, |graph| {
+        let task = graph.get_task_mut_or_err(task_id)?;
+        let exec_cmd = exec_command(task)?;
+        let was_open = task.status == Status::Open;
+
+        if was_open {
+            task.status = Status::InProgress;
+            task.started_at = Some(Utc::now().to_rfc3339());
+            if let Some(actor_id) = actor {
+                task.assigned = Some(actor_id.to_string());
+            }
+            task.log.push(LogEntry {
+                timestamp: Utc::now().to_rfc3339(),
+                actor: actor.map(String::from),
+                message: format!("Started execution: {}", exec_cmd),
+            });
+        }
+        Ok((exec_cmd, was_open))
+    })?;
 
     if was_open {
-        task.status = Status::InProgress;
-        task.started_at = Some(Utc::now().to_rfc3339());
-        if let Some(actor_id) = actor {
-            task.assigned = Some(actor_id.to_string());
-        }
-        task.log.push(LogEntry {
-            timestamp: Utc::now().to_rfc3339(),
-            actor: actor.map(String::from),
-            message: format!("Started execution: {}", exec_cmd),
-        });
-        save_graph(&graph, &path).context("Failed to save graph")?;
         super::notify_graph_changed(dir);
         println!("Claimed task '{}' for execution", task_id);
     }
@@ -78,53 +72,129 @@ pub fn run(dir: &Path, task_id: &str, actor: Option<&str>, dry_run: bool) -> Res
         eprintln!("{}", stderr);
     }
 
-    // Reload graph and update status (task may have been modified by exec command)
-    let mut graph = load_graph(&path).context("Failed to reload graph")?;
-    let task = graph.get_task_mut_or_err(task_id)?;
-
+    // Update status in a fresh transaction (task may have been modified by exec command)
     if success {
-        task.status = Status::Done;
-        task.completed_at = Some(Utc::now().to_rfc3339());
-        task.log.push(LogEntry {
-            timestamp: Utc::now().to_rfc3339(),
-            actor: actor.map(String::from),
-            message: "Execution completed successfully".to_string(),
-        });
-        // Reward loop edges: re-activate upstream tasks if conditions are met
-        let reactivated = reward_loop_edges(&mut graph, task_id);
-        save_graph(&graph, &path).context("Failed to save graph")?;
+        // Verification checks and loop guards can run commands, so they're
+        // run against a snapshot before the graph lock
+        let guards = {
+            let (graph, _path) = super::load_workgraph(dir)?;
+            let task = graph.get_task_or_err(task_id)?;
+            let failures = match task.status {
+                Status::Done | Status::InReview => vec![],
+                _ => verify::run(task, dir)?,
+            };
+            if !failures.is_empty() {
+                super::done::print_failures(&failures);
+                let reason = format!("Verification failed: {}", verify::summary(&failures));
+                fail(dir, task_id, actor, None, &reason)?;
+                anyhow::bail!(
+                    "Task '{}' failed: {} verification check(s) failed",
+                    task_id,
+                    failures.len()
+                );
+            }
+            read_guards(&graph, dir, task_id)
+        };
+        let done = super::with_workgraph_mut(dir, |graph| {
+            // The command may have finished the task itself with 'wg done'
+            let status = graph.get_task_or_err(task_id)?.status;
+            if matches!(status, Status::Done | Status::InReview) {
+                return Ok(None);
+            }
+            check_completable(graph, task_id)?;
+            let task = graph.get_task_mut_or_err(task_id)?;
+
+            // As with 'wg done', an agent's work on a task with verify
+            // criteria waits for a human to sign off
+            if task.verify.is_some() && super::done::run_by_agent() {
+                task.status = Status::InReview;
+                task.log.push(LogEntry {
+                    timestamp: Utc::now().to_rfc3339(),
+                    actor: actor.map(String::from),
+                    message: "Task submitted for review".to_string(),
+                });
+                return Ok(Some((task.clone(), None)));
+            }
+
+            task.log.push(LogEntry {
+                timestamp: Utc::now().to_rfc3339(),
+                actor: actor.map(String::from),
+                message: "Execution completed successfully".to_string(),
+            });
+            let completed = complete(graph, dir, task_id, &guards)?;
+            let task = graph.get_task_or_err(task_id)?.clone();
+            Ok(Some((task, Some(completed))))
+        })?;
         super::notify_graph_changed(dir);
-        println!("Task '{}' completed successfully", task_id);
-        for tid in &reactivated {
-            println!("  Loop: re-activated '{}'", tid);
+        match done {
+            Some((task, Some(completed))) => {
+                println!("Task '{}' completed successfully", task_id);
+                super::done::print_completed(&completed, &task);
+            }
+            Some((_, None)) => println!(
+                "Submitted '{}' for review. Dependents stay blocked until 'wg approve {}'",
+                task_id, task_id
+            ),
+            None => println!("Task '{}' completed successfully", task_id),
         }
     } else {
         let exit_code = output.status.code().unwrap_or(-1);
+        let reason = format!("Command exited with code {}", exit_code);
+        fail(dir, task_id, actor, Some(exit_code), &reason)?;
+        anyhow::bail!("Task '{}' failed with exit code {}", task_id, exit_code);
+    }
+
+    Ok(())
+}
+
+/// Mark the task failed after its command ran
+fn fail(
+    dir: &Path,
+    task_id: &str,
+    actor: Option<&str>,
+    exit_code: Option<i32>,
+    reason: &str,
+) -> Result<()> {
+    super::with_workgraph_mut(dir, |graph| {
+        let task = graph.get_task_mut_or_err(task_id)?;
         task.status = Status::Failed;
         task.retry_count += 1;
-        task.failure_reason = Some(format!("Command exited with code {}", exit_code));
+        task.failure_reason = Some(reason.to_string());
+        workgraph::retry::record_failure(task, exit_code, None);
         task.log.push(LogEntry {
             timestamp: Utc::now().to_rfc3339(),
             actor: actor.map(String::from),
-            message: format!("Execution failed with exit code {}", exit_code),
+            message: format!("Execution failed: {}", reason),
         });
-        save_graph(&graph, &path).context("Failed to save graph")?;
-        super::notify_graph_changed(dir);
-        anyhow::bail!("Task '{}' failed with exit code {}", task_id, exit_code);
-    }
-
+        skip_unreachable(graph);
+        Ok(())
+    })?;
+    super::notify_graph_changed(dir);
     Ok(())
 }
 
-/// Set the exec command for a task
-pub fn set_exec(dir: &Path, task_id: &str, command: &str) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
-
-    let task = graph.get_task_mut_or_err(task_id)?;
+/// The exec command of a task that is still runnable
+fn exec_command(task: &Task) -> Result<String> {
+    // Check task has an exec command
+    let exec_cmd = task
+        .exec
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("Task '{}' has no exec command defined", task.id))?;
 
-    task.exec = Some(command.to_string());
+    // Check task status
+    if task.status == Status::Done {
+        anyhow::bail!("Task '{}' is already done", task.id);
+    }
+    Ok(exec_cmd)
+}
 
-    save_graph(&graph, &path).context("Failed to save graph")?;
+/// Set the exec command for a task
+pub fn set_exec(dir: &Path, task_id: &str, command: &str) -> Result<()> {
+    super::with_workgraph_mut(dir, |graph| {
+        let task = graph.get_task_mut_or_err(task_id)?;
+        task.exec = Some(command.to_string());
+        Ok(())
+    })?;
     super::notify_graph_changed(dir);
 
     println!("Set exec command for '{}': {}", task_id, command);
@@ -133,18 +203,15 @@ pub fn set_exec(dir: &Path, task_id: &str, command: &str) -> Result<()> {
 
 /// Clear the exec command for a task
 pub fn clear_exec(dir: &Path, task_id: &str) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
-
-    let task = graph.get_task_mut_or_err(task_id)?;
+    let cleared = super::with_workgraph_mut(dir, |graph| {
+        let task = graph.get_task_mut_or_err(task_id)?;
+        Ok(task.exec.take().is_some())
+    })?;
 
-    if task.exec.is_none() {
+    if !cleared {
         println!("Task '{}' has no exec command to clear", task_id);
         return Ok(());
     }
-
-    task.exec = None;
-
-    save_graph(&graph, &path).context("Failed to save graph")?;
     super::notify_graph_changed(dir);
 
     println!("Cleared exec command for '{}'", task_id);
@@ -156,7 +223,7 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
     use workgraph::graph::{Node, Task, WorkGraph};
-    use workgraph::parser::save_graph;
+    use workgraph::parser::{load_graph, save_graph};
 
     fn make_task(id: &str, title: &str) -> Task {
         Task {
@@ -192,6 +259,77 @@ mod tests {
         assert_eq!(task.status, Status::Done);
     }
 
+    #[test]
+    fn test_exec_last_child_completes_parent() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("graph.jsonl");
+
+        let mut graph = WorkGraph::new();
+        let mut epic = make_task("epic", "Epic");
+        epic.auto_complete = true;
+        let mut a = make_task("a", "A");
+        a.parent = Some("epic".to_string());
+        a.status = Status::Done;
+        let mut b = make_task("b", "B");
+        b.parent = Some("epic".to_string());
+        b.exec = Some("true".to_string());
+        for task in [epic, a, b] {
+            graph.add_node(Node::Task(task));
+        }
+        save_graph(&graph, &path).unwrap();
+
+        run(temp_dir.path(), "b", None, false).unwrap();
+
+        let graph = load_graph(graph_path(temp_dir.path())).unwrap();
+        assert_eq!(graph.get_task("b").unwrap().status, Status::Done);
+        let epic = graph.get_task("epic").unwrap();
+        assert_eq!(epic.status, Status::Done);
+        assert!(epic.completed_at.is_some());
+    }
+
+    #[test]
+    fn test_exec_fans_out_before_join_is_ready() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join(".workgraph");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut graph = WorkGraph::new();
+        let mut list = make_task("list", "List files");
+        list.fan_out = Some(workgraph::fan_out::FanOut::new(
+            "list",
+            "Review {{input.item}}",
+            None,
+            Some("files.json"),
+            "list-join",
+        ));
+        list.exec = Some(format!(
+            "printf '[\"a.rs\", \"b.rs\"]' > '{}'",
+            temp_dir.path().join("files.json").display()
+        ));
+        list.blocks = vec!["list-join".to_string()];
+        let mut join = make_task("list-join", "Join");
+        join.blocked_by = vec!["list".to_string()];
+        graph.add_node(Node::Task(list));
+        graph.add_node(Node::Task(join));
+        save_graph(&graph, graph_path(&dir)).unwrap();
+
+        run(&dir, "list", None, false).unwrap();
+
+        // The children exist as soon as the task is done, so the join is
+        // never ready without them
+        let graph = load_graph(graph_path(&dir)).unwrap();
+        assert_eq!(graph.get_task("list").unwrap().status, Status::Done);
+        assert_eq!(
+            graph.get_task("list-join").unwrap().blocked_by,
+            vec!["list", "list-1", "list-2"]
+        );
+        let ready: Vec<&str> = workgraph::query::ready_tasks(&graph)
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(ready, vec!["list-1", "list-2"]);
+    }
+
     #[test]
     fn test_exec_failure() {
         let temp_dir = TempDir::new().unwrap();
@@ -212,6 +350,32 @@ mod tests {
         assert_eq!(task.status, Status::Failed);
     }
 
+    #[test]
+    fn test_exec_verification_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("graph.jsonl");
+
+        let mut graph = WorkGraph::new();
+        let mut task = make_task("t1", "Unverified Task");
+        task.exec = Some("true".to_string());
+        task.verify = Some("shell:exit 1".to_string());
+        graph.add_node(Node::Task(task));
+        save_graph(&graph, &path).unwrap();
+
+        let result = run(temp_dir.path(), "t1", None, false);
+        assert!(result.is_err());
+
+        let graph = load_graph(graph_path(temp_dir.path())).unwrap();
+        let task = graph.get_task("t1").unwrap();
+        assert_eq!(task.status, Status::Failed);
+        assert!(
+            task.failure_reason
+                .as_deref()
+                .unwrap()
+                .starts_with("Verification failed: shell:exit 1")
+        );
+    }
+
     #[test]
     fn test_exec_no_command() {
         let temp_dir = TempDir::new().unwrap();
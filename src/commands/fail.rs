@@ -1,32 +1,54 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::Utc;
 use std::path::Path;
 use workgraph::identity::capture_task_output;
-use workgraph::graph::{LogEntry, Status};
-use workgraph::parser::save_graph;
+use workgraph::graph::{LogEntry, Status, skip_unreachable};
 
 #[cfg(test)]
 use super::graph_path;
 #[cfg(test)]
 use workgraph::parser::load_graph;
 
-pub fn run(dir: &Path, id: &str, reason: Option<&str>) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
+pub fn run(dir: &Path, id: &str, reason: Option<&str>, exit_code: Option<i32>) -> Result<()> {
+    let ((task, newly_failed, skipped), changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
 
-    let task = graph.get_task_mut_or_err(id)?;
+        if task.status == Status::Done {
+            anyhow::bail!(
+                "Task '{}' is already done and cannot be marked as failed",
+                id
+            );
+        }
 
-    if task.status == Status::Done {
-        anyhow::bail!(
-            "Task '{}' is already done and cannot be marked as failed",
-            id
-        );
-    }
+        if task.status == Status::Abandoned {
+            anyhow::bail!("Task '{}' is already abandoned", id);
+        }
 
-    if task.status == Status::Abandoned {
-        anyhow::bail!("Task '{}' is already abandoned", id);
-    }
+        if task.status == Status::Failed {
+            return Ok((task.clone(), false, vec![]));
+        }
 
-    if task.status == Status::Failed {
+        task.status = Status::Failed;
+        task.retry_count += 1;
+        task.failure_reason = reason.map(String::from);
+        workgraph::retry::record_failure(task, exit_code, None);
+
+        let log_message = match reason {
+            Some(r) => format!("Task marked as failed: {}", r),
+            None => "Task marked as failed".to_string(),
+        };
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: task.assigned.clone(),
+            message: log_message,
+        });
+
+        let task = task.clone();
+        let skipped = skip_unreachable(graph);
+        Ok((task, true, skipped))
+    })?;
+
+    if !newly_failed {
         println!(
             "Task '{}' is already failed (retry_count: {})",
             id, task.retry_count
@@ -34,25 +56,10 @@ pub fn run(dir: &Path, id: &str, reason: Option<&str>) -> Result<()> {
         return Ok(());
     }
 
-    task.status = Status::Failed;
-    task.retry_count += 1;
-    task.failure_reason = reason.map(String::from);
-
-    let log_message = match reason {
-        Some(r) => format!("Task marked as failed: {}", r),
-        None => "Task marked as failed".to_string(),
-    };
-    task.log.push(LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: task.assigned.clone(),
-        message: log_message,
-    });
-
-    // Extract values we need for printing before saving
+    // Extract values we need for printing
     let retry_count = task.retry_count;
     let max_retries = task.max_retries;
 
-    save_graph(&graph, &path).context("Failed to save graph")?;
     super::notify_graph_changed(dir);
 
     // Record operation
@@ -66,7 +73,7 @@ pub fn run(dir: &Path, id: &str, reason: Option<&str>) -> Result<()> {
         "fail",
         Some(id),
         None,
-        detail,
+        workgraph::undo::with_changes(detail, &changes),
         config.log.rotation_threshold,
     );
 
@@ -76,6 +83,13 @@ pub fn run(dir: &Path, id: &str, reason: Option<&str>) -> Result<()> {
         id, reason_msg, retry_count
     );
 
+    for skipped_id in &skipped {
+        println!(
+            "  Skipped '{}' (its dependency conditions can't be met)",
+            skipped_id
+        );
+    }
+
     // Show retry info if max_retries is set
     if let Some(max) = max_retries {
         if retry_count >= max {
@@ -89,9 +103,7 @@ pub fn run(dir: &Path, id: &str, reason: Option<&str>) -> Result<()> {
     }
 
     // Archive agent conversation (prompt + output) for provenance
-    if let Some(task) = graph.get_task(id)
-        && let Some(ref agent_id) = task.assigned
-    {
+    if let Some(ref agent_id) = task.assigned {
         match super::log::archive_agent(dir, id, agent_id) {
             Ok(archive_dir) => {
                 eprintln!("Agent archived to {}", archive_dir.display());
@@ -105,14 +117,12 @@ pub fn run(dir: &Path, id: &str, reason: Option<&str>) -> Result<()> {
     // Capture task output (git diff, artifacts, log) for reward.
     // Failed tasks are also rewarded when auto_reward is enabled — there is
     // useful signal in what kinds of tasks cause which agents to fail.
-    if let Some(task) = graph.get_task(id) {
-        match capture_task_output(dir, task) {
-            Ok(output_dir) => {
-                eprintln!("Output captured to {}", output_dir.display());
-            }
-            Err(e) => {
-                eprintln!("Warning: output capture failed: {}", e);
-            }
+    match capture_task_output(dir, &task) {
+        Ok(output_dir) => {
+            eprintln!("Output captured to {}", output_dir.display());
+        }
+        Err(e) => {
+            eprintln!("Warning: output capture failed: {}", e);
         }
     }
 
@@ -133,7 +143,7 @@ mod tests {
         task.assigned = Some("agent-1".to_string());
         setup_workgraph(dir_path, vec![task]);
 
-        let result = run(dir_path, "t1", Some("compilation error"));
+        let result = run(dir_path, "t1", Some("compilation error"), None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -148,7 +158,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Open)]);
 
-        let result = run(dir_path, "t1", None);
+        let result = run(dir_path, "t1", None, None);
         assert!(result.is_ok());
 
         let path = graph_path(dir_path);
@@ -163,7 +173,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Done)]);
 
-        let result = run(dir_path, "t1", Some("reason"));
+        let result = run(dir_path, "t1", Some("reason"), None);
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
         assert!(
@@ -182,7 +192,7 @@ mod tests {
             vec![make_task("t1", "Test task", Status::Abandoned)],
         );
 
-        let result = run(dir_path, "t1", Some("reason"));
+        let result = run(dir_path, "t1", Some("reason"), None);
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
         assert!(
@@ -198,7 +208,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Open)]);
 
-        run(dir_path, "t1", None).unwrap();
+        run(dir_path, "t1", None, None).unwrap();
 
         let path = graph_path(dir_path);
         let graph = load_graph(&path).unwrap();
@@ -215,7 +225,7 @@ mod tests {
             vec![make_task("t1", "Test task", Status::InProgress)],
         );
 
-        run(dir_path, "t1", Some("timeout exceeded")).unwrap();
+        run(dir_path, "t1", Some("timeout exceeded"), None).unwrap();
 
         let path = graph_path(dir_path);
         let graph = load_graph(&path).unwrap();
@@ -231,7 +241,7 @@ mod tests {
         task.failure_reason = Some("old reason".to_string());
         setup_workgraph(dir_path, vec![task]);
 
-        run(dir_path, "t1", None).unwrap();
+        run(dir_path, "t1", None, None).unwrap();
 
         let path = graph_path(dir_path);
         let graph = load_graph(&path).unwrap();
@@ -245,7 +255,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Open)]);
 
-        run(dir_path, "t1", Some("network failure")).unwrap();
+        run(dir_path, "t1", Some("network failure"), None).unwrap();
 
         let path = graph_path(dir_path);
         let graph = load_graph(&path).unwrap();
@@ -265,7 +275,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Open)]);
 
-        run(dir_path, "t1", None).unwrap();
+        run(dir_path, "t1", None, None).unwrap();
 
         let path = graph_path(dir_path);
         let graph = load_graph(&path).unwrap();
@@ -282,7 +292,7 @@ mod tests {
         task.retry_count = 2;
         setup_workgraph(dir_path, vec![task]);
 
-        let result = run(dir_path, "t1", Some("new reason"));
+        let result = run(dir_path, "t1", Some("new reason"), None);
         assert!(result.is_ok());
 
         // Verify nothing changed
@@ -299,7 +309,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Open)]);
 
-        let result = run(dir_path, "nonexistent", None);
+        let result = run(dir_path, "nonexistent", None, None);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
@@ -311,8 +321,8 @@ mod tests {
         setup_workgraph(dir_path, vec![make_task("t1", "Test task", Status::Open)]);
 
         // Run fail - capture_task_output will be called but may fail in test env
-        // (no git repo). The important thing is that run() itself still succeeds.
-        let result = run(dir_path, "t1", None);
+        // (no git repo). The important thing is that run(, None) itself still succeeds.
+        let result = run(dir_path, "t1", None, None);
         assert!(result.is_ok());
 
         // Verify the task was still properly marked as failed despite capture outcome
@@ -150,7 +150,8 @@ fn calculate_remaining_work(graph: &WorkGraph) -> RemainingWork {
                     open_hours += hours;
                 }
             }
-            Status::Done => {}
+            // The work itself is finished; only sign-off remains
+            Status::Done | Status::InReview => {}
             Status::Failed | Status::Abandoned => {
                 // Failed/abandoned tasks don't count toward remaining work
             }
@@ -312,12 +313,8 @@ fn find_critical_path(graph: &WorkGraph) -> Option<CriticalPath> {
         }
 
         // Check if all blockers are resolved (terminal or nonexistent)
-        let all_blockers_done = task.blocked_by.iter().all(|bid| {
-            graph
-                .get_task(bid)
-                .map(|t| t.status.is_terminal())
-                .unwrap_or(true)
-        });
+        let all_blockers_done =
+            workgraph::query::dependencies_met(task, |bid| graph.get_task(bid).map(|t| t.status));
 
         if all_blockers_done {
             entry_points.push(&task.id);
@@ -1,21 +1,95 @@
 use anyhow::{Context, Result};
 use std::collections::HashSet;
 use std::path::Path;
-use workgraph::graph::Status;
-use workgraph::parser::{load_graph, save_graph};
+use workgraph::graph::{Status, Task, WorkGraph};
+use workgraph::parser::load_graph;
+use workgraph::query::filter::{Filter, FilterContext};
 
 use super::graph_path;
 
 /// Auto-generated task prefixes that should be gc'd alongside their parent task.
 const INTERNAL_PREFIXES: &[&str] = &["assign-", "reward-"];
 
-pub fn run(dir: &Path, dry_run: bool, include_done: bool) -> Result<()> {
+pub fn run(dir: &Path, dry_run: bool, include_done: bool, filter: Option<&str>) -> Result<()> {
     let path = graph_path(dir);
     if !path.exists() {
         anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
     }
+    let filter = super::parse_filter(dir, filter)?;
 
-    let graph = load_graph(&path).context("Failed to load graph")?;
+    if dry_run {
+        let graph = load_graph(&path).context("Failed to load graph")?;
+        let gc_list = gc_candidates(&graph, include_done, filter.as_ref());
+        if gc_list.is_empty() {
+            println!("No tasks to garbage collect.");
+            return Ok(());
+        }
+        println!("Would remove {} tasks:", gc_list.len());
+        for id in &gc_list {
+            if let Some(task) = graph.get_task(id) {
+                println!("  {} - {} [{}]", task.id, task.title, task.status);
+            }
+        }
+        return Ok(());
+    }
+
+    let ((gc_list, removed_details), changes) = super::with_workgraph_changes(dir, |graph| {
+        let gc_list = gc_candidates(graph, include_done, filter.as_ref());
+
+        // Capture details of tasks being removed for provenance
+        let removed_details: Vec<serde_json::Value> = gc_list
+            .iter()
+            .filter_map(|id| {
+                graph.get_task(id).map(|t| {
+                    serde_json::json!({
+                        "id": t.id,
+                        "status": format!("{:?}", t.status),
+                        "title": t.title,
+                    })
+                })
+            })
+            .collect();
+
+        for id in &gc_list {
+            graph.remove_node(id);
+        }
+        Ok((gc_list, removed_details))
+    })?;
+
+    if gc_list.is_empty() {
+        println!("No tasks to garbage collect.");
+        return Ok(());
+    }
+    super::notify_graph_changed(dir);
+
+    // Record operation
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = workgraph::provenance::record(
+        dir,
+        "gc",
+        None,
+        None,
+        workgraph::undo::with_changes(
+            serde_json::json!({ "removed": removed_details }),
+            &changes,
+        ),
+        config.log.rotation_threshold,
+    );
+
+    println!("Removed {} tasks:", gc_list.len());
+    for id in &gc_list {
+        println!("  {}", id);
+    }
+
+    Ok(())
+}
+
+/// IDs of terminal tasks that can be removed without stranding a dependent, sorted.
+/// With a filter, only matching tasks (and the internal tasks of matching
+/// parents) are candidates.
+fn gc_candidates(graph: &WorkGraph, include_done: bool, filter: Option<&Filter>) -> Vec<String> {
+    let ctx = FilterContext::new(graph);
+    let selected = |task: &Task| filter.is_none_or(|f| f.matches(task, &ctx));
 
     // Collect all task IDs and their statuses for dependency checking
     let all_tasks: Vec<_> = graph.tasks().cloned().collect();
@@ -46,6 +120,9 @@ pub fn run(dir: &Path, dry_run: bool, include_done: bool) -> Result<()> {
         if has_open_dependent.contains(&task.id) {
             continue;
         }
+        if !selected(task) {
+            continue;
+        }
         to_gc.insert(task.id.clone());
     }
 
@@ -71,69 +148,19 @@ pub fn run(dir: &Path, dry_run: bool, include_done: bool) -> Result<()> {
         let is_internal = INTERNAL_PREFIXES
             .iter()
             .any(|prefix| task.id.starts_with(prefix));
-        if is_internal && task.status.is_terminal() && !has_open_dependent.contains(&task.id) {
+        if is_internal
+            && task.status.is_terminal()
+            && !has_open_dependent.contains(&task.id)
+            && selected(task)
+        {
             to_gc.insert(task.id.clone());
         }
     }
 
-    if to_gc.is_empty() {
-        println!("No tasks to garbage collect.");
-        return Ok(());
-    }
-
     // Sort for deterministic output
-    let mut gc_list: Vec<_> = to_gc.iter().cloned().collect();
+    let mut gc_list: Vec<_> = to_gc.into_iter().collect();
     gc_list.sort();
-
-    if dry_run {
-        println!("Would remove {} tasks:", gc_list.len());
-        for id in &gc_list {
-            if let Some(task) = graph.get_task(id) {
-                println!("  {} - {} [{}]", task.id, task.title, task.status);
-            }
-        }
-        return Ok(());
-    }
-
-    // Capture details of tasks being removed for provenance
-    let removed_details: Vec<serde_json::Value> = gc_list
-        .iter()
-        .filter_map(|id| {
-            graph.get_task(id).map(|t| {
-                serde_json::json!({
-                    "id": t.id,
-                    "status": format!("{:?}", t.status),
-                    "title": t.title,
-                })
-            })
-        })
-        .collect();
-
-    let mut modified_graph = graph;
-    for id in &gc_list {
-        modified_graph.remove_node(id);
-    }
-
-    save_graph(&modified_graph, &path).context("Failed to save graph")?;
-    super::notify_graph_changed(dir);
-
-    // Record operation
-    let config = workgraph::config::Config::load_or_default(dir);
-    let _ = workgraph::provenance::record(
-        dir,
-        "gc",
-        None,
-        None,
-        serde_json::json!({ "removed": removed_details }),
-        config.log.rotation_threshold,
-    );
-
-    println!("Removed {} tasks:", gc_list.len());
-    for id in &gc_list {
-        println!("  {}", id);
-    }
-
-    Ok(())
+    gc_list
 }
 
 #[cfg(test)]
@@ -141,6 +168,7 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
     use workgraph::graph::{Node, WorkGraph};
+    use workgraph::parser::save_graph;
 
     fn make_task(id: &str, title: &str, status: Status) -> workgraph::graph::Task {
         workgraph::graph::Task {
@@ -194,7 +222,7 @@ mod tests {
             ],
         );
 
-        run(wg_dir, false, false).unwrap();
+        run(wg_dir, false, false, None).unwrap();
 
         let remaining = load_task_ids(wg_dir);
         assert!(
@@ -216,7 +244,7 @@ mod tests {
             ],
         );
 
-        run(wg_dir, false, false).unwrap();
+        run(wg_dir, false, false, None).unwrap();
 
         let remaining = load_task_ids(wg_dir);
         assert!(
@@ -238,7 +266,7 @@ mod tests {
             ],
         );
 
-        run(wg_dir, false, false).unwrap();
+        run(wg_dir, false, false, None).unwrap();
 
         let remaining = load_task_ids(wg_dir);
         assert!(
@@ -260,7 +288,7 @@ mod tests {
             ],
         );
 
-        run(wg_dir, true, false).unwrap();
+        run(wg_dir, true, false, None).unwrap();
 
         let remaining = load_task_ids(wg_dir);
         assert!(
@@ -284,7 +312,7 @@ mod tests {
             ],
         );
 
-        run(wg_dir, false, false).unwrap();
+        run(wg_dir, false, false, None).unwrap();
 
         let remaining = load_task_ids(wg_dir);
         assert!(!remaining.contains("my-task"), "parent should be removed");
@@ -311,7 +339,7 @@ mod tests {
             ],
         );
 
-        run(wg_dir, false, false).unwrap();
+        run(wg_dir, false, false, None).unwrap();
 
         let remaining = load_task_ids(wg_dir);
         assert!(
@@ -336,7 +364,7 @@ mod tests {
             ],
         );
 
-        run(wg_dir, false, true).unwrap();
+        run(wg_dir, false, true, None).unwrap();
 
         let remaining = load_task_ids(wg_dir);
         assert!(
@@ -363,7 +391,7 @@ mod tests {
             ],
         );
 
-        run(wg_dir, false, false).unwrap();
+        run(wg_dir, false, false, None).unwrap();
 
         let remaining = load_task_ids(wg_dir);
         assert!(
@@ -394,7 +422,7 @@ mod tests {
             ],
         );
 
-        run(wg_dir, false, false).unwrap();
+        run(wg_dir, false, false, None).unwrap();
 
         let remaining = load_task_ids(wg_dir);
         assert!(
@@ -409,7 +437,35 @@ mod tests {
         let wg_dir = dir.path();
         setup_graph(wg_dir, vec![]);
 
-        run(wg_dir, false, false).unwrap();
+        run(wg_dir, false, false, None).unwrap();
         // Should not panic, just print "No tasks to garbage collect."
     }
+
+    #[test]
+    fn gc_filter_restricts_candidates() {
+        let dir = tempdir().unwrap();
+        let wg_dir = dir.path();
+        let mut tagged = make_task("task-a", "Failed experiment", Status::Failed);
+        tagged.tags = vec!["experiment".to_string()];
+        setup_graph(
+            wg_dir,
+            vec![
+                tagged,
+                make_task("task-b", "Failed build", Status::Failed),
+                make_task("assign-task-a", "Assign task-a", Status::Done),
+            ],
+        );
+
+        run(wg_dir, false, false, Some("tag:experiment")).unwrap();
+
+        let remaining = load_task_ids(wg_dir);
+        assert!(!remaining.contains("task-a"), "matching task should be removed");
+        assert!(
+            !remaining.contains("assign-task-a"),
+            "internal task of a removed parent should go with it"
+        );
+        assert!(remaining.contains("task-b"), "non-matching task should remain");
+
+        assert!(run(wg_dir, false, false, Some("tag:(")).is_err());
+    }
 }
@@ -5,6 +5,7 @@ use std::io::{BufRead, BufReader};
 use std::path::Path;
 use workgraph::graph::{Node, Status, Task};
 use workgraph::parser::load_graph;
+use workgraph::query::filter::FilterContext;
 
 use super::graph_path;
 
@@ -91,6 +92,7 @@ fn status_color(status: &Status, is_archived: bool) -> &'static str {
         Status::Open => "white",       // Ready to pick up
         Status::Failed => "salmon",    // Red-ish - needs attention
         Status::Abandoned => "lightgray", // Grayed out
+        Status::InReview => "plum",       // Waiting on a reviewer
     }
 }
 
@@ -111,23 +113,30 @@ pub fn run(
     include_archive: bool,
     since: Option<&str>,
     until: Option<&str>,
+    filter: Option<&str>,
 ) -> Result<()> {
     let path = graph_path(dir);
 
     if !path.exists() {
         anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
     }
+    let filter = super::parse_filter(dir, filter)?;
 
     // Parse date filters
     let since_dt = since.map(parse_date).transpose()?;
     let until_dt = until.map(parse_date).transpose()?;
 
     let graph = load_graph(&path).context("Failed to load graph")?;
+    let ctx = FilterContext::new(&graph);
+    let wanted = |t: &Task| {
+        in_date_range(t, since_dt.as_ref(), until_dt.as_ref())
+            && filter.as_ref().is_none_or(|f| f.matches(t, &ctx))
+    };
 
     // Collect tasks from main graph
     let mut all_tasks: Vec<(Task, bool)> = graph
         .tasks()
-        .filter(|t| in_date_range(t, since_dt.as_ref(), until_dt.as_ref()))
+        .filter(|t| wanted(t))
         .map(|t| (t.clone(), false))
         .collect();
 
@@ -136,7 +145,7 @@ pub fn run(
         let arch_path = archive_path(dir);
         let archived = load_archive(&arch_path)?;
         for task in archived {
-            if in_date_range(&task, since_dt.as_ref(), until_dt.as_ref()) {
+            if wanted(&task) {
                 all_tasks.push((task, true));
             }
         }
@@ -367,7 +376,7 @@ mod tests {
     fn test_run_not_initialized() {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join(".workgraph");
-        let result = run(&dir, false, None, None);
+        let result = run(&dir, false, None, None, None);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not initialized"));
     }
@@ -383,7 +392,7 @@ mod tests {
         graph.add_node(Node::Task(make_task("t2", "Second task")));
         setup_graph(&dir, &graph);
 
-        let result = run(&dir, false, None, None);
+        let result = run(&dir, false, None, None, None);
         assert!(result.is_ok());
     }
 
@@ -400,7 +409,7 @@ mod tests {
         graph.add_node(Node::Task(t2));
         setup_graph(&dir, &graph);
 
-        let result = run(&dir, false, None, None);
+        let result = run(&dir, false, None, None, None);
         assert!(result.is_ok());
     }
 
@@ -420,7 +429,7 @@ mod tests {
         }));
         setup_graph(&dir, &graph);
 
-        let result = run(&dir, false, None, None);
+        let result = run(&dir, false, None, None, None);
         assert!(result.is_ok());
     }
 
@@ -441,7 +450,7 @@ mod tests {
         setup_graph(&dir, &graph);
 
         // Both should pass with --since filtering
-        let result = run(&dir, false, Some("2024-06-01"), None);
+        let result = run(&dir, false, Some("2024-06-01"), None, None);
         assert!(result.is_ok());
     }
 
@@ -455,7 +464,7 @@ mod tests {
         setup_graph(&dir, &graph);
 
         // include_archive=true but no archive.jsonl exists — should still succeed
-        let result = run(&dir, true, None, None);
+        let result = run(&dir, true, None, None, None);
         assert!(result.is_ok());
     }
 
@@ -479,7 +488,7 @@ mod tests {
         let arch = archive_path(&dir);
         std::fs::write(&arch, format!("{}\n", archived_task)).unwrap();
 
-        let result = run(&dir, true, None, None);
+        let result = run(&dir, true, None, None, None);
         assert!(result.is_ok());
     }
 
@@ -492,7 +501,7 @@ mod tests {
         graph.add_node(Node::Task(make_task("t1", "Task")));
         setup_graph(&dir, &graph);
 
-        let result = run(&dir, false, Some("bad-date"), None);
+        let result = run(&dir, false, Some("bad-date"), None, None);
         assert!(result.is_err());
     }
 
@@ -10,6 +10,9 @@ agents/
 # Service files
 service/
 
+# Search index (rebuilt by `wg search --reindex`)
+search-index.json
+
 # Never commit credentials (Matrix config should be in ~/.config/workgraph/)
 matrix.toml
 *.secret
@@ -12,7 +12,7 @@ use chrono::Utc;
 use std::path::Path;
 use std::time::Duration;
 use workgraph::graph::{LogEntry, Status};
-use workgraph::parser::{load_graph, save_graph};
+use workgraph::parser::with_graph_mut;
 use workgraph::service::{AgentRegistry, AgentStatus};
 
 use super::graph_path;
@@ -254,24 +254,28 @@ fn unclaim_task(dir: &Path, task_id: &str, agent_id: &str) -> Result<()> {
         return Ok(()); // No graph, nothing to unclaim
     }
 
-    let mut graph = load_graph(&path).context("Failed to load graph")?;
-
-    if let Some(task) = graph.get_task_mut(task_id) {
+    let unclaimed = with_graph_mut(&path, |graph| -> Result<bool> {
+        let Some(task) = graph.get_task_mut(task_id) else {
+            return Ok(false);
+        };
         // Only unclaim if task is in progress
-        if task.status == Status::InProgress {
-            task.status = Status::Open;
-            task.assigned = None;
-
-            // Add log entry
-            task.log.push(LogEntry {
-                timestamp: Utc::now().to_rfc3339(),
-                actor: None,
-                message: format!("Task unclaimed: agent '{}' was killed", agent_id),
-            });
-
-            save_graph(&graph, &path).context("Failed to save graph")?;
-            super::notify_graph_changed(dir);
+        if task.status != Status::InProgress {
+            return Ok(false);
         }
+        task.status = Status::Open;
+        task.assigned = None;
+
+        // Add log entry
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: None,
+            message: format!("Task unclaimed: agent '{}' was killed", agent_id),
+        });
+        Ok(true)
+    })?;
+
+    if unclaimed {
+        super::notify_graph_changed(dir);
     }
 
     Ok(())
@@ -285,7 +289,7 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
     use workgraph::graph::{Node, Task, WorkGraph};
-    use workgraph::parser::save_graph;
+    use workgraph::parser::{load_graph, save_graph};
 
     fn make_task(id: &str, title: &str, status: Status) -> Task {
         Task {
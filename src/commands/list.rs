@@ -1,11 +1,22 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use std::collections::HashSet;
 use std::path::Path;
-use workgraph::graph::Status;
-
-pub fn run(dir: &Path, status_filter: Option<&str>, paused_only: bool, json: bool) -> Result<()> {
-    let (graph, _path) = super::load_workgraph(dir)?;
-
+use workgraph::graph::{Priority, Status, Task, WorkGraph};
+use workgraph::query::filter::FilterContext;
+use workgraph::query::rollup;
+
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    dir: &Path,
+    status_filter: Option<&str>,
+    paused_only: bool,
+    tree: bool,
+    field_filters: &[String],
+    filter: Option<&str>,
+    at: Option<&str>,
+    json: bool,
+) -> Result<()> {
     let status_filter: Option<Status> = match status_filter {
         Some("open") => Some(Status::Open),
         Some("done") => Some(Status::Done),
@@ -13,64 +24,216 @@ pub fn run(dir: &Path, status_filter: Option<&str>, paused_only: bool, json: boo
         Some("blocked") => Some(Status::Blocked),
         Some("failed") => Some(Status::Failed),
         Some("abandoned") => Some(Status::Abandoned),
+        Some("in-review") => Some(Status::InReview),
         Some(s) => anyhow::bail!(
-            "Unknown status: '{}'. Valid values: open, in-progress, done, blocked, failed, abandoned",
+            "Unknown status: '{}'. Valid values: open, in-progress, done, blocked, failed, abandoned, in-review",
             s
         ),
         None => None,
     };
+    let field_filters = field_filters
+        .iter()
+        .map(|f| workgraph::fields::parse_assignment(f))
+        .collect::<Result<Vec<_>>>()?;
+    let filter = super::parse_filter(dir, filter)?;
+
+    if tree || filter.is_some() || at.is_some() {
+        // Rollups, filter expressions and past states need the whole graph
+        let graph = super::load_workgraph_at(dir, at)?;
+        let ctx = FilterContext::new(&graph);
+        let tasks: Vec<&Task> = graph
+            .tasks()
+            .filter(|t| status_filter.is_none_or(|s| t.status == s))
+            .filter(|t| !paused_only || t.paused)
+            .filter(|t| workgraph::fields::matches(&t.fields, &field_filters))
+            .filter(|t| filter.as_ref().is_none_or(|f| f.matches(t, &ctx)))
+            .collect();
+        if tree {
+            return print_tree(&graph, &tasks, json);
+        }
+        return print_tasks(&tasks, json);
+    }
 
-    let tasks: Vec<_> = graph
-        .tasks()
-        .filter(|t| status_filter.as_ref().is_none_or(|s| &t.status == s))
+    // A status filter is answered by the store, which uses its index on SQLite
+    let tasks: Vec<Task> = match status_filter {
+        Some(status) => super::open_store(dir)?
+            .tasks_with_status(status)
+            .context("Failed to load graph")?,
+        None => {
+            let (graph, _path) = super::load_workgraph(dir)?;
+            graph.tasks().cloned().collect()
+        }
+    };
+    let tasks: Vec<&Task> = tasks
+        .iter()
         .filter(|t| !paused_only || t.paused)
+        .filter(|t| workgraph::fields::matches(&t.fields, &field_filters))
         .collect();
+    print_tasks(&tasks, json)
+}
 
+fn print_tasks(tasks: &[&Task], json: bool) -> Result<()> {
     if json {
-        let output: Vec<_> = tasks
-            .iter()
-            .map(|t| {
-                let mut obj = serde_json::json!({
-                    "id": t.id,
-                    "title": t.title,
-                    "status": t.status,
-                    "assigned": t.assigned,
-                    "blocked_by": t.blocked_by,
-                });
-                if let Some(ref ra) = t.ready_after {
-                    obj["ready_after"] = serde_json::json!(ra);
-                }
-                if t.paused {
-                    obj["paused"] = serde_json::json!(true);
-                }
-                obj
-            })
-            .collect();
+        let output: Vec<_> = tasks.iter().map(|t| task_json(t)).collect();
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else if tasks.is_empty() {
         println!("No tasks found");
     } else {
         for task in tasks {
-            let status = match task.status {
-                Status::Open => "[ ]",
-                Status::InProgress => "[~]",
-                Status::Done => "[x]",
-                Status::Blocked => "[!]",
-                Status::Failed => "[F]",
-                Status::Abandoned => "[A]",
-            };
-            let pause_str = if task.paused { " [PAUSED]" } else { "" };
-            let delay_str = format_ready_after_hint(task.ready_after.as_deref());
-            println!(
-                "{} {} - {}{}{}",
-                status, task.id, task.title, pause_str, delay_str
-            );
+            println!("{}", format_task_line(task));
         }
     }
 
     Ok(())
 }
 
+fn task_json(t: &Task) -> serde_json::Value {
+    let mut obj = serde_json::json!({
+        "id": t.id,
+        "title": t.title,
+        "status": t.status,
+        "assigned": t.assigned,
+        "blocked_by": t.blocked_by,
+    });
+    if let Some(ref ra) = t.ready_after {
+        obj["ready_after"] = serde_json::json!(ra);
+    }
+    if t.paused {
+        obj["paused"] = serde_json::json!(true);
+    }
+    if t.priority != Priority::Normal {
+        obj["priority"] = serde_json::json!(t.priority);
+    }
+    if let Some(ref parent) = t.parent {
+        obj["parent"] = serde_json::json!(parent);
+    }
+    if !t.fields.is_empty() {
+        obj["fields"] = serde_json::json!(t.fields);
+    }
+    obj
+}
+
+fn format_task_line(task: &Task) -> String {
+    let status = match task.status {
+        Status::Open => "[ ]",
+        Status::InProgress => "[~]",
+        Status::Done => "[x]",
+        Status::Blocked => "[!]",
+        Status::Failed => "[F]",
+        Status::Abandoned => "[A]",
+        Status::InReview => "[?]",
+    };
+    let pause_str = if task.paused { " [PAUSED]" } else { "" };
+    let priority_str = format_priority_hint(task.priority);
+    let delay_str = format_ready_after_hint(task.ready_after.as_deref());
+    let fields_str = if task.fields.is_empty() {
+        String::new()
+    } else {
+        format!(" {{{}}}", workgraph::fields::display_all(&task.fields))
+    };
+    format!(
+        "{} {} - {}{}{}{}{}",
+        status, task.id, task.title, priority_str, pause_str, delay_str, fields_str
+    )
+}
+
+/// Print `tasks` nested under their parents. A task whose parent is not
+/// among `tasks` (filtered out or missing) is shown at the top level.
+fn print_tree(graph: &WorkGraph, tasks: &[&Task], json: bool) -> Result<()> {
+    let included: HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+    let mut roots: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| {
+            t.parent
+                .as_deref()
+                .is_none_or(|p| !included.contains(p) || p == t.id)
+        })
+        .copied()
+        .collect();
+    roots.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut visited = HashSet::new();
+    if json {
+        let output: Vec<_> = roots
+            .iter()
+            .map(|t| tree_json(graph, t, &included, &mut visited))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if roots.is_empty() {
+        println!("No tasks found");
+    } else {
+        for task in roots {
+            print_tree_node(graph, task, &included, 0, &mut visited);
+        }
+    }
+    Ok(())
+}
+
+fn print_tree_node<'a>(
+    graph: &'a WorkGraph,
+    task: &'a Task,
+    included: &HashSet<&str>,
+    depth: usize,
+    visited: &mut HashSet<&'a str>,
+) {
+    if !visited.insert(task.id.as_str()) {
+        return;
+    }
+    let rollup_str = rollup(graph, &task.id)
+        .map(|r| {
+            format!(
+                " ({}/{} done, {:.0}%)",
+                r.done,
+                r.total - r.abandoned,
+                r.progress
+            )
+        })
+        .unwrap_or_default();
+    println!(
+        "{}{}{}",
+        "  ".repeat(depth),
+        format_task_line(task),
+        rollup_str
+    );
+    for child in graph.children(&task.id) {
+        if included.contains(child.id.as_str()) {
+            print_tree_node(graph, child, included, depth + 1, visited);
+        }
+    }
+}
+
+fn tree_json<'a>(
+    graph: &'a WorkGraph,
+    task: &'a Task,
+    included: &HashSet<&str>,
+    visited: &mut HashSet<&'a str>,
+) -> serde_json::Value {
+    visited.insert(task.id.as_str());
+    let mut obj = task_json(task);
+    if let Some(r) = rollup(graph, &task.id) {
+        obj["rollup"] = serde_json::json!(r);
+    }
+    let mut children = Vec::new();
+    for child in graph.children(&task.id) {
+        if included.contains(child.id.as_str()) && !visited.contains(child.id.as_str()) {
+            children.push(tree_json(graph, child, included, visited));
+        }
+    }
+    if !children.is_empty() {
+        obj["children"] = serde_json::json!(children);
+    }
+    obj
+}
+
+/// Non-default priorities are shown inline, e.g. " [high]"; normal is omitted.
+pub(crate) fn format_priority_hint(priority: Priority) -> String {
+    if priority == Priority::Normal {
+        String::new()
+    } else {
+        format!(" [{}]", priority)
+    }
+}
+
 /// If ready_after is set and in the future, return a hint string like " [ready in 5m 30s]".
 fn format_ready_after_hint(ready_after: Option<&str>) -> String {
     let Some(ra) = ready_after else {
@@ -182,7 +345,7 @@ mod tests {
     #[test]
     fn test_run_uninitialized() {
         let dir = tempdir().unwrap();
-        let result = run(dir.path(), None, false, false);
+        let result = run(dir.path(), None, false, false, &[], None, None, false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not initialized"));
     }
@@ -191,7 +354,7 @@ mod tests {
     fn test_run_no_tasks() {
         let dir = tempdir().unwrap();
         setup_workgraph(dir.path(), vec![]);
-        let result = run(dir.path(), None, false, false);
+        let result = run(dir.path(), None, false, false, &[], None, None, false);
         assert!(result.is_ok());
     }
 
@@ -206,7 +369,16 @@ mod tests {
                 make_task("t3", "In-progress task", Status::InProgress),
             ],
         );
-        let result = run(dir.path(), Some("open"), false, false);
+        let result = run(
+            dir.path(),
+            Some("open"),
+            false,
+            false,
+            &[],
+            None,
+            None,
+            false,
+        );
         assert!(result.is_ok());
     }
 
@@ -220,7 +392,16 @@ mod tests {
                 make_task("t2", "Done task", Status::Done),
             ],
         );
-        let result = run(dir.path(), Some("done"), false, false);
+        let result = run(
+            dir.path(),
+            Some("done"),
+            false,
+            false,
+            &[],
+            None,
+            None,
+            false,
+        );
         assert!(result.is_ok());
     }
 
@@ -231,7 +412,16 @@ mod tests {
             dir.path(),
             vec![make_task("t1", "IP task", Status::InProgress)],
         );
-        let result = run(dir.path(), Some("in-progress"), false, false);
+        let result = run(
+            dir.path(),
+            Some("in-progress"),
+            false,
+            false,
+            &[],
+            None,
+            None,
+            false,
+        );
         assert!(result.is_ok());
     }
 
@@ -242,7 +432,16 @@ mod tests {
             dir.path(),
             vec![make_task("t1", "Blocked task", Status::Blocked)],
         );
-        let result = run(dir.path(), Some("blocked"), false, false);
+        let result = run(
+            dir.path(),
+            Some("blocked"),
+            false,
+            false,
+            &[],
+            None,
+            None,
+            false,
+        );
         assert!(result.is_ok());
     }
 
@@ -250,7 +449,16 @@ mod tests {
     fn test_run_unknown_status_filter() {
         let dir = tempdir().unwrap();
         setup_workgraph(dir.path(), vec![make_task("t1", "Task", Status::Open)]);
-        let result = run(dir.path(), Some("nonexistent-status"), false, false);
+        let result = run(
+            dir.path(),
+            Some("nonexistent-status"),
+            false,
+            false,
+            &[],
+            None,
+            None,
+            false,
+        );
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Unknown status"));
     }
@@ -293,6 +501,124 @@ mod tests {
         assert_eq!(all.len(), 4);
     }
 
+    #[test]
+    fn test_run_tree_nests_children() {
+        let dir = tempdir().unwrap();
+        let mut child = make_task("child", "Child", Status::Done);
+        child.parent = Some("epic".to_string());
+        let mut orphan = make_task("orphan", "Orphan", Status::Open);
+        orphan.parent = Some("missing".to_string());
+        setup_workgraph(
+            dir.path(),
+            vec![make_task("epic", "Epic", Status::Open), child, orphan],
+        );
+
+        assert!(run(dir.path(), None, false, true, &[], None, None, false).is_ok());
+        assert!(run(dir.path(), None, false, true, &[], None, None, true).is_ok());
+        // Filtering out the parent lifts the child to the top level
+        assert!(
+            run(
+                dir.path(),
+                Some("done"),
+                false,
+                true,
+                &[],
+                None,
+                None,
+                false
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_field_filter_and_display() {
+        let dir = tempdir().unwrap();
+        let mut acme = make_task("t1", "Acme work", Status::Open);
+        acme.fields
+            .insert("customer".to_string(), serde_json::json!("acme"));
+        acme.fields
+            .insert("points".to_string(), serde_json::json!(3));
+        setup_workgraph(
+            dir.path(),
+            vec![acme.clone(), make_task("t2", "Other", Status::Open)],
+        );
+
+        assert_eq!(
+            format_task_line(&acme),
+            "[ ] t1 - Acme work {customer=acme points=3}"
+        );
+        assert_eq!(task_json(&acme)["fields"]["points"], 3);
+        let filter = vec!["customer=acme".to_string()];
+        assert!(run(dir.path(), None, false, false, &filter, None, None, false).is_ok());
+        let bad = vec!["customer".to_string()];
+        assert!(run(dir.path(), None, false, false, &bad, None, None, false).is_err());
+    }
+
+    #[test]
+    fn test_filter_expression_and_saved_query() {
+        let dir = tempdir().unwrap();
+        let mut rust = make_task("t1", "Rust work", Status::Open);
+        rust.tags = vec!["rust".to_string()];
+        setup_workgraph(
+            dir.path(),
+            vec![rust, make_task("t2", "Other", Status::Done)],
+        );
+        std::fs::write(
+            dir.path().join("config.toml"),
+            "[queries]\nrusty = \"tag:rust and status:open\"\n",
+        )
+        .unwrap();
+
+        for expr in [
+            "tag:rust",
+            "@rusty",
+            "not @rusty",
+            "status:done or is:ready",
+        ] {
+            assert!(run(dir.path(), None, false, false, &[], Some(expr), None, false).is_ok());
+        }
+        assert!(
+            run(
+                dir.path(),
+                None,
+                false,
+                true,
+                &[],
+                Some("@rusty"),
+                None,
+                true
+            )
+            .is_ok()
+        );
+        assert!(
+            run(
+                dir.path(),
+                None,
+                false,
+                false,
+                &[],
+                Some("@missing"),
+                None,
+                false
+            )
+            .is_err()
+        );
+        assert!(
+            run(
+                dir.path(),
+                None,
+                false,
+                false,
+                &[],
+                Some("hours>"),
+                None,
+                false
+            )
+            .is_err()
+        );
+    }
+
     // --- run() tests: ready_after display ---
 
     #[test]
@@ -303,7 +629,7 @@ mod tests {
         task.ready_after = Some(future.to_rfc3339());
         setup_workgraph(dir.path(), vec![task]);
 
-        let result = run(dir.path(), None, false, false);
+        let result = run(dir.path(), None, false, false, &[], None, None, false);
         assert!(result.is_ok());
     }
 
@@ -317,7 +643,7 @@ mod tests {
         task.blocked_by = vec!["dep-1".to_string()];
         setup_workgraph(dir.path(), vec![task]);
 
-        let result = run(dir.path(), None, false, true);
+        let result = run(dir.path(), None, false, false, &[], None, None, true);
         assert!(result.is_ok());
     }
 
@@ -389,7 +715,16 @@ mod tests {
                 make_task("t2", "Open task", Status::Open),
             ],
         );
-        let result = run(dir.path(), Some("failed"), false, false);
+        let result = run(
+            dir.path(),
+            Some("failed"),
+            false,
+            false,
+            &[],
+            None,
+            None,
+            false,
+        );
         assert!(result.is_ok());
     }
 
@@ -403,7 +738,16 @@ mod tests {
                 make_task("t2", "Open task", Status::Open),
             ],
         );
-        let result = run(dir.path(), Some("abandoned"), false, false);
+        let result = run(
+            dir.path(),
+            Some("abandoned"),
+            false,
+            false,
+            &[],
+            None,
+            None,
+            false,
+        );
         assert!(result.is_ok());
     }
 
@@ -411,7 +755,16 @@ mod tests {
     fn test_unknown_status_error_lists_valid_values() {
         let dir = tempdir().unwrap();
         setup_workgraph(dir.path(), vec![make_task("t1", "Task", Status::Open)]);
-        let result = run(dir.path(), Some("bogus"), false, false);
+        let result = run(
+            dir.path(),
+            Some("bogus"),
+            false,
+            false,
+            &[],
+            None,
+            None,
+            false,
+        );
         assert!(result.is_err());
         let msg = result.unwrap_err().to_string();
         assert!(msg.contains("Valid values:"));
@@ -455,7 +808,16 @@ mod tests {
                 make_task("t2", "Done", Status::Done),
             ],
         );
-        let result = run(dir.path(), Some("done"), false, true);
+        let result = run(
+            dir.path(),
+            Some("done"),
+            false,
+            false,
+            &[],
+            None,
+            None,
+            true,
+        );
         assert!(result.is_ok());
     }
 
@@ -486,11 +848,20 @@ mod tests {
         assert_eq!(paused_open[0].id, "t-paused");
 
         // run() with paused_only=true should succeed
-        let result = run(dir.path(), None, true, false);
+        let result = run(dir.path(), None, true, false, &[], None, None, false);
         assert!(result.is_ok());
 
         // run() with paused_only=true and status filter should succeed
-        let result = run(dir.path(), Some("open"), true, false);
+        let result = run(
+            dir.path(),
+            Some("open"),
+            true,
+            false,
+            &[],
+            None,
+            None,
+            false,
+        );
         assert!(result.is_ok());
     }
 }
@@ -3,28 +3,37 @@ use chrono::Utc;
 use std::fs;
 use std::path::{Path, PathBuf};
 use workgraph::graph::LogEntry;
-use workgraph::parser::save_graph;
 
 #[cfg(test)]
 use super::graph_path;
 
 /// Add a log entry to a task
 pub fn run_add(dir: &Path, id: &str, message: &str, actor: Option<&str>) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
+    let ((), changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
 
-    let task = graph.get_task_mut_or_err(id)?;
+        let entry = LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: actor.map(String::from),
+            message: message.to_string(),
+        };
 
-    let entry = LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: actor.map(String::from),
-        message: message.to_string(),
-    };
-
-    task.log.push(entry);
-
-    save_graph(&graph, &path).context("Failed to save graph")?;
+        task.log.push(entry);
+        Ok(())
+    })?;
     super::notify_graph_changed(dir);
 
+    // Record operation
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = workgraph::provenance::record(
+        dir,
+        "log",
+        Some(id),
+        actor,
+        workgraph::undo::with_changes(serde_json::Value::Null, &changes),
+        config.log.rotation_threshold,
+    );
+
     let actor_str = actor.map(|a| format!(" ({})", a)).unwrap_or_default();
     println!("Added log entry to '{}'{}", id, actor_str);
     Ok(())
@@ -102,11 +102,7 @@ pub struct LoopEdgeInfo {
 fn format_guard(guard: &Option<LoopGuard>) -> String {
     match guard {
         None => "none (always)".to_string(),
-        Some(LoopGuard::Always) => "always".to_string(),
-        Some(LoopGuard::IterationLessThan(n)) => format!("iteration < {}", n),
-        Some(LoopGuard::TaskStatus { task, status }) => {
-            format!("task:{}={:?}", task, status)
-        }
+        Some(guard) => guard.to_string(),
     }
 }
 
@@ -286,7 +282,7 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use workgraph::graph::{Node, Status, Task};
+    use workgraph::graph::{Node, Priority, Status, Task};
 
     fn make_task(id: &str, title: &str) -> Task {
         Task {
@@ -328,6 +324,21 @@ mod tests {
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         }
     }
 
@@ -0,0 +1,80 @@
+//! Merge driver command - semantic three-way merge of graph.jsonl for git
+//!
+//! Register it once per clone:
+//!   git config merge.workgraph.name "workgraph graph merge"
+//!   git config merge.workgraph.driver "wg merge-driver %O %A %B"
+//!   echo ".workgraph/graph.jsonl merge=workgraph" >> .gitattributes
+//!
+//! Git calls it with the base, ours and theirs versions of the file; the
+//! merged graph is written over ours. Conflicting tasks are tagged `conflict`
+//! rather than left with conflict markers, so the merge always succeeds.
+
+use anyhow::Result;
+use std::path::Path;
+use workgraph::merge::{self, CONFLICT_TAG};
+
+pub fn run(base: &Path, ours: &Path, theirs: &Path) -> Result<()> {
+    let conflicts = merge::merge_files(base, ours, theirs)?;
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    // Git shows the driver's stderr while merging
+    eprintln!(
+        "workgraph: {} task(s) changed on both branches, tagged '{}':",
+        conflicts.len(),
+        CONFLICT_TAG
+    );
+    for conflict in &conflicts {
+        eprintln!("  {}: {}", conflict.id, conflict.fields.join(", "));
+    }
+    eprintln!("Resolve with 'wg list --filter tag:{}' and 'wg edit'", CONFLICT_TAG);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use workgraph::graph::{Node, Status, Task, WorkGraph};
+    use workgraph::parser::{load_graph, save_graph};
+
+    fn write(path: &Path, tasks: &[(&str, Status)]) {
+        let mut graph = WorkGraph::new();
+        for (id, status) in tasks {
+            graph.add_node(Node::Task(Task {
+                id: id.to_string(),
+                title: id.to_string(),
+                status: *status,
+                ..Task::default()
+            }));
+        }
+        save_graph(&graph, path).unwrap();
+    }
+
+    #[test]
+    fn test_merge_driver_writes_merged_graph_sorted() {
+        let dir = tempdir().unwrap();
+        let (base, ours, theirs) = (
+            dir.path().join("base"),
+            dir.path().join("ours"),
+            dir.path().join("theirs"),
+        );
+        write(&base, &[("b", Status::Open)]);
+        write(&ours, &[("b", Status::InProgress), ("c", Status::Open)]);
+        write(&theirs, &[("b", Status::Done), ("a", Status::Open)]);
+
+        run(&base, &ours, &theirs).unwrap();
+
+        let content = std::fs::read_to_string(&ours).unwrap();
+        let ids: Vec<String> = content
+            .lines()
+            .filter_map(|l| {
+                let node: serde_json::Value = serde_json::from_str(l).unwrap();
+                node["id"].as_str().map(str::to_string)
+            })
+            .collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        let merged = load_graph(&ours).unwrap();
+        assert_eq!(merged.get_task("b").unwrap().status, Status::Done);
+    }
+}
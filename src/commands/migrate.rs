@@ -0,0 +1,367 @@
+//! Migrate command - convert the graph between storage formats
+//!
+//! Usage:
+//!   wg migrate                 # Show the current storage format
+//!   wg migrate --to journal    # Append mutations to graph.journal over a snapshot
+//!   wg migrate --to sqlite     # Move the graph into graph.db (needs the `sqlite` feature)
+//!   wg migrate --to jsonl      # Convert back to a plain graph.jsonl
+//!   wg migrate --compact       # Fold the journal into the snapshot now
+//!   wg migrate --check         # List schema migrations the graph still needs
+//!   wg migrate --apply         # Run them now, keeping a backup
+//!
+//! In journaled mode `graph.jsonl` stays a valid JSONL graph (the last
+//! compacted snapshot). In SQLite mode it only holds a comment pointing at
+//! `graph.db`. Every migration is reversible with `--to jsonl`.
+//!
+//! Schema migrations (see `workgraph::schema`) upgrade the graph's contents
+//! rather than its storage. Older graphs are upgraded in memory on load
+//! anyway; `--apply` rewrites them on disk right away.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use workgraph::parser::{
+    JOURNAL_COMPACT_THRESHOLD, compact_graph, is_journaled, is_sqlite, journal_path, migrate_schema,
+    schema_version, set_journaled, sqlite_path,
+};
+use workgraph::schema::{self, SCHEMA_VERSION};
+#[cfg(feature = "sqlite")]
+use workgraph::store::sqlite::set_sqlite;
+
+use super::graph_path;
+
+/// Storage format of the graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageFormat {
+    /// Whole graph rewritten on every save
+    Jsonl,
+    /// Snapshot plus append-only journal of delta records
+    Journal,
+    /// SQLite database with indexed status and tag lookups
+    Sqlite,
+}
+
+impl std::fmt::Display for StorageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageFormat::Jsonl => write!(f, "jsonl"),
+            StorageFormat::Journal => write!(f, "journal"),
+            StorageFormat::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+impl std::str::FromStr for StorageFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "jsonl" => Ok(StorageFormat::Jsonl),
+            "journal" => Ok(StorageFormat::Journal),
+            "sqlite" => Ok(StorageFormat::Sqlite),
+            _ => anyhow::bail!(
+                "Unknown storage format '{}'. Must be: jsonl, journal, sqlite",
+                s
+            ),
+        }
+    }
+}
+
+/// Current storage format of the graph in `dir`
+pub fn current_format(dir: &Path) -> StorageFormat {
+    let path = graph_path(dir);
+    if is_sqlite(&path) {
+        StorageFormat::Sqlite
+    } else if is_journaled(&path) {
+        StorageFormat::Journal
+    } else {
+        StorageFormat::Jsonl
+    }
+}
+
+pub fn run(dir: &Path, to: Option<&str>, compact: bool, check: bool, apply: bool) -> Result<()> {
+    let path = graph_path(dir);
+    if !path.exists() {
+        anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
+    }
+
+    if check {
+        return check_schema(&path);
+    }
+    if apply {
+        apply_schema(dir, &path)?;
+    }
+
+    let current = current_format(dir);
+
+    if compact {
+        if current != StorageFormat::Journal {
+            println!("Graph is not journaled; nothing to compact.");
+        } else {
+            compact_graph(&path).context("Failed to compact graph journal")?;
+            println!("Compacted journal into {}", path.display());
+        }
+    }
+
+    let Some(to) = to else {
+        if !compact && !apply {
+            print_format(&path, current);
+        }
+        return Ok(());
+    };
+    let target: StorageFormat = to.parse()?;
+
+    if target == current {
+        println!("Graph already uses {} storage.", current);
+        return Ok(());
+    }
+
+    convert(&path, current, target)
+        .with_context(|| format!("Failed to migrate graph to {} storage", target))?;
+    super::notify_graph_changed(dir);
+
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = workgraph::provenance::record(
+        dir,
+        "migrate",
+        None,
+        None,
+        serde_json::json!({ "from": current.to_string(), "to": target.to_string() }),
+        config.log.rotation_threshold,
+    );
+
+    println!("Migrated graph storage: {} -> {}", current, target);
+    if target == StorageFormat::Journal {
+        println!(
+            "  Mutations are appended to {}; compaction runs every {} records.",
+            journal_path(&path).display(),
+            JOURNAL_COMPACT_THRESHOLD
+        );
+    }
+    if target == StorageFormat::Sqlite {
+        println!(
+            "  Nodes are stored in {}; {} now only points at it.",
+            sqlite_path(&path).display(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Move the graph at `path` from `current` to `target` storage.
+/// Leaving SQLite goes through plain JSONL first.
+fn convert(
+    path: &Path,
+    current: StorageFormat,
+    target: StorageFormat,
+) -> Result<(), workgraph::parser::ParseError> {
+    if current == StorageFormat::Sqlite {
+        set_sqlite(path, false)?;
+    }
+    match target {
+        StorageFormat::Jsonl => set_journaled(path, false),
+        StorageFormat::Journal => set_journaled(path, true),
+        StorageFormat::Sqlite => set_sqlite(path, true),
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn set_sqlite(path: &Path, _enabled: bool) -> Result<(), workgraph::parser::ParseError> {
+    Err(workgraph::parser::ParseError::SqliteUnsupported(
+        sqlite_path(path),
+    ))
+}
+
+/// List the schema migrations the graph still needs; fails if there are any
+fn check_schema(path: &Path) -> Result<()> {
+    let version = schema_version(path).context("Failed to read graph schema version")?;
+    if version > SCHEMA_VERSION {
+        anyhow::bail!(
+            "Graph uses schema version {}, newer than this wg supports ({}); upgrade wg",
+            version,
+            SCHEMA_VERSION
+        );
+    }
+    let pending = schema::pending(version);
+    if pending.is_empty() {
+        println!("Graph schema version {} is current.", version);
+        return Ok(());
+    }
+    println!(
+        "Graph schema version {} (this wg writes {}). Pending migrations:",
+        version, SCHEMA_VERSION
+    );
+    for step in pending {
+        println!("  v{} {}: {}", step.version, step.name, step.description);
+    }
+    anyhow::bail!(
+        "{} schema migration(s) pending; run 'wg migrate --apply'",
+        pending.len()
+    )
+}
+
+fn apply_schema(dir: &Path, path: &Path) -> Result<()> {
+    let Some((from, backup)) = migrate_schema(path).context("Failed to migrate graph schema")?
+    else {
+        println!("Graph schema version {} is current.", SCHEMA_VERSION);
+        return Ok(());
+    };
+    super::notify_graph_changed(dir);
+
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = workgraph::provenance::record(
+        dir,
+        "migrate",
+        None,
+        None,
+        serde_json::json!({
+            "schema_from": from,
+            "schema_to": SCHEMA_VERSION,
+            "backup": backup.display().to_string(),
+        }),
+        config.log.rotation_threshold,
+    );
+
+    println!("Migrated graph schema: v{} -> v{}", from, SCHEMA_VERSION);
+    for step in schema::pending(from) {
+        println!("  v{} {}: {}", step.version, step.name, step.description);
+    }
+    println!("  Backup: {}", backup.display());
+    Ok(())
+}
+
+fn print_format(path: &Path, format: StorageFormat) {
+    println!("Storage format: {}", format);
+    if format == StorageFormat::Journal {
+        let records = std::fs::read_to_string(journal_path(path))
+            .map(|content| content.lines().filter(|l| !l.trim().is_empty()).count())
+            .unwrap_or(0);
+        println!(
+            "  Journal: {} records (compacts at {})",
+            records, JOURNAL_COMPACT_THRESHOLD
+        );
+    }
+    if format == StorageFormat::Sqlite {
+        println!("  Database: {}", sqlite_path(path).display());
+    }
+    match schema_version(path) {
+        Ok(version) if version < SCHEMA_VERSION => println!(
+            "Schema version: {} (run 'wg migrate --apply' to upgrade to {})",
+            version, SCHEMA_VERSION
+        ),
+        Ok(version) => println!("Schema version: {}", version),
+        Err(e) => println!("Schema version: unknown ({})", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use workgraph::graph::{Node, Status, Task, WorkGraph};
+    use workgraph::parser::{load_graph, save_graph};
+
+    fn setup(dir: &Path) {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(Task {
+            id: "t1".to_string(),
+            title: "Task 1".to_string(),
+            ..Task::default()
+        }));
+        save_graph(&graph, graph_path(dir)).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_to_journal_and_back() {
+        let temp_dir = TempDir::new().unwrap();
+        setup(temp_dir.path());
+        assert_eq!(current_format(temp_dir.path()), StorageFormat::Jsonl);
+
+        run(temp_dir.path(), Some("journal"), false, false, false).unwrap();
+        assert_eq!(current_format(temp_dir.path()), StorageFormat::Journal);
+
+        let path = graph_path(temp_dir.path());
+        let mut graph = load_graph(&path).unwrap();
+        graph.get_task_mut("t1").unwrap().title = "Edited".to_string();
+        save_graph(&graph, &path).unwrap();
+
+        run(temp_dir.path(), Some("jsonl"), false, false, false).unwrap();
+        assert_eq!(current_format(temp_dir.path()), StorageFormat::Jsonl);
+        assert!(std::fs::read_to_string(&path).unwrap().contains("Edited"));
+    }
+
+    #[test]
+    fn test_migrate_rejects_unknown_format() {
+        let temp_dir = TempDir::new().unwrap();
+        setup(temp_dir.path());
+        assert!(run(temp_dir.path(), Some("xml"), false, false, false).is_err());
+    }
+
+    #[test]
+    fn test_migrate_schema_check_and_apply() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = graph_path(temp_dir.path());
+        let legacy = concat!(
+            r#"{"id":"erik","kind":"actor","name":"Erik"}"#,
+            "\n",
+            r#"{"id":"t1","kind":"task","title":"Task 1","status":"pending-review"}"#,
+            "\n",
+        );
+        std::fs::write(&path, legacy).unwrap();
+
+        assert!(run(temp_dir.path(), None, false, true, false).is_err());
+        run(temp_dir.path(), None, false, false, true).unwrap();
+        assert!(run(temp_dir.path(), None, false, true, false).is_ok());
+
+        let backup = temp_dir.path().join("graph.jsonl.v0.bak");
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), legacy);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with(&schema::header_line()));
+        assert!(!content.contains("actor"));
+        assert!(content.contains(r#""status":"done""#));
+    }
+
+    #[test]
+    fn test_migrate_check_lists_in_review_step() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = graph_path(temp_dir.path());
+        let v10 = concat!(
+            r#"{"kind":"schema","version":10}"#,
+            "\n",
+            r#"{"id":"t1","kind":"task","title":"Task 1","status":"in-review"}"#,
+            "\n",
+        );
+        std::fs::write(&path, v10).unwrap();
+
+        let pending: Vec<&str> = schema::pending(10).iter().map(|step| step.name).collect();
+        assert_eq!(pending, ["in-review-status"]);
+        assert!(run(temp_dir.path(), None, false, true, false).is_err());
+        run(temp_dir.path(), None, false, false, true).unwrap();
+        assert!(run(temp_dir.path(), None, false, true, false).is_ok());
+        assert_eq!(
+            load_graph(&path).unwrap().get_task("t1").unwrap().status,
+            Status::InReview
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_migrate_through_sqlite() {
+        let temp_dir = TempDir::new().unwrap();
+        setup(temp_dir.path());
+        let path = graph_path(temp_dir.path());
+
+        run(temp_dir.path(), Some("sqlite"), false, false, false).unwrap();
+        assert_eq!(current_format(temp_dir.path()), StorageFormat::Sqlite);
+        let mut graph = load_graph(&path).unwrap();
+        graph.get_task_mut("t1").unwrap().title = "Edited".to_string();
+        save_graph(&graph, &path).unwrap();
+
+        run(temp_dir.path(), Some("journal"), false, false, false).unwrap();
+        assert_eq!(current_format(temp_dir.path()), StorageFormat::Journal);
+        assert!(!sqlite_path(&path).exists());
+        assert_eq!(
+            load_graph(&path).unwrap().get_task("t1").unwrap().title,
+            "Edited"
+        );
+    }
+}
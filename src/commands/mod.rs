@@ -15,6 +15,7 @@ pub mod analyze;
 pub mod archive;
 pub mod artifact;
 pub mod assign;
+pub mod at_risk;
 pub mod blocked;
 pub mod bottlenecks;
 pub mod check;
@@ -42,9 +43,12 @@ pub mod list;
 pub mod log;
 pub mod loops;
 pub mod match_cmd;
+pub mod merge_driver;
+pub mod migrate;
 #[cfg(any(feature = "matrix", feature = "matrix-lite"))]
 pub mod matrix;
 pub mod objective;
+pub mod output;
 pub mod next;
 #[cfg(any(feature = "matrix", feature = "matrix-lite"))]
 pub mod notify;
@@ -60,8 +64,11 @@ pub mod resource;
 pub mod resources;
 pub mod resume;
 pub mod retry;
+pub mod review;
 pub mod role;
 pub mod runs_cmd;
+pub mod schedule;
+pub mod search;
 pub mod service;
 pub mod setup;
 pub mod show;
@@ -74,6 +81,7 @@ pub mod trace_extract;
 pub mod trace_function_cmd;
 pub mod trace_instantiate;
 pub mod trajectory;
+pub mod undo;
 pub mod velocity;
 pub mod viz;
 pub mod why_blocked;
@@ -96,10 +104,82 @@ pub fn load_workgraph(dir: &Path) -> Result<(workgraph::graph::WorkGraph, PathBu
     Ok((graph, path))
 }
 
-/// Load the workgraph (mutable) from the given directory.
-/// Returns the graph and the path to the graph file (needed for save_graph).
-pub fn load_workgraph_mut(dir: &Path) -> Result<(workgraph::graph::WorkGraph, PathBuf)> {
-    load_workgraph(dir)
+/// Load the workgraph as it was at `at` (a `--at` timestamp, see
+/// `history::graph_at`), or the current graph when `at` is `None`. Warns on
+/// stderr when the operations log could not be fully replayed.
+pub fn load_workgraph_at(dir: &Path, at: Option<&str>) -> Result<workgraph::graph::WorkGraph> {
+    let Some(at) = at else {
+        return load_workgraph(dir).map(|(graph, _)| graph);
+    };
+    if !graph_path(dir).exists() {
+        anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
+    }
+    let at = workgraph::history::parse_timestamp(at)?;
+    let result = workgraph::history::graph_at(dir, at)?;
+    if !result.skipped.is_empty() {
+        eprintln!(
+            "warning: graph at {} may be incomplete; {} operation(s) had no recorded changes:",
+            at.to_rfc3339(),
+            result.skipped.len()
+        );
+        for op in result.skipped.iter().take(10) {
+            eprintln!("  {}", op);
+        }
+        if result.skipped.len() > 10 {
+            eprintln!("  ... and {} more", result.skipped.len() - 10);
+        }
+    }
+    Ok(result.graph)
+}
+
+/// Open the storage backend of the workgraph in the given directory, for
+/// point reads and indexed queries that don't need the whole graph.
+pub fn open_store(dir: &Path) -> Result<Box<dyn workgraph::store::GraphStore>> {
+    let path = graph_path(dir);
+    if !path.exists() {
+        anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
+    }
+    Ok(workgraph::store::open(&path))
+}
+
+/// Parse a `--filter` expression, expanding `@name` from the saved
+/// `[queries]` in config.toml.
+pub fn parse_filter(
+    dir: &Path,
+    expr: Option<&str>,
+) -> Result<Option<workgraph::query::filter::Filter>> {
+    let Some(expr) = expr else {
+        return Ok(None);
+    };
+    let config = workgraph::config::Config::load_or_default(dir);
+    workgraph::query::filter::Filter::parse_with_saved(expr, &config.queries).map(Some)
+}
+
+/// Load, modify and save the workgraph in the given directory as one
+/// transaction, holding the graph lock throughout (see `parser::with_graph_mut`).
+/// If the closure returns an error or changes nothing, nothing is saved.
+pub fn with_workgraph_mut<T>(
+    dir: &Path,
+    f: impl FnOnce(&mut workgraph::graph::WorkGraph) -> Result<T>,
+) -> Result<T> {
+    let path = graph_path(dir);
+    if !path.exists() {
+        anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
+    }
+    workgraph::parser::with_graph_mut(&path, f)
+}
+
+/// [`with_workgraph_mut`] that also returns the task changes made, for
+/// recording in the operation's provenance so it can be undone.
+pub fn with_workgraph_changes<T>(
+    dir: &Path,
+    f: impl FnOnce(&mut workgraph::graph::WorkGraph) -> Result<T>,
+) -> Result<(T, Vec<workgraph::undo::TaskChange>)> {
+    let path = graph_path(dir);
+    if !path.exists() {
+        anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
+    }
+    workgraph::undo::with_graph_changes(&path, f)
 }
 
 /// Check if a process with the given PID is alive.
@@ -199,7 +279,7 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "Test task", Some("prov-add"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
 
         let entries = ops_with_type(dir, "add_task");
@@ -213,12 +293,12 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "Edit target", Some("prov-edit"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
 
         super::edit::run(
             dir, "prov-edit", Some("New Title"), None,
-            &[], &[], &[], &[], None, &[], &[], None, None, None, None, None, None,
+            &[], &[], &[], &[], None, &[], &[], None, None, None, None, None, None, None, None, &[], &[], None, false, None, &[], &[], None, false, None, false,
         ).unwrap();
 
         let entries = ops_with_type(dir, "edit");
@@ -235,7 +315,7 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "Claim target", Some("prov-claim"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
 
         super::claim::claim(dir, "prov-claim", Some("agent-1")).unwrap();
@@ -256,10 +336,10 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "Done target", Some("prov-done"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
 
-        super::done::run(dir, "prov-done", false).unwrap();
+        super::done::run(dir, "prov-done", false, false, None).unwrap();
         let entries = ops_with_type(dir, "done");
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].task_id.as_deref(), Some("prov-done"));
@@ -271,10 +351,10 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "Fail target", Some("prov-fail"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
 
-        super::fail::run(dir, "prov-fail", Some("timeout")).unwrap();
+        super::fail::run(dir, "prov-fail", Some("timeout"), None).unwrap();
         let entries = ops_with_type(dir, "fail");
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].detail["reason"], "timeout");
@@ -286,7 +366,7 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "Abandon target", Some("prov-abandon"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
 
         super::abandon::run(dir, "prov-abandon", Some("no longer needed")).unwrap();
@@ -301,10 +381,10 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "Retry target", Some("prov-retry"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
 
-        super::fail::run(dir, "prov-retry", Some("compile error")).unwrap();
+        super::fail::run(dir, "prov-retry", Some("compile error"), None).unwrap();
         super::retry::run(dir, "prov-retry").unwrap();
 
         let entries = ops_with_type(dir, "retry");
@@ -319,7 +399,7 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "Pause target", Some("prov-pause"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
 
         super::pause::run(dir, "prov-pause").unwrap();
@@ -339,10 +419,10 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "Artifact target", Some("prov-art"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
 
-        super::artifact::run_add(dir, "prov-art", "output.txt").unwrap();
+        super::artifact::run_add(dir, "prov-art", "output.txt", false).unwrap();
         let entries = ops_with_type(dir, "artifact_add");
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].detail["path"], "output.txt");
@@ -359,11 +439,11 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "Archive target", Some("prov-archive"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
-        super::done::run(dir, "prov-archive", false).unwrap();
+        super::done::run(dir, "prov-archive", false, false, None).unwrap();
 
-        super::archive::run(dir, false, None, false, false).unwrap();
+        super::archive::run(dir, false, None, false, None, false).unwrap();
         let entries = ops_with_type(dir, "archive");
         assert_eq!(entries.len(), 1);
         let task_ids = entries[0].detail["task_ids"].as_array().unwrap();
@@ -376,12 +456,12 @@ mod provenance_coverage_tests {
         let dir = tmp.path();
         super::add::run(
             dir, "GC target", Some("prov-gc"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
-        super::fail::run(dir, "prov-gc", Some("oops")).unwrap();
+        super::fail::run(dir, "prov-gc", Some("oops"), None).unwrap();
         super::abandon::run(dir, "prov-gc", Some("giving up")).unwrap();
 
-        super::gc::run(dir, false, false).unwrap();
+        super::gc::run(dir, false, false, None).unwrap();
         let entries = ops_with_type(dir, "gc");
         assert_eq!(entries.len(), 1);
         let removed = entries[0].detail["removed"].as_array().unwrap();
@@ -396,12 +476,12 @@ mod provenance_coverage_tests {
         // add
         super::add::run(
             dir, "Lifecycle task", Some("lifecycle"), None,
-            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None,
+            &[], None, None, None, &[], &[], &[], &[], None, None, None, None, None, None, None, None, None, &[], None, false, &[], None, None, &[], &[], false, &[], &[], None, None, None, None, None,
         ).unwrap();
         // edit
         super::edit::run(
             dir, "lifecycle", Some("Renamed"), None,
-            &[], &[], &["tag1".to_string()], &[], None, &[], &[], None, None, None, None, None, None,
+            &[], &[], &["tag1".to_string()], &[], None, &[], &[], None, None, None, None, None, None, None, None, &[], &[], None, false, None, &[], &[], None, false, None, false,
         ).unwrap();
         // pause
         super::pause::run(dir, "lifecycle").unwrap();
@@ -410,15 +490,15 @@ mod provenance_coverage_tests {
         // claim
         super::claim::claim(dir, "lifecycle", Some("worker")).unwrap();
         // artifact add
-        super::artifact::run_add(dir, "lifecycle", "result.txt").unwrap();
+        super::artifact::run_add(dir, "lifecycle", "result.txt", false).unwrap();
         // unclaim
         super::claim::unclaim(dir, "lifecycle").unwrap();
         // fail
-        super::fail::run(dir, "lifecycle", Some("timeout")).unwrap();
+        super::fail::run(dir, "lifecycle", Some("timeout"), None).unwrap();
         // retry
         super::retry::run(dir, "lifecycle").unwrap();
         // done
-        super::done::run(dir, "lifecycle", false).unwrap();
+        super::done::run(dir, "lifecycle", false, false, None).unwrap();
 
         let all = read_all_operations(dir).unwrap();
         let ops: Vec<&str> = all.iter().map(|e| e.op.as_str()).collect();
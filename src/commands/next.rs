@@ -1,16 +1,18 @@
 use anyhow::Result;
 use serde::Serialize;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use workgraph::identity;
-use workgraph::graph::TrustLevel;
-use workgraph::query::ready_tasks;
+use workgraph::graph::{Priority, Task, TrustLevel, WorkGraph};
+use workgraph::identity::Agent;
+use workgraph::query::{ready_tasks, sort_by_dispatch_order_then};
 
 /// Candidate task for an agent
 #[derive(Debug, Serialize)]
 struct TaskCandidate {
     id: String,
     title: String,
+    priority: Priority,
     score: i32,
     matched_skills: Vec<String>,
     missing_skills: Vec<String>,
@@ -37,13 +39,67 @@ pub fn run(dir: &Path, agent_id: &str, json: bool) -> Result<()> {
     let agent = identity::find_agent_by_prefix(&agents_dir, agent_id)
         .map_err(|e| anyhow::anyhow!("Agent '{}' not found: {}", agent_id, e))?;
 
+    let viable = rank_candidates(&graph, &agent);
+
+    let (recommended, alternatives) = if viable.is_empty() {
+        (None, vec![])
+    } else {
+        let mut iter = viable.into_iter();
+        (iter.next(), iter.take(4).collect())
+    };
+
+    let result = NextTaskResult {
+        agent_id: agent.id.clone(),
+        agent_name: agent.name.clone(),
+        agent_capabilities: agent.capabilities.clone(),
+        recommended,
+        alternatives,
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        println!(
+            "Next task for: {} ({})",
+            agent.name,
+            identity::short_hash(&agent.id)
+        );
+        if !result.agent_capabilities.is_empty() {
+            println!("Capabilities: {}", result.agent_capabilities.join(", "));
+        }
+        println!();
+
+        if let Some(ref task) = result.recommended {
+            println!("Recommended:");
+            print_candidate(task);
+
+            if !result.alternatives.is_empty() {
+                println!();
+                println!("Alternatives:");
+                for alt in &result.alternatives {
+                    print_candidate(alt);
+                }
+            }
+        } else {
+            println!("No suitable tasks available.");
+            println!();
+            println!("The agent should sleep and retry later.");
+        }
+    }
+
+    Ok(())
+}
+
+/// Score the ready tasks for an agent and return the viable ones in
+/// recommendation order
+fn rank_candidates(graph: &WorkGraph, agent: &Agent) -> Vec<TaskCandidate> {
     let agent_skills: HashSet<&String> = agent.capabilities.iter().collect();
 
     // Get ready tasks
-    let ready = ready_tasks(&graph);
+    let mut ready = ready_tasks(graph);
 
     // Score each task for this agent
-    let mut candidates: Vec<TaskCandidate> = ready
+    let mut candidates: HashMap<String, TaskCandidate> = ready
         .iter()
         .map(|task| {
             let task_skills: HashSet<&String> = task.skills.iter().collect();
@@ -92,72 +148,35 @@ pub fn run(dir: &Path, agent_id: &str, json: bool) -> Result<()> {
                 score += 5;
             }
 
-            TaskCandidate {
+            let candidate = TaskCandidate {
                 id: task.id.clone(),
                 title: task.title.clone(),
+                priority: task.priority,
                 score,
                 matched_skills: matched,
                 missing_skills: missing,
                 hours: task.estimate.as_ref().and_then(|e| e.hours),
                 inputs_available: inputs_available || task.inputs.is_empty(),
-            }
+            };
+            (task.id.clone(), candidate)
         })
         .collect();
 
-    // Sort by score descending
-    candidates.sort_by(|a, b| b.score.cmp(&a.score));
+    // Keep the coordinator's dispatch order (priority, deadline slack,
+    // critical path, value) and only use the skill score to break ties, so
+    // `wg next` never recommends less urgent work than the coordinator would
+    // dispatch.
+    let score_of = |t: &Task| candidates.get(&t.id).map_or(0, |c| c.score);
+    sort_by_dispatch_order_then(graph, &mut ready, |a, b| score_of(b).cmp(&score_of(a)));
+    let order: Vec<String> = ready.iter().map(|t| t.id.clone()).collect();
 
     // Filter to only tasks with non-negative score (at least partial capability match)
     // But include tasks with no skill requirements
-    let viable: Vec<TaskCandidate> = candidates.into_iter().filter(|c| c.score >= 0).collect();
-
-    let (recommended, alternatives) = if viable.is_empty() {
-        (None, vec![])
-    } else {
-        let mut iter = viable.into_iter();
-        (iter.next(), iter.take(4).collect())
-    };
-
-    let result = NextTaskResult {
-        agent_id: agent.id.clone(),
-        agent_name: agent.name.clone(),
-        agent_capabilities: agent.capabilities.clone(),
-        recommended,
-        alternatives,
-    };
-
-    if json {
-        println!("{}", serde_json::to_string_pretty(&result)?);
-    } else {
-        println!(
-            "Next task for: {} ({})",
-            agent.name,
-            identity::short_hash(&agent.id)
-        );
-        if !result.agent_capabilities.is_empty() {
-            println!("Capabilities: {}", result.agent_capabilities.join(", "));
-        }
-        println!();
-
-        if let Some(ref task) = result.recommended {
-            println!("Recommended:");
-            print_candidate(task);
-
-            if !result.alternatives.is_empty() {
-                println!();
-                println!("Alternatives:");
-                for alt in &result.alternatives {
-                    print_candidate(alt);
-                }
-            }
-        } else {
-            println!("No suitable tasks available.");
-            println!();
-            println!("The agent should sleep and retry later.");
-        }
-    }
-
-    Ok(())
+    order
+        .iter()
+        .filter_map(|id| candidates.remove(id))
+        .filter(|c| c.score >= 0)
+        .collect()
 }
 
 fn print_candidate(task: &TaskCandidate) {
@@ -168,7 +187,11 @@ fn print_candidate(task: &TaskCandidate) {
         " [waiting for inputs]"
     };
 
-    println!("  {} - {}{}{}", task.id, task.title, hours_str, inputs_str);
+    let priority_str = super::list::format_priority_hint(task.priority);
+    println!(
+        "  {} - {}{}{}{}",
+        task.id, task.title, priority_str, hours_str, inputs_str
+    );
     println!("    Score: {}", task.score);
 
     if !task.matched_skills.is_empty() {
@@ -313,4 +336,31 @@ mod tests {
         let result = run(temp_dir.path(), &agent_id, true);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_next_follows_dispatch_order_before_score() {
+        let mut graph = WorkGraph::new();
+
+        // `chain` heads a longer critical path, so the coordinator dispatches
+        // it first even though `rust` is a better skill match
+        let chain = make_task("chain", "Generic head of chain");
+        let mut tail = make_task("tail", "Tail");
+        tail.blocked_by = vec!["chain".to_string()];
+        let mut rust = make_task("rust", "Rust Task");
+        rust.skills = vec!["rust".to_string()];
+        let mut tied = make_task("a-tied", "Generic, same urgency as rust");
+        tied.skills = vec![];
+
+        for task in [chain, tail, rust, tied] {
+            graph.add_node(Node::Task(task));
+        }
+
+        let agent = make_agent("rust-dev", vec!["rust"]);
+        let ranked: Vec<String> = rank_candidates(&graph, &agent)
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        // Skill score only breaks the tie between `rust` and `a-tied`
+        assert_eq!(ranked, vec!["chain", "rust", "a-tied"]);
+    }
 }
@@ -201,6 +201,7 @@ fn format_notification(task: &Task, custom_message: Option<&str>) -> (String, St
         Status::Blocked => "🚫",
         Status::Failed => "❌",
         Status::Abandoned => "🗑️",
+        Status::InReview => "👀",
     };
 
     let status_str = task.status.to_string();
@@ -312,7 +313,7 @@ fn escape_html(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use workgraph::graph::Task;
+    use workgraph::graph::{Priority, Task};
 
     fn make_test_task() -> Task {
         Task {
@@ -346,6 +347,21 @@ mod tests {
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         }
     }
 
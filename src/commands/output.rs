@@ -0,0 +1,104 @@
+use anyhow::Result;
+use std::path::Path;
+use workgraph::outputs;
+
+/// Record named outputs on a task (`key=value` pairs or a JSON object), and
+/// remove the `remove` keys
+pub fn run_set(dir: &Path, task_id: &str, values: &[String], remove: &[String]) -> Result<()> {
+    let values = outputs::parse_args(values)?;
+    if values.is_empty() && remove.is_empty() {
+        anyhow::bail!("Nothing to record. Use key=value or a JSON object");
+    }
+
+    let ((), changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(task_id)?;
+        for key in remove {
+            if task.outputs.remove(key).is_none() {
+                anyhow::bail!("Task '{}' has no output '{}'", task_id, key);
+            }
+        }
+        task.outputs.extend(values.clone());
+        Ok(())
+    })?;
+    super::notify_graph_changed(dir);
+
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = workgraph::provenance::record(
+        dir,
+        "output",
+        Some(task_id),
+        None,
+        workgraph::undo::with_changes(
+            serde_json::json!({ "set": values, "removed": remove }),
+            &changes,
+        ),
+        config.log.rotation_threshold,
+    );
+
+    for (key, value) in &values {
+        println!("Output {}.{} = {}", task_id, key, outputs::render(value));
+    }
+    for key in remove {
+        println!("Removed output {}.{}", task_id, key);
+    }
+    Ok(())
+}
+
+/// List a task's outputs
+pub fn run_list(dir: &Path, task_id: &str, json: bool) -> Result<()> {
+    let (graph, _path) = super::load_workgraph(dir)?;
+    let task = graph.get_task_or_err(task_id)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&task.outputs)?);
+        return Ok(());
+    }
+
+    if task.outputs.is_empty() {
+        println!("Task '{}' has no outputs.", task_id);
+        return Ok(());
+    }
+    println!("Outputs of {} - {}:", task.id, task.title);
+    for (key, value) in &task.outputs {
+        println!("  {} = {}", key, outputs::render(value));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use workgraph::graph::Status;
+    use workgraph::parser::load_graph;
+    use workgraph::test_helpers::{make_task_with_status, setup_workgraph};
+
+    #[test]
+    fn test_set_and_remove_outputs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = setup_workgraph(
+            dir,
+            vec![make_task_with_status("build", "Build", Status::Done)],
+        );
+
+        run_set(
+            dir,
+            "build",
+            &["version=1.2.0".to_string(), "size:=42".to_string()],
+            &[],
+        )
+        .unwrap();
+        run_set(dir, "build", &[r#"{"commit": "abc123"}"#.to_string()], &[]).unwrap();
+        run_set(dir, "build", &[], &["size".to_string()]).unwrap();
+
+        let graph = load_graph(&path).unwrap();
+        let outputs = &graph.get_task("build").unwrap().outputs;
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs["version"], serde_json::json!("1.2.0"));
+        assert_eq!(outputs["commit"], serde_json::json!("abc123"));
+
+        assert!(run_set(dir, "build", &[], &[]).is_err());
+        assert!(run_set(dir, "build", &[], &["size".to_string()]).is_err());
+        assert!(run_list(dir, "build", true).is_ok());
+    }
+}
@@ -75,6 +75,8 @@ TASK STATE COMMANDS
   wg done <task-id>           # Mark task complete
   wg fail <task-id> --reason  # Mark failed (can be retried)
   wg abandon <task-id>        # Give up permanently
+  wg approve <task-id>        # Sign off on an agent's task awaiting review
+  wg reject <task-id> --reason "..."  # Send it back with reviewer notes
 
 CONTEXT & ARTIFACTS
 ─────────────────────────────────────────
@@ -184,7 +186,9 @@ fn json_output() -> serde_json::Value {
             "completion": {
                 "done": "Mark task complete",
                 "fail": "Mark failed (can be retried)",
-                "abandon": "Give up permanently"
+                "abandon": "Give up permanently",
+                "approve": "Sign off on a task awaiting review (humans only)",
+                "reject": "Reopen a task awaiting review with reviewer notes (humans only)"
             }
         },
         "loops": {
@@ -1,16 +1,31 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use std::path::Path;
-use workgraph::graph::Status;
+use workgraph::graph::{Status, Task};
+use workgraph::query::filter::FilterContext;
 use workgraph::query::ready_tasks;
 
-pub fn run(dir: &Path, json: bool) -> Result<()> {
-    let (graph, _path) = super::load_workgraph(dir)?;
-    let ready = ready_tasks(&graph);
+pub fn run(dir: &Path, filter: Option<&str>, json: bool) -> Result<()> {
+    let filter = super::parse_filter(dir, filter)?;
+    // Filter expressions may look at any task; otherwise only unfinished
+    // tasks and their blockers matter, which the store loads on its own
+    let graph = match &filter {
+        Some(_) => super::load_workgraph(dir)?.0,
+        None => super::open_store(dir)?
+            .load_unfinished()
+            .context("Failed to load graph")?,
+    };
+    let ctx = FilterContext::new(&graph);
+    let wanted = |task: &Task| filter.as_ref().is_none_or(|f| f.matches(task, &ctx));
+    let ready: Vec<_> = ready_tasks(&graph)
+        .into_iter()
+        .filter(|t| wanted(t))
+        .collect();
 
     // Find tasks that would be ready except they're waiting on ready_after
     let waiting: Vec<_> = graph
         .tasks()
+        .filter(|task| wanted(task))
         .filter(|task| {
             if task.status != Status::Open {
                 return false;
@@ -25,11 +40,8 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
                 return false;
             }
             // All blockers must be terminal (i.e. only ready_after is holding it back)
-            task.blocked_by.iter().all(|blocker_id| {
-                graph
-                    .get_task(blocker_id)
-                    .map(|t| t.status.is_terminal())
-                    .unwrap_or(true)
+            workgraph::query::dependencies_met(task, |blocker_id| {
+                graph.get_task(blocker_id).map(|t| t.status)
             })
         })
         .collect();
@@ -43,6 +55,7 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
                     "title": t.title,
                     "assigned": t.assigned,
                     "estimate": t.estimate,
+                    "priority": t.priority,
                     "ready": true,
                 })
             })
@@ -69,7 +82,8 @@ pub fn run(dir: &Path, json: bool) -> Result<()> {
                     .as_ref()
                     .map(|a| format!(" ({})", a))
                     .unwrap_or_default();
-                println!("  {} - {}{}", task.id, task.title, assigned);
+                let priority = super::list::format_priority_hint(task.priority);
+                println!("  {} - {}{}{}", task.id, task.title, priority, assigned);
             }
         }
         if !waiting.is_empty() {
@@ -107,7 +121,7 @@ mod tests {
     use chrono::Duration;
     use std::fs;
     use tempfile::tempdir;
-    use workgraph::graph::{Node, Task, WorkGraph};
+    use workgraph::graph::{Node, WorkGraph};
     use workgraph::parser::{load_graph, save_graph};
 
     fn make_task(id: &str, title: &str, status: Status) -> Task {
@@ -204,7 +218,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![]);
 
-        let result = run(dir_path, false);
+        let result = run(dir_path, None, false);
         assert!(result.is_ok());
     }
 
@@ -214,7 +228,7 @@ mod tests {
         let dir_path = dir.path();
         // Don't initialize workgraph
 
-        let result = run(dir_path, false);
+        let result = run(dir_path, None, false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not initialized"));
     }
@@ -225,7 +239,7 @@ mod tests {
         let dir_path = dir.path();
         setup_workgraph(dir_path, vec![make_task("t1", "Ready task", Status::Open)]);
 
-        let result = run(dir_path, false);
+        let result = run(dir_path, None, false);
         assert!(result.is_ok());
     }
 
@@ -239,7 +253,7 @@ mod tests {
         setup_workgraph(dir_path, vec![task]);
 
         // Task with past ready_after should appear in ready list
-        let result = run(dir_path, false);
+        let result = run(dir_path, None, false);
         assert!(result.is_ok());
     }
 
@@ -253,7 +267,7 @@ mod tests {
         setup_workgraph(dir_path, vec![task]);
 
         // Task with future ready_after should be in waiting section, not ready
-        let result = run(dir_path, false);
+        let result = run(dir_path, None, false);
         assert!(result.is_ok());
     }
 
@@ -265,7 +279,7 @@ mod tests {
         task.assigned = Some("agent-1".to_string());
         setup_workgraph(dir_path, vec![task]);
 
-        let result = run(dir_path, true);
+        let result = run(dir_path, None, true);
         assert!(result.is_ok());
         // JSON output goes to stdout; we verify it doesn't error
     }
@@ -279,7 +293,7 @@ mod tests {
         task.ready_after = Some(future.to_rfc3339());
         setup_workgraph(dir_path, vec![task]);
 
-        let result = run(dir_path, true);
+        let result = run(dir_path, None, true);
         assert!(result.is_ok());
     }
 
@@ -295,7 +309,7 @@ mod tests {
 
         setup_workgraph(dir_path, vec![ready_task, waiting_task]);
 
-        let result = run(dir_path, false);
+        let result = run(dir_path, None, false);
         assert!(result.is_ok());
     }
 
@@ -306,7 +320,7 @@ mod tests {
         setup_workgraph(dir_path, vec![make_task("t1", "Done task", Status::Done)]);
 
         // Done tasks should not appear in ready or waiting
-        let result = run(dir_path, false);
+        let result = run(dir_path, None, false);
         assert!(result.is_ok());
     }
 
@@ -319,7 +333,7 @@ mod tests {
             vec![make_task("t1", "In-progress task", Status::InProgress)],
         );
 
-        let result = run(dir_path, false);
+        let result = run(dir_path, None, false);
         assert!(result.is_ok());
     }
 
@@ -338,7 +352,7 @@ mod tests {
 
         setup_workgraph(dir_path, vec![blocker, task]);
 
-        let result = run(dir_path, false);
+        let result = run(dir_path, None, false);
         assert!(result.is_ok());
     }
 
@@ -356,7 +370,7 @@ mod tests {
 
         setup_workgraph(dir_path, vec![blocker, task]);
 
-        let result = run(dir_path, false);
+        let result = run(dir_path, None, false);
         assert!(result.is_ok());
     }
 
@@ -1,8 +1,7 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::Utc;
 use std::path::Path;
-use workgraph::graph::{LogEntry, Status};
-use workgraph::parser::save_graph;
+use workgraph::graph::{LogEntry, Status, WorkGraph};
 
 #[cfg(test)]
 use super::graph_path;
@@ -14,8 +13,25 @@ use workgraph::parser::load_graph;
 /// This allows forcefully taking over a task that is currently assigned to another agent.
 /// The task must be in InProgress status to be reclaimed.
 pub fn run(dir: &Path, task_id: &str, from_actor: &str, to_actor: &str) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
+    super::with_workgraph_mut(dir, |graph| {
+        reclaim_task(graph, task_id, from_actor, to_actor)
+    })?;
+    super::notify_graph_changed(dir);
+
+    println!(
+        "Reclaimed task '{}' from '{}' to '{}'",
+        task_id, from_actor, to_actor
+    );
+
+    Ok(())
+}
 
+fn reclaim_task(
+    graph: &mut WorkGraph,
+    task_id: &str,
+    from_actor: &str,
+    to_actor: &str,
+) -> Result<()> {
     let task = graph.get_task_mut_or_err(task_id)?;
 
     // Check that task is in progress
@@ -63,14 +79,6 @@ pub fn run(dir: &Path, task_id: &str, from_actor: &str, to_actor: &str) -> Resul
         message: log_message,
     });
 
-    save_graph(&graph, &path).context("Failed to save graph")?;
-    super::notify_graph_changed(dir);
-
-    println!(
-        "Reclaimed task '{}' from '{}' to '{}'",
-        task_id, from_actor, to_actor
-    );
-
     Ok(())
 }
 
@@ -80,6 +88,7 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
     use workgraph::graph::{Node, Task, WorkGraph};
+    use workgraph::parser::save_graph;
 
     fn make_task(id: &str, title: &str, status: Status) -> Task {
         Task {
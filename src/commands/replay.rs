@@ -9,7 +9,7 @@ use std::path::Path;
 use workgraph::identity::{load_all_rewards_or_warn, Reward};
 use workgraph::config::Config;
 use workgraph::graph::{Status, Task};
-use workgraph::parser::save_graph;
+use workgraph::query::filter::FilterContext;
 use workgraph::runs::{self, RunMeta};
 
 /// Options controlling which tasks to reset.
@@ -21,6 +21,8 @@ pub struct ReplayOptions {
     pub keep_done: Option<f64>,
     pub plan_only: bool,
     pub subgraph: Option<String>,
+    /// Only seed tasks matching this filter expression
+    pub filter: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -34,8 +36,10 @@ struct ReplayOutput {
 }
 
 pub fn run(dir: &Path, opts: &ReplayOptions, json: bool) -> Result<()> {
-    let (mut graph, graph_path) = super::load_workgraph_mut(dir)?;
+    let (graph, _path) = super::load_workgraph(dir)?;
     let config = Config::load_or_default(dir);
+    let filter = super::parse_filter(dir, opts.filter.as_deref())?;
+    let ctx = FilterContext::new(&graph);
 
     // Determine keep_done threshold
     let keep_done_threshold = opts.keep_done.unwrap_or(config.replay.keep_done_threshold);
@@ -68,6 +72,12 @@ pub fn run(dir: &Path, opts: &ReplayOptions, json: bool) -> Result<()> {
                 continue;
             }
 
+        if let Some(ref filter) = filter
+            && !filter.matches(task, &ctx)
+        {
+            continue;
+        }
+
         if !opts.tasks.is_empty() {
             // Explicit task list: only seed listed tasks
             if opts.tasks.contains(&task.id) {
@@ -190,32 +200,35 @@ pub fn run(dir: &Path, opts: &ReplayOptions, json: bool) -> Result<()> {
         return Ok(());
     }
 
-    // Phase 4: Snapshot current state
-    let run_id = runs::next_run_id(dir);
-    let filter_desc = build_filter_desc(opts);
-    let meta = RunMeta {
-        id: run_id.clone(),
-        timestamp: Utc::now().to_rfc3339(),
-        model: opts.model.clone(),
-        reset_tasks: reset_ids.clone(),
-        preserved_tasks: preserved_ids.clone(),
-        filter: Some(filter_desc),
-    };
-    runs::snapshot(dir, &run_id, &meta)?;
-
-    // Phase 5: Reset selected tasks
-    for task_id in &reset_ids {
-        if let Some(task) = graph.get_task_mut(task_id) {
-            reset_task(task);
-            // Apply model override
-            if let Some(ref model) = opts.model {
-                task.model = Some(model.clone());
+    // Phases 4-6 run under the graph lock so the snapshot matches what is reset
+    let (run_id, changes) = super::with_workgraph_changes(dir, |graph| {
+        // Phase 4: Snapshot current state
+        let run_id = runs::next_run_id(dir);
+        let filter_desc = build_filter_desc(opts);
+        let meta = RunMeta {
+            id: run_id.clone(),
+            timestamp: Utc::now().to_rfc3339(),
+            model: opts.model.clone(),
+            reset_tasks: reset_ids.clone(),
+            preserved_tasks: preserved_ids.clone(),
+            filter: Some(filter_desc),
+        };
+        runs::snapshot(dir, &run_id, &meta)?;
+
+        // Phase 5: Reset selected tasks
+        for task_id in &reset_ids {
+            if let Some(task) = graph.get_task_mut(task_id) {
+                reset_task(task);
+                // Apply model override
+                if let Some(ref model) = opts.model {
+                    task.model = Some(model.clone());
+                }
             }
         }
-    }
 
-    // Phase 6: Save graph
-    save_graph(&graph, &graph_path).context("Failed to save graph after replay")?;
+        // Phase 6: Save graph (on closure return)
+        Ok(run_id)
+    })?;
     super::notify_graph_changed(dir);
 
     // Phase 7: Record provenance
@@ -224,12 +237,15 @@ pub fn run(dir: &Path, opts: &ReplayOptions, json: bool) -> Result<()> {
         "replay",
         None,
         None,
-        serde_json::json!({
-            "run_id": run_id,
-            "model": opts.model,
-            "reset_count": reset_ids.len(),
-            "reset_tasks": reset_ids,
-        }),
+        workgraph::undo::with_changes(
+            serde_json::json!({
+                "run_id": run_id,
+                "model": opts.model,
+                "reset_count": reset_ids.len(),
+                "reset_tasks": reset_ids,
+            }),
+            &changes,
+        ),
         config.log.rotation_threshold,
     );
 
@@ -268,6 +284,7 @@ fn reset_task(task: &mut Task) {
     task.started_at = None;
     task.completed_at = None;
     task.artifacts.clear();
+    task.artifact_snapshots.clear();
     task.loop_iteration = 0;
     task.failure_reason = None;
     task.paused = false;
@@ -383,6 +400,7 @@ mod tests {
             keep_done: None,
             plan_only: false,
             subgraph: None,
+            filter: None,
         };
 
         run(&dir, &opts, false).unwrap();
@@ -418,6 +436,7 @@ mod tests {
             keep_done: None,
             plan_only: false,
             subgraph: None,
+            filter: None,
         };
 
         run(&dir, &opts, false).unwrap();
@@ -445,6 +464,7 @@ mod tests {
             keep_done: None,
             plan_only: true,
             subgraph: None,
+            filter: None,
         };
 
         run(&dir, &opts, false).unwrap();
@@ -476,6 +496,7 @@ mod tests {
             keep_done: None,
             plan_only: false,
             subgraph: Some("root".to_string()),
+            filter: None,
         };
 
         run(&dir, &opts, false).unwrap();
@@ -531,6 +552,7 @@ mod tests {
             keep_done: None,
             plan_only: false,
             subgraph: None,
+            filter: None,
         };
 
         // Should succeed without error
@@ -558,6 +580,7 @@ mod tests {
             keep_done: Some(1.0), // don't keep any done tasks (threshold unreachable)
             plan_only: false,
             subgraph: None,
+            filter: None,
         };
 
         run(&dir, &opts, false).unwrap();
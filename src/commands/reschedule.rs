@@ -1,7 +1,6 @@
 use anyhow::{Context, Result};
 use chrono::{Duration, Utc};
 use std::path::Path;
-use workgraph::parser::save_graph;
 
 #[cfg(test)]
 use super::graph_path;
@@ -14,10 +13,6 @@ pub fn run(
     after_hours: Option<f64>,
     at_timestamp: Option<&str>,
 ) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
-
-    let task = graph.get_task_mut_or_err(id)?;
-
     let new_timestamp = if let Some(hours) = after_hours {
         // Calculate timestamp as now + hours
         let secs = hours * 3600.0;
@@ -26,27 +21,29 @@ pub fn run(
         }
         let duration = Duration::seconds(secs as i64);
         let future_time = Utc::now() + duration;
-        future_time.to_rfc3339()
+        Some(future_time.to_rfc3339())
     } else if let Some(timestamp) = at_timestamp {
         // Validate the timestamp
         timestamp.parse::<chrono::DateTime<Utc>>().context(
             "Invalid timestamp format. Use ISO 8601 format (e.g., 2024-01-20T10:00:00Z)",
         )?;
-        timestamp.to_string()
+        Some(timestamp.to_string())
     } else {
         // Clear the not_before (make it ready now)
-        task.not_before = None;
-        save_graph(&graph, &path).context("Failed to save graph")?;
-        super::notify_graph_changed(dir);
-        println!("Cleared not_before for '{}' - task is now ready", id);
-        return Ok(());
+        None
     };
 
-    task.not_before = Some(new_timestamp.clone());
-    save_graph(&graph, &path).context("Failed to save graph")?;
+    super::with_workgraph_mut(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
+        task.not_before = new_timestamp.clone();
+        Ok(())
+    })?;
     super::notify_graph_changed(dir);
 
-    println!("Rescheduled '{}' - not ready until {}", id, new_timestamp);
+    match new_timestamp {
+        Some(ts) => println!("Rescheduled '{}' - not ready until {}", id, ts),
+        None => println!("Cleared not_before for '{}' - task is now ready", id),
+    }
     Ok(())
 }
 
@@ -57,6 +54,7 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
     use workgraph::graph::{Node, Task, WorkGraph};
+    use workgraph::parser::save_graph;
 
     fn make_task(id: &str, title: &str) -> Task {
         Task {
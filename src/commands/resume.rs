@@ -1,8 +1,7 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::Utc;
 use std::path::Path;
 use workgraph::graph::LogEntry;
-use workgraph::parser::save_graph;
 
 #[cfg(test)]
 use super::graph_path;
@@ -10,22 +9,21 @@ use super::graph_path;
 use workgraph::parser::load_graph;
 
 pub fn run(dir: &Path, id: &str) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
+    let ((), changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
 
-    let task = graph.get_task_mut_or_err(id)?;
-
-    if !task.paused {
-        anyhow::bail!("Task '{}' is not paused", id);
-    }
-
-    task.paused = false;
-    task.log.push(LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: None,
-        message: "Task resumed".to_string(),
-    });
+        if !task.paused {
+            anyhow::bail!("Task '{}' is not paused", id);
+        }
 
-    save_graph(&graph, &path).context("Failed to save graph")?;
+        task.paused = false;
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: None,
+            message: "Task resumed".to_string(),
+        });
+        Ok(())
+    })?;
     super::notify_graph_changed(dir);
 
     // Record operation
@@ -35,7 +33,7 @@ pub fn run(dir: &Path, id: &str) -> Result<()> {
         "resume",
         Some(id),
         None,
-        serde_json::json!({}),
+        workgraph::undo::with_changes(serde_json::json!({}), &changes),
         config.log.rotation_threshold,
     );
 
@@ -49,6 +47,7 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
     use workgraph::graph::{Node, Status, Task, WorkGraph};
+    use workgraph::parser::save_graph;
 
     fn make_task(id: &str, title: &str, status: Status) -> Task {
         Task {
@@ -1,8 +1,7 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::Utc;
 use std::path::Path;
-use workgraph::graph::{LogEntry, Status};
-use workgraph::parser::save_graph;
+use workgraph::graph::{LogEntry, Status, unskip_dependents};
 
 #[cfg(test)]
 use super::graph_path;
@@ -10,53 +9,56 @@ use super::graph_path;
 use workgraph::parser::load_graph;
 
 pub fn run(dir: &Path, id: &str) -> Result<()> {
-    let (mut graph, path) = super::load_workgraph_mut(dir)?;
-
-    let task = graph.get_task_mut_or_err(id)?;
-
-    if task.status != Status::Failed {
-        anyhow::bail!(
-            "Task '{}' is not failed (status: {:?}). Only failed tasks can be retried.",
-            id,
-            task.status
-        );
-    }
+    let (retried, changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
+
+        if task.status != Status::Failed {
+            anyhow::bail!(
+                "Task '{}' is not failed (status: {:?}). Only failed tasks can be retried.",
+                id,
+                task.status
+            );
+        }
 
-    // Check if max retries exceeded
-    if let Some(max) = task.max_retries
-        && task.retry_count >= max
-    {
-        anyhow::bail!(
-            "Task '{}' has reached max retries ({}/{}). Consider abandoning or increasing max_retries.",
-            id,
-            task.retry_count,
-            max
-        );
-    }
+        // Check if max retries exceeded
+        if let Some(max) = task.max_retries
+            && task.retry_count >= max
+        {
+            anyhow::bail!(
+                "Task '{}' has reached max retries ({}/{}). Consider abandoning or increasing max_retries.",
+                id,
+                task.retry_count,
+                max
+            );
+        }
 
-    let prev_failure_reason = task.failure_reason.clone();
-    let attempt = task.retry_count + 1;
-
-    task.status = Status::Open;
-    // Keep retry_count for history - don't reset it
-    // Clear failure_reason since we're retrying
-    task.failure_reason = None;
-    // Clear assigned so the coordinator can re-spawn an agent
-    task.assigned = None;
-    // Clear converged tag so the loop can fire again if needed
-    task.tags.retain(|t| t != "converged");
-
-    task.log.push(LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: None,
-        message: format!("Task reset for retry (attempt #{})", task.retry_count + 1),
-    });
-
-    // Extract values we need for printing before saving
+        let prev_failure_reason = task.failure_reason.clone();
+        task.status = Status::Open;
+        // Keep retry_count for history - don't reset it
+        // Clear failure_reason since we're retrying
+        task.failure_reason = None;
+        // Clear assigned so the coordinator can re-spawn an agent
+        task.assigned = None;
+        // Clear converged tag so the loop can fire again if needed
+        task.tags.retain(|t| t != "converged");
+
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: None,
+            message: format!("Task reset for retry (attempt #{})", task.retry_count + 1),
+        });
+
+        let task = task.clone();
+        let reopened = unskip_dependents(graph, id);
+        Ok((prev_failure_reason, task, reopened))
+    })?;
+    let (prev_failure_reason, task, reopened) = retried;
+
+    // Extract values we need for printing
     let retry_count = task.retry_count;
     let max_retries = task.max_retries;
+    let attempt = retry_count + 1;
 
-    save_graph(&graph, &path).context("Failed to save graph")?;
     super::notify_graph_changed(dir);
 
     // Record operation
@@ -66,7 +68,10 @@ pub fn run(dir: &Path, id: &str) -> Result<()> {
         "retry",
         Some(id),
         None,
-        serde_json::json!({ "attempt": attempt, "prev_failure_reason": prev_failure_reason }),
+        workgraph::undo::with_changes(
+            serde_json::json!({ "attempt": attempt, "prev_failure_reason": prev_failure_reason }),
+            &changes,
+        ),
         config.log.rotation_threshold,
     );
 
@@ -79,6 +84,9 @@ pub fn run(dir: &Path, id: &str) -> Result<()> {
     if let Some(max) = max_retries {
         println!("  Retries remaining after this: {}", max - retry_count);
     }
+    for task_id in &reopened {
+        println!("  Reopened skipped task '{}'", task_id);
+    }
 
     Ok(())
 }
@@ -89,6 +97,7 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
     use workgraph::graph::{Node, Task, WorkGraph};
+    use workgraph::parser::save_graph;
 
     fn make_task(id: &str, title: &str, status: Status) -> Task {
         Task {
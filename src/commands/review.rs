@@ -0,0 +1,191 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::path::Path;
+use workgraph::completion::{complete, read_guards};
+use workgraph::graph::{LogEntry, Status, Task};
+
+#[cfg(test)]
+use super::graph_path;
+#[cfg(test)]
+use workgraph::parser::load_graph;
+
+/// Sign off on a task an agent submitted for review, marking it done
+pub fn run_approve(dir: &Path, id: &str, actor: Option<&str>) -> Result<()> {
+    refuse_agents("approve", id)?;
+    // Loop guards can run commands, so they're read before the graph lock
+    let guards = {
+        let (graph, _path) = super::load_workgraph(dir)?;
+        read_guards(&graph, dir, id)
+    };
+    let ((task, completed), changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
+        check_in_review(task)?;
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: actor.map(String::from),
+            message: "Review approved, task marked as done".to_string(),
+        });
+        let completed = complete(graph, dir, id, &guards)?;
+        Ok((graph.get_task_or_err(id)?.clone(), completed))
+    })?;
+
+    super::notify_graph_changed(dir);
+
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = workgraph::provenance::record(
+        dir,
+        "approve",
+        Some(id),
+        actor,
+        workgraph::undo::with_changes(serde_json::Value::Null, &changes),
+        config.log.rotation_threshold,
+    );
+
+    println!("Approved '{}' and marked it done", id);
+    super::done::print_completed(&completed, &task);
+    Ok(())
+}
+
+/// Send a task back for rework, appending the reviewer's notes to its description
+pub fn run_reject(dir: &Path, id: &str, reason: &str, actor: Option<&str>) -> Result<()> {
+    refuse_agents("reject", id)?;
+    if reason.trim().is_empty() {
+        anyhow::bail!("Rejecting '{}' needs a --reason for the next attempt", id);
+    }
+    let (prev_assigned, changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
+        check_in_review(task)?;
+
+        let reviewer = actor.map(|a| format!(" by {}", a)).unwrap_or_default();
+        let notes = format!(
+            "## Review notes (rejected{}, {})\n{}",
+            reviewer,
+            Utc::now().format("%Y-%m-%d %H:%M UTC"),
+            reason.trim()
+        );
+        task.description = Some(match task.description.take() {
+            Some(desc) if !desc.trim().is_empty() => format!("{}\n\n{}", desc.trim_end(), notes),
+            _ => notes,
+        });
+
+        let prev_assigned = task.assigned.take();
+        task.status = Status::Open;
+        // Clear converged tag so the loop can fire again once approved
+        task.tags.retain(|t| t != "converged");
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: actor.map(String::from),
+            message: format!("Review rejected: {}", reason.trim()),
+        });
+        Ok(prev_assigned)
+    })?;
+
+    super::notify_graph_changed(dir);
+
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = workgraph::provenance::record(
+        dir,
+        "reject",
+        Some(id),
+        actor,
+        workgraph::undo::with_changes(
+            serde_json::json!({ "reason": reason, "prev_assigned": prev_assigned }),
+            &changes,
+        ),
+        config.log.rotation_threshold,
+    );
+
+    println!(
+        "Rejected '{}' and reopened it; review notes added to its description",
+        id
+    );
+    Ok(())
+}
+
+fn check_in_review(task: &Task) -> Result<()> {
+    if task.status != Status::InReview {
+        anyhow::bail!(
+            "Task '{}' is not awaiting review (status: {})",
+            task.id,
+            task.status
+        );
+    }
+    Ok(())
+}
+
+/// Review is a human gate: an agent can't sign off on work, its own or another's
+fn refuse_agents(action: &str, id: &str) -> Result<()> {
+    if super::done::run_by_agent() {
+        anyhow::bail!("Agents can't {} '{}'. Reviews are for humans", action, id);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use workgraph::test_helpers::{make_task_with_status as make_task, setup_workgraph};
+
+    fn setup(dir: &Path) {
+        let mut task = make_task("audit", "Security audit", Status::InReview);
+        task.description = Some("Audit the login flow".to_string());
+        task.verify = Some("All findings documented".to_string());
+        task.assigned = Some("agent-1".to_string());
+        let mut report = make_task("report", "Write report", Status::Open);
+        report.blocked_by = vec!["audit".to_string()];
+        setup_workgraph(dir, vec![task, report]);
+    }
+
+    #[test]
+    fn test_approve_marks_done_and_unblocks() {
+        let dir = tempdir().unwrap();
+        setup(dir.path());
+        assert!(
+            workgraph::query::ready_tasks(&load_graph(graph_path(dir.path())).unwrap()).is_empty()
+        );
+
+        run_approve(dir.path(), "audit", Some("erik")).unwrap();
+
+        let graph = load_graph(graph_path(dir.path())).unwrap();
+        let task = graph.get_task("audit").unwrap();
+        assert_eq!(task.status, Status::Done);
+        assert!(task.completed_at.is_some());
+        assert_eq!(task.log.last().unwrap().actor.as_deref(), Some("erik"));
+        let ready = workgraph::query::ready_tasks(&graph);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].id, "report");
+
+        // Only tasks awaiting review can be approved
+        let err = run_approve(dir.path(), "audit", None).unwrap_err();
+        assert!(err.to_string().contains("not awaiting review"));
+    }
+
+    #[test]
+    fn test_reject_reopens_with_review_notes() {
+        let dir = tempdir().unwrap();
+        setup(dir.path());
+
+        assert!(run_reject(dir.path(), "audit", "  ", None).is_err());
+        run_reject(
+            dir.path(),
+            "audit",
+            "Missing the session fixation case",
+            Some("erik"),
+        )
+        .unwrap();
+
+        let graph = load_graph(graph_path(dir.path())).unwrap();
+        let task = graph.get_task("audit").unwrap();
+        assert_eq!(task.status, Status::Open);
+        assert_eq!(task.assigned, None);
+        let desc = task.description.as_deref().unwrap();
+        assert!(desc.starts_with("Audit the login flow\n\n## Review notes (rejected by erik, "));
+        assert!(desc.ends_with("\nMissing the session fixation case"));
+        assert_eq!(
+            task.log.last().unwrap().message,
+            "Review rejected: Missing the session fixation case"
+        );
+        assert_eq!(graph.get_task("report").unwrap().status, Status::Open);
+    }
+}
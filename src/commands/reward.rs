@@ -130,6 +130,15 @@ pub fn run(
 
     // Step 3: Collect task artifacts and log entries
     let artifacts = &task.artifacts;
+    for (path, snapshot) in &task.artifact_snapshots {
+        let drift = workgraph::artifacts::check(dir, path, snapshot);
+        if drift != workgraph::artifacts::Drift::Intact {
+            eprintln!(
+                "Warning: artifact '{}' is {} since '{}' produced it. Restore it with 'wg artifact checkout {}'",
+                path, drift, task_id, task_id
+            );
+        }
+    }
     let log_entries = &task.log;
 
     // Step 4: Build evaluator prompt
@@ -256,7 +256,6 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
     use workgraph::graph::Status;
-    use workgraph::parser::save_graph;
     use workgraph::test_helpers::{make_task, make_task_with_status, setup_workgraph};
 
     fn make_dir() -> (TempDir, std::path::PathBuf) {
@@ -333,9 +332,11 @@ mod tests {
         runs::snapshot(&dir, "run-001", &meta).unwrap();
 
         // Modify current graph: reset t2 to open
-        let (mut graph, path) = super::super::load_workgraph_mut(&dir).unwrap();
-        graph.get_task_mut("t2").unwrap().status = Status::Open;
-        save_graph(&graph, &path).unwrap();
+        super::super::with_workgraph_mut(&dir, |graph| {
+            graph.get_task_mut("t2").unwrap().status = Status::Open;
+            Ok(())
+        })
+        .unwrap();
 
         // Diff should show t2 changed
         run_diff(&dir, "run-001", false).unwrap();
@@ -0,0 +1,211 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+use workgraph::graph::Status;
+use workgraph::schedule::{MissedFire, Schedule};
+
+/// A scheduled task with its upcoming fire times
+#[derive(Debug, Serialize)]
+struct ScheduleEntry {
+    id: String,
+    title: String,
+    status: Status,
+    cron: String,
+    mode: &'static str,
+    missed: MissedFire,
+    next_fires: Vec<DateTime<Utc>>,
+}
+
+/// Attach a cron schedule to a task, replacing any existing one
+pub fn run_set(dir: &Path, id: &str, cron: &str, copy: bool, missed: Option<&str>) -> Result<()> {
+    let missed: Option<MissedFire> = missed.map(str::parse).transpose()?;
+    let schedule = Schedule::new(cron, copy, missed, Utc::now())?;
+    let next_fire = schedule.next_fire.clone();
+
+    let ((), changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
+        if task.status == Status::Abandoned {
+            anyhow::bail!("Task '{}' is abandoned and would never fire", id);
+        }
+        workgraph::schedule::apply(task, schedule.clone());
+        Ok(())
+    })?;
+    super::notify_graph_changed(dir);
+
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = workgraph::provenance::record(
+        dir,
+        "schedule",
+        Some(id),
+        None,
+        workgraph::undo::with_changes(serde_json::json!({ "schedule": schedule }), &changes),
+        config.log.rotation_threshold,
+    );
+
+    println!(
+        "Scheduled '{}' ({}, {}): next fire {}",
+        id,
+        schedule.cron,
+        if copy { "copy" } else { "reopen" },
+        next_fire.as_deref().unwrap_or("never")
+    );
+    if copy {
+        println!("  '{}' is paused as the template for its copies", id);
+    }
+    Ok(())
+}
+
+/// Remove a task's schedule
+pub fn run_clear(dir: &Path, id: &str) -> Result<()> {
+    let (was_copy, changes) = super::with_workgraph_changes(dir, |graph| {
+        let task = graph.get_task_mut_or_err(id)?;
+        let Some(schedule) = task.schedule.take() else {
+            anyhow::bail!("Task '{}' has no schedule", id);
+        };
+        // Release a task held back for its first fire
+        if task.ready_after.is_some() && task.ready_after == schedule.next_fire {
+            task.ready_after = None;
+        }
+        Ok(schedule.copy)
+    })?;
+    super::notify_graph_changed(dir);
+
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = workgraph::provenance::record(
+        dir,
+        "unschedule",
+        Some(id),
+        None,
+        workgraph::undo::with_changes(serde_json::json!({}), &changes),
+        config.log.rotation_threshold,
+    );
+
+    println!("Removed schedule from '{}'", id);
+    if was_copy {
+        println!("  The task is still paused; 'wg resume {}' to run it", id);
+    }
+    Ok(())
+}
+
+/// List scheduled tasks with their next `count` fire times
+pub fn run_list(dir: &Path, count: usize, json: bool) -> Result<()> {
+    let (graph, _path) = super::load_workgraph(dir)?;
+    let config = workgraph::config::Config::load_or_default(dir);
+    let now = Utc::now();
+
+    let mut entries: Vec<ScheduleEntry> = graph
+        .tasks()
+        .filter_map(|task| {
+            let schedule = task.schedule.as_ref()?;
+            let next_fires = if task.status == Status::Abandoned {
+                vec![]
+            } else {
+                schedule.upcoming(now, count.max(1))
+            };
+            Some(ScheduleEntry {
+                id: task.id.clone(),
+                title: task.title.clone(),
+                status: task.status,
+                cron: schedule.cron.clone(),
+                mode: if schedule.copy { "copy" } else { "reopen" },
+                missed: schedule.missed.unwrap_or(config.coordinator.missed_fire),
+                next_fires,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        a.next_fires
+            .first()
+            .cmp(&b.next_fires.first())
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No scheduled tasks.");
+        return Ok(());
+    }
+
+    for entry in &entries {
+        println!(
+            "{} [{}] - {}\n  cron: {}  mode: {}  missed: {}",
+            entry.id, entry.status, entry.title, entry.cron, entry.mode, entry.missed
+        );
+        match entry.next_fires.as_slice() {
+            [] => println!("  next: never"),
+            [next] => println!("  next: {}", format_fire(next, now)),
+            fires => {
+                println!("  next:");
+                for fire in fires {
+                    println!("    {}", format_fire(fire, now));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn format_fire(fire: &DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let when = fire.format("%Y-%m-%d %H:%M UTC");
+    if *fire <= now {
+        format!("{} (due)", when)
+    } else {
+        format!(
+            "{} (in {})",
+            when,
+            workgraph::format_duration((*fire - now).num_seconds(), false)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use workgraph::graph::{Node, Task, WorkGraph};
+    use workgraph::parser::{load_graph, save_graph};
+
+    #[test]
+    fn test_set_list_clear() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(Task {
+            id: "audit".to_string(),
+            title: "Dependency audit".to_string(),
+            ..Task::default()
+        }));
+        let path = super::super::graph_path(dir);
+        save_graph(&graph, &path).unwrap();
+
+        assert!(run_set(dir, "audit", "not cron", false, None).is_err());
+        assert!(run_set(dir, "audit", "0 9 * * mon-fri", false, Some("sometimes")).is_err());
+        run_set(dir, "audit", "0 9 * * mon-fri", false, Some("skip")).unwrap();
+
+        let task = load_graph(&path)
+            .unwrap()
+            .get_task("audit")
+            .unwrap()
+            .clone();
+        let schedule = task.schedule.unwrap();
+        assert_eq!(schedule.missed, Some(MissedFire::Skip));
+        // Held back until the first fire
+        assert!(task.ready_after.is_some());
+        assert_eq!(task.ready_after, schedule.next_fire);
+        assert!(run_list(dir, 3, true).is_ok());
+
+        run_clear(dir, "audit").unwrap();
+        let task = load_graph(&path)
+            .unwrap()
+            .get_task("audit")
+            .unwrap()
+            .clone();
+        assert!(task.schedule.is_none());
+        assert!(task.ready_after.is_none());
+        assert!(run_clear(dir, "audit").is_err());
+    }
+}
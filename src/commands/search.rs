@@ -0,0 +1,171 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::Path;
+use workgraph::graph::Task;
+use workgraph::search::{self, SearchIndex, Snippet};
+
+pub fn run(dir: &Path, terms: &[String], reindex: bool, limit: usize, json: bool) -> Result<()> {
+    let (graph, _path) = super::load_workgraph(dir)?;
+
+    let index = if reindex {
+        let index = SearchIndex::rebuild(dir, &graph)?;
+        if terms.is_empty() {
+            let (total, archived) = index.counts();
+            if json {
+                println!(
+                    "{}",
+                    serde_json::json!({ "indexed": total, "archived": archived })
+                );
+            } else {
+                println!("Indexed {} tasks ({} archived)", total, archived);
+            }
+            return Ok(());
+        }
+        index
+    } else {
+        SearchIndex::open(dir, &graph)?
+    };
+
+    let query = terms.join(" ");
+    if search::tokenize(&query).next().is_none() {
+        anyhow::bail!("Nothing to search for in '{}'", query);
+    }
+    let hits: Vec<_> = index.search(&query).into_iter().take(limit).collect();
+
+    // Archived tasks are only read when a hit needs one
+    let archived: HashMap<String, Task> = if hits.iter().any(|h| h.archived) {
+        search::archived_tasks(dir)?
+            .into_iter()
+            .map(|t| (t.id.clone(), t))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    let results: Vec<(&search::SearchHit, &Task)> = hits
+        .iter()
+        .filter_map(|hit| {
+            let task = if hit.archived {
+                archived.get(&hit.id)
+            } else {
+                graph.get_task(&hit.id)
+            };
+            task.map(|t| (hit, t))
+        })
+        .collect();
+
+    if json {
+        let output: Vec<_> = results
+            .iter()
+            .map(|(hit, task)| {
+                serde_json::json!({
+                    "id": task.id,
+                    "title": task.title,
+                    "status": task.status,
+                    "archived": hit.archived,
+                    "score": hit.score,
+                    "snippet": search::snippet(task, &query),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if results.is_empty() {
+        println!("No tasks match '{}'", query);
+        return Ok(());
+    }
+
+    let use_color = std::io::stdout().is_terminal();
+    for (hit, task) in &results {
+        let archived = if hit.archived { " (archived)" } else { "" };
+        println!(
+            "{} [{}] {}{}  score {:.2}",
+            task.id, task.status, task.title, archived, hit.score
+        );
+        if let Some(snippet) = search::snippet(task, &query)
+            && snippet.field != "title"
+        {
+            println!("    {}: {}", snippet.field, highlight(&snippet, use_color));
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a snippet with its matches in bold yellow, or `*marked*` when not
+/// writing to a terminal
+fn highlight(snippet: &Snippet, use_color: bool) -> String {
+    let (open, close) = if use_color {
+        ("\x1b[1;33m", "\x1b[0m")
+    } else {
+        ("*", "*")
+    };
+    let mut out = String::new();
+    let mut pos = 0;
+    for &(start, end) in &snippet.highlights {
+        out.push_str(&snippet.text[pos..start]);
+        out.push_str(open);
+        out.push_str(&snippet.text[start..end]);
+        out.push_str(close);
+        pos = end;
+    }
+    out.push_str(&snippet.text[pos..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use workgraph::graph::{Node, Status, WorkGraph};
+    use workgraph::parser::save_graph;
+
+    fn setup(dir: &Path) {
+        std::fs::create_dir_all(dir).unwrap();
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(Task {
+            id: "migrate-db".to_string(),
+            title: "Migrate database".to_string(),
+            description: Some("The flaky migration keeps timing out".to_string()),
+            status: Status::Open,
+            ..Task::default()
+        }));
+        save_graph(&graph, super::super::graph_path(dir)).unwrap();
+    }
+
+    #[test]
+    fn test_search_builds_index_and_tracks_saves() {
+        let dir = tempdir().unwrap();
+        setup(dir.path());
+        let terms = vec!["flaky".to_string()];
+
+        assert!(run(dir.path(), &terms, false, 20, false).is_ok());
+        assert!(dir.path().join(search::INDEX_FILE).exists());
+
+        // A later save keeps the existing index current
+        super::super::with_workgraph_mut(dir.path(), |graph| {
+            graph.get_task_mut("migrate-db").unwrap().description =
+                Some("Now about caching".to_string());
+            Ok(())
+        })
+        .unwrap();
+        let index = SearchIndex::load(dir.path()).unwrap().unwrap();
+        assert!(index.search("flaky").is_empty());
+        assert_eq!(index.search("caching")[0].id, "migrate-db");
+
+        assert!(run(dir.path(), &[], true, 20, true).is_ok());
+        assert!(run(dir.path(), &["the".to_string()], false, 20, false).is_err());
+    }
+
+    #[test]
+    fn test_highlight() {
+        let snippet = Snippet {
+            field: "description",
+            text: "a flaky test".to_string(),
+            highlights: vec![(2, 7)],
+        };
+        assert_eq!(highlight(&snippet, false), "a *flaky* test");
+    }
+}
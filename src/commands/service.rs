@@ -31,11 +31,14 @@ use std::os::unix::net::{UnixListener, UnixStream};
 use chrono::Utc;
 
 use workgraph::identity;
+use workgraph::completion::{check_completable, complete, read_guards};
 use workgraph::config::Config;
-use workgraph::graph::{LogEntry, Node, Status, Task, reward_loop_edges};
-use workgraph::parser::{load_graph, save_graph};
+use workgraph::graph::{LogEntry, Node, Priority, Status, Task, skip_unreachable};
+use workgraph::loop_guard::Readings;
+use workgraph::parser::{load_graph, with_graph_mut};
 use workgraph::query::ready_tasks_with_peers;
 use workgraph::service::registry::{AgentEntry, AgentRegistry, AgentStatus};
+use workgraph::verify;
 
 use super::{graph_path, is_process_alive, spawn};
 
@@ -427,13 +430,22 @@ fn build_auto_assign_tasks(graph: &mut workgraph::graph::WorkGraph, config: &Con
                     t.agent.clone(),
                     t.assigned.clone(),
                     t.tags.clone(),
+                    t.priority,
                 )
             })
             .collect()
     };
 
-    for (task_id, task_title, task_desc, task_skills, task_agent, task_assigned, task_tags) in
-        ready_task_data
+    for (
+        task_id,
+        task_title,
+        task_desc,
+        task_skills,
+        task_agent,
+        task_assigned,
+        task_tags,
+        task_priority,
+    ) in ready_task_data
     {
         // Skip tasks that already have an agent or are already claimed
         if task_agent.is_some() || task_assigned.is_some() {
@@ -577,6 +589,22 @@ fn build_auto_assign_tasks(graph: &mut workgraph::graph::WorkGraph, config: &Con
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            // Inherit priority so urgent work isn't held up behind its assignment
+            priority: task_priority,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         };
 
         graph.add_node(Node::Task(assign_task));
@@ -706,6 +734,21 @@ fn build_auto_reward_tasks(
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         };
 
         graph.add_node(Node::Task(eval_task));
@@ -771,9 +814,9 @@ fn spawn_eval_inline(
     use std::process::{Command, Stdio};
 
     let graph_path = graph_path(dir);
-    let mut graph = load_graph(&graph_path).context("Failed to load graph for eval spawn")?;
+    let graph = load_graph(&graph_path).context("Failed to load graph for eval spawn")?;
 
-    let task = graph.get_task_mut_or_err(eval_task_id)?;
+    let task = graph.get_task_or_err(eval_task_id)?;
     if task.status != Status::Open {
         anyhow::bail!("Eval task '{}' is not open (status: {:?})", eval_task_id, task.status);
     }
@@ -823,21 +866,32 @@ fi
 exit $EXIT_CODE"#,
     );
 
-    // Claim the task before spawning
-    task.status = Status::InProgress;
-    task.started_at = Some(Utc::now().to_rfc3339());
-    task.assigned = Some(agent_id.clone());
-    task.log.push(LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: Some(agent_id.clone()),
-        message: format!(
-            "Spawned eval inline{}",
-            evaluator_model
-                .map(|m| format!(" --model {}", m))
-                .unwrap_or_default()
-        ),
-    });
-    save_graph(&graph, &graph_path).context("Failed to save graph after claiming eval task")?;
+    // Claim the task before spawning, re-checking its status under the graph lock
+    with_graph_mut(&graph_path, |graph| -> Result<()> {
+        let task = graph.get_task_mut_or_err(eval_task_id)?;
+        if task.status != Status::Open {
+            anyhow::bail!(
+                "Eval task '{}' is not open (status: {:?})",
+                eval_task_id,
+                task.status
+            );
+        }
+        task.status = Status::InProgress;
+        task.started_at = Some(Utc::now().to_rfc3339());
+        task.assigned = Some(agent_id.clone());
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: Some(agent_id.clone()),
+            message: format!(
+                "Spawned eval inline{}",
+                evaluator_model
+                    .map(|m| format!(" --model {}", m))
+                    .unwrap_or_default()
+            ),
+        });
+        Ok(())
+    })
+    .context("Failed to claim eval task")?;
 
     // Fork the process
     let mut cmd = Command::new("bash");
@@ -862,8 +916,8 @@ exit $EXIT_CODE"#,
         Ok(child) => child,
         Err(e) => {
             // Rollback the claim
-            if let Ok(mut rollback_graph) = load_graph(&graph_path)
-                && let Some(t) = rollback_graph.get_task_mut(eval_task_id) {
+            let _ = with_graph_mut(&graph_path, |graph| -> Result<()> {
+                if let Some(t) = graph.get_task_mut(eval_task_id) {
                     t.status = Status::Open;
                     t.started_at = None;
                     t.assigned = None;
@@ -872,8 +926,9 @@ exit $EXIT_CODE"#,
                         actor: Some(agent_id.clone()),
                         message: format!("Eval spawn failed, reverting claim: {}", e),
                     });
-                    let _ = save_graph(&rollback_graph, &graph_path);
                 }
+                Ok(())
+            });
             return Err(anyhow::anyhow!("Failed to spawn eval process: {}", e));
         }
     };
@@ -895,6 +950,9 @@ exit $EXIT_CODE"#,
 
 /// Spawn agents on ready tasks, up to `slots_available`. Returns the number of
 /// agents successfully spawned.
+///
+/// Ready tasks arrive in dispatch order (priority, then critical-path length),
+/// so when there are more ready tasks than slots the most urgent work wins.
 fn spawn_agents_for_ready_tasks(
     dir: &Path,
     graph: &workgraph::graph::WorkGraph,
@@ -905,13 +963,30 @@ fn spawn_agents_for_ready_tasks(
     let final_ready = ready_tasks_with_peers(graph, dir);
     let agents_dir = dir.join("identity").join("agents");
     let mut spawned = 0;
+    let mut attempted = 0;
 
-    let to_spawn = final_ready.iter().take(slots_available);
-    for task in to_spawn {
-        // Skip if already claimed
-        if task.assigned.is_some() {
+    // Resources are counting semaphores: each ready task fits on its own, but
+    // spawning several in one tick must not oversubscribe a shared resource.
+    let mut resource_usage = workgraph::query::resource_usage(graph);
+
+    // Already-claimed tasks don't consume a slot
+    for task in final_ready.iter().filter(|task| task.assigned.is_none()) {
+        if attempted >= slots_available {
+            break;
+        }
+        let waits = workgraph::query::resource_waits(graph, task, &resource_usage);
+        if !waits.is_empty() {
+            let names: Vec<&str> = waits.iter().map(|w| w.resource.as_str()).collect();
+            eprintln!(
+                "[coordinator] Deferring {}: waiting on resource {}",
+                task.id,
+                names.join(", ")
+            );
             continue;
         }
+        attempted += 1;
+        // Reserve up front; a failed spawn only idles the capacity until next tick
+        workgraph::query::reserve_resources(task, &mut resource_usage);
 
         // Reward tasks run inline: fork `wg reward` directly instead of
         // going through the full spawn machinery (run.sh, executor config, etc.)
@@ -967,6 +1042,130 @@ fn spawn_agents_for_ready_tasks(
     spawned
 }
 
+/// Fan out done fan-out tasks that were completed other than by `wg done`
+/// (e.g. by `wg exec` or triage), recording the changes as a `fan_out`
+/// operation. Errors are logged, not fatal.
+fn expand_fan_outs(dir: &Path, graph_path: &Path, config: &Config) {
+    let expanded = workgraph::undo::with_graph_changes(graph_path, |graph| {
+        Ok(workgraph::fan_out::expand_pending(graph, dir))
+    });
+    let (expansions, changes) = match expanded {
+        Ok(expanded) => expanded,
+        Err(e) => {
+            eprintln!("[coordinator] Failed to expand fan-out tasks: {}", e);
+            return;
+        }
+    };
+    if changes.is_empty() {
+        return;
+    }
+
+    for e in &expansions {
+        match &e.error {
+            Some(error) => eprintln!("[coordinator] {}: {}", e.task, error),
+            None => eprintln!(
+                "[coordinator] {} fanned out into {} task(s)",
+                e.task,
+                e.children.len()
+            ),
+        }
+    }
+    let _ = workgraph::provenance::record(
+        dir,
+        "fan_out",
+        None,
+        Some("coordinator"),
+        workgraph::undo::with_changes(serde_json::json!({ "expansions": expansions }), &changes),
+        config.log.rotation_threshold,
+    );
+}
+
+/// Reopen failed tasks their retry policy says to retry, recording the
+/// changes as an `auto_retry` operation. Errors are logged, not fatal.
+fn apply_retry_policies(dir: &Path, graph_path: &Path, config: &Config) {
+    let now = chrono::Utc::now();
+    let retried = workgraph::undo::with_graph_changes(graph_path, |graph| {
+        Ok(workgraph::retry::apply_policies(graph, &config.retry, now))
+    });
+    let (retried, changes) = match retried {
+        Ok(retried) => retried,
+        Err(e) => {
+            eprintln!("[coordinator] Failed to apply retry policies: {}", e);
+            return;
+        }
+    };
+    if changes.is_empty() {
+        return;
+    }
+
+    for r in &retried {
+        eprintln!(
+            "[coordinator] Retrying {} (attempt #{}) after {} failure, ready after {}",
+            r.task, r.attempt, r.class, r.retry_at
+        );
+    }
+    let _ = workgraph::provenance::record(
+        dir,
+        "auto_retry",
+        None,
+        Some("coordinator"),
+        workgraph::undo::with_changes(serde_json::json!({ "retried": retried }), &changes),
+        config.log.rotation_threshold,
+    );
+}
+
+/// Reopen or copy scheduled tasks whose fire time has come, recording the
+/// changes as a `schedule_fire` operation. Errors are logged, not fatal: a
+/// failed fire is retried on the next tick.
+fn fire_schedules(dir: &Path, graph_path: &Path, config: &Config) {
+    let now = chrono::Utc::now();
+    let grace = chrono::Duration::seconds(2 * config.coordinator.poll_interval as i64);
+    let fired = workgraph::undo::with_graph_changes(graph_path, |graph| {
+        Ok(workgraph::schedule::fire_due(
+            graph,
+            now,
+            config.coordinator.missed_fire,
+            grace,
+        ))
+    });
+    let (fired, changes) = match fired {
+        Ok(fired) => fired,
+        Err(e) => {
+            eprintln!("[coordinator] Failed to fire schedules: {}", e);
+            return;
+        }
+    };
+    if changes.is_empty() {
+        return;
+    }
+
+    for f in &fired {
+        if !f.created.is_empty() {
+            eprintln!(
+                "[coordinator] Schedule on {} created {}",
+                f.task,
+                f.created.join(", ")
+            );
+        } else if !f.fires.is_empty() {
+            eprintln!("[coordinator] Schedule fired {}", f.task);
+        }
+        if f.missed > 0 {
+            eprintln!(
+                "[coordinator] Schedule on {} skipped {} missed fire(s)",
+                f.task, f.missed
+            );
+        }
+    }
+    let _ = workgraph::provenance::record(
+        dir,
+        "schedule_fire",
+        None,
+        Some("coordinator"),
+        workgraph::undo::with_changes(serde_json::json!({ "fired": fired }), &changes),
+        config.log.rotation_threshold,
+    );
+}
+
 /// Single coordinator tick: spawn agents on ready tasks
 pub fn coordinator_tick(
     dir: &Path,
@@ -979,37 +1178,38 @@ pub fn coordinator_tick(
     // Load config for identity settings
     let config = Config::load_or_default(dir);
 
+    // Phase 0: Fire due cron schedules, apply retry policies and expand fan-outs,
+    // before any early return so recurring tasks keep firing while all agent
+    // slots are busy
+    fire_schedules(dir, &graph_path, &config);
+    apply_retry_policies(dir, &graph_path, &config);
+    expand_fan_outs(dir, &graph_path, &config);
+
     // Phase 1: Clean up dead agents and count alive ones
     let alive_count = match cleanup_and_count_alive(dir, &graph_path, max_agents)? {
         Ok(count) => count,
         Err(early_result) => return Ok(early_result),
     };
 
-    // Phase 2: Load graph
-    let mut graph = load_graph(&graph_path).context("Failed to load graph")?;
-
     let slots_available = max_agents.saturating_sub(alive_count);
 
-    // Phase 3: Auto-assign unassigned ready tasks
+    // Phases 2-4: Load graph, auto-assign unassigned ready tasks and create
+    // auto-reward tasks in one locked transaction.
     // NOTE: These must run BEFORE the early-return check, because they may
     // create new ready tasks (e.g. reward-* tasks) that weren't there before.
-    let mut graph_modified = false;
-    if config.identity.auto_assign {
-        graph_modified |= build_auto_assign_tasks(&mut graph, &config, dir);
-    }
-
-    // Phase 4: Auto-reward tasks
-    if config.identity.auto_reward {
-        graph_modified |= build_auto_reward_tasks(dir, &mut graph, &config);
-    }
-
-    // Save graph once if it was modified during auto-assign or auto-reward.
-    // Abort tick if save fails — continuing with unsaved state would spawn agents
-    // on tasks that haven't been persisted.
-    if graph_modified {
-        save_graph(&graph, &graph_path)
-            .context("Failed to save graph after auto-assign/auto-reward; aborting tick")?;
-    }
+    // The graph is only written if a phase modified it. Abort tick if the save
+    // fails — continuing with unsaved state would spawn agents on tasks that
+    // haven't been persisted.
+    let graph = with_graph_mut(&graph_path, |graph| -> Result<_> {
+        if config.identity.auto_assign {
+            build_auto_assign_tasks(graph, &config, dir);
+        }
+        if config.identity.auto_reward {
+            build_auto_reward_tasks(dir, graph, &config);
+        }
+        Ok(graph.clone())
+    })
+    .context("Failed to update graph for auto-assign/auto-reward; aborting tick")?;
 
     // Phase 5: Check for ready tasks (after identity phases may have created new ones)
     if let Some(early_result) = check_ready_or_return(&graph, alive_count, dir) {
@@ -1094,49 +1294,86 @@ fn cleanup_dead_agents(dir: &Path, graph_path: &Path) -> Result<Vec<String>> {
     // Load config for triage settings
     let config = Config::load_or_default(dir);
 
-    // Unclaim their tasks (if still in progress - agent may have completed or failed them already)
-    let mut graph = load_graph(graph_path).context("Failed to load graph")?;
-    let mut tasks_modified = false;
-    let mut tasks_completed_by_triage: Vec<String> = Vec::new();
+    // Triage may take a while (it calls a model), so it runs against a
+    // snapshot of the graph before the graph lock is taken, as do the
+    // verification checks and loop guards of tasks it finds done.
+    let mut triage_results: std::collections::HashMap<String, Result<TriageVerdict>> =
+        std::collections::HashMap::new();
+    let mut guards: std::collections::HashMap<String, Readings> = std::collections::HashMap::new();
+    let mut failed_checks: std::collections::HashMap<String, String> =
+        std::collections::HashMap::new();
+    if config.identity.auto_triage {
+        let snapshot = load_graph(graph_path).context("Failed to load graph")?;
+        for (_agent_id, task_id, _pid, output_file, _reason) in &dead {
+            if let Some(task) = snapshot.get_task(task_id)
+                && task.status == Status::InProgress
+            {
+                let result = run_triage(&config, task, output_file);
+                if result.as_ref().is_ok_and(|v| v.verdict == "done") {
+                    let failed = match verify::run(task, dir) {
+                        Ok(failures) if failures.is_empty() => None,
+                        Ok(failures) => Some(verify::summary(&failures)),
+                        Err(e) => Some(e.to_string()),
+                    };
+                    if let Some(failed) = failed {
+                        failed_checks.insert(task_id.clone(), failed);
+                    }
+                    guards.insert(task_id.clone(), read_guards(&snapshot, dir, task_id));
+                }
+                triage_results.insert(task_id.clone(), result);
+            }
+        }
+    }
 
-    for (agent_id, task_id, pid, output_file, reason) in &dead {
-        if let Some(task) = graph.get_task_mut(task_id) {
+    // Unclaim their tasks (if still in progress - agent may have completed or failed them already)
+    with_graph_mut(graph_path, |graph| -> Result<()> {
+        for (agent_id, task_id, pid, _output_file, reason) in &dead {
+            let Some(task) = graph.get_task_mut(task_id) else {
+                continue;
+            };
             // Only unclaim if task is still in progress (agent didn't finish it properly)
-            if task.status == Status::InProgress {
-                if config.identity.auto_triage {
-                    // Run synchronous triage to assess progress
-                    match run_triage(&config, task, output_file) {
-                        Ok(verdict) => {
-                            let is_done = verdict.verdict == "done";
-                            apply_triage_verdict(task, &verdict, agent_id, *pid);
-                            eprintln!(
-                                "[coordinator] Triage for '{}': verdict={}, reason={}",
-                                task_id, verdict.verdict, verdict.reason
-                            );
-                            if is_done && task.status == Status::Done {
-                                tasks_completed_by_triage.push(task_id.clone());
-                            }
-                        }
-                        Err(e) => {
-                            // Triage failed, fall back to restart behavior
-                            eprintln!(
-                                "[coordinator] Triage failed for '{}': {}, falling back to restart",
-                                task_id, e
-                            );
-                            task.status = Status::Open;
-                            task.assigned = None;
-                            task.log.push(LogEntry {
-                                timestamp: Utc::now().to_rfc3339(),
-                                actor: Some("triage".to_string()),
-                                message: format!(
-                                    "Triage failed ({}), task reset: agent '{}' (PID {}) process exited",
-                                    e, agent_id, pid
-                                ),
-                            });
-                        }
+            if task.status != Status::InProgress {
+                continue;
+            }
+            match triage_results.remove(task_id) {
+                Some(Ok(verdict)) if verdict.verdict == "done" => {
+                    eprintln!(
+                        "[coordinator] Triage for '{}': verdict={}, reason={}",
+                        task_id, verdict.verdict, verdict.reason
+                    );
+                    if let Some(failed) = failed_checks.remove(task_id) {
+                        fail_triaged_verification(task, &failed, agent_id, *pid);
+                    } else {
+                        let readings = guards.remove(task_id).unwrap_or_default();
+                        complete_triaged(graph, dir, task_id, &verdict, agent_id, *pid, &readings);
                     }
-                } else {
-                    // Existing behavior: simple unclaim
+                }
+                Some(Ok(verdict)) => {
+                    apply_triage_verdict(task, &verdict, agent_id, *pid);
+                    eprintln!(
+                        "[coordinator] Triage for '{}': verdict={}, reason={}",
+                        task_id, verdict.verdict, verdict.reason
+                    );
+                }
+                Some(Err(e)) => {
+                    // Triage failed, fall back to restart behavior
+                    eprintln!(
+                        "[coordinator] Triage failed for '{}': {}, falling back to restart",
+                        task_id, e
+                    );
+                    task.status = Status::Open;
+                    task.assigned = None;
+                    task.log.push(LogEntry {
+                        timestamp: Utc::now().to_rfc3339(),
+                        actor: Some("triage".to_string()),
+                        message: format!(
+                            "Triage failed ({}), task reset: agent '{}' (PID {}) process exited",
+                            e, agent_id, pid
+                        ),
+                    });
+                }
+                None => {
+                    // No triage: simple unclaim
                     task.status = Status::Open;
                     task.assigned = None;
                     let reason_msg = match reason {
@@ -1151,33 +1388,30 @@ fn cleanup_dead_agents(dir: &Path, graph_path: &Path) -> Result<Vec<String>> {
                         message: reason_msg,
                     });
                 }
-                tasks_modified = true;
             }
         }
-    }
 
-    // Reward loop edges for tasks that were triaged as done
-    for task_id in &tasks_completed_by_triage {
-        reward_loop_edges(&mut graph, task_id);
-    }
-
-    if tasks_modified {
-        save_graph(&graph, graph_path).context("Failed to save graph")?;
-    }
+        // Triage may have failed tasks with conditional dependents
+        skip_unreachable(graph);
+        Ok(())
+    })?;
 
     // Capture output for completed/failed tasks whose agents just died.
     // done.rs already captures output, but fail.rs does not,
     // and the agent may have completed without triggering capture (e.g. wrapper
     // script marked it done but output capture wasn't invoked). This is a
     // best-effort safety net.
-    let graph = load_graph(graph_path).context("Failed to reload graph for output capture")?;
+    let store = workgraph::store::open(graph_path);
     for (_agent_id, task_id, _pid, _output_file, _reason) in &dead {
-        if let Some(task) = graph.get_task(task_id)
+        let task = store
+            .get_task(task_id)
+            .context("Failed to reload task for output capture")?;
+        if let Some(task) = task
             && matches!(task.status, Status::Done | Status::Failed)
         {
             let output_dir = dir.join("output").join(task_id);
             if !output_dir.exists() {
-                if let Err(e) = identity::capture_task_output(dir, task) {
+                if let Err(e) = identity::capture_task_output(dir, &task) {
                     eprintln!(
                         "[coordinator] Warning: output capture failed for '{}': {}",
                         task_id, e
@@ -1381,21 +1615,90 @@ fn extract_triage_json(raw: &str) -> Option<String> {
     None
 }
 
-/// Apply a triage verdict to a task.
-fn apply_triage_verdict(task: &mut Task, verdict: &TriageVerdict, agent_id: &str, pid: u32) {
-    match verdict.verdict.as_str() {
-        "done" => {
-            task.status = Status::Done;
-            task.completed_at = Some(Utc::now().to_rfc3339());
+/// Finish a task triaged as done the way `wg done` would, so fan-outs,
+/// loop edges, parents, quorum siblings and conditional dependents react
+/// (see [`complete`]). As with any agent's work, a task with verify criteria
+/// is submitted for review instead, and one that can't be marked done yet is
+/// reset for another agent.
+fn complete_triaged(
+    graph: &mut workgraph::graph::WorkGraph,
+    dir: &Path,
+    task_id: &str,
+    verdict: &TriageVerdict,
+    agent_id: &str,
+    pid: u32,
+    guards: &Readings,
+) {
+    let completed = check_completable(graph, task_id).and_then(|()| {
+        let task = graph.get_task_mut_or_err(task_id)?;
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: Some("triage".to_string()),
+            message: format!(
+                "Triage: work complete (agent '{}' PID {} died) — {}",
+                agent_id, pid, verdict.reason
+            ),
+        });
+        if task.verify.is_some() {
+            task.status = Status::InReview;
             task.log.push(LogEntry {
                 timestamp: Utc::now().to_rfc3339(),
                 actor: Some("triage".to_string()),
-                message: format!(
-                    "Triage: work complete (agent '{}' PID {} died) — {}",
-                    agent_id, pid, verdict.reason
-                ),
+                message: "Task submitted for review".to_string(),
             });
+            return Ok(());
         }
+        complete(graph, dir, task_id, guards).map(|_| ())
+    });
+    let Err(e) = completed else {
+        return;
+    };
+    eprintln!(
+        "[coordinator] Triage found '{}' done but it can't be completed: {}",
+        task_id, e
+    );
+    let Some(task) = graph.get_task_mut(task_id) else {
+        return;
+    };
+    task.status = Status::Open;
+    task.assigned = None;
+    task.completed_at = None;
+    task.log.push(LogEntry {
+        timestamp: Utc::now().to_rfc3339(),
+        actor: Some("triage".to_string()),
+        message: format!(
+            "Triage: work complete but not completable ({}), task reset: agent '{}' (PID {}) process exited",
+            e, agent_id, pid
+        ),
+    });
+}
+
+/// Fail a task triaged as done whose verification checks don't pass, as
+/// `wg exec` and `wg agent` do. `failed` summarizes the failed checks.
+fn fail_triaged_verification(task: &mut Task, failed: &str, agent_id: &str, pid: u32) {
+    eprintln!(
+        "[coordinator] Triage found '{}' done but verification failed: {}",
+        task.id, failed
+    );
+    task.status = Status::Failed;
+    task.assigned = None;
+    task.retry_count += 1;
+    task.failure_reason = Some(format!("Verification failed: {}", failed));
+    workgraph::retry::record_failure(task, None, None);
+    task.log.push(LogEntry {
+        timestamp: Utc::now().to_rfc3339(),
+        actor: Some("triage".to_string()),
+        message: format!(
+            "Triage: work complete but verification failed (agent '{}' PID {} died) — {}",
+            agent_id, pid, failed
+        ),
+    });
+}
+
+/// Apply a "continue" or "restart" triage verdict to a task ("done" goes
+/// through [`complete_triaged`]).
+fn apply_triage_verdict(task: &mut Task, verdict: &TriageVerdict, agent_id: &str, pid: u32) {
+    match verdict.verdict.as_str() {
         "continue" => {
             // Check max_retries before allowing continue
             if let Some(max) = task.max_retries
@@ -1407,6 +1710,7 @@ fn apply_triage_verdict(task: &mut Task, verdict: &TriageVerdict, agent_id: &str
                     task.retry_count, max, verdict.reason
                 ));
                 task.assigned = None;
+                workgraph::retry::record_failure(task, None, Some(&verdict.verdict));
                 task.log.push(LogEntry {
                     timestamp: Utc::now().to_rfc3339(),
                     actor: Some("triage".to_string()),
@@ -1462,6 +1766,7 @@ fn apply_triage_verdict(task: &mut Task, verdict: &TriageVerdict, agent_id: &str
                     task.retry_count, max, verdict.reason
                 ));
                 task.assigned = None;
+                workgraph::retry::record_failure(task, None, Some(&verdict.verdict));
                 task.log.push(LogEntry {
                     timestamp: Utc::now().to_rfc3339(),
                     actor: Some("triage".to_string()),
@@ -2715,111 +3020,124 @@ fn handle_add_task(
     verify: Option<&str>,
     origin: Option<&str>,
 ) -> IpcResponse {
-    use workgraph::graph::{Node, Status, Task};
-    use workgraph::parser::{load_graph, save_graph};
+    use workgraph::graph::{Node, Priority, Status, Task};
 
     let graph_path = super::graph_path(dir);
-    let mut graph = match load_graph(&graph_path) {
-        Ok(g) => g,
-        Err(e) => return IpcResponse::error(&format!("Failed to load graph: {}", e)),
-    };
-
-    // Generate or validate task ID
-    let task_id = match id {
-        Some(id) => {
-            if graph.get_node(id).is_some() {
-                return IpcResponse::error(&format!("Task with ID '{}' already exists", id));
+    let added = workgraph::undo::with_graph_changes(&graph_path, |graph| -> Result<String> {
+        // Generate or validate task ID
+        let task_id = match id {
+            Some(id) => {
+                if graph.get_node(id).is_some() {
+                    anyhow::bail!("Task with ID '{}' already exists", id);
+                }
+                id.to_string()
             }
-            id.to_string()
-        }
-        None => {
-            // Reuse the same slug generation logic as add.rs
-            let slug: String = title
-                .to_lowercase()
-                .chars()
-                .map(|c| if c.is_alphanumeric() { c } else { '-' })
-                .collect::<String>()
-                .split('-')
-                .filter(|s| !s.is_empty())
-                .take(3)
-                .collect::<Vec<_>>()
-                .join("-");
-            let base_id = if slug.is_empty() {
-                "task".to_string()
-            } else {
-                slug
-            };
-            if graph.get_node(&base_id).is_none() {
-                base_id
-            } else {
-                let mut found = None;
-                for i in 2..1000 {
-                    let candidate = format!("{}-{}", base_id, i);
-                    if graph.get_node(&candidate).is_none() {
-                        found = Some(candidate);
-                        break;
+            None => {
+                // Reuse the same slug generation logic as add.rs
+                let slug: String = title
+                    .to_lowercase()
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                    .collect::<String>()
+                    .split('-')
+                    .filter(|s| !s.is_empty())
+                    .take(3)
+                    .collect::<Vec<_>>()
+                    .join("-");
+                let base_id = if slug.is_empty() {
+                    "task".to_string()
+                } else {
+                    slug
+                };
+                if graph.get_node(&base_id).is_none() {
+                    base_id
+                } else {
+                    let mut found = None;
+                    for i in 2..1000 {
+                        let candidate = format!("{}-{}", base_id, i);
+                        if graph.get_node(&candidate).is_none() {
+                            found = Some(candidate);
+                            break;
+                        }
                     }
+                    found.unwrap_or_else(|| {
+                        format!(
+                            "task-{}",
+                            std::time::SystemTime::now()
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .map(|d| d.as_secs())
+                                .unwrap_or(0)
+                        )
+                    })
                 }
-                found.unwrap_or_else(|| {
-                    format!(
-                        "task-{}",
-                        std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .map(|d| d.as_secs())
-                            .unwrap_or(0)
-                    )
-                })
             }
-        }
-    };
+        };
 
-    let task = Task {
-        id: task_id.clone(),
-        title: title.to_string(),
-        description: description.map(String::from),
-        status: Status::Open,
-        assigned: None,
-        estimate: None,
-        blocks: vec![],
-        blocked_by: blocked_by.to_vec(),
-        requires: vec![],
-        tags: tags.to_vec(),
-        skills: skills.to_vec(),
-        inputs: vec![],
-        deliverables: deliverables.to_vec(),
-        artifacts: vec![],
-        exec: None,
-        not_before: None,
-        created_at: Some(chrono::Utc::now().to_rfc3339()),
-        started_at: None,
-        completed_at: None,
-        log: vec![],
-        retry_count: 0,
-        max_retries: None,
-        failure_reason: None,
-        model: model.map(String::from),
-        verify: verify.map(String::from),
-        agent: None,
-        loops_to: vec![],
-        loop_iteration: 0,
-        ready_after: None,
-        paused: false,
-    };
+        let task = Task {
+            id: task_id.clone(),
+            title: title.to_string(),
+            description: description.map(String::from),
+            status: Status::Open,
+            assigned: None,
+            estimate: None,
+            blocks: vec![],
+            blocked_by: blocked_by.to_vec(),
+            requires: vec![],
+            tags: tags.to_vec(),
+            skills: skills.to_vec(),
+            inputs: vec![],
+            deliverables: deliverables.to_vec(),
+            artifacts: vec![],
+            exec: None,
+            not_before: None,
+            created_at: Some(chrono::Utc::now().to_rfc3339()),
+            started_at: None,
+            completed_at: None,
+            log: vec![],
+            retry_count: 0,
+            max_retries: None,
+            failure_reason: None,
+            model: model.map(String::from),
+            verify: verify.map(String::from),
+            agent: None,
+            loops_to: vec![],
+            loop_iteration: 0,
+            ready_after: None,
+            paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
+        };
 
-    graph.add_node(Node::Task(task));
+        graph.add_node(Node::Task(task));
 
-    // Maintain bidirectional blocked_by/blocks consistency
-    for dep in blocked_by {
-        if let Some(blocker) = graph.get_task_mut(dep)
-            && !blocker.blocks.contains(&task_id)
-        {
-            blocker.blocks.push(task_id.clone());
+        // Maintain bidirectional blocked_by/blocks consistency
+        for dep in blocked_by {
+            if let Some(blocker) = graph.get_task_mut(dep)
+                && !blocker.blocks.contains(&task_id)
+            {
+                blocker.blocks.push(task_id.clone());
+            }
         }
-    }
 
-    if let Err(e) = save_graph(&graph, &graph_path) {
-        return IpcResponse::error(&format!("Failed to save graph: {}", e));
-    }
+        Ok(task_id)
+    });
+    let (task_id, changes) = match added {
+        Ok(added) => added,
+        Err(e) => return IpcResponse::error(&e.to_string()),
+    };
 
     // Record provenance
     let origin_str = origin.unwrap_or("unknown");
@@ -2829,7 +3147,10 @@ fn handle_add_task(
         "add_task",
         Some(&task_id),
         None,
-        serde_json::json!({ "title": title, "origin": origin_str, "remote": true }),
+        workgraph::undo::with_changes(
+            serde_json::json!({ "title": title, "origin": origin_str, "remote": true }),
+            &changes,
+        ),
         config.log.rotation_threshold,
     );
 
@@ -2841,15 +3162,13 @@ fn handle_add_task(
 
 /// Handle QueryTask IPC request — return a task's status for cross-repo dependency checking.
 fn handle_query_task(dir: &Path, task_id: &str) -> IpcResponse {
-    use workgraph::parser::load_graph;
-
-    let graph_path = super::graph_path(dir);
-    let graph = match load_graph(&graph_path) {
-        Ok(g) => g,
+    let store = workgraph::store::open(super::graph_path(dir));
+    let task = match store.get_task(task_id) {
+        Ok(task) => task,
         Err(e) => return IpcResponse::error(&format!("Failed to load graph: {}", e)),
     };
 
-    match graph.get_task(task_id) {
+    match task {
         Some(task) => IpcResponse::success(serde_json::json!({
             "task_id": task.id,
             "title": task.title,
@@ -3752,6 +4071,21 @@ poll_interval = 120
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         };
         let prompt = build_triage_prompt(&task, "some log output");
         assert!(prompt.contains("test-task"));
@@ -3891,92 +4225,133 @@ poll_interval = 120
         assert!(ServiceState::load(dir).unwrap().is_none());
     }
 
-    #[test]
-    fn test_apply_triage_verdict_done() {
-        let mut task = Task {
-            id: "t1".to_string(),
-            title: "Test".to_string(),
-            description: None,
+    fn triage_graph(tasks: Vec<Task>) -> workgraph::graph::WorkGraph {
+        let mut graph = workgraph::graph::WorkGraph::new();
+        for task in tasks {
+            graph.add_node(Node::Task(task));
+        }
+        graph
+    }
+
+    fn in_progress(id: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            title: id.to_string(),
             status: Status::InProgress,
             assigned: Some("agent-1".to_string()),
-            estimate: None,
-            blocks: vec![],
-            blocked_by: vec![],
-            requires: vec![],
-            tags: vec![],
-            skills: vec![],
-            inputs: vec![],
-            deliverables: vec![],
-            artifacts: vec![],
-            exec: None,
-            not_before: None,
-            created_at: None,
-            started_at: None,
-            completed_at: None,
-            log: vec![],
-            retry_count: 0,
-            max_retries: None,
-            failure_reason: None,
-            model: None,
-            verify: None,
-            agent: None,
-            loops_to: vec![],
-            loop_iteration: 0,
-            ready_after: None,
-            paused: false,
+            ..Task::default()
+        }
+    }
+
+    #[test]
+    fn test_complete_triaged_goes_through_completion() {
+        let dir = TempDir::new().unwrap();
+        let epic = Task {
+            id: "epic".to_string(),
+            title: "Epic".to_string(),
+            auto_complete: true,
+            ..Task::default()
         };
+        let mut t1 = in_progress("t1");
+        t1.parent = Some("epic".to_string());
+        let mut graph = triage_graph(vec![epic, t1]);
         let verdict = TriageVerdict {
             verdict: "done".to_string(),
             reason: "work complete".to_string(),
             summary: "all files written".to_string(),
         };
-        apply_triage_verdict(&mut task, &verdict, "agent-1", 1234);
+
+        complete_triaged(
+            &mut graph,
+            dir.path(),
+            "t1",
+            &verdict,
+            "agent-1",
+            1234,
+            &Readings::default(),
+        );
+        let task = graph.get_task("t1").unwrap();
         assert_eq!(task.status, Status::Done);
         assert!(task.completed_at.is_some());
         assert!(task.log.last().unwrap().message.contains("work complete"));
+        // The auto-complete parent reacts as it would to 'wg done'
+        assert_eq!(graph.get_task("epic").unwrap().status, Status::Done);
     }
 
     #[test]
-    fn test_apply_triage_verdict_done_verified() {
-        let mut task = Task {
-            id: "t1".to_string(),
-            title: "Test".to_string(),
-            description: None,
-            status: Status::InProgress,
-            assigned: Some("agent-1".to_string()),
-            estimate: None,
-            blocks: vec![],
-            blocked_by: vec![],
-            requires: vec![],
-            tags: vec![],
-            skills: vec![],
-            inputs: vec![],
-            deliverables: vec![],
-            artifacts: vec![],
-            exec: None,
-            not_before: None,
-            created_at: None,
-            started_at: None,
-            completed_at: None,
-            log: vec![],
-            retry_count: 0,
-            max_retries: None,
-            failure_reason: None,
-            model: None,
-            verify: Some("Check tests pass".to_string()),
-            agent: None,
-            loops_to: vec![],
-            loop_iteration: 0,
-            ready_after: None,
-            paused: false,
-        };
+    fn test_complete_triaged_resets_task_that_cannot_complete() {
+        let dir = TempDir::new().unwrap();
+        let mut child = in_progress("child");
+        child.parent = Some("t1".to_string());
+        let mut graph = triage_graph(vec![in_progress("t1"), child]);
         let verdict = TriageVerdict {
             verdict: "done".to_string(),
             reason: "tests pass".to_string(),
             summary: "implementation complete".to_string(),
         };
-        apply_triage_verdict(&mut task, &verdict, "agent-1", 1234);
-        assert_eq!(task.status, Status::Done);
+
+        complete_triaged(
+            &mut graph,
+            dir.path(),
+            "t1",
+            &verdict,
+            "agent-1",
+            1234,
+            &Readings::default(),
+        );
+        let task = graph.get_task("t1").unwrap();
+        assert_eq!(task.status, Status::Open);
+        assert!(task.assigned.is_none());
+        assert!(task.completed_at.is_none());
+        assert!(task.log.last().unwrap().message.contains("not completable"));
+    }
+
+    #[test]
+    fn test_complete_triaged_sends_verified_task_to_review() {
+        let dir = TempDir::new().unwrap();
+        let mut t1 = in_progress("t1");
+        t1.verify = Some("Output looks right".to_string());
+        let mut graph = triage_graph(vec![t1]);
+        let verdict = TriageVerdict {
+            verdict: "done".to_string(),
+            reason: "work complete".to_string(),
+            summary: "all files written".to_string(),
+        };
+
+        complete_triaged(
+            &mut graph,
+            dir.path(),
+            "t1",
+            &verdict,
+            "agent-1",
+            1234,
+            &Readings::default(),
+        );
+        let task = graph.get_task("t1").unwrap();
+        assert_eq!(task.status, Status::InReview);
+        assert!(task.completed_at.is_none());
+        assert_eq!(
+            task.log.last().unwrap().message,
+            "Task submitted for review"
+        );
+    }
+
+    #[test]
+    fn test_fail_triaged_verification() {
+        let mut task = in_progress("t1");
+        task.verify = Some("shell:exit 1".to_string());
+
+        fail_triaged_verification(&mut task, "shell:exit 1", "agent-1", 1234);
+        assert_eq!(task.status, Status::Failed);
+        assert!(task.assigned.is_none());
+        assert_eq!(task.retry_count, 1);
+        assert_eq!(task.retry_history.len(), 1);
+        assert_eq!(
+            task.failure_reason.as_deref(),
+            Some("Verification failed: shell:exit 1")
+        );
+        let message = &task.log.last().unwrap().message;
+        assert!(message.contains("verification failed"));
     }
 
     #[test]
@@ -4012,6 +4387,21 @@ poll_interval = 120
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         };
         let verdict = TriageVerdict {
             verdict: "continue".to_string(),
@@ -4069,6 +4459,21 @@ poll_interval = 120
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         };
         let verdict = TriageVerdict {
             verdict: "restart".to_string(),
@@ -4116,6 +4521,21 @@ poll_interval = 120
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         };
         let verdict = TriageVerdict {
             verdict: "continue".to_string(),
@@ -4167,6 +4587,21 @@ poll_interval = 120
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         };
         let verdict = TriageVerdict {
             verdict: "restart".to_string(),
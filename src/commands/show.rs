@@ -1,9 +1,12 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::Path;
-use workgraph::graph::{LogEntry, LoopEdge, LoopGuard, Status};
-use workgraph::query::build_reverse_index;
+use workgraph::graph::{DepCondition, DepGroup, LogEntry, LoopEdge, Priority, Status};
+use workgraph::query::{Rollup, Slack, build_reverse_index, compute_slack, rollup};
+use workgraph::retry::{FailureRecord, RetryPolicy, policy_for};
+use workgraph::schedule::Schedule;
 
 /// Blocker info with status
 #[derive(Debug, Serialize)]
@@ -12,6 +15,15 @@ struct BlockerInfo {
     status: Status,
 }
 
+/// A fan-out task's template, join task and generated children
+#[derive(Debug, Serialize)]
+struct FanOutInfo {
+    title: String,
+    join: String,
+    expanded: bool,
+    children: Vec<BlockerInfo>,
+}
+
 fn is_zero(val: &u32) -> bool {
     *val == 0
 }
@@ -40,11 +52,27 @@ struct TaskDetails {
     deliverables: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     artifacts: Vec<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    outputs: BTreeMap<String, serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     exec: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    fields: BTreeMap<String, serde_json::Value>,
     blocked_by: Vec<BlockerInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    dep_groups: Vec<DepGroup>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    dep_conditions: BTreeMap<String, DepCondition>,
     blocks: Vec<BlockerInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<BlockerInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rollup: Option<Rollup>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fan_out: Option<FanOutInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     created_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     started_at: Option<String>,
@@ -52,12 +80,26 @@ struct TaskDetails {
     completed_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     not_before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deadline: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slack: Option<Slack>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schedule: Option<Schedule>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     log: Vec<LogEntry>,
     #[serde(skip_serializing_if = "is_zero")]
     retry_count: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_retries: Option<u32>,
+    /// Effective retry policy: the task's own, or one from config by tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_policy: Option<RetryPolicy>,
+    /// Tag the retry policy comes from, if not the task's own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_policy_tag: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    retry_history: Vec<FailureRecord>,
     #[serde(skip_serializing_if = "Option::is_none")]
     failure_reason: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -74,14 +116,27 @@ struct TaskDetails {
     ready_after: Option<String>,
     #[serde(default, skip_serializing_if = "is_not_paused")]
     paused: bool,
+    #[serde(skip_serializing_if = "is_normal_priority")]
+    priority: Priority,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<f64>,
 }
 
 fn is_not_paused(val: &bool) -> bool {
     !*val
 }
 
-pub fn run(dir: &Path, id: &str, json: bool) -> Result<()> {
-    let (graph, _path) = super::load_workgraph(dir)?;
+fn is_normal_priority(val: &Priority) -> bool {
+    *val == Priority::Normal
+}
+
+pub fn run(dir: &Path, id: &str, at: Option<&str>, json: bool) -> Result<()> {
+    let graph = super::load_workgraph_at(dir, at)?;
+    // Slack is relative to the moment being shown
+    let now = match at {
+        Some(at) => workgraph::history::parse_timestamp(at)?,
+        None => Utc::now(),
+    };
 
     let task = graph.get_task_or_err(id)?;
 
@@ -145,6 +200,42 @@ pub fn run(dir: &Path, id: &str, json: bool) -> Result<()> {
         })
         .unwrap_or_default();
 
+    // Generated children are listed with their fan-out rather than under "Blocks"
+    let fan_out_info = task.fan_out.as_ref().map(|fan_out| FanOutInfo {
+        title: fan_out.template.title.clone(),
+        join: fan_out.join.clone(),
+        expanded: fan_out.expanded,
+        children: fan_out
+            .children
+            .iter()
+            .map(|child| BlockerInfo {
+                id: child.clone(),
+                status: graph
+                    .get_task(child)
+                    .map_or(Status::Abandoned, |t| t.status),
+            })
+            .collect(),
+    });
+    let blocks_info: Vec<BlockerInfo> = match &fan_out_info {
+        Some(fan_out) => blocks_info
+            .into_iter()
+            .filter(|b| !fan_out.children.iter().any(|c| c.id == b.id))
+            .collect(),
+        None => blocks_info,
+    };
+
+    let children_info: Vec<BlockerInfo> = graph
+        .children(id)
+        .iter()
+        .map(|child| BlockerInfo {
+            id: child.id.clone(),
+            status: child.status,
+        })
+        .collect();
+
+    let config = workgraph::config::Config::load_or_default(dir);
+    let policy = policy_for(task, &config.retry);
+
     let details = TaskDetails {
         id: task.id.clone(),
         title: task.title.clone(),
@@ -158,16 +249,30 @@ pub fn run(dir: &Path, id: &str, json: bool) -> Result<()> {
         inputs: task.inputs.clone(),
         deliverables: task.deliverables.clone(),
         artifacts: task.artifacts.clone(),
+        outputs: task.outputs.clone(),
         exec: task.exec.clone(),
+        fields: task.fields.clone(),
         blocked_by: blocked_by_info,
+        dep_groups: task.dep_groups.clone(),
+        dep_conditions: task.dep_conditions.clone(),
         blocks: blocks_info,
+        parent: task.parent.clone(),
+        children: children_info,
+        rollup: rollup(&graph, id),
+        fan_out: fan_out_info,
         created_at: task.created_at.clone(),
         started_at: task.started_at.clone(),
         completed_at: task.completed_at.clone(),
         not_before: task.not_before.clone(),
+        deadline: task.deadline.clone(),
+        slack: compute_slack(&graph, now).remove(id),
+        schedule: task.schedule.clone(),
         log: task.log.clone(),
         retry_count: task.retry_count,
         max_retries: task.max_retries,
+        retry_policy: policy.map(|(p, _)| p.clone()),
+        retry_policy_tag: policy.and_then(|(_, tag)| tag.map(String::from)),
+        retry_history: task.retry_history.clone(),
         failure_reason: task.failure_reason.clone(),
         model: task.model.clone(),
         verify: task.verify.clone(),
@@ -176,6 +281,8 @@ pub fn run(dir: &Path, id: &str, json: bool) -> Result<()> {
         loop_iteration: task.loop_iteration,
         ready_after: task.ready_after.clone(),
         paused: task.paused,
+        priority: task.priority,
+        value: task.value,
     };
 
     if json {
@@ -187,6 +294,31 @@ pub fn run(dir: &Path, id: &str, json: bool) -> Result<()> {
     Ok(())
 }
 
+/// One line of retry history: attempt, time, class, reason and outcome
+fn format_failure(record: &FailureRecord) -> String {
+    let mut line = format!("#{} failed {}", record.attempt, record.failed_at);
+    if let Some(ref class) = record.class {
+        line.push_str(&format!(" [{}]", class));
+    }
+    if let Some(ref reason) = record.reason {
+        line.push_str(&format!(": {}", reason));
+    }
+    let mut extra = Vec::new();
+    if let Some(code) = record.exit_code {
+        extra.push(format!("exit {}", code));
+    }
+    if let Some(ref verdict) = record.verdict {
+        extra.push(format!("triage: {}", verdict));
+    }
+    if !extra.is_empty() {
+        line.push_str(&format!(" ({})", extra.join(", ")));
+    }
+    if let Some(ref retry_at) = record.retry_at {
+        line.push_str(&format!(" -> retried after {}", retry_at));
+    }
+    line
+}
+
 fn print_human_readable(details: &TaskDetails) {
     println!("Task: {}", details.id);
     println!("Title: {}", details.title);
@@ -202,6 +334,12 @@ fn print_human_readable(details: &TaskDetails) {
     if let Some(ref agent) = details.agent {
         println!("Agent: {}", agent);
     }
+    if details.priority != Priority::Normal {
+        println!("Priority: {}", details.priority);
+    }
+    if let Some(value) = details.value {
+        println!("Value: {}", value);
+    }
 
     // Failure info
     if (details.status == Status::Failed || details.status == Status::Abandoned)
@@ -218,6 +356,18 @@ fn print_human_readable(details: &TaskDetails) {
     } else if let Some(max) = details.max_retries {
         println!("Max retries: {}", max);
     }
+    if let Some(ref policy) = details.retry_policy {
+        match details.retry_policy_tag {
+            Some(ref tag) => println!("Retry policy: {} (tag '{}')", policy, tag),
+            None => println!("Retry policy: {}", policy),
+        }
+    }
+    if !details.retry_history.is_empty() {
+        println!("Retry history:");
+        for record in &details.retry_history {
+            println!("  {}", format_failure(record));
+        }
+    }
 
     // Description
     if let Some(ref description) = details.description {
@@ -263,6 +413,22 @@ fn print_human_readable(details: &TaskDetails) {
         println!("Deliverables: {}", details.deliverables.join(", "));
     }
 
+    // Custom fields
+    if !details.fields.is_empty() {
+        println!("Fields:");
+        for (key, value) in &details.fields {
+            println!("  {}: {}", key, workgraph::fields::display(value));
+        }
+    }
+
+    // Outputs
+    if !details.outputs.is_empty() {
+        println!("Outputs:");
+        for (key, value) in &details.outputs {
+            println!("  {} = {}", key, workgraph::outputs::render(value));
+        }
+    }
+
     println!();
 
     // Blocked by section
@@ -271,9 +437,24 @@ fn print_human_readable(details: &TaskDetails) {
         println!("  (none)");
     } else {
         for blocker in &details.blocked_by {
-            println!("  - {} ({})", blocker.id, blocker.status);
+            match details.dep_conditions.get(&blocker.id) {
+                Some(condition) => {
+                    println!("  - {} ({}, {})", blocker.id, blocker.status, condition)
+                }
+                None => println!("  - {} ({})", blocker.id, blocker.status),
+            }
         }
     }
+    for group in &details.dep_groups {
+        let done = group.done_count(|id| {
+            details
+                .blocked_by
+                .iter()
+                .find(|b| b.id == id)
+                .map(|b| b.status)
+        });
+        println!("  Needs {} ({} of {} done)", group, done, group.need);
+    }
 
     println!();
 
@@ -287,19 +468,66 @@ fn print_human_readable(details: &TaskDetails) {
         }
     }
 
+    // Hierarchy
+    if let Some(ref parent) = details.parent {
+        println!();
+        println!("Parent: {}", parent);
+    }
+    if !details.children.is_empty() {
+        println!();
+        println!("Children:");
+        for child in &details.children {
+            println!("  - {} ({})", child.id, child.status);
+        }
+        if let Some(ref rollup) = details.rollup {
+            let mut line = format!(
+                "  Rollup: {}/{} done ({:.0}%)",
+                rollup.done, rollup.total, rollup.progress
+            );
+            if rollup.hours > 0.0 {
+                line.push_str(&format!(", {}h", rollup.hours));
+            }
+            if rollup.cost > 0.0 {
+                line.push_str(&format!(", ${}", rollup.cost));
+            }
+            println!("{}", line);
+        }
+    }
+
+    // Fan-out
+    if let Some(ref fan_out) = details.fan_out {
+        println!();
+        if fan_out.expanded {
+            let done = fan_out
+                .children
+                .iter()
+                .filter(|c| c.status == Status::Done)
+                .count();
+            println!(
+                "Fan-out: {} ({} task(s), {} done), joined by {}",
+                fan_out.title,
+                fan_out.children.len(),
+                done,
+                fan_out.join
+            );
+            for child in &fan_out.children {
+                println!("  - {} ({})", child.id, child.status);
+            }
+        } else {
+            println!(
+                "Fan-out: {} (one task per item when done), joined by {}",
+                fan_out.title, fan_out.join
+            );
+        }
+    }
+
     // Loop edges
     if !details.loops_to.is_empty() || details.loop_iteration > 0 {
         println!();
         println!("Loops:");
         for edge in &details.loops_to {
             let guard_str = match &edge.guard {
-                Some(LoopGuard::TaskStatus { task, status }) => {
-                    format!(", guard: task:{}={}", task, status)
-                }
-                Some(LoopGuard::IterationLessThan(n)) => {
-                    format!(", guard: iteration<{}", n)
-                }
-                Some(LoopGuard::Always) => ", guard: always".to_string(),
+                Some(guard) => format!(", guard: {}", guard),
                 None => String::new(),
             };
             let delay_str = match &edge.delay {
@@ -331,6 +559,36 @@ fn print_human_readable(details: &TaskDetails) {
     if let Some(ref not_before) = details.not_before {
         println!("Not before: {}", not_before);
     }
+    if let Some(ref deadline) = details.deadline {
+        println!("Deadline: {}", deadline);
+    }
+    if let Some(ref slack) = details.slack {
+        let binding = if slack.deadline_task == details.id {
+            String::new()
+        } else {
+            format!(", for {}", slack.deadline_task)
+        };
+        println!(
+            "Slack: {}h (start by {}, finish by {}{}){}",
+            workgraph::format_hours(slack.slack_hours),
+            slack.latest_start.format("%Y-%m-%d %H:%M"),
+            slack.latest_finish.format("%Y-%m-%d %H:%M"),
+            binding,
+            if slack.slack_hours < 0.0 {
+                " AT RISK"
+            } else {
+                ""
+            }
+        );
+    }
+    if let Some(ref schedule) = details.schedule {
+        println!(
+            "Schedule: {} ({}), next fire {}",
+            schedule.cron,
+            if schedule.copy { "copy" } else { "reopen" },
+            schedule.next_fire.as_deref().unwrap_or("never")
+        );
+    }
     if let Some(ref ready_after) = details.ready_after {
         println!(
             "Ready after: {}{}",
@@ -436,19 +694,33 @@ mod tests {
             inputs: vec![],
             deliverables: vec![],
             artifacts: vec![],
+            outputs: BTreeMap::new(),
             exec: None,
+            fields: BTreeMap::new(),
             blocked_by: vec![],
+            dep_groups: vec![],
+            dep_conditions: BTreeMap::new(),
             blocks: vec![BlockerInfo {
                 id: "t2".to_string(),
                 status: Status::Open,
             }],
+            parent: None,
+            children: vec![],
+            rollup: None,
+            fan_out: None,
             created_at: Some("2026-01-20T15:35:50+00:00".to_string()),
             started_at: Some("2026-01-20T16:30:00+00:00".to_string()),
             completed_at: None,
             not_before: None,
+            deadline: None,
+            slack: None,
+            schedule: None,
             log: vec![],
             retry_count: 0,
             max_retries: None,
+            retry_policy: None,
+            retry_policy_tag: None,
+            retry_history: vec![],
             failure_reason: None,
             model: None,
             verify: None,
@@ -457,6 +729,8 @@ mod tests {
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
         };
 
         let json = serde_json::to_string(&details).unwrap();
@@ -496,7 +770,7 @@ mod tests {
         let graph = WorkGraph::new();
         workgraph::parser::save_graph(&graph, &path).unwrap();
 
-        let result = run(temp_dir.path(), "no-such-task", false);
+        let result = run(temp_dir.path(), "no-such-task", None, false);
         assert!(result.is_err());
     }
 
@@ -508,7 +782,7 @@ mod tests {
         graph.add_node(Node::Task(make_task("t1", "Test task")));
         workgraph::parser::save_graph(&graph, &path).unwrap();
 
-        let result = run(temp_dir.path(), "t1", false);
+        let result = run(temp_dir.path(), "t1", None, false);
         assert!(result.is_ok());
     }
 
@@ -520,10 +794,27 @@ mod tests {
         graph.add_node(Node::Task(make_task("t1", "Test task")));
         workgraph::parser::save_graph(&graph, &path).unwrap();
 
-        let result = run(temp_dir.path(), "t1", true);
+        let result = run(temp_dir.path(), "t1", None, true);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_run_at_past_timestamp() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("graph.jsonl");
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("t1", "Test task")));
+        workgraph::parser::save_graph(&graph, &path).unwrap();
+
+        let before = Utc::now().to_rfc3339();
+        super::super::done::run(temp_dir.path(), "t1", false, false, None).unwrap();
+
+        let past = super::super::load_workgraph_at(temp_dir.path(), Some(&before)).unwrap();
+        assert_eq!(past.get_task("t1").unwrap().status, Status::Open);
+        assert!(run(temp_dir.path(), "t1", Some(&before), true).is_ok());
+        assert!(run(temp_dir.path(), "t1", Some("last tuesday"), false).is_err());
+    }
+
     #[test]
     fn test_run_task_with_orphan_blocker() {
         // A task references a blocker that doesn't exist in the graph
@@ -536,7 +827,7 @@ mod tests {
         workgraph::parser::save_graph(&graph, &path).unwrap();
 
         // Should succeed (not crash), blocker defaults to Status::Open with a warning
-        let result = run(temp_dir.path(), "t1", false);
+        let result = run(temp_dir.path(), "t1", None, false);
         assert!(result.is_ok());
     }
 
@@ -550,14 +841,30 @@ mod tests {
         graph.add_node(Node::Task(task));
         workgraph::parser::save_graph(&graph, &path).unwrap();
 
-        let result = run(temp_dir.path(), "t1", true);
+        let result = run(temp_dir.path(), "t1", None, true);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_run_no_graph_file() {
         let temp_dir = tempfile::TempDir::new().unwrap();
-        let result = run(temp_dir.path(), "t1", false);
+        let result = run(temp_dir.path(), "t1", None, false);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_run_parent_with_children() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("graph.jsonl");
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("epic", "Epic")));
+        let mut child = make_task("c1", "Child");
+        child.parent = Some("epic".to_string());
+        graph.add_node(Node::Task(child));
+        workgraph::parser::save_graph(&graph, &path).unwrap();
+
+        assert!(run(temp_dir.path(), "epic", None, false).is_ok());
+        assert!(run(temp_dir.path(), "epic", None, true).is_ok());
+        assert!(run(temp_dir.path(), "c1", None, false).is_ok());
+    }
 }
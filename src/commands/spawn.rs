@@ -17,8 +17,8 @@ use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use workgraph::graph::{LogEntry, Status};
-use workgraph::parser::{load_graph, save_graph};
+use workgraph::graph::{LogEntry, Status, Task};
+use workgraph::parser::{load_graph, with_graph_mut};
 use workgraph::service::executor::{ExecutorRegistry, TemplateVars};
 use workgraph::service::registry::AgentRegistry;
 
@@ -49,8 +49,19 @@ pub struct SpawnResult {
     pub model: Option<String>,
 }
 
+/// Grace period between SIGTERM and SIGKILL when an agent exceeds its timeout
+const TIMEOUT_KILL_GRACE_SECS: u64 = 30;
+
+/// Find the coreutils `timeout` binary used to enforce agent deadlines.
+/// Homebrew installs it as `gtimeout` on macOS.
+fn find_timeout_program() -> Option<&'static str> {
+    let path = std::env::var_os("PATH")?;
+    ["timeout", "gtimeout"]
+        .into_iter()
+        .find(|name| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+}
+
 /// Parse a timeout duration string like "30m", "1h", "90s"
-#[cfg(test)]
 fn parse_timeout(timeout_str: &str) -> Result<std::time::Duration> {
     let timeout_str = timeout_str.trim();
     if timeout_str.is_empty() {
@@ -77,6 +88,10 @@ fn parse_timeout(timeout_str: &str) -> Result<std::time::Duration> {
         _ => num,
     };
 
+    if secs == 0 {
+        anyhow::bail!("Timeout must be greater than zero");
+    }
+
     Ok(std::time::Duration::from_secs(secs))
 }
 
@@ -85,6 +100,49 @@ fn agent_output_dir(workgraph_dir: &Path, agent_id: &str) -> PathBuf {
     workgraph_dir.join("agents").join(agent_id)
 }
 
+/// Only allow spawning on tasks that are Open or Blocked
+fn check_spawnable(task: &Task) -> Result<()> {
+    match task.status {
+        Status::Open | Status::Blocked => {}
+        Status::InProgress => {
+            let since = task
+                .started_at
+                .as_ref()
+                .map(|t| format!(" (since {})", t))
+                .unwrap_or_default();
+            match &task.assigned {
+                Some(assigned) => {
+                    anyhow::bail!(
+                        "Task '{}' is already claimed by @{}{}",
+                        task.id,
+                        assigned,
+                        since
+                    );
+                }
+                None => {
+                    anyhow::bail!("Task '{}' is already in progress{}", task.id, since);
+                }
+            }
+        }
+        Status::Done => {
+            anyhow::bail!("Task '{}' is already done", task.id);
+        }
+        Status::Failed => {
+            anyhow::bail!(
+                "Cannot spawn on task '{}': task is Failed. Use 'wg retry' first.",
+                task.id
+            );
+        }
+        Status::Abandoned => {
+            anyhow::bail!("Cannot spawn on task '{}': task is Abandoned", task.id);
+        }
+        Status::InReview => {
+            anyhow::bail!("Cannot spawn on task '{}': task is awaiting review", task.id);
+        }
+    }
+    Ok(())
+}
+
 /// Build context string from dependency artifacts and logs
 fn build_task_context(graph: &workgraph::WorkGraph, task: &workgraph::graph::Task) -> String {
     let mut context_parts = Vec::new();
@@ -99,6 +157,15 @@ fn build_task_context(graph: &workgraph::WorkGraph, task: &workgraph::graph::Tas
                 ));
             }
 
+            for (key, value) in &dep_task.outputs {
+                context_parts.push(format!(
+                    "From {}: output {} = {}",
+                    dep_id,
+                    key,
+                    workgraph::outputs::render(value)
+                ));
+            }
+
             if dep_task.status == Status::Done && !dep_task.log.is_empty() {
                 let logs: Vec<&LogEntry> = dep_task.log.iter().rev().take(5).collect();
                 for entry in logs.iter().rev() {
@@ -111,6 +178,22 @@ fn build_task_context(graph: &workgraph::WorkGraph, task: &workgraph::graph::Tas
         }
     }
 
+    // A fan-out task's agent has to produce the items
+    if let Some(fan_out) = &task.fan_out {
+        let output = match &fan_out.items {
+            Some(path) => format!("write them to {}", path),
+            None => format!(
+                "write them to a .json file and register it with 'wg artifact {} <path>'",
+                task.id
+            ),
+        };
+        context_parts.push(format!(
+            "Fan-out: before 'wg done', produce a JSON array of items and {}. \
+             One task '{}' is added per item.",
+            output, fan_out.template.title
+        ));
+    }
+
     if context_parts.is_empty() {
         "No context from dependencies".to_string()
     } else {
@@ -135,52 +218,20 @@ fn spawn_agent_inner(
     }
 
     // Load the graph and get task info
-    let mut graph = load_graph(&graph_path).context("Failed to load graph")?;
+    let graph = load_graph(&graph_path).context("Failed to load graph")?;
 
     let task = graph.get_task_or_err(task_id)?;
+    check_spawnable(task)?;
 
-    // Only allow spawning on tasks that are Open or Blocked
-    match task.status {
-        Status::Open | Status::Blocked => {}
-        Status::InProgress => {
-            let since = task
-                .started_at
-                .as_ref()
-                .map(|t| format!(" (since {})", t))
-                .unwrap_or_default();
-            match &task.assigned {
-                Some(assigned) => {
-                    anyhow::bail!(
-                        "Task '{}' is already claimed by @{}{}",
-                        task_id,
-                        assigned,
-                        since
-                    );
-                }
-                None => {
-                    anyhow::bail!("Task '{}' is already in progress{}", task_id, since);
-                }
-            }
-        }
-        Status::Done => {
-            anyhow::bail!("Task '{}' is already done", task_id);
-        }
-        Status::Failed => {
-            anyhow::bail!(
-                "Cannot spawn on task '{}': task is Failed. Use 'wg retry' first.",
-                task_id
-            );
-        }
-        Status::Abandoned => {
-            anyhow::bail!("Cannot spawn on task '{}': task is Abandoned", task_id);
-        }
-    }
+    // Validate --timeout before touching the registry or output directories
+    let cli_timeout_secs = timeout.map(parse_timeout).transpose()?.map(|d| d.as_secs());
 
     // Build context from dependencies
     let task_context = build_task_context(&graph, task);
 
     // Create template variables
     let mut vars = TemplateVars::from_task(task, Some(&task_context), Some(dir));
+    vars.dep_outputs = workgraph::outputs::dep_outputs(&graph, task);
 
     // Get task exec command for shell executor
     let task_exec = task.exec.clone();
@@ -195,6 +246,21 @@ fn spawn_agent_inner(
         anyhow::bail!("Task '{}' has no exec command for shell executor", task_id);
     }
 
+    // Timeout resolution: CLI/IPC --timeout > executor config timeout (seconds)
+    let timeout_secs =
+        cli_timeout_secs.or(executor_config.executor.timeout.filter(|&secs| secs > 0));
+    let timeout_program = match timeout_secs {
+        Some(_) => Some(find_timeout_program().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Task '{}' has a timeout, but neither `timeout` nor `gtimeout` is on PATH. \
+                 Install GNU coreutils (e.g. `brew install coreutils` on macOS) \
+                 or spawn without a timeout",
+                task_id
+            )
+        })?),
+        None => None,
+    };
+
     // Model resolution hierarchy:
     //   task.model > executor.model > model param (CLI --model or coordinator.model)
     let effective_model = task_model
@@ -305,31 +371,64 @@ fn spawn_agent_inner(
     let complete_cmd = "wg done \"$TASK_ID\" 2>> \"$OUTPUT_FILE\" || echo \"[wrapper] WARNING: 'wg done' failed with exit code $?\" >> \"$OUTPUT_FILE\"".to_string();
     let complete_msg = "[wrapper] Agent exited successfully, marking task done";
 
+    // With a timeout, coreutils `timeout` runs the agent in its own process group,
+    // sends SIGTERM to the whole group at the deadline and SIGKILL after the grace
+    // period. `--preserve-status` keeps the agent's own exit code, so the wrapper
+    // decides whether the deadline fired from the elapsed time, not from 124/137
+    // (which the agent or the OOM killer can produce too).
+    let (run_command, timeout_label) = match (timeout_secs, timeout_program) {
+        (Some(secs), Some(program)) => (
+            format!(
+                "{} --preserve-status --signal=TERM --kill-after={}s {}s bash -c {}",
+                program,
+                TIMEOUT_KILL_GRACE_SECS,
+                secs,
+                shell_escape(&inner_command)
+            ),
+            workgraph::format_duration(secs as i64, true),
+        ),
+        _ => (inner_command, String::new()),
+    };
+
     let wrapper_script = format!(
         r#"#!/bin/bash
 TASK_ID={escaped_task_id}
 OUTPUT_FILE={escaped_output_file}
+TIMEOUT_SECS={timeout_secs}
+TIMEOUT_LABEL={escaped_timeout_label}
 
 # Allow nested Claude Code sessions (spawned agents are independent)
 unset CLAUDECODE
 unset CLAUDE_CODE_ENTRYPOINT
 
 # Run the agent command
-{inner_command} >> "$OUTPUT_FILE" 2>&1
+START_SECONDS=$SECONDS
+{run_command} >> "$OUTPUT_FILE" 2>&1
 EXIT_CODE=$?
 
+# `timeout` only signals the agent once the deadline has passed, so running
+# that long is what marks a timeout
+TIMED_OUT=0
+if [ -n "$TIMEOUT_SECS" ] && [ $((SECONDS - START_SECONDS)) -ge "$TIMEOUT_SECS" ]; then
+    TIMED_OUT=1
+fi
+
 # Check if task is still in progress (agent didn't mark it done/failed)
 TASK_STATUS=$(wg show "$TASK_ID" --json 2>/dev/null | grep -o '"status": *"[^"]*"' | head -1 | sed 's/.*"status": *"//;s/"//' || echo "unknown")
 
 if [ "$TASK_STATUS" = "in-progress" ]; then
-    if [ $EXIT_CODE -eq 0 ]; then
+    if [ $TIMED_OUT -eq 1 ]; then
+        echo "" >> "$OUTPUT_FILE"
+        echo "[wrapper] Agent timed out after $TIMEOUT_LABEL, marking task failed" >> "$OUTPUT_FILE"
+        wg fail "$TASK_ID" --reason "timed out after $TIMEOUT_LABEL" --exit-code $EXIT_CODE 2>> "$OUTPUT_FILE" || echo "[wrapper] WARNING: 'wg fail' failed with exit code $?" >> "$OUTPUT_FILE"
+    elif [ $EXIT_CODE -eq 0 ]; then
         echo "" >> "$OUTPUT_FILE"
         echo "{complete_msg}" >> "$OUTPUT_FILE"
         {complete_cmd}
     else
         echo "" >> "$OUTPUT_FILE"
         echo "[wrapper] Agent exited with code $EXIT_CODE, marking task failed" >> "$OUTPUT_FILE"
-        wg fail "$TASK_ID" --reason "Agent exited with code $EXIT_CODE" 2>> "$OUTPUT_FILE" || echo "[wrapper] WARNING: 'wg fail' failed with exit code $?" >> "$OUTPUT_FILE"
+        wg fail "$TASK_ID" --reason "Agent exited with code $EXIT_CODE" --exit-code $EXIT_CODE 2>> "$OUTPUT_FILE" || echo "[wrapper] WARNING: 'wg fail' failed with exit code $?" >> "$OUTPUT_FILE"
     fi
 fi
 
@@ -337,7 +436,9 @@ exit $EXIT_CODE
 "#,
         escaped_task_id = shell_escape(task_id),
         escaped_output_file = shell_escape(&output_file_str),
-        inner_command = inner_command,
+        timeout_secs = timeout_secs.map(|s| s.to_string()).unwrap_or_default(),
+        escaped_timeout_label = shell_escape(&timeout_label),
+        run_command = run_command,
         complete_cmd = complete_cmd,
         complete_msg = complete_msg,
     );
@@ -395,58 +496,53 @@ exit $EXIT_CODE
         }
     }
 
-    // Claim the task BEFORE spawning the process to prevent race conditions
-    // where two concurrent spawns both pass the status check.
-    let task = graph.get_task_mut_or_err(task_id)?;
-    task.status = Status::InProgress;
-    task.started_at = Some(Utc::now().to_rfc3339());
-    task.assigned = Some(temp_agent_id.clone());
-    task.log.push(LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: Some(temp_agent_id.clone()),
-        message: format!(
-            "Spawned by {} --executor {}{}",
-            spawned_by,
-            executor_name,
-            effective_model
-                .as_ref()
-                .map(|m| format!(" --model {}", m))
-                .unwrap_or_default()
-        ),
-    });
-
-    save_graph(&graph, &graph_path).context("Failed to save graph")?;
+    // Claim the task BEFORE spawning the process. The status is re-checked
+    // under the graph lock so two concurrent spawns cannot both claim it.
+    with_graph_mut(&graph_path, |graph| -> Result<()> {
+        let task = graph.get_task_mut_or_err(task_id)?;
+        check_spawnable(task)?;
+        task.status = Status::InProgress;
+        task.started_at = Some(Utc::now().to_rfc3339());
+        task.assigned = Some(temp_agent_id.clone());
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: Some(temp_agent_id.clone()),
+            message: format!(
+                "Spawned by {} --executor {}{}",
+                spawned_by,
+                executor_name,
+                effective_model
+                    .as_ref()
+                    .map(|m| format!(" --model {}", m))
+                    .unwrap_or_default()
+            ),
+        });
+        Ok(())
+    })?;
 
     // Spawn the process (don't wait). If spawn fails, unclaim the task.
     let child = match cmd.spawn() {
         Ok(child) => child,
         Err(e) => {
             // Spawn failed — revert the task claim so it's not stuck
-            match load_graph(&graph_path) {
-                Ok(mut rollback_graph) => {
-                    if let Some(t) = rollback_graph.get_task_mut(task_id) {
-                        t.status = Status::Open;
-                        t.started_at = None;
-                        t.assigned = None;
-                        t.log.push(LogEntry {
-                            timestamp: Utc::now().to_rfc3339(),
-                            actor: Some(temp_agent_id.clone()),
-                            message: format!("Spawn failed, reverting claim: {}", e),
-                        });
-                        if let Err(save_err) = save_graph(&rollback_graph, &graph_path) {
-                            eprintln!(
-                                "Warning: failed to save rollback graph for task '{}': {}",
-                                task_id, save_err
-                            );
-                        }
-                    }
-                }
-                Err(load_err) => {
-                    eprintln!(
-                        "Warning: failed to load graph for rollback of task '{}': {}",
-                        task_id, load_err
-                    );
+            let rollback = with_graph_mut(&graph_path, |graph| -> Result<()> {
+                if let Some(t) = graph.get_task_mut(task_id) {
+                    t.status = Status::Open;
+                    t.started_at = None;
+                    t.assigned = None;
+                    t.log.push(LogEntry {
+                        timestamp: Utc::now().to_rfc3339(),
+                        actor: Some(temp_agent_id.clone()),
+                        message: format!("Spawn failed, reverting claim: {}", e),
+                    });
                 }
+                Ok(())
+            });
+            if let Err(rollback_err) = rollback {
+                eprintln!(
+                    "Warning: failed to roll back claim of task '{}': {}",
+                    task_id, rollback_err
+                );
             }
             return Err(anyhow::anyhow!(
                 "Failed to spawn executor '{}' (command: {}): {}",
@@ -493,6 +589,7 @@ exit $EXIT_CODE
         "model": &effective_model,
         "started_at": Utc::now().to_rfc3339(),
         "timeout": timeout,
+        "timeout_secs": timeout_secs,
     });
     fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?)?;
 
@@ -603,6 +700,13 @@ mod tests {
         assert_eq!(dur, std::time::Duration::from_secs(60));
     }
 
+    #[test]
+    fn test_parse_timeout_rejects_zero_and_garbage() {
+        assert!(parse_timeout("0m").is_err());
+        assert!(parse_timeout("").is_err());
+        assert!(parse_timeout("soon").is_err());
+    }
+
     #[test]
     fn test_spawn_task_not_found() {
         let temp_dir = TempDir::new().unwrap();
@@ -901,4 +1005,66 @@ mod tests {
         // Should redirect errors and log failures instead of silencing
         assert!(script.contains("2>> \"$OUTPUT_FILE\" || echo \"[wrapper] WARNING:"));
     }
+
+    #[test]
+    fn test_wrapper_enforces_timeout() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut task = make_task("t1", "Test Task");
+        task.exec = Some("sleep 5".to_string());
+        setup_graph(temp_dir.path(), vec![task]);
+
+        run(temp_dir.path(), "t1", "shell", Some("30m"), None, false).unwrap();
+
+        let output_dir = agent_output_dir(temp_dir.path(), "agent-1");
+        let script = fs::read_to_string(output_dir.join("run.sh")).unwrap();
+
+        // SIGTERM at the deadline, SIGKILL after the grace period
+        let timeout_line = script
+            .lines()
+            .find(|l| {
+                l.contains("timeout --preserve-status --signal=TERM --kill-after=30s 1800s bash -c ")
+            })
+            .expect("agent command should run under timeout");
+        assert!(timeout_line.contains("sleep 5"));
+        assert!(script.contains("TIMEOUT_SECS=1800\n"));
+        assert!(script.contains("TIMEOUT_LABEL='30m'"));
+        assert!(
+            script.contains("wg fail \"$TASK_ID\" --reason \"timed out after $TIMEOUT_LABEL\"")
+        );
+
+        let metadata: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_dir.join("metadata.json")).unwrap())
+                .unwrap();
+        assert_eq!(metadata["timeout_secs"], 1800);
+    }
+
+    #[test]
+    fn test_wrapper_without_timeout_runs_command_directly() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut task = make_task("t1", "Test Task");
+        task.exec = Some("true".to_string());
+        setup_graph(temp_dir.path(), vec![task]);
+
+        run(temp_dir.path(), "t1", "shell", None, None, false).unwrap();
+
+        let wrapper_path = agent_output_dir(temp_dir.path(), "agent-1").join("run.sh");
+        let script = fs::read_to_string(&wrapper_path).unwrap();
+        assert!(!script.contains("timeout --signal"));
+        assert!(script.contains("TIMEOUT_SECS=\n"));
+        assert!(script.contains("TIMEOUT_LABEL=''"));
+    }
+
+    #[test]
+    fn test_spawn_invalid_timeout_leaves_task_open() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut task = make_task("t1", "Test Task");
+        task.exec = Some("true".to_string());
+        setup_graph(temp_dir.path(), vec![task]);
+
+        let result = run(temp_dir.path(), "t1", "shell", Some("0s"), None, false);
+        assert!(result.is_err());
+
+        let graph = load_graph(graph_path(temp_dir.path())).unwrap();
+        assert_eq!(graph.get_task("t1").unwrap().status, Status::Open);
+    }
 }
@@ -4,7 +4,7 @@
 //! - Service status (running/stopped, PID, uptime, socket)
 //! - Coordinator config (max_agents, executor, model, poll_interval)
 //! - Agent summary (alive/dead counts, active agents with tasks)
-//! - Task summary (in-progress, ready, blocked, done counts)
+//! - Task summary (in-progress, ready, blocked, done counts, tasks awaiting review)
 //! - Recent activity (last 5 task completions)
 //!
 //! Usage:
@@ -13,10 +13,11 @@
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
 use std::path::Path;
 use workgraph::graph::Status;
 use workgraph::parser::load_graph;
-use workgraph::query::ready_tasks;
+use workgraph::query::{ready_tasks, resource_usage, resource_waits};
 use workgraph::service::{AgentRegistry, AgentStatus};
 
 use super::dead_agents::is_process_alive;
@@ -65,6 +66,13 @@ struct TaskSummaryInfo {
     ready: usize,
     blocked: usize,
     delayed: usize,
+    /// Unblocked tasks waiting for a resource at capacity
+    waiting: usize,
+    /// Resource id -> tasks waiting on it
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    resource_waits: BTreeMap<String, Vec<String>>,
+    /// Tasks waiting for 'wg approve' or 'wg reject'
+    in_review: Vec<String>,
     done_today: usize,
     done_total: usize,
 }
@@ -217,6 +225,9 @@ fn gather_task_summary(dir: &Path) -> Result<TaskSummaryInfo> {
             ready: 0,
             blocked: 0,
             delayed: 0,
+            waiting: 0,
+            resource_waits: BTreeMap::new(),
+            in_review: Vec::new(),
             done_today: 0,
             done_total: 0,
         });
@@ -231,6 +242,10 @@ fn gather_task_summary(dir: &Path) -> Result<TaskSummaryInfo> {
     let mut in_progress = 0;
     let mut blocked = 0;
     let mut delayed = 0;
+    let mut waiting = 0;
+    let mut waits_by_resource: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let usage = resource_usage(&graph);
+    let mut in_review = Vec::new();
     let mut done_today = 0;
     let mut done_total = 0;
 
@@ -250,14 +265,20 @@ fn gather_task_summary(dir: &Path) -> Result<TaskSummaryInfo> {
                             .map(|ts| ts > now)
                             .unwrap_or(false)
                     });
-                    let all_blockers_done = task.blocked_by.iter().all(|bid| {
-                        graph
-                            .get_task(bid)
-                            .map(|t| t.status.is_terminal())
-                            .unwrap_or(true)
+                    let all_blockers_done = workgraph::query::dependencies_met(task, |bid| {
+                        graph.get_task(bid).map(|t| t.status)
                     });
+                    let waits = resource_waits(&graph, task, &usage);
                     if has_future_ready_after && all_blockers_done {
                         delayed += 1;
+                    } else if all_blockers_done && !waits.is_empty() {
+                        waiting += 1;
+                        for wait in waits {
+                            waits_by_resource
+                                .entry(wait.resource)
+                                .or_default()
+                                .push(task.id.clone());
+                        }
                     } else {
                         blocked += 1;
                     }
@@ -287,6 +308,9 @@ fn gather_task_summary(dir: &Path) -> Result<TaskSummaryInfo> {
             Status::Blocked => {
                 blocked += 1;
             }
+            Status::InReview => {
+                in_review.push(task.id.clone());
+            }
             Status::Failed | Status::Abandoned => {
                 // Terminal states, not counted in summary
             }
@@ -298,6 +322,9 @@ fn gather_task_summary(dir: &Path) -> Result<TaskSummaryInfo> {
         ready: ready_tasks_list.len(),
         blocked,
         delayed,
+        waiting,
+        resource_waits: waits_by_resource,
+        in_review,
         done_today,
         done_total,
     })
@@ -403,15 +430,40 @@ fn print_status(status: &StatusOutput) {
     } else {
         String::new()
     };
+    let waiting_str = if status.tasks.waiting > 0 {
+        format!(", {} waiting", status.tasks.waiting)
+    } else {
+        String::new()
+    };
+    let review_str = if status.tasks.in_review.is_empty() {
+        String::new()
+    } else {
+        format!(", {} in review", status.tasks.in_review.len())
+    };
     println!(
-        "Tasks: {} in-progress, {} ready, {} blocked{}, {} done (today: {})",
+        "Tasks: {} in-progress, {} ready, {} blocked{}{}{}, {} done (today: {})",
         status.tasks.in_progress,
         status.tasks.ready,
         status.tasks.blocked,
         delayed_str,
+        waiting_str,
+        review_str,
         status.tasks.done_total,
         status.tasks.done_today
     );
+    for (resource, task_ids) in &status.tasks.resource_waits {
+        println!(
+            "  waiting on resource {}: {}",
+            resource,
+            task_ids.join(", ")
+        );
+    }
+    if !status.tasks.in_review.is_empty() {
+        println!(
+            "  awaiting review: {} (wg approve / wg reject)",
+            status.tasks.in_review.join(", ")
+        );
+    }
 
     // Recent activity
     if !status.recent.is_empty() {
@@ -483,6 +535,14 @@ mod tests {
         t4.blocked_by = vec!["t1".to_string()];
         graph.add_node(Node::Task(t4));
 
+        // Task awaiting review keeps its dependent blocked
+        let mut t5 = make_task("t5", "In Review");
+        t5.status = Status::InReview;
+        graph.add_node(Node::Task(t5));
+        let mut t6 = make_task("t6", "After Review");
+        t6.blocked_by = vec!["t5".to_string()];
+        graph.add_node(Node::Task(t6));
+
         save_graph(&graph, &path).unwrap();
 
         let summary = gather_task_summary(temp_dir.path()).unwrap();
@@ -490,7 +550,8 @@ mod tests {
         assert_eq!(summary.in_progress, 1);
         assert_eq!(summary.done_total, 1);
         assert_eq!(summary.done_today, 1);
-        assert_eq!(summary.blocked, 1);
+        assert_eq!(summary.blocked, 2);
+        assert_eq!(summary.in_review, vec!["t5".to_string()]);
     }
 
     #[test]
@@ -613,4 +674,37 @@ mod tests {
         assert!(task_display.ends_with("..."));
         assert!(task_display.chars().count() <= 24);
     }
+
+    #[test]
+    fn test_gather_task_summary_waiting_on_resource() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("graph.jsonl");
+
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Resource(workgraph::graph::Resource {
+            id: "gpu-box".to_string(),
+            name: None,
+            resource_type: None,
+            available: Some(1.0),
+            unit: None,
+        }));
+        let mut running = make_task("running", "Running");
+        running.status = Status::InProgress;
+        running.requires = vec!["gpu-box".to_string()];
+        graph.add_node(Node::Task(running));
+        let mut queued = make_task("queued", "Queued");
+        queued.requires = vec!["gpu-box".to_string()];
+        graph.add_node(Node::Task(queued));
+
+        save_graph(&graph, &path).unwrap();
+
+        let summary = gather_task_summary(temp_dir.path()).unwrap();
+        assert_eq!(summary.ready, 0);
+        assert_eq!(summary.blocked, 0);
+        assert_eq!(summary.waiting, 1);
+        assert_eq!(
+            summary.resource_waits["gpu-box"],
+            vec!["queued".to_string()]
+        );
+    }
 }
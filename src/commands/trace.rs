@@ -462,7 +462,7 @@ fn print_agent_runs_full(runs: &[AgentRun]) {
 pub struct HumanIntervention {
     pub timestamp: String,
     pub task_id: String,
-    pub kind: String, // "fail", "retry", "add_task", "edit", "abandon"
+    pub kind: String, // "fail", "retry", "add_task", "edit", "abandon", "reject"
     pub actor: Option<String>,
     pub detail: String,
 }
@@ -539,8 +539,8 @@ fn detect_interventions(
     let all_ops = provenance::read_all_operations(dir).unwrap_or_default();
     let mut interventions = Vec::new();
 
-    // Ops that indicate human intervention: fail, retry, abandon, add_task (manual),
-    // edit (manual changes)
+    // Ops that indicate human intervention: fail, retry, abandon, reject, add_task
+    // (manual), edit (manual changes)
     for op in &all_ops {
         let task_id = match op.task_id.as_deref() {
             Some(id) if task_ids.contains(id) => id,
@@ -577,6 +577,13 @@ fn detect_interventions(
                     .to_string();
                 ("abandon".to_string(), format!("Task abandoned: {}", reason))
             }
+            "reject" => {
+                let reason = op.detail.get("reason")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                ("reject".to_string(), format!("Review rejected: {}", reason))
+            }
             "add_task" => {
                 ("add_task".to_string(), "Task manually added".to_string())
             }
@@ -796,6 +803,7 @@ fn print_recursive_tree(
                 Status::InProgress => "\x1b[33m",
                 Status::Failed => "\x1b[31m",
                 Status::Open => "\x1b[37m",
+                Status::InReview => "\x1b[35m",
                 Status::Blocked | Status::Abandoned => "\x1b[90m",
             }
         };
@@ -808,6 +816,7 @@ fn print_recursive_tree(
                 Status::Open => "open",
                 Status::Blocked => "blocked",
                 Status::Abandoned => "abandoned",
+                Status::InReview => "in-review",
             }
         };
 
@@ -959,6 +968,7 @@ fn print_timeline(
             Status::InProgress => "\x1b[33m",
             Status::Failed => "\x1b[31m",
             Status::Open => "\x1b[37m",
+            Status::InReview => "\x1b[35m",
             Status::Blocked | Status::Abandoned => "\x1b[90m",
         }
     };
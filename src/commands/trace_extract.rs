@@ -247,7 +247,15 @@ fn build_template(
         .map(|l| LoopEdgeTemplate {
             target: strip_prefix(&l.target, root_id),
             max_iterations: l.max_iterations,
-            guard: l.guard.as_ref().map(|g| serde_json::to_string(g).unwrap_or_default()),
+            guard: l.guard.as_ref().map(|g| {
+                let mut guard = g.clone();
+                if let Some(task) = workgraph::loop_guard::referenced_task_mut(&mut guard)
+                    && subgraph_ids.contains(task.as_str())
+                {
+                    *task = strip_prefix(task, root_id);
+                }
+                guard.to_string()
+            }),
             delay: l.delay.clone(),
         })
         .collect();
@@ -2,8 +2,8 @@ use anyhow::{Context, Result};
 use chrono::Utc;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use workgraph::graph::{LoopEdge, Node, Status, Task};
-use workgraph::parser::{load_graph, save_graph};
+use workgraph::graph::{LoopEdge, Node, Priority, Status, Task, WorkGraph};
+use workgraph::parser::load_graph;
 use workgraph::trace_function::{
     self, FunctionInput, InputType, TaskTemplate, TraceFunction,
 };
@@ -121,14 +121,139 @@ pub fn run(
         })
         .unwrap_or_else(|| func.id.clone());
 
-    // 6. Load graph (needed for creating tasks)
-    let graph_file = graph_path(dir);
-    let mut graph = if graph_file.exists() {
-        load_graph(&graph_file).context("Failed to load graph")?
+    // 6. Create tasks: a dry run plans against a read-only copy of the graph,
+    //    otherwise the graph is locked for the whole load-modify-save
+    if dry_run {
+        let graph_file = graph_path(dir);
+        let mut graph = if graph_file.exists() {
+            load_graph(&graph_file).context("Failed to load graph")?
+        } else {
+            anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
+        };
+        let created_ids = instantiate_tasks(
+            &mut graph,
+            &func,
+            &final_inputs,
+            &prefix,
+            blocked_by,
+            model,
+            true,
+        )?;
+        if json {
+            let output = serde_json::json!({
+                "dry_run": true,
+                "function_id": func.id,
+                "prefix": prefix,
+                "task_count": created_ids.len(),
+                "task_ids": created_ids,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            println!(
+                "\nDry run: would create {} tasks from function '{}'",
+                created_ids.len(),
+                func.id
+            );
+        }
+        return Ok(());
+    }
+
+    let (created_tasks, changes): (Vec<Task>, _) = super::with_workgraph_changes(dir, |graph| {
+        let created_ids = instantiate_tasks(
+            graph,
+            &func,
+            &final_inputs,
+            &prefix,
+            blocked_by,
+            model,
+            false,
+        )?;
+        Ok(created_ids
+            .iter()
+            .filter_map(|id| graph.get_task(id).cloned())
+            .collect())
+    })?;
+    let created_ids: Vec<String> = created_tasks.iter().map(|t| t.id.clone()).collect();
+    super::notify_graph_changed(dir);
+
+    // Record provenance
+    let config = workgraph::config::Config::load_or_default(dir);
+    let input_summary: serde_json::Value = final_inputs
+        .iter()
+        .map(|(k, v)| {
+            (
+                k.clone(),
+                serde_json::Value::String(trace_function::render_value(v)),
+            )
+        })
+        .collect::<serde_json::Map<String, serde_json::Value>>()
+        .into();
+
+    let _ = workgraph::provenance::record(
+        dir,
+        "instantiate",
+        None,
+        None,
+        workgraph::undo::with_changes(
+            serde_json::json!({
+                "function_id": func.id,
+                "inputs": input_summary,
+                "created_task_ids": created_ids,
+                "prefix": prefix,
+            }),
+            &changes,
+        ),
+        config.log.rotation_threshold,
+    );
+
+    // Output
+    if json {
+        let output = serde_json::json!({
+            "function_id": func.id,
+            "prefix": prefix,
+            "task_count": created_ids.len(),
+            "task_ids": created_ids,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
-        anyhow::bail!("Workgraph not initialized. Run 'wg init' first.");
-    };
+        println!(
+            "Created {} tasks from function '{}':",
+            created_ids.len(),
+            func.id
+        );
+        for task in &created_tasks {
+            let blocked_str = if task.blocked_by.is_empty() {
+                String::new()
+            } else {
+                format!(" (blocked by {})", task.blocked_by.join(", "))
+            };
+            let loops_str = if task.loops_to.is_empty() {
+                String::new()
+            } else {
+                let targets: Vec<&str> = task.loops_to.iter().map(|e| e.target.as_str()).collect();
+                format!(", loops to {}", targets.join(", "))
+            };
+            println!("  {} (Open{}{})", task.id, blocked_str, loops_str);
+        }
+        println!();
+        super::print_service_hint(dir);
+    }
+
+    Ok(())
+}
 
+/// Add the tasks of an instantiated function to `graph`, returning their IDs.
+///
+/// With `dry_run` the planned tasks are printed instead of added.
+fn instantiate_tasks(
+    graph: &mut WorkGraph,
+    func: &TraceFunction,
+    final_inputs: &HashMap<String, serde_yaml::Value>,
+    prefix: &str,
+    blocked_by: &[String],
+    model: Option<&str>,
+    dry_run: bool,
+) -> Result<Vec<String>> {
     // Validate external blocked-by references exist
     for dep in blocked_by {
         if graph.get_node(dep).is_none() {
@@ -156,7 +281,7 @@ pub fn run(
     }
 
     for template in &func.tasks {
-        let rendered = trace_function::substitute_task_template(template, &final_inputs);
+        let rendered = trace_function::substitute_task_template(template, final_inputs);
         let task_id = id_map[&template.template_id].clone();
 
         // Remap blocked_by from template_ids to real task_ids
@@ -186,8 +311,16 @@ pub fn run(
                     Some(LoopEdge {
                         target: real_target.clone(),
                         guard: edge.guard.as_ref().and_then(|g| {
-                            // Parse guard string into LoopGuard
-                            super::add::parse_guard_expr(g).ok()
+                            // Parse guard string into LoopGuard, pointing task
+                            // references at the instantiated tasks
+                            let mut guard = trace_function::parse_template_guard(g).ok()?;
+                            if let Some(task) =
+                                workgraph::loop_guard::referenced_task_mut(&mut guard)
+                                && let Some(real) = id_map.get(task.as_str())
+                            {
+                                *task = real.clone();
+                            }
+                            Some(guard)
                         }),
                         max_iterations: edge.max_iterations,
                         delay: edge.delay.clone(),
@@ -251,6 +384,21 @@ pub fn run(
                 loop_iteration: 0,
                 ready_after: None,
                 paused: false,
+                priority: Priority::Normal,
+                value: None,
+                resource_amounts: Default::default(),
+                parent: None,
+                auto_complete: false,
+                fields: Default::default(),
+                deadline: None,
+                schedule: None,
+                dep_groups: vec![],
+                dep_conditions: Default::default(),
+                retry_policy: None,
+                retry_history: Vec::new(),
+                fan_out: None,
+                outputs: Default::default(),
+                artifact_snapshots: Default::default(),
             };
 
             graph.add_node(Node::Task(task));
@@ -268,92 +416,7 @@ pub fn run(
         created_ids.push(task_id);
     }
 
-    if dry_run {
-        if json {
-            let output = serde_json::json!({
-                "dry_run": true,
-                "function_id": func.id,
-                "prefix": prefix,
-                "task_count": created_ids.len(),
-                "task_ids": created_ids,
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
-        } else {
-            println!(
-                "\nDry run: would create {} tasks from function '{}'",
-                created_ids.len(),
-                func.id
-            );
-        }
-        return Ok(());
-    }
-
-    // Save graph
-    save_graph(&graph, &graph_file).context("Failed to save graph")?;
-    super::notify_graph_changed(dir);
-
-    // Record provenance
-    let config = workgraph::config::Config::load_or_default(dir);
-    let input_summary: serde_json::Value = final_inputs
-        .iter()
-        .map(|(k, v)| {
-            (
-                k.clone(),
-                serde_json::Value::String(trace_function::render_value(v)),
-            )
-        })
-        .collect::<serde_json::Map<String, serde_json::Value>>()
-        .into();
-
-    let _ = workgraph::provenance::record(
-        dir,
-        "instantiate",
-        None,
-        None,
-        serde_json::json!({
-            "function_id": func.id,
-            "inputs": input_summary,
-            "created_task_ids": created_ids,
-            "prefix": prefix,
-        }),
-        config.log.rotation_threshold,
-    );
-
-    // Output
-    if json {
-        let output = serde_json::json!({
-            "function_id": func.id,
-            "prefix": prefix,
-            "task_count": created_ids.len(),
-            "task_ids": created_ids,
-        });
-        println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
-        println!(
-            "Created {} tasks from function '{}':",
-            created_ids.len(),
-            func.id
-        );
-        for task_id in &created_ids {
-            let task = graph.get_task(task_id).unwrap();
-            let blocked_str = if task.blocked_by.is_empty() {
-                String::new()
-            } else {
-                format!(" (blocked by {})", task.blocked_by.join(", "))
-            };
-            let loops_str = if task.loops_to.is_empty() {
-                String::new()
-            } else {
-                let targets: Vec<&str> = task.loops_to.iter().map(|e| e.target.as_str()).collect();
-                format!(", loops to {}", targets.join(", "))
-            };
-            println!("  {} (Open{}{})", task_id, blocked_str, loops_str);
-        }
-        println!();
-        super::print_service_hint(dir);
-    }
-
-    Ok(())
+    Ok(created_ids)
 }
 
 /// Parse a key=value input pair, converting the value to the appropriate YAML type
@@ -508,6 +571,7 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
     use workgraph::graph::WorkGraph;
+    use workgraph::parser::save_graph;
     use workgraph::trace_function::*;
 
     fn sample_function() -> TraceFunction {
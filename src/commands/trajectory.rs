@@ -151,6 +151,7 @@ pub fn run(dir: &Path, task_id: &str, json: bool) -> Result<()> {
                 Status::InProgress => " [in-progress]",
                 Status::Failed => " [failed]",
                 Status::Abandoned => " [abandoned]",
+                Status::InReview => " [in-review]",
                 _ => "",
             };
             let hours_str = step.hours.map(|h| format!(" ({}h)", h)).unwrap_or_default();
@@ -187,12 +188,7 @@ pub fn suggest_for_actor(dir: &Path, actor_id: &str, json: bool) -> Result<()> {
         .tasks()
         .filter(|t| {
             t.status == Status::Open
-                && t.blocked_by.iter().all(|b| {
-                    graph
-                        .get_task(b)
-                        .map(|bt| bt.status.is_terminal())
-                        .unwrap_or(true)
-                })
+                && workgraph::query::dependencies_met(t, |b| graph.get_task(b).map(|bt| bt.status))
         })
         .collect();
 
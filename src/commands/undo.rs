@@ -0,0 +1,202 @@
+use anyhow::Result;
+use std::path::Path;
+use workgraph::provenance::{self, OperationEntry};
+use workgraph::undo::{self, REDO_OP, UNDO_OP};
+
+/// Revert the last `count` undoable operations, most recent first
+pub fn run_undo(dir: &Path, count: usize, force: bool) -> Result<()> {
+    if count == 0 {
+        anyhow::bail!("Nothing to do: count must be at least 1");
+    }
+    for step in 0..count {
+        let ops = provenance::read_all_operations(dir)?;
+        let Some(index) = undo::next_undo(&ops) else {
+            if step == 0 {
+                anyhow::bail!("Nothing to undo");
+            }
+            println!("Nothing more to undo");
+            break;
+        };
+
+        if !force {
+            let conflicts = undo::later_conflicts(&ops, index);
+            if !conflicts.is_empty() {
+                let list: Vec<String> = conflicts
+                    .iter()
+                    .map(|(op, id)| format!("  - '{}' on '{}'", op, id))
+                    .collect();
+                anyhow::bail!(
+                    "Cannot undo {}: later operations touched the same task(s):\n{}\nUse --force to undo anyway",
+                    describe(&ops[index]),
+                    list.join("\n")
+                );
+            }
+        }
+        apply(dir, &ops[index], false, force)?;
+    }
+    Ok(())
+}
+
+/// Re-apply the most recently undone operation
+pub fn run_redo(dir: &Path, force: bool) -> Result<()> {
+    let ops = provenance::read_all_operations(dir)?;
+    let Some(index) = undo::next_redo(&ops) else {
+        anyhow::bail!("Nothing to redo");
+    };
+    apply(dir, &ops[index], true, force)
+}
+
+fn apply(dir: &Path, entry: &OperationEntry, forward: bool, force: bool) -> Result<()> {
+    let changes = undo::changes(entry);
+    let verb = if forward { "redo" } else { "undo" };
+
+    super::with_workgraph_mut(dir, |graph| {
+        let mismatched = undo::mismatches(graph, &changes, forward);
+        if !mismatched.is_empty() && !force {
+            anyhow::bail!(
+                "Cannot {} {}: task(s) changed since: {}\nUse --force to {} anyway",
+                verb,
+                describe(entry),
+                mismatched.join(", "),
+                verb
+            );
+        }
+        undo::apply(graph, &changes, forward)
+    })?;
+    super::notify_graph_changed(dir);
+
+    // Record operation
+    let config = workgraph::config::Config::load_or_default(dir);
+    let _ = provenance::record(
+        dir,
+        if forward { REDO_OP } else { UNDO_OP },
+        entry.task_id.as_deref(),
+        None,
+        serde_json::json!({ "target": entry.timestamp, "op": entry.op }),
+        config.log.rotation_threshold,
+    );
+
+    println!(
+        "{} {} ({})",
+        if forward { "Redid" } else { "Undid" },
+        describe(entry),
+        entry.timestamp
+    );
+    for change in &changes {
+        // The state this step moved the task to
+        let target = if forward { &change.after } else { &change.before };
+        let what = match (change.before.is_none() || change.after.is_none(), target) {
+            (true, Some(_)) => "restored",
+            (true, None) => "removed",
+            (false, _) if forward => "reapplied",
+            (false, _) => "reverted",
+        };
+        println!("  {}: {}", change.id, what);
+    }
+    Ok(())
+}
+
+fn describe(entry: &OperationEntry) -> String {
+    match &entry.task_id {
+        Some(id) => format!("'{}' on '{}'", entry.op, id),
+        None => format!("'{}'", entry.op),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use workgraph::graph::{Node, Status, Task, WorkGraph};
+    use workgraph::parser::{load_graph, save_graph};
+
+    fn setup(dir: &Path) {
+        std::fs::create_dir_all(dir).unwrap();
+        let mut graph = WorkGraph::new();
+        for id in ["a", "b"] {
+            graph.add_node(Node::Task(Task {
+                id: id.to_string(),
+                title: id.to_string(),
+                ..Task::default()
+            }));
+        }
+        save_graph(&graph, super::super::graph_path(dir)).unwrap();
+    }
+
+    fn status(dir: &Path, id: &str) -> Status {
+        let graph = load_graph(super::super::graph_path(dir)).unwrap();
+        graph.get_task(id).unwrap().status
+    }
+
+    #[test]
+    fn test_undo_and_redo_done() {
+        let dir = tempdir().unwrap();
+        setup(dir.path());
+        assert!(run_undo(dir.path(), 1, false).is_err());
+
+        super::super::done::run(dir.path(), "a", false, false, None).unwrap();
+        assert_eq!(status(dir.path(), "a"), Status::Done);
+
+        run_undo(dir.path(), 1, false).unwrap();
+        assert_eq!(status(dir.path(), "a"), Status::Open);
+        let graph = load_graph(super::super::graph_path(dir.path())).unwrap();
+        let a = graph.get_task("a").unwrap();
+        assert!(a.completed_at.is_none());
+        assert!(a.log.is_empty());
+
+        run_redo(dir.path(), false).unwrap();
+        assert_eq!(status(dir.path(), "a"), Status::Done);
+        assert!(run_redo(dir.path(), false).is_err());
+    }
+
+    #[test]
+    fn test_undo_refuses_when_later_op_touched_task() {
+        let dir = tempdir().unwrap();
+        setup(dir.path());
+        super::super::done::run(dir.path(), "a", false, false, None).unwrap();
+        // An operation that cannot itself be undone still blocks undoing 'done'
+        provenance::record(
+            dir.path(),
+            "note",
+            Some("a"),
+            None,
+            serde_json::Value::Null,
+            provenance::DEFAULT_ROTATION_THRESHOLD,
+        )
+        .unwrap();
+
+        let err = run_undo(dir.path(), 1, false).unwrap_err();
+        assert!(err.to_string().contains("'note' on 'a'"));
+        run_undo(dir.path(), 1, true).unwrap();
+        assert_eq!(status(dir.path(), "a"), Status::Open);
+    }
+
+    #[test]
+    fn test_undo_refuses_when_task_changed_outside_log() {
+        let dir = tempdir().unwrap();
+        setup(dir.path());
+        super::super::log::run_add(dir.path(), "b", "first", None).unwrap();
+        super::super::done::run(dir.path(), "b", false, false, None).unwrap();
+
+        // Reopen 'b' behind the log's back
+        let path = super::super::graph_path(dir.path());
+        let mut graph = load_graph(&path).unwrap();
+        graph.get_task_mut("b").unwrap().status = Status::InProgress;
+        save_graph(&graph, &path).unwrap();
+
+        let err = run_undo(dir.path(), 1, false).unwrap_err();
+        assert!(err.to_string().contains("changed since"));
+
+        // Forcing restores the changed fields and drops the appended log entry
+        run_undo(dir.path(), 1, true).unwrap();
+        let graph = load_graph(&path).unwrap();
+        let b = graph.get_task("b").unwrap();
+        assert_eq!(b.status, Status::Open);
+        assert_eq!(b.log.len(), 1);
+
+        // Undo with a count walks further back
+        run_undo(dir.path(), 5, false).unwrap();
+        let graph = load_graph(&path).unwrap();
+        assert!(graph.get_task("b").unwrap().log.is_empty());
+    }
+}
@@ -5,6 +5,7 @@ use std::path::Path;
 use std::process::{Command, Stdio};
 use workgraph::format_hours;
 use workgraph::graph::{Status, Task, WorkGraph};
+use workgraph::query::filter::FilterContext;
 
 /// Output format for visualization
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -41,6 +42,17 @@ pub struct VizOptions {
     pub output: Option<String>,
     /// Show internal tasks (assign-*, reward-*) that are normally hidden
     pub show_internal: bool,
+    /// Draw parent tasks as clusters around their children (dot/mermaid)
+    pub clusters: bool,
+    /// Custom field filters as `key=value`; a task must match all of them
+    pub fields: Vec<String>,
+    /// Filter expression (see `query::filter`); replaces the default of
+    /// hiding done tasks
+    pub filter: Option<String>,
+    /// Show the graph as it was at this timestamp (see `history::graph_at`)
+    pub at: Option<String>,
+    /// Draw each fan-out child instead of one node per fan-out
+    pub expand_fan_out: bool,
 }
 
 impl Default for VizOptions {
@@ -52,6 +64,11 @@ impl Default for VizOptions {
             format: OutputFormat::Ascii,
             output: None,
             show_internal: false,
+            clusters: false,
+            fields: Vec::new(),
+            filter: None,
+            at: None,
+            expand_fan_out: false,
         }
     }
 }
@@ -126,12 +143,101 @@ fn filter_internal_tasks<'a>(
     (filtered, annotations)
 }
 
+/// Replace the shown children of each fan-out (when there are at least two)
+/// with a single node summarizing them, rewiring edges to and from them.
+fn collapse_fan_outs(graph: &WorkGraph, tasks: &[&Task]) -> Vec<Task> {
+    let shown: HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+    let mut replaced: HashMap<&str, String> = HashMap::new();
+    let mut groups = Vec::new();
+    for task in graph.tasks() {
+        let Some(fan_out) = &task.fan_out else {
+            continue;
+        };
+        let children: Vec<&Task> = fan_out
+            .children
+            .iter()
+            .filter(|c| shown.contains(c.as_str()))
+            .filter_map(|c| graph.get_task(c))
+            .collect();
+        if children.len() < 2 {
+            continue;
+        }
+        let group_id = workgraph::fan_out::unique_id(graph, &format!("{}-fan-out", task.id));
+        for child in &children {
+            replaced.insert(child.id.as_str(), group_id.clone());
+        }
+        let count = |status: Status| children.iter().filter(|c| c.status == status).count();
+        let status = if count(Status::InProgress) > 0 {
+            Status::InProgress
+        } else if count(Status::Open) + count(Status::Blocked) > 0 {
+            Status::Open
+        } else if count(Status::Failed) > 0 {
+            Status::Failed
+        } else if count(Status::Done) > 0 {
+            Status::Done
+        } else {
+            Status::Abandoned
+        };
+        groups.push(Task {
+            id: group_id,
+            title: format!(
+                "{} x {} ({} done)",
+                children.len(),
+                fan_out.template.title,
+                count(Status::Done)
+            ),
+            status,
+            blocked_by: vec![task.id.clone()],
+            blocks: vec![fan_out.join.clone()],
+            tags: vec![workgraph::fan_out::FAN_OUT_TAG.to_string()],
+            ..Task::default()
+        });
+    }
+
+    let rewire = |ids: &[String]| -> Vec<String> {
+        let mut rewired: Vec<String> = Vec::new();
+        for id in ids {
+            let id = replaced.get(id.as_str()).unwrap_or(id);
+            if !rewired.contains(id) {
+                rewired.push(id.clone());
+            }
+        }
+        rewired
+    };
+    let mut collapsed: Vec<Task> = tasks
+        .iter()
+        .filter(|t| !replaced.contains_key(t.id.as_str()))
+        .map(|t| Task {
+            blocked_by: rewire(&t.blocked_by),
+            blocks: rewire(&t.blocks),
+            ..(*t).clone()
+        })
+        .collect();
+    collapsed.extend(groups);
+    collapsed
+}
+
 pub fn run(dir: &Path, options: &VizOptions) -> Result<()> {
-    let (graph, _path) = super::load_workgraph(dir)?;
+    if options.clusters && !matches!(options.format, OutputFormat::Dot | OutputFormat::Mermaid) {
+        anyhow::bail!("--clusters requires --dot or --mermaid");
+    }
+
+    let field_filters = options
+        .fields
+        .iter()
+        .map(|f| workgraph::fields::parse_assignment(f))
+        .collect::<Result<Vec<_>>>()?;
+
+    let filter = super::parse_filter(dir, options.filter.as_deref())?;
+
+    let graph = super::load_workgraph_at(dir, options.at.as_deref())?;
+    let ctx = FilterContext::new(&graph);
 
     // Determine which tasks to include
     let tasks_to_show: Vec<_> = graph
         .tasks()
+        .filter(|t| workgraph::fields::matches(&t.fields, &field_filters))
+        .filter(|t| filter.as_ref().is_none_or(|f| f.matches(t, &ctx)))
         .filter(|t| {
             // If --all, show everything
             if options.all {
@@ -147,10 +253,16 @@ pub fn run(dir: &Path, options: &VizOptions) -> Result<()> {
                     Status::Blocked => "blocked",
                     Status::Failed => "failed",
                     Status::Abandoned => "abandoned",
+                    Status::InReview => "in-review",
                 };
                 return task_status == status_filter.to_lowercase();
             }
 
+            // A filter expression decides on its own which statuses to show
+            if filter.is_some() {
+                return true;
+            }
+
             // Default: show only non-done tasks
             t.status != Status::Done
         })
@@ -164,6 +276,15 @@ pub fn run(dir: &Path, options: &VizOptions) -> Result<()> {
         filter_internal_tasks(&graph, tasks_to_show, &empty_annotations)
     };
 
+    // One node per fan-out unless --expand-fan-out
+    let collapsed;
+    let tasks_to_show = if options.expand_fan_out {
+        tasks_to_show
+    } else {
+        collapsed = collapse_fan_outs(&graph, &tasks_to_show);
+        collapsed.iter().collect()
+    };
+
     let task_ids: HashSet<&str> = tasks_to_show.iter().map(|t| t.id.as_str()).collect();
 
     // Calculate critical path if requested
@@ -181,6 +302,7 @@ pub fn run(dir: &Path, options: &VizOptions) -> Result<()> {
             &task_ids,
             &critical_path_set,
             &annotations,
+            options.clusters,
         ),
         OutputFormat::Mermaid => generate_mermaid(
             &graph,
@@ -188,6 +310,7 @@ pub fn run(dir: &Path, options: &VizOptions) -> Result<()> {
             &task_ids,
             &critical_path_set,
             &annotations,
+            options.clusters,
         ),
         OutputFormat::Ascii => generate_ascii(&graph, &tasks_to_show, &task_ids, &annotations),
         OutputFormat::Graph => generate_graph(&graph, &tasks_to_show, &task_ids, &annotations),
@@ -213,6 +336,7 @@ fn generate_dot(
     task_ids: &HashSet<&str>,
     critical_path: &HashSet<String>,
     annotations: &HashMap<String, String>,
+    clusters: bool,
 ) -> String {
     let mut lines = vec![
         "digraph workgraph {".to_string(),
@@ -222,6 +346,7 @@ fn generate_dot(
     ];
 
     // Print task nodes
+    let mut node_lines = HashMap::new();
     for task in tasks {
         let style = match task.status {
             Status::Done => "style=filled, fillcolor=lightgreen",
@@ -230,6 +355,7 @@ fn generate_dot(
             Status::Open => "style=filled, fillcolor=white",
             Status::Failed => "style=filled, fillcolor=salmon",
             Status::Abandoned => "style=filled, fillcolor=lightgray",
+            Status::InReview => "style=filled, fillcolor=plum",
         };
 
         // Build label with hours estimate if available
@@ -246,7 +372,19 @@ fn generate_dot(
             .map(|a| format!(" {}", a))
             .unwrap_or_default();
 
-        let label = format!("{}\\n{}{}{}", task.id, task.title, hours_str, phase_str);
+        let fields_str = if task.fields.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "\\n{}",
+                workgraph::fields::display_all(&task.fields).replace('"', "'")
+            )
+        };
+
+        let label = format!(
+            "{}\\n{}{}{}{}",
+            task.id, task.title, hours_str, fields_str, phase_str
+        );
 
         // Check if on critical path
         let node_style = if critical_path.contains(&task.id) {
@@ -255,11 +393,20 @@ fn generate_dot(
             style.to_string()
         };
 
-        lines.push(format!(
-            "  \"{}\" [label=\"{}\", {}];",
-            task.id, label, node_style
-        ));
+        node_lines.insert(
+            task.id.as_str(),
+            format!("  \"{}\" [label=\"{}\", {}];", task.id, label, node_style),
+        );
     }
+    push_task_nodes(
+        &mut lines,
+        graph,
+        tasks,
+        task_ids,
+        &node_lines,
+        clusters,
+        OutputFormat::Dot,
+    );
 
     // Print assigned actors as ellipse nodes
     let assigned_actors: HashSet<&str> =
@@ -303,15 +450,22 @@ fn generate_dot(
                         ""
                     };
 
+                // Dependency group edges are dashed and labelled with the quorum
+                let (label, edge_style) = match group_label(task, blocked_by) {
+                    Some(label) if edge_style.is_empty() => (label, "style=dashed".to_string()),
+                    Some(label) => (label, format!("style=dashed, {}", edge_style)),
+                    None => ("blocks".to_string(), edge_style.to_string()),
+                };
+
                 if edge_style.is_empty() {
                     lines.push(format!(
-                        "  \"{}\" -> \"{}\" [label=\"blocks\"];",
-                        blocked_by, task.id
+                        "  \"{}\" -> \"{}\" [label=\"{}\"];",
+                        blocked_by, task.id, label
                     ));
                 } else {
                     lines.push(format!(
-                        "  \"{}\" -> \"{}\" [label=\"blocks\", {}];",
-                        blocked_by, task.id, edge_style
+                        "  \"{}\" -> \"{}\" [label=\"{}\", {}];",
+                        blocked_by, task.id, label, edge_style
                     ));
                 }
             }
@@ -363,12 +517,14 @@ fn generate_mermaid(
     task_ids: &HashSet<&str>,
     critical_path: &HashSet<String>,
     annotations: &HashMap<String, String>,
+    clusters: bool,
 ) -> String {
     let mut lines = Vec::new();
 
     lines.push("flowchart LR".to_string());
 
     // Print task nodes
+    let mut node_lines = HashMap::new();
     for task in tasks {
         let hours_str = task
             .estimate
@@ -386,7 +542,19 @@ fn generate_mermaid(
             .map(|a| format!(" {}", a))
             .unwrap_or_default();
 
-        let label = format!("{}: {}{}{}", task.id, title, hours_str, phase_str);
+        let fields_str = if task.fields.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " [{}]",
+                workgraph::fields::display_all(&task.fields).replace('"', "'")
+            )
+        };
+
+        let label = format!(
+            "{}: {}{}{}{}",
+            task.id, title, hours_str, fields_str, phase_str
+        );
 
         // Mermaid node shape based on status
         let node = match task.status {
@@ -396,9 +564,19 @@ fn generate_mermaid(
             Status::Open => format!("  {}[\"{}\"]", task.id, label),
             Status::Failed => format!("  {}{{{{\"{}\"}}}}!", task.id, label),
             Status::Abandoned => format!("  {}[\"{}\"]:::abandoned", task.id, label),
+            Status::InReview => format!("  {}([\"{}\"])", task.id, label),
         };
-        lines.push(node);
-    }
+        node_lines.insert(task.id.as_str(), node);
+    }
+    push_task_nodes(
+        &mut lines,
+        graph,
+        tasks,
+        task_ids,
+        &node_lines,
+        clusters,
+        OutputFormat::Mermaid,
+    );
 
     lines.push(String::new());
 
@@ -414,7 +592,12 @@ fn generate_mermaid(
                         "-->"
                     };
 
-                lines.push(format!("  {} {} {}", blocked_by, arrow, task.id));
+                match group_label(task, blocked_by) {
+                    Some(label) => {
+                        lines.push(format!("  {} -. \"{}\" .-> {}", blocked_by, label, task.id))
+                    }
+                    None => lines.push(format!("  {} {} {}", blocked_by, arrow, task.id)),
+                }
             }
         }
     }
@@ -480,19 +663,141 @@ fn generate_mermaid(
 }
 
 /// Calculate the critical path (longest dependency chain by hours)
+/// Emit task node lines in `tasks` order, or, with `clusters`, nested inside
+/// a DOT `subgraph cluster_*` / Mermaid `subgraph` for each shown parent.
+/// A task whose parent is hidden is drawn at the top level.
+fn push_task_nodes(
+    lines: &mut Vec<String>,
+    graph: &WorkGraph,
+    tasks: &[&Task],
+    task_ids: &HashSet<&str>,
+    node_lines: &HashMap<&str, String>,
+    clusters: bool,
+    format: OutputFormat,
+) {
+    if !clusters {
+        for task in tasks {
+            lines.push(node_lines[task.id.as_str()].clone());
+        }
+        return;
+    }
+
+    let mut children: HashMap<&str, Vec<&Task>> = HashMap::new();
+    for task in tasks {
+        if let Some(ref parent) = task.parent
+            && task_ids.contains(parent.as_str())
+        {
+            children.entry(parent.as_str()).or_default().push(task);
+        }
+    }
+
+    let mut emitted = HashSet::new();
+    for task in tasks {
+        let top_level = task
+            .parent
+            .as_deref()
+            .is_none_or(|p| !task_ids.contains(p));
+        if top_level {
+            push_cluster(
+                lines, graph, task, &children, node_lines, &mut emitted, 0, format,
+            );
+        }
+    }
+    // Tasks caught in a parent cycle never reach the top level
+    for task in tasks {
+        if !emitted.contains(task.id.as_str()) {
+            emitted.insert(task.id.as_str());
+            lines.push(node_lines[task.id.as_str()].clone());
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn push_cluster<'a>(
+    lines: &mut Vec<String>,
+    graph: &WorkGraph,
+    task: &'a Task,
+    children: &HashMap<&str, Vec<&'a Task>>,
+    node_lines: &HashMap<&str, String>,
+    emitted: &mut HashSet<&'a str>,
+    depth: usize,
+    format: OutputFormat,
+) {
+    if !emitted.insert(task.id.as_str()) {
+        return;
+    }
+    let indent = "  ".repeat(depth);
+    let node = format!("{}{}", indent, node_lines[task.id.as_str()]);
+    let Some(kids) = children.get(task.id.as_str()) else {
+        lines.push(node);
+        return;
+    };
+
+    let progress = workgraph::query::rollup(graph, &task.id)
+        .map(|r| format!(" ({}/{} done)", r.done, r.total))
+        .unwrap_or_default();
+    let label = format!("{}: {}{}", task.id, task.title.replace('"', "'"), progress);
+    match format {
+        OutputFormat::Dot => {
+            lines.push(format!("{}  subgraph \"cluster_{}\" {{", indent, task.id));
+            lines.push(format!("{}    label=\"{}\";", indent, label));
+            lines.push(format!("{}    style=rounded;", indent));
+        }
+        _ => {
+            lines.push(format!("{}  subgraph cluster_{} [\"{}\"]", indent, task.id, label));
+        }
+    }
+    lines.push(format!("  {}", node));
+    for child in kids {
+        push_cluster(
+            lines,
+            graph,
+            child,
+            children,
+            node_lines,
+            emitted,
+            depth + 1,
+            format,
+        );
+    }
+    match format {
+        OutputFormat::Dot => lines.push(format!("{}  }}", indent)),
+        _ => lines.push(format!("{}  end", indent)),
+    }
+}
+
+/// Edge label for a blocker that belongs to one of the task's dependency
+/// groups ("any of 3", "2 of 3"), or `None` for an ordinary blocker
+fn group_label(task: &Task, blocker: &str) -> Option<String> {
+    let group = task
+        .dep_groups
+        .iter()
+        .find(|g| g.tasks.iter().any(|t| t == blocker))?;
+    Some(if group.need == 1 {
+        format!("any of {}", group.tasks.len())
+    } else {
+        format!("{} of {}", group.need, group.tasks.len())
+    })
+}
+
 fn calculate_critical_path(graph: &WorkGraph, active_ids: &HashSet<&str>) -> HashSet<String> {
     // Build forward index: task_id -> tasks that it blocks
+    // (leaving out dependency group members the task won't wait for)
     let mut forward_index: HashMap<&str, Vec<&str>> = HashMap::new();
+    let finish = workgraph::query::finish_hours(graph, |t| active_ids.contains(t.id.as_str()));
 
     for task in graph.tasks() {
         if !active_ids.contains(task.id.as_str()) {
             continue;
         }
 
-        for blocker_id in &task.blocked_by {
-            if active_ids.contains(blocker_id.as_str()) {
+        let gating = workgraph::query::gating_blockers(task, |blocker| {
+            finish.get(blocker).copied().unwrap_or(0.0)
+        });
+        for (blocker_id, _) in gating {
+            if active_ids.contains(blocker_id) {
                 forward_index
-                    .entry(blocker_id.as_str())
+                    .entry(blocker_id)
                     .or_default()
                     .push(task.id.as_str());
             }
@@ -682,6 +987,7 @@ fn generate_ascii(
             Status::Blocked => "\x1b[90m",    // gray
             Status::Failed => "\x1b[31m",     // red
             Status::Abandoned => "\x1b[90m",  // gray
+            Status::InReview => "\x1b[35m",   // magenta
         }
     };
     let reset = if use_color { "\x1b[0m" } else { "" };
@@ -694,6 +1000,7 @@ fn generate_ascii(
             Status::Blocked => "blocked",
             Status::Failed => "failed",
             Status::Abandoned => "abandoned",
+            Status::InReview => "in-review",
         }
     };
 
@@ -1001,6 +1308,7 @@ fn generate_graph(
             Status::Blocked => "\x1b[90m",
             Status::Failed => "\x1b[31m",
             Status::Abandoned => "\x1b[90m",
+            Status::InReview => "\x1b[35m",
         }
     };
     let reset = if use_color { "\x1b[0m" } else { "" };
@@ -1013,6 +1321,7 @@ fn generate_graph(
             Status::Blocked => "blocked",
             Status::Failed => "failed",
             Status::Abandoned => "abandoned",
+            Status::InReview => "in-review",
         }
     };
 
@@ -1492,7 +1801,7 @@ fn upgrade_connector(existing: char, from_above: bool, to_below: bool) -> char {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use workgraph::graph::{Estimate, Node, Task};
+    use workgraph::graph::{Estimate, Node, Priority, Task};
 
     fn make_task(id: &str, title: &str) -> Task {
         Task {
@@ -1537,6 +1846,21 @@ mod tests {
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         }
     }
 
@@ -1562,7 +1886,7 @@ mod tests {
         let critical_path = HashSet::new();
 
         let no_annots = HashMap::new();
-        let dot = generate_dot(&graph, &tasks, &task_ids, &critical_path, &no_annots);
+        let dot = generate_dot(&graph, &tasks, &task_ids, &critical_path, &no_annots, false);
         assert!(dot.contains("digraph workgraph"));
         assert!(dot.contains("\"t1\""));
         assert!(dot.contains("Task 1"));
@@ -1579,7 +1903,7 @@ mod tests {
         let critical_path = HashSet::new();
         let no_annots = HashMap::new();
 
-        let dot = generate_dot(&graph, &tasks, &task_ids, &critical_path, &no_annots);
+        let dot = generate_dot(&graph, &tasks, &task_ids, &critical_path, &no_annots, false);
         assert!(dot.contains("8h"));
     }
 
@@ -1600,7 +1924,7 @@ mod tests {
         critical_path.insert("t2".to_string());
         let no_annots = HashMap::new();
 
-        let dot = generate_dot(&graph, &tasks, &task_ids, &critical_path, &no_annots);
+        let dot = generate_dot(&graph, &tasks, &task_ids, &critical_path, &no_annots, false);
         assert!(dot.contains("color=red"));
         assert!(dot.contains("penwidth"));
     }
@@ -1616,7 +1940,7 @@ mod tests {
         let critical_path = HashSet::new();
         let no_annots = HashMap::new();
 
-        let mermaid = generate_mermaid(&graph, &tasks, &task_ids, &critical_path, &no_annots);
+        let mermaid = generate_mermaid(&graph, &tasks, &task_ids, &critical_path, &no_annots, false);
         assert!(mermaid.contains("flowchart LR"));
         assert!(mermaid.contains("t1"));
     }
@@ -1636,10 +1960,80 @@ mod tests {
         let critical_path = HashSet::new();
         let no_annots = HashMap::new();
 
-        let mermaid = generate_mermaid(&graph, &tasks, &task_ids, &critical_path, &no_annots);
+        let mermaid = generate_mermaid(&graph, &tasks, &task_ids, &critical_path, &no_annots, false);
         assert!(mermaid.contains("t1 --> t2"));
     }
 
+    fn make_hierarchy() -> WorkGraph {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("epic", "Epic")));
+        let mut c1 = make_task("c1", "Child 1");
+        c1.parent = Some("epic".to_string());
+        let mut c2 = make_task("c2", "Child 2");
+        c2.parent = Some("epic".to_string());
+        c2.blocked_by = vec!["c1".to_string()];
+        graph.add_node(Node::Task(c1));
+        graph.add_node(Node::Task(c2));
+        graph.add_node(Node::Task(make_task("solo", "Solo")));
+        graph
+    }
+
+    #[test]
+    fn test_generate_dot_clusters_children_under_parent() {
+        let graph = make_hierarchy();
+        let tasks: Vec<_> = graph.tasks().collect();
+        let task_ids: HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+        let no_annots = HashMap::new();
+
+        let dot = generate_dot(&graph, &tasks, &task_ids, &HashSet::new(), &no_annots, true);
+        let start = dot.find("subgraph \"cluster_epic\"").unwrap();
+        let end = start + dot[start..].find("\n  }").unwrap();
+        let cluster = &dot[start..end];
+        assert!(cluster.contains("label=\"epic: Epic (0/2 done)\""));
+        assert!(cluster.contains("\"c1\" [label="));
+        assert!(cluster.contains("\"c2\" [label="));
+        assert!(!cluster.contains("\"solo\""));
+        assert!(dot.contains("\"c1\" -> \"c2\""));
+
+        let flat = generate_dot(&graph, &tasks, &task_ids, &HashSet::new(), &no_annots, false);
+        assert!(!flat.contains("subgraph"));
+    }
+
+    #[test]
+    fn test_generate_mermaid_clusters_children_under_parent() {
+        let graph = make_hierarchy();
+        let tasks: Vec<_> = graph.tasks().collect();
+        let task_ids: HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+        let no_annots = HashMap::new();
+
+        let mermaid =
+            generate_mermaid(&graph, &tasks, &task_ids, &HashSet::new(), &no_annots, true);
+        let start = mermaid.find("subgraph cluster_epic").unwrap();
+        let end = start + mermaid[start..].find("\n  end").unwrap();
+        let cluster = &mermaid[start..end];
+        assert!(cluster.contains("c1[\""));
+        assert!(cluster.contains("c2[\""));
+        assert!(!cluster.contains("solo"));
+    }
+
+    #[test]
+    fn test_labels_show_custom_fields() {
+        let mut graph = WorkGraph::new();
+        let mut t1 = make_task("t1", "Task 1");
+        t1.fields
+            .insert("customer".to_string(), serde_json::json!("acme"));
+        graph.add_node(Node::Task(t1));
+        let tasks: Vec<_> = graph.tasks().collect();
+        let task_ids: HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+        let no_annots = HashMap::new();
+
+        let dot = generate_dot(&graph, &tasks, &task_ids, &HashSet::new(), &no_annots, false);
+        assert!(dot.contains("Task 1\\ncustomer=acme"));
+        let mermaid =
+            generate_mermaid(&graph, &tasks, &task_ids, &HashSet::new(), &no_annots, false);
+        assert!(mermaid.contains("t1: Task 1 [customer=acme]"));
+    }
+
     #[test]
     fn test_calculate_critical_path_simple() {
         let mut graph = WorkGraph::new();
@@ -1984,7 +2378,7 @@ mod tests {
         let task_ids: HashSet<&str> = filtered.iter().map(|t| t.id.as_str()).collect();
         let critical_path = HashSet::new();
 
-        let result = generate_dot(&graph, &filtered, &task_ids, &critical_path, &annots);
+        let result = generate_dot(&graph, &filtered, &task_ids, &critical_path, &annots, false);
 
         assert!(!result.contains("assign-my-task"));
         assert!(result.contains("my-task"));
@@ -2013,7 +2407,7 @@ mod tests {
         let task_ids: HashSet<&str> = filtered.iter().map(|t| t.id.as_str()).collect();
         let critical_path = HashSet::new();
 
-        let result = generate_mermaid(&graph, &filtered, &task_ids, &critical_path, &annots);
+        let result = generate_mermaid(&graph, &filtered, &task_ids, &critical_path, &annots, false);
 
         assert!(!result.contains("assign-my-task"));
         assert!(result.contains("my-task"));
@@ -2402,4 +2796,36 @@ mod tests {
     fn test_generate_graph_format_parsing() {
         assert_eq!("graph".parse::<OutputFormat>().unwrap(), OutputFormat::Graph);
     }
+
+    #[test]
+    fn test_collapse_fan_outs() {
+        let mut graph = WorkGraph::new();
+        let mut list = make_task("list", "List files");
+        list.status = Status::Done;
+        list.fan_out = Some(workgraph::fan_out::FanOut::new(
+            "list",
+            "Review {{input.item}}",
+            None,
+            None,
+            "join",
+        ));
+        graph.add_node(Node::Task(list));
+        let mut join = make_task("join", "Join");
+        join.blocked_by = vec!["list".to_string()];
+        graph.add_node(Node::Task(join));
+        let items: Vec<serde_json::Value> = vec!["a".into(), "b".into(), "c".into()];
+        workgraph::fan_out::expand(&mut graph, "list", &items).unwrap();
+        graph.get_task_mut("list-1").unwrap().status = Status::Done;
+
+        let tasks: Vec<_> = graph.tasks().collect();
+        let collapsed = collapse_fan_outs(&graph, &tasks);
+        let ids: HashSet<&str> = collapsed.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, HashSet::from(["list", "join", "list-fan-out"]));
+
+        let group = collapsed.iter().find(|t| t.id == "list-fan-out").unwrap();
+        assert_eq!(group.title, "3 x Review {{input.item}} (1 done)");
+        assert_eq!(group.status, Status::Open);
+        let join = collapsed.iter().find(|t| t.id == "join").unwrap();
+        assert_eq!(join.blocked_by, vec!["list", "list-fan-out"]);
+    }
 }
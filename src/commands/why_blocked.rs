@@ -2,16 +2,48 @@ use anyhow::Result;
 use std::collections::HashSet;
 use std::path::Path;
 use workgraph::WorkGraph;
-use workgraph::graph::{Status, Task};
+use workgraph::graph::{DepCondition, Status, Task, dep_condition, unreachable_condition};
+use workgraph::query::{
+    ResourceShortfall, ResourceWait, blocker_status, resource_shortfalls, resource_usage,
+    resource_waits,
+};
 
 /// Information about a blocking chain node
 #[derive(Debug, Clone)]
 struct BlockingNode {
     id: String,
     status: Status,
+    /// Outcome the dependent needs from this blocker
+    condition: DepCondition,
     children: Vec<BlockingNode>,
 }
 
+/// A dependency group that hasn't reached its quorum
+#[derive(Debug, Clone, serde::Serialize)]
+struct GroupWait {
+    group: String,
+    done: usize,
+    need: u32,
+}
+
+/// A conditional dependency that ended the wrong way, so the task will be
+/// skipped instead of unblocked
+#[derive(Debug, Clone, serde::Serialize)]
+struct UnmetCondition {
+    blocker: String,
+    condition: DepCondition,
+    status: Status,
+}
+
+/// Resources keeping a task from starting
+#[derive(Debug, Clone, Default)]
+struct ResourceBlocks {
+    /// Resources at capacity right now
+    waits: Vec<ResourceWait>,
+    /// Requirements larger than the resource's capacity, which never clear
+    shortfalls: Vec<ResourceShortfall>,
+}
+
 /// Root blocker information
 #[derive(Debug, Clone)]
 struct RootBlocker<'a> {
@@ -24,6 +56,44 @@ pub fn run(dir: &Path, id: &str, json: bool) -> Result<()> {
 
     let task = graph.get_task_or_err(id)?;
 
+    // Resources at capacity only matter for tasks that are waiting to start;
+    // a requirement beyond capacity is reported on its own, not as a wait
+    let usage = resource_usage(&graph);
+    let mut resources = ResourceBlocks::default();
+    if !task.status.is_terminal() {
+        resources.shortfalls = resource_shortfalls(&graph, task);
+    }
+    if task.status == Status::Open {
+        resources.waits = resource_waits(&graph, task, &usage);
+        resources
+            .waits
+            .retain(|w| !resources.shortfalls.iter().any(|s| s.resource == w.resource));
+    }
+
+    // Dependency groups still short of their quorum
+    let groups: Vec<GroupWait> = task
+        .dep_groups
+        .iter()
+        .filter(|g| !g.is_met(|b| blocker_status(b, &graph, Some(dir))))
+        .map(|g| GroupWait {
+            group: g.to_string(),
+            done: g.done_count(|b| blocker_status(b, &graph, Some(dir))),
+            need: g.need,
+        })
+        .collect();
+
+    let unmet = if task.status.is_terminal() {
+        None
+    } else {
+        unreachable_condition(task, |b| blocker_status(b, &graph, Some(dir))).map(
+            |(blocker, condition, status)| UnmetCondition {
+                blocker: blocker.to_string(),
+                condition,
+                status,
+            },
+        )
+    };
+
     // Build the blocking chain tree (resolves remote deps via federation)
     let mut visited = HashSet::new();
     let blocking_tree = build_blocking_tree(&graph, id, &mut visited, dir);
@@ -39,7 +109,8 @@ pub fn run(dir: &Path, id: &str, json: bool) -> Result<()> {
             // has the status. Root blockers from remote peers are only shown in the
             // tree; they won't appear here (since graph.get_task won't find them).
             graph.get_task(rid).map(|t| {
-                let is_ready = is_task_ready(&graph, t, dir);
+                let is_ready =
+                    is_task_ready(&graph, t, dir) && resource_waits(&graph, t, &usage).is_empty();
                 RootBlocker { task: t, is_ready }
             })
         })
@@ -49,9 +120,25 @@ pub fn run(dir: &Path, id: &str, json: bool) -> Result<()> {
     let total_blockers = count_blockers(&blocking_tree);
 
     if json {
-        print_json(task, &blocking_tree, &root_blockers, total_blockers)?;
+        print_json(
+            task,
+            &blocking_tree,
+            &root_blockers,
+            total_blockers,
+            &resources,
+            &groups,
+            unmet.as_ref(),
+        )?;
     } else {
-        print_human(task, &blocking_tree, &root_blockers, total_blockers);
+        print_human(
+            task,
+            &blocking_tree,
+            &root_blockers,
+            total_blockers,
+            &resources,
+            &groups,
+            unmet.as_ref(),
+        );
     }
 
     Ok(())
@@ -69,6 +156,7 @@ fn build_blocking_tree(
     let mut node = BlockingNode {
         id: task_id.to_string(),
         status,
+        condition: DepCondition::Always,
         children: vec![],
     };
 
@@ -78,9 +166,16 @@ fn build_blocking_tree(
     visited.insert(task_id.to_string());
 
     if let Some(task) = task {
+        // Members of groups that reached their quorum no longer block
+        let met: HashSet<&str> = task
+            .dep_groups
+            .iter()
+            .filter(|g| g.is_met(|b| blocker_status(b, graph, Some(dir))))
+            .flat_map(|g| g.tasks.iter().map(String::as_str))
+            .collect();
         for blocker_id in &task.blocked_by {
             // Skip if already visited (cycle detection)
-            if visited.contains(blocker_id) {
+            if visited.contains(blocker_id) || met.contains(blocker_id.as_str()) {
                 continue;
             }
 
@@ -97,6 +192,7 @@ fn build_blocking_tree(
                     let child = BlockingNode {
                         id: blocker_id.clone(),
                         status: remote.status,
+                        condition: dep_condition(task, blocker_id),
                         children: vec![], // Don't recurse into remote graphs
                     };
                     node.children.push(child);
@@ -104,7 +200,8 @@ fn build_blocking_tree(
             } else if let Some(blocker) = graph.get_task(blocker_id) {
                 // Local dependency — only include if still actively blocking
                 if !blocker.status.is_terminal() {
-                    let child = build_blocking_tree(graph, blocker_id, visited, dir);
+                    let mut child = build_blocking_tree(graph, blocker_id, visited, dir);
+                    child.condition = dep_condition(task, blocker_id);
                     node.children.push(child);
                 }
             }
@@ -134,8 +231,8 @@ fn is_task_ready(graph: &WorkGraph, task: &Task, dir: &Path) -> bool {
     if task.status != Status::Open {
         return false;
     }
-    task.blocked_by.iter().all(|blocker_id| {
-        workgraph::query::is_blocker_satisfied(blocker_id, graph, Some(dir))
+    workgraph::query::dependencies_met(task, |blocker_id| {
+        blocker_status(blocker_id, graph, Some(dir))
     })
 }
 
@@ -156,17 +253,71 @@ fn count_blockers_recursive(node: &BlockingNode, count: &mut usize, visited: &mu
     }
 }
 
-fn print_human(task: &Task, tree: &BlockingNode, root_blockers: &[RootBlocker], total: usize) {
+/// Human-readable "waiting on resource X" line for a resource at capacity
+fn format_resource_wait(wait: &ResourceWait) -> String {
+    format!(
+        "waiting on resource {} (needs {}, {} of {} in use)",
+        wait.resource, wait.needed, wait.in_use, wait.available
+    )
+}
+
+fn print_human(
+    task: &Task,
+    tree: &BlockingNode,
+    root_blockers: &[RootBlocker],
+    total: usize,
+    resources: &ResourceBlocks,
+    groups: &[GroupWait],
+    unmet: Option<&UnmetCondition>,
+) {
     println!("Task: {}", task.id);
 
+    if let Some(unmet) = unmet {
+        println!("Status: {:?}", task.status);
+        println!();
+        println!(
+            "{} will be skipped: it depends on '{}' ({}), which is {}.",
+            task.id, unmet.blocker, unmet.condition, unmet.status
+        );
+        return;
+    }
+
+    if !resources.shortfalls.is_empty() {
+        println!("Status: {:?}", task.status);
+        println!();
+        for shortfall in &resources.shortfalls {
+            println!(
+                "{} can never start: it needs {} of resource {}, which only has {}.",
+                task.id, shortfall.needed, shortfall.resource, shortfall.available
+            );
+        }
+        println!("Lower the requirement or raise the resource's capacity.");
+        return;
+    }
+
     if tree.children.is_empty() {
         println!("Status: {:?}", task.status);
         println!();
-        println!("{} has no blockers.", task.id);
+        if resources.waits.is_empty() {
+            println!("{} has no blockers.", task.id);
+        } else {
+            for wait in &resources.waits {
+                println!("{} is {}.", task.id, format_resource_wait(wait));
+            }
+        }
         return;
     }
 
     println!("Status: blocked (transitively)");
+    for wait in &resources.waits {
+        println!("Also {}", format_resource_wait(wait));
+    }
+    for group in groups {
+        println!(
+            "Needs {} ({} of {} done)",
+            group.group, group.done, group.need
+        );
+    }
     println!();
     println!("Blocking chain:");
     println!();
@@ -224,7 +375,10 @@ fn print_tree(node: &BlockingNode, prefix: &str, depth: usize) {
         println!("{}", node.id);
     } else {
         // Child node - print with tree connector and status
-        let status_str = format!("(status: {:?})", node.status);
+        let status_str = match node.condition {
+            DepCondition::Always => format!("(status: {:?})", node.status),
+            condition => format!("(status: {:?}, {})", node.status, condition),
+        };
         let root_marker = if node.children.is_empty() && !node.status.is_terminal() {
             " <-- ROOT CAUSE"
         } else {
@@ -253,6 +407,9 @@ fn print_json(
     tree: &BlockingNode,
     root_blockers: &[RootBlocker],
     total: usize,
+    resources: &ResourceBlocks,
+    groups: &[GroupWait],
+    unmet: Option<&UnmetCondition>,
 ) -> Result<()> {
     let output = serde_json::json!({
         "task": {
@@ -260,7 +417,14 @@ fn print_json(
             "title": task.title,
             "status": task.status,
         },
-        "is_blocked": !tree.children.is_empty(),
+        "is_blocked": !tree.children.is_empty()
+            || !resources.waits.is_empty()
+            || !resources.shortfalls.is_empty()
+            || unmet.is_some(),
+        "waiting_on_resources": resources.waits,
+        "exceeds_resource_capacity": resources.shortfalls,
+        "waiting_on_groups": groups,
+        "unmet_condition": unmet,
         "blocking_chain": tree_to_json(tree),
         "root_blockers": root_blockers.iter().map(|rb| {
             serde_json::json!({
@@ -281,6 +445,7 @@ fn tree_to_json(node: &BlockingNode) -> serde_json::Value {
     serde_json::json!({
         "id": node.id,
         "status": format!("{:?}", node.status),
+        "condition": node.condition,
         "blocked_by": node.children.iter().map(tree_to_json).collect::<Vec<_>>(),
     })
 }
@@ -500,4 +665,18 @@ mod tests {
         assert_eq!(roots.len(), 1);
         assert!(roots.contains("root"));
     }
+
+    #[test]
+    fn test_format_resource_wait() {
+        let wait = ResourceWait {
+            resource: "gpu-box".to_string(),
+            needed: 1.0,
+            in_use: 2.0,
+            available: 2.0,
+        };
+        assert_eq!(
+            format_resource_wait(&wait),
+            "waiting on resource gpu-box (needs 1, 2 of 2 in use)"
+        );
+    }
 }
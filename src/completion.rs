@@ -0,0 +1,152 @@
+//! Marking tasks done
+//!
+//! Every way of finishing a task (`wg done`, `wg approve`, `wg exec`,
+//! `wg agent`, the Matrix `done` command and the coordinator's triage of dead
+//! agents) goes through [`complete`], so the rest of the graph reacts the
+//! same way no matter who finished it: fan-outs expand, loop edges fire,
+//! auto-complete parents finish, quorum siblings are abandoned and dependents
+//! whose conditions can no longer be met are skipped.
+
+use crate::fan_out;
+use crate::graph::{
+    Status, WorkGraph, abandon_quorum_siblings, complete_parents, reward_loop_edges_with,
+    skip_unreachable,
+};
+use crate::loop_guard::Readings;
+use crate::query;
+use anyhow::Result;
+use chrono::Utc;
+use std::path::Path;
+
+/// What finishing a task set off elsewhere in the graph
+#[derive(Debug, Clone, Default)]
+pub struct Completed {
+    /// Generated fan-out children, if the task fanned out
+    pub fanned_out: Option<Vec<String>>,
+    /// Loop targets re-activated by the task's loop edges
+    pub reactivated: Vec<String>,
+    /// Auto-complete parents finished because this was their last open child
+    pub completed_parents: Vec<String>,
+    /// Siblings abandoned because a dependency group reached its quorum
+    pub abandoned: Vec<String>,
+    /// Dependents skipped because their dependency conditions can't be met
+    pub skipped: Vec<String>,
+}
+
+/// Fail unless `id` can be marked done: every blocker must be resolved and
+/// every child finished.
+pub fn check_completable(graph: &WorkGraph, id: &str) -> Result<()> {
+    let blockers = query::blocked_by(graph, id);
+    if !blockers.is_empty() {
+        let blocker_list: Vec<String> = blockers
+            .iter()
+            .map(|t| format!("  - {} ({}): {:?}", t.id, t.title, t.status))
+            .collect();
+        anyhow::bail!(
+            "Cannot mark '{}' as done: blocked by {} unresolved task(s):\n{}",
+            id,
+            blockers.len(),
+            blocker_list.join("\n")
+        );
+    }
+
+    // A parent is done only once its children have finished
+    let open_children: Vec<String> = graph
+        .children(id)
+        .iter()
+        .filter(|c| !c.status.is_terminal())
+        .map(|c| format!("  - {} ({}): {:?}", c.id, c.title, c.status))
+        .collect();
+    if !open_children.is_empty() {
+        anyhow::bail!(
+            "Cannot mark '{}' as done: {} child task(s) not finished:\n{}",
+            id,
+            open_children.len(),
+            open_children.join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Read the reward, artifact and shell guards on `id`'s loop edges from
+/// `graph`, a snapshot loaded before the graph lock is taken. A converged
+/// task's loops don't fire, so its guards aren't read.
+pub fn read_guards(graph: &WorkGraph, dir: &Path, id: &str) -> Readings {
+    match graph.get_task(id) {
+        Some(task) if !task.tags.iter().any(|t| t == "converged") => Readings::of(task, dir),
+        _ => Readings::default(),
+    }
+}
+
+/// Mark a task done and apply what follows from it: fan-out expansion, loop
+/// edges, parent completion, quorum siblings and unreachable dependents.
+/// Callers check [`check_completable`] and log the transition; `guards` come
+/// from [`read_guards`].
+pub fn complete(
+    graph: &mut WorkGraph,
+    dir: &Path,
+    id: &str,
+    guards: &Readings,
+) -> Result<Completed> {
+    // A fan-out task can't finish without items to fan out into
+    let task = graph.get_task_or_err(id)?;
+    let items = match &task.fan_out {
+        Some(fan_out) if !fan_out.expanded => Some(fan_out::load_items(task, dir)?),
+        _ => None,
+    };
+
+    let task = graph.get_task_mut_or_err(id)?;
+    task.status = Status::Done;
+    task.completed_at = Some(Utc::now().to_rfc3339());
+
+    let fanned_out = items
+        .map(|items| fan_out::expand(graph, id, &items))
+        .transpose()?;
+
+    // Reward loop edges: re-activate upstream tasks if conditions are met
+    let reactivated = reward_loop_edges_with(graph, id, guards);
+    let completed_parents = complete_parents(graph, id);
+    let abandoned = abandon_quorum_siblings(graph, id);
+    let skipped = skip_unreachable(graph);
+
+    Ok(Completed {
+        fanned_out,
+        reactivated,
+        completed_parents,
+        abandoned,
+        skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+    use crate::test_helpers::make_task_with_status as make_task;
+
+    #[test]
+    fn test_complete_finishes_auto_complete_parent() {
+        let mut graph = WorkGraph::new();
+        let mut epic = make_task("epic", "Epic", Status::Open);
+        epic.auto_complete = true;
+        let mut a = make_task("a", "A", Status::InProgress);
+        a.parent = Some("epic".to_string());
+        let mut b = make_task("b", "B", Status::InProgress);
+        b.parent = Some("epic".to_string());
+        for task in [epic, a, b] {
+            graph.add_node(Node::Task(task));
+        }
+
+        // The parent can't finish ahead of its children
+        let err = check_completable(&graph, "epic").unwrap_err();
+        assert!(err.to_string().contains("2 child task(s) not finished"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let guards = Readings::default();
+        let completed = complete(&mut graph, dir.path(), "a", &guards).unwrap();
+        assert!(completed.completed_parents.is_empty());
+        let completed = complete(&mut graph, dir.path(), "b", &guards).unwrap();
+        assert_eq!(completed.completed_parents, vec!["epic"]);
+        assert_eq!(graph.get_task("epic").unwrap().status, Status::Done);
+    }
+}
@@ -41,6 +41,72 @@ pub struct Config {
     /// Replay configuration
     #[serde(default)]
     pub replay: ReplayConfig,
+
+    /// Custom task field schema, one `[fields.<name>]` table per field
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, FieldSpec>,
+
+    /// Saved task filters, usable as `@name` in `--filter` expressions
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub queries: BTreeMap<String, String>,
+
+    /// Automatic retry policies and failure classes
+    #[serde(default)]
+    pub retry: RetryConfig,
+}
+
+/// Retry configuration (see [`crate::retry`])
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Failure classes, tried in order before the built-in ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<crate::retry::FailureClass>,
+
+    /// Retry policy per tag, for tasks without a policy of their own
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, crate::retry::RetryPolicy>,
+}
+
+/// Declared type and constraints of a custom task field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSpec {
+    /// Value type: "string", "number", "enum" or "date"
+    #[serde(rename = "type")]
+    pub kind: FieldType,
+
+    /// Every task must set this field
+    #[serde(default)]
+    pub required: bool,
+
+    /// Allowed values for an enum field
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+
+    /// What the field is for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Type of a custom task field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    Number,
+    Enum,
+    /// Calendar date, `YYYY-MM-DD`
+    Date,
+}
+
+impl std::fmt::Display for FieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldType::String => write!(f, "string"),
+            FieldType::Number => write!(f, "number"),
+            FieldType::Enum => write!(f, "enum"),
+            FieldType::Date => write!(f, "date"),
+        }
+    }
 }
 
 /// Help display configuration
@@ -223,6 +289,12 @@ pub struct CoordinatorConfig {
     /// Overrides agent.model when set. Can be further overridden by CLI --model.
     #[serde(default)]
     pub model: Option<String>,
+
+    /// Default policy for scheduled tasks' missed fire times: "skip",
+    /// "catch-up-one" (default) or "catch-up-all". A fire counts as missed once
+    /// it is more than two poll intervals old.
+    #[serde(default)]
+    pub missed_fire: crate::schedule::MissedFire,
 }
 
 fn default_max_agents() -> usize {
@@ -245,6 +317,7 @@ impl Default for CoordinatorConfig {
             poll_interval: default_poll_interval(),
             executor: default_executor(),
             model: None,
+            missed_fire: Default::default(),
         }
     }
 }
@@ -1035,4 +1108,30 @@ model = "haiku"
         assert_eq!(ConfigSource::Local.to_string(), "local");
         assert_eq!(ConfigSource::Default.to_string(), "default");
     }
+
+    #[test]
+    fn test_fields_schema_parses() {
+        let temp_dir = TempDir::new().unwrap();
+        let toml = r#"
+[fields.customer]
+type = "string"
+required = true
+
+[fields.risk]
+type = "enum"
+values = ["low", "high"]
+"#;
+        fs::write(temp_dir.path().join("config.toml"), toml).unwrap();
+
+        let config = Config::load(temp_dir.path()).unwrap();
+        assert_eq!(config.fields.len(), 2);
+        assert_eq!(config.fields["customer"].kind, FieldType::String);
+        assert!(config.fields["customer"].required);
+        assert_eq!(config.fields["risk"].values, vec!["low", "high"]);
+
+        // Round-trips through TOML
+        let out = toml::to_string_pretty(&config).unwrap();
+        let reparsed: Config = toml::from_str(&out).unwrap();
+        assert_eq!(reparsed.fields, config.fields);
+    }
 }
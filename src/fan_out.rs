@@ -0,0 +1,364 @@
+//! Dynamic fan-out: one generated task per item a task produces
+//!
+//! A task with a [`FanOut`] emits a list of items (a JSON file registered as
+//! one of its artifacts, or named by [`FanOut::items`]). Once it is done,
+//! [`expand`] instantiates the fan-out's [`TaskTemplate`] once per item with
+//! [`substitute_task_template`], where `{{input.item}}` is the item,
+//! `{{input.index}}` its 1-based position, `{{input.count}}` the number of
+//! items and, for object items, `{{input.<key>}}` each of its fields.
+//!
+//! Each child is blocked by the fan-out task, and a join task (created
+//! along with the fan-out task, so other tasks can depend on it before the
+//! items are known) is blocked by every child.
+//!
+//! Finishing a task through [`crate::completion::complete`] (`wg done`,
+//! `wg exec`, `wg agent`, coordinator triage, ...) expands it; the
+//! coordinator expands tasks marked done any other way on its next tick
+//! ([`expand_pending`]), and until then their dependents aren't ready.
+
+use crate::graph::{LogEntry, Node, Status, Task, WorkGraph};
+use crate::trace_function::{TaskTemplate, substitute_task_template};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Tag on every task generated by a fan-out
+pub const FAN_OUT_TAG: &str = "fan-out";
+
+/// What a task fans out into
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FanOut {
+    /// Template instantiated once per item
+    pub template: TaskTemplate,
+    /// JSON file holding the items, relative to the project root (None = the
+    /// task's last `.json` artifact)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<String>,
+    /// The join task, blocked by every child
+    pub join: String,
+    /// Generated children, in item order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<String>,
+    /// Whether the fan-out has happened
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub expanded: bool,
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+impl FanOut {
+    /// A fan-out of `task_id` into children titled `title`, joined by `join`
+    pub fn new(
+        task_id: &str,
+        title: &str,
+        description: Option<&str>,
+        items: Option<&str>,
+        join: &str,
+    ) -> Self {
+        let description = description.map(String::from).unwrap_or_else(|| {
+            format!(
+                "Item {{{{input.index}}}} of {{{{input.count}}}} from '{}':\n\n{{{{input.item}}}}",
+                task_id
+            )
+        });
+        Self {
+            template: TaskTemplate {
+                template_id: "item".to_string(),
+                title: title.to_string(),
+                description,
+                skills: vec![],
+                blocked_by: vec![],
+                loops_to: vec![],
+                role_hint: None,
+                deliverables: vec![],
+                verify: None,
+                tags: vec![FAN_OUT_TAG.to_string()],
+            },
+            items: items.map(String::from),
+            join: join.to_string(),
+            children: vec![],
+            expanded: false,
+        }
+    }
+}
+
+/// `base`, or `base-2`, `base-3`, ... if it is taken
+pub fn unique_id(graph: &WorkGraph, base: &str) -> String {
+    if graph.get_node(base).is_none() {
+        return base.to_string();
+    }
+    (2..)
+        .map(|i| format!("{}-{}", base, i))
+        .find(|id| graph.get_node(id).is_none())
+        .unwrap_or_else(|| base.to_string())
+}
+
+/// The items a list of JSON holds: a top-level array, or the `items` array of
+/// an object
+pub fn parse_items(json: &str) -> Result<Vec<serde_json::Value>> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    match value {
+        serde_json::Value::Array(items) => Ok(items),
+        serde_json::Value::Object(mut map) => match map.remove("items") {
+            Some(serde_json::Value::Array(items)) => Ok(items),
+            _ => anyhow::bail!("expected a JSON array, or an object with an \"items\" array"),
+        },
+        _ => anyhow::bail!("expected a JSON array, or an object with an \"items\" array"),
+    }
+}
+
+/// Read the items of fan-out task `task` (`dir` is the `.workgraph` directory)
+pub fn load_items(task: &Task, dir: &Path) -> Result<Vec<serde_json::Value>> {
+    let fan_out = task
+        .fan_out
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Task '{}' does not fan out", task.id))?;
+    let path = match &fan_out.items {
+        Some(path) => path.clone(),
+        None => task
+            .artifacts
+            .iter()
+            .rev()
+            .find(|a| a.ends_with(".json"))
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Task '{}' fans out but has no items: register a .json artifact with \
+                     'wg artifact {} <path>'",
+                    task.id,
+                    task.id
+                )
+            })?,
+    };
+    let root = dir.parent().unwrap_or(dir);
+    let json = std::fs::read_to_string(root.join(&path))
+        .with_context(|| format!("Failed to read fan-out items from '{}'", path))?;
+    parse_items(&json).with_context(|| format!("Invalid fan-out items in '{}'", path))
+}
+
+/// Template inputs for the `index`-th (0-based) of `count` items
+fn item_inputs(
+    item: &serde_json::Value,
+    index: usize,
+    count: usize,
+) -> HashMap<String, serde_yaml::Value> {
+    let mut inputs = HashMap::new();
+    if let serde_json::Value::Object(fields) = item {
+        for (key, value) in fields {
+            if let Ok(value) = serde_yaml::to_value(value) {
+                inputs.insert(key.clone(), value);
+            }
+        }
+    }
+    let item = match item {
+        // Plain strings render without quotes
+        serde_json::Value::String(s) => serde_yaml::Value::String(s.clone()),
+        other => serde_yaml::Value::String(other.to_string()),
+    };
+    inputs.insert("item".to_string(), item);
+    inputs.insert(
+        "index".to_string(),
+        serde_yaml::Value::from(index as u64 + 1),
+    );
+    inputs.insert("count".to_string(), serde_yaml::Value::from(count as u64));
+    inputs
+}
+
+/// Add one child of fan-out task `id` per item and block its join task on
+/// them. Returns the children's IDs; a task already expanded gets none.
+pub fn expand(graph: &mut WorkGraph, id: &str, items: &[serde_json::Value]) -> Result<Vec<String>> {
+    let task = graph
+        .get_task(id)
+        .ok_or_else(|| anyhow::anyhow!("Task '{}' not found", id))?
+        .clone();
+    let Some(fan_out) = task.fan_out.as_ref().filter(|f| !f.expanded) else {
+        return Ok(vec![]);
+    };
+    let now = Utc::now().to_rfc3339();
+    let join = graph.get_task(&fan_out.join).map(|t| t.id.clone());
+
+    let mut children = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let rendered =
+            substitute_task_template(&fan_out.template, &item_inputs(item, index, items.len()));
+        let child_id = unique_id(graph, &format!("{}-{}", id, index + 1));
+        graph.add_node(Node::Task(Task {
+            id: child_id.clone(),
+            title: rendered.title,
+            description: Some(rendered.description).filter(|d| !d.is_empty()),
+            skills: rendered.skills,
+            deliverables: rendered.deliverables,
+            verify: rendered.verify,
+            tags: rendered.tags,
+            blocked_by: vec![id.to_string()],
+            blocks: join.iter().cloned().collect(),
+            parent: task.parent.clone(),
+            model: task.model.clone(),
+            created_at: Some(now.clone()),
+            log: vec![LogEntry {
+                timestamp: now.clone(),
+                actor: None,
+                message: format!("Created by fan-out of '{}' (item {})", id, index + 1),
+            }],
+            ..Task::default()
+        }));
+        children.push(child_id);
+    }
+
+    if let Some(join) = join.as_deref().and_then(|j| graph.get_task_mut(j)) {
+        for child in &children {
+            if !join.blocked_by.contains(child) {
+                join.blocked_by.push(child.clone());
+            }
+        }
+    }
+    let task = graph.get_task_mut_or_err(id)?;
+    task.blocks.extend(children.iter().cloned());
+    if let Some(fan_out) = task.fan_out.as_mut() {
+        fan_out.children = children.clone();
+        fan_out.expanded = true;
+    }
+    task.log.push(LogEntry {
+        timestamp: now,
+        actor: None,
+        message: format!("Fanned out into {} task(s)", children.len()),
+    });
+    Ok(children)
+}
+
+/// Outcome of expanding one fan-out task in [`expand_pending`]
+#[derive(Debug, Clone, Serialize)]
+pub struct Expansion {
+    pub task: String,
+    pub children: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Expand every done fan-out task that hasn't been. A task whose items can't
+/// be read is marked failed, so it can be fixed and retried.
+pub fn expand_pending(graph: &mut WorkGraph, dir: &Path) -> Vec<Expansion> {
+    let pending: Vec<Task> = graph
+        .tasks()
+        .filter(|t| t.status == Status::Done && t.fan_out.as_ref().is_some_and(|f| !f.expanded))
+        .cloned()
+        .collect();
+
+    let mut expansions = Vec::new();
+    for task in pending {
+        let result = load_items(&task, dir).and_then(|items| expand(graph, &task.id, &items));
+        match result {
+            Ok(children) => expansions.push(Expansion {
+                task: task.id,
+                children,
+                error: None,
+            }),
+            Err(e) => {
+                let reason = format!("Fan-out failed: {:#}", e);
+                if let Some(task) = graph.get_task_mut(&task.id) {
+                    task.status = Status::Failed;
+                    task.retry_count += 1;
+                    task.failure_reason = Some(reason.clone());
+                    crate::retry::record_failure(task, None, None);
+                    task.log.push(LogEntry {
+                        timestamp: Utc::now().to_rfc3339(),
+                        actor: Some("coordinator".to_string()),
+                        message: reason.clone(),
+                    });
+                }
+                expansions.push(Expansion {
+                    task: task.id,
+                    children: vec![],
+                    error: Some(reason),
+                });
+            }
+        }
+    }
+    expansions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fan_out_graph() -> WorkGraph {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(Task {
+            id: "list".to_string(),
+            title: "List files".to_string(),
+            status: Status::Done,
+            blocks: vec!["list-join".to_string()],
+            fan_out: Some(FanOut::new(
+                "list",
+                "Review {{input.path}}",
+                None,
+                Some("files.json"),
+                "list-join",
+            )),
+            ..Task::default()
+        }));
+        graph.add_node(Node::Task(Task {
+            id: "list-join".to_string(),
+            title: "Join".to_string(),
+            blocked_by: vec!["list".to_string()],
+            ..Task::default()
+        }));
+        graph
+    }
+
+    #[test]
+    fn test_parse_items() {
+        assert_eq!(parse_items(r#"["a", "b"]"#).unwrap().len(), 2);
+        assert_eq!(parse_items(r#"{"items": [1, 2, 3]}"#).unwrap().len(), 3);
+        assert!(parse_items(r#"{"files": []}"#).is_err());
+        assert!(parse_items("42").is_err());
+    }
+
+    #[test]
+    fn test_expand() {
+        let mut graph = fan_out_graph();
+        let items = parse_items(r#"[{"path": "src/a.rs"}, {"path": "src/b.rs"}]"#).unwrap();
+        let children = expand(&mut graph, "list", &items).unwrap();
+        assert_eq!(children, vec!["list-1", "list-2"]);
+
+        let child = graph.get_task("list-2").unwrap();
+        assert_eq!(child.title, "Review src/b.rs");
+        assert!(child.description.as_ref().unwrap().contains("Item 2 of 2"));
+        assert_eq!(child.blocked_by, vec!["list"]);
+        assert!(child.tags.contains(&FAN_OUT_TAG.to_string()));
+
+        let join = graph.get_task("list-join").unwrap();
+        assert_eq!(join.blocked_by, vec!["list", "list-1", "list-2"]);
+        let list = graph.get_task("list").unwrap();
+        assert_eq!(list.fan_out.as_ref().unwrap().children, children);
+
+        // Only once
+        assert!(expand(&mut graph, "list", &items).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_expand_pending() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join(".workgraph");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut graph = fan_out_graph();
+        let expansions = expand_pending(&mut graph, &dir);
+        assert!(expansions[0].error.is_some());
+        assert_eq!(graph.get_task("list").unwrap().status, Status::Failed);
+
+        graph.get_task_mut("list").unwrap().status = Status::Done;
+        std::fs::write(tmp.path().join("files.json"), r#"["x", "y", "z"]"#).unwrap();
+        let expansions = expand_pending(&mut graph, &dir);
+        assert_eq!(expansions[0].children.len(), 3);
+        assert_eq!(
+            graph.get_task("list-3").unwrap().title,
+            "Review {{input.path}}"
+        );
+        assert!(expand_pending(&mut graph, &dir).is_empty());
+    }
+}
@@ -469,25 +469,23 @@ pub fn resolve_remote_task_status(
         };
     }
 
-    match crate::parser::load_graph(&graph_path) {
-        Ok(graph) => match graph.get_task(task_id) {
-            Some(task) => RemoteTaskStatus {
-                task_id: task.id.clone(),
-                status: task.status,
-                title: Some(task.title.clone()),
-                assigned: task.assigned.clone(),
-                resolution: RemoteResolution::DirectFileAccess,
-            },
-            None => RemoteTaskStatus {
-                task_id: task_id.to_string(),
-                status: crate::graph::Status::Open,
-                title: None,
-                assigned: None,
-                resolution: RemoteResolution::Unreachable(format!(
-                    "Task '{}' not found in peer '{}'",
-                    task_id, peer_name
-                )),
-            },
+    match crate::store::open(&graph_path).get_task(task_id) {
+        Ok(Some(task)) => RemoteTaskStatus {
+            task_id: task.id,
+            status: task.status,
+            title: Some(task.title),
+            assigned: task.assigned,
+            resolution: RemoteResolution::DirectFileAccess,
+        },
+        Ok(None) => RemoteTaskStatus {
+            task_id: task_id.to_string(),
+            status: crate::graph::Status::Open,
+            title: None,
+            assigned: None,
+            resolution: RemoteResolution::Unreachable(format!(
+                "Task '{}' not found in peer '{}'",
+                task_id, peer_name
+            )),
         },
         Err(e) => RemoteTaskStatus {
             task_id: task_id.to_string(),
@@ -573,6 +571,7 @@ fn parse_status_string(s: &str) -> crate::graph::Status {
         "failed" => crate::graph::Status::Failed,
         "abandoned" => crate::graph::Status::Abandoned,
         "blocked" => crate::graph::Status::Blocked,
+        "in-review" => crate::graph::Status::InReview,
         _ => crate::graph::Status::Open,
     }
 }
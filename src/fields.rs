@@ -0,0 +1,228 @@
+//! Custom task fields
+//!
+//! Tasks carry structured metadata in [`Task::fields`](crate::graph::Task),
+//! set with `wg add/edit --field key=value`. A project can declare a schema
+//! for them in `config.toml`:
+//!
+//! ```toml
+//! [fields.customer]
+//! type = "string"
+//! required = true
+//!
+//! [fields.risk]
+//! type = "enum"
+//! values = ["low", "medium", "high"]
+//!
+//! [fields.due]
+//! type = "date"
+//! ```
+//!
+//! Declared fields are validated and stored with their type (numbers as JSON
+//! numbers, everything else as strings). Undeclared fields are accepted
+//! as-is and stored as strings, so `ticket=00123` keeps its leading zeros.
+//! Declare a field as `number` to store it numerically.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::config::{FieldSpec, FieldType};
+
+/// Split a `key=value` argument.
+pub fn parse_assignment(arg: &str) -> Result<(String, String)> {
+    let (key, value) = arg
+        .split_once('=')
+        .with_context(|| format!("Invalid field '{}': expected key=value", arg))?;
+    let key = key.trim();
+    if key.is_empty() {
+        anyhow::bail!("Invalid field '{}': missing key", arg);
+    }
+    Ok((key.to_string(), value.trim().to_string()))
+}
+
+/// Convert the raw string for field `key` into its stored value, checking it
+/// against the schema entry if there is one.
+pub fn coerce(schema: &BTreeMap<String, FieldSpec>, key: &str, raw: &str) -> Result<Value> {
+    let Some(spec) = schema.get(key) else {
+        return Ok(Value::String(raw.to_string()));
+    };
+    match spec.kind {
+        FieldType::String => Ok(Value::String(raw.to_string())),
+        FieldType::Number => match raw.parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(number(n)),
+            _ => anyhow::bail!("Field '{}' must be a number, got '{}'", key, raw),
+        },
+        FieldType::Enum => {
+            if !spec.values.iter().any(|v| v == raw) {
+                anyhow::bail!(
+                    "Field '{}' must be one of: {} (got '{}')",
+                    key,
+                    spec.values.join(", "),
+                    raw
+                );
+            }
+            Ok(Value::String(raw.to_string()))
+        }
+        FieldType::Date => {
+            chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d").with_context(|| {
+                format!("Field '{}' must be a date (YYYY-MM-DD), got '{}'", key, raw)
+            })?;
+            Ok(Value::String(raw.to_string()))
+        }
+    }
+}
+
+/// Parse `key=value` arguments against the schema.
+pub fn parse_all(
+    schema: &BTreeMap<String, FieldSpec>,
+    args: &[String],
+) -> Result<BTreeMap<String, Value>> {
+    let mut fields = BTreeMap::new();
+    for arg in args {
+        let (key, raw) = parse_assignment(arg)?;
+        let value = coerce(schema, &key, &raw)?;
+        fields.insert(key, value);
+    }
+    Ok(fields)
+}
+
+/// Fail if a field the schema marks as required is missing.
+pub fn check_required(
+    schema: &BTreeMap<String, FieldSpec>,
+    fields: &BTreeMap<String, Value>,
+) -> Result<()> {
+    let missing: Vec<&str> = schema
+        .iter()
+        .filter(|(key, spec)| spec.required && !fields.contains_key(*key))
+        .map(|(key, _)| key.as_str())
+        .collect();
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "Missing required field(s): {} (set with --field key=value)",
+            missing.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Render a field value for display, without JSON quoting for strings.
+pub fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Render fields as `k=v` pairs separated by spaces.
+pub fn display_all(fields: &BTreeMap<String, Value>) -> String {
+    fields
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, display(v)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// True if `fields` has every `key=value` pair in `filters`. Numbers compare
+/// numerically, so `points=3` matches a stored `3.0`.
+pub fn matches(fields: &BTreeMap<String, Value>, filters: &[(String, String)]) -> bool {
+    filters.iter().all(|(key, expected)| match fields.get(key) {
+        Some(Value::Number(n)) => expected
+            .parse::<f64>()
+            .is_ok_and(|e| n.as_f64() == Some(e)),
+        Some(value) => display(value) == *expected,
+        None => false,
+    })
+}
+
+/// Store whole numbers as integers so `3` round-trips as `3`, not `3.0`.
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        Value::from(n as i64)
+    } else {
+        Value::from(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> BTreeMap<String, FieldSpec> {
+        let spec = |kind, required, values: &[&str]| FieldSpec {
+            kind,
+            required,
+            values: values.iter().map(|v| v.to_string()).collect(),
+            description: None,
+        };
+        BTreeMap::from([
+            ("customer".to_string(), spec(FieldType::String, true, &[])),
+            ("points".to_string(), spec(FieldType::Number, false, &[])),
+            (
+                "risk".to_string(),
+                spec(FieldType::Enum, false, &["low", "high"]),
+            ),
+            ("due".to_string(), spec(FieldType::Date, false, &[])),
+        ])
+    }
+
+    #[test]
+    fn test_coerce_against_schema() {
+        let schema = schema();
+        assert_eq!(coerce(&schema, "customer", "42").unwrap(), Value::from("42"));
+        assert_eq!(coerce(&schema, "points", "3").unwrap(), Value::from(3));
+        assert_eq!(coerce(&schema, "points", "2.5").unwrap(), Value::from(2.5));
+        assert!(coerce(&schema, "points", "lots").is_err());
+        assert_eq!(coerce(&schema, "risk", "low").unwrap(), Value::from("low"));
+        assert!(coerce(&schema, "risk", "medium").is_err());
+        assert!(coerce(&schema, "due", "2026-03-01").is_ok());
+        assert!(coerce(&schema, "due", "next week").is_err());
+    }
+
+    #[test]
+    fn test_coerce_undeclared_keeps_strings() {
+        let schema = BTreeMap::new();
+        assert_eq!(
+            coerce(&schema, "ticket", "00123").unwrap(),
+            Value::from("00123")
+        );
+        assert_eq!(coerce(&schema, "build", "1e3").unwrap(), Value::from("1e3"));
+        assert_eq!(
+            coerce(&schema, "ticket", "ENG-12").unwrap(),
+            Value::from("ENG-12")
+        );
+    }
+
+    #[test]
+    fn test_parse_all_and_required() {
+        let schema = schema();
+        assert!(parse_assignment("novalue").is_err());
+        assert!(parse_assignment("=x").is_err());
+
+        let fields = parse_all(&schema, &["points=5".to_string()]).unwrap();
+        let err = check_required(&schema, &fields).unwrap_err();
+        assert!(err.to_string().contains("customer"));
+
+        let fields = parse_all(
+            &schema,
+            &["customer=acme".to_string(), "points=5".to_string()],
+        )
+        .unwrap();
+        assert!(check_required(&schema, &fields).is_ok());
+        assert_eq!(display_all(&fields), "customer=acme points=5");
+    }
+
+    #[test]
+    fn test_matches() {
+        let fields = BTreeMap::from([
+            ("customer".to_string(), Value::from("acme")),
+            ("points".to_string(), Value::from(3)),
+        ]);
+        let filter = |k: &str, v: &str| vec![(k.to_string(), v.to_string())];
+        assert!(matches(&fields, &filter("customer", "acme")));
+        assert!(matches(&fields, &filter("points", "3.0")));
+        assert!(!matches(&fields, &filter("customer", "globex")));
+        assert!(!matches(&fields, &filter("risk", "low")));
+        assert!(matches(&fields, &[]));
+    }
+}
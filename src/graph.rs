@@ -1,6 +1,6 @@
 use chrono::{Duration, Utc};
 use serde::{Deserialize, Deserializer, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// A loop edge: a conditional back-edge that can re-activate upstream tasks on completion.
 /// Loop edges are NOT blocking edges — they are separate from `blocked_by` and don't affect
@@ -29,6 +29,29 @@ pub enum LoopGuard {
     IterationLessThan(u32),
     /// Always loop (up to max_iterations)
     Always,
+    /// Loop if the latest reward for a task compares true against `value`
+    RewardValue {
+        task: String,
+        op: crate::loop_guard::Comparison,
+        value: f64,
+    },
+    /// Loop if a file exists (path relative to the project root)
+    ArtifactExists { path: String },
+    /// Loop if a file does not exist (path relative to the project root)
+    ArtifactMissing { path: String },
+    /// Loop if a file's content hash differs from the one seen at the last check
+    ArtifactChanged {
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        last_hash: Option<String>,
+    },
+    /// Loop if a shell command run in the project root exits with `code`
+    /// (None = any non-zero exit)
+    ShellExit {
+        command: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code: Option<i32>,
+    },
 }
 
 /// Parse a human-readable duration string like "30s", "5m", "1h", "24h" into seconds.
@@ -53,6 +76,176 @@ pub fn parse_delay(s: &str) -> Option<u64> {
     }
 }
 
+/// A dependency group (OR-dependency or quorum join): the task may start once
+/// `need` of these tasks are done, rather than waiting for all of them.
+///
+/// Members are also listed in the task's `blocked_by`, so edges, cycles and
+/// impact analysis see them as ordinary dependencies; the group only changes
+/// when they stop blocking. If every member is terminal without reaching the
+/// quorum, the group stops blocking too, as failed blockers do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepGroup {
+    pub tasks: Vec<String>,
+    /// How many members must be done (1 = any-of)
+    #[serde(default = "default_need")]
+    pub need: u32,
+    /// Abandon the members still pending once the quorum is reached
+    #[serde(default, skip_serializing_if = "is_bool_false")]
+    pub abandon_rest: bool,
+}
+
+fn default_need() -> u32 {
+    1
+}
+
+impl DepGroup {
+    /// Build a group, checking that the quorum is reachable
+    pub fn new(tasks: Vec<String>, need: u32, abandon_rest: bool) -> anyhow::Result<Self> {
+        let mut seen = std::collections::HashSet::new();
+        if let Some(dup) = tasks.iter().find(|t| !seen.insert(t.as_str())) {
+            anyhow::bail!("Task '{}' is listed twice in a dependency group", dup);
+        }
+        if tasks.len() < 2 {
+            anyhow::bail!("A dependency group needs at least two tasks");
+        }
+        if need == 0 || need as usize > tasks.len() {
+            anyhow::bail!(
+                "Quorum must be between 1 and {} (the group size), got {}",
+                tasks.len(),
+                need
+            );
+        }
+        Ok(Self {
+            tasks,
+            need,
+            abandon_rest,
+        })
+    }
+
+    /// Number of members that are done, given each member's status
+    /// (`None` for a missing task)
+    pub fn done_count(&self, status: impl Fn(&str) -> Option<Status>) -> usize {
+        self.tasks
+            .iter()
+            .filter(|id| status(id) == Some(Status::Done))
+            .count()
+    }
+
+    /// Whether the group no longer blocks: the quorum is done, or every
+    /// member is terminal (missing tasks count as terminal)
+    pub fn is_met(&self, status: impl Fn(&str) -> Option<Status>) -> bool {
+        self.done_count(&status) >= self.need as usize
+            || self
+                .tasks
+                .iter()
+                .all(|id| status(id).is_none_or(|s| s.is_terminal()))
+    }
+}
+
+impl std::fmt::Display for DepGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.need == 1 {
+            write!(f, "any of {}", self.tasks.join(", "))?;
+        } else {
+            write!(f, "{} of {}", self.need, self.tasks.join(", "))?;
+        }
+        if self.abandon_rest {
+            write!(f, " (abandon rest)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Which outcomes of a dependency let its dependent start.
+///
+/// By default any terminal status unblocks, so a task runs after its blocker
+/// fails just as after it succeeds. Conditional edges instead skip the
+/// dependent when the blocker ends the wrong way (see [`skip_unreachable`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DepCondition {
+    /// Any terminal status
+    #[default]
+    Always,
+    /// Only done; a failed or abandoned blocker skips the dependent
+    OnSuccess,
+    /// Only failed, for compensation and rollback tasks; a blocker that is
+    /// done or abandoned skips the dependent
+    OnFailure,
+}
+
+impl DepCondition {
+    /// Whether a blocker with `status` lets the dependent start
+    pub fn is_met(self, status: Status) -> bool {
+        match self {
+            DepCondition::Always => status.is_terminal(),
+            DepCondition::OnSuccess => status == Status::Done,
+            DepCondition::OnFailure => status == Status::Failed,
+        }
+    }
+
+    /// Whether a blocker with `status` has ended without meeting the condition
+    pub fn is_unreachable(self, status: Status) -> bool {
+        status.is_terminal() && !self.is_met(status)
+    }
+}
+
+impl std::fmt::Display for DepCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DepCondition::Always => write!(f, "always"),
+            DepCondition::OnSuccess => write!(f, "on_success"),
+            DepCondition::OnFailure => write!(f, "on_failure"),
+        }
+    }
+}
+
+/// Parse the task list of a dependency group: "a,b,c" (any of) or
+/// "2:a,b,c" (quorum of 2).
+pub fn parse_dep_group(s: &str, abandon_rest: bool) -> anyhow::Result<DepGroup> {
+    let (need, list) = match s.split_once(':') {
+        Some((need, list)) if need.trim().chars().all(|c| c.is_ascii_digit()) => {
+            let need = need.trim().parse().map_err(|_| {
+                anyhow::anyhow!("Invalid quorum in '{}'. Use format: [<k>:]<id>,<id>,...", s)
+            })?;
+            (need, list)
+        }
+        _ => (1, s),
+    };
+    let tasks: Vec<String> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+        .collect();
+    DepGroup::new(tasks, need, abandon_rest)
+}
+
+/// Parse a resource requirement like "gpu-box" or "gpu-box:2" into
+/// (resource id, amount). The amount defaults to 1 and must be positive.
+pub fn parse_resource_requirement(s: &str) -> anyhow::Result<(String, f64)> {
+    let s = s.trim();
+    let (id, amount) = match s.rsplit_once(':') {
+        Some((id, amount)) => {
+            let amount: f64 = amount.trim().parse().map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid resource amount in '{}'. Use format: <resource>[:<amount>]",
+                    s
+                )
+            })?;
+            (id.trim(), amount)
+        }
+        None => (s, 1.0),
+    };
+    if id.is_empty() {
+        anyhow::bail!("Resource requirement '{}' is missing a resource id", s);
+    }
+    if !amount.is_finite() || amount <= 0.0 {
+        anyhow::bail!("Resource amount in '{}' must be greater than zero", s);
+    }
+    Ok((id.to_string(), amount))
+}
+
 /// A log entry for tracking progress/notes on a task
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LogEntry {
@@ -82,6 +275,8 @@ pub enum Status {
     Blocked,
     Failed,
     Abandoned,
+    /// Finished by an agent, waiting for a human to `wg approve` or `wg reject` it
+    InReview,
 }
 
 impl std::fmt::Display for Status {
@@ -93,11 +288,16 @@ impl std::fmt::Display for Status {
             Status::Blocked => write!(f, "blocked"),
             Status::Failed => write!(f, "failed"),
             Status::Abandoned => write!(f, "abandoned"),
+            Status::InReview => write!(f, "in-review"),
         }
     }
 }
 
 /// Custom deserializer that maps legacy "pending-review" status to Done.
+///
+/// The graph itself is upgraded by the `legacy-nodes` step in
+/// [`crate::schema::MIGRATIONS`]; this fallback covers tasks read from
+/// elsewhere, such as `archive.jsonl`.
 impl<'de> serde::Deserialize<'de> for Status {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -111,6 +311,7 @@ impl<'de> serde::Deserialize<'de> for Status {
             "blocked" => Ok(Status::Blocked),
             "failed" => Ok(Status::Failed),
             "abandoned" => Ok(Status::Abandoned),
+            "in-review" => Ok(Status::InReview),
             // Migration: pending-review is treated as done
             "pending-review" => Ok(Status::Done),
             other => Err(serde::de::Error::unknown_variant(
@@ -122,12 +323,62 @@ impl<'de> serde::Deserialize<'de> for Status {
                     "blocked",
                     "failed",
                     "abandoned",
+                    "in-review",
                 ],
             )),
         }
     }
 }
 
+/// Task priority, used to order ready work for dispatch.
+///
+/// Variants are declared lowest-first so the derived `Ord` ranks
+/// `Critical > High > Normal > Low`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "low"),
+            Priority::Normal => write!(f, "normal"),
+            Priority::High => write!(f, "high"),
+            Priority::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "normal" => Ok(Priority::Normal),
+            "high" => Ok(Priority::High),
+            "critical" | "urgent" => Ok(Priority::Critical),
+            other => anyhow::bail!(
+                "Unknown priority '{}'. Must be: critical, high, normal, low",
+                other
+            ),
+        }
+    }
+}
+
+impl Priority {
+    fn is_normal(&self) -> bool {
+        *self == Priority::Normal
+    }
+}
+
 impl Status {
     /// Whether this status is terminal — the task will not progress further
     /// without explicit intervention (retry, reopen, etc.).
@@ -143,7 +394,8 @@ impl Status {
 ///
 /// Custom `Deserialize` handles migration from the old `identity` field
 /// (`{"role_id": "...", "objective_id": "..."}`) to the new `agent` field
-/// (content-hash string).
+/// (content-hash string), for tasks that don't go through
+/// [`crate::schema::MIGRATIONS`] (e.g. archived ones).
 #[derive(Debug, Clone, PartialEq, Serialize, Default)]
 pub struct Task {
     pub id: String,
@@ -161,8 +413,19 @@ pub struct Task {
     pub blocks: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub blocked_by: Vec<String>,
+    /// Dependency groups that relax `blocked_by` for their members: the task
+    /// needs only a quorum of each group done, instead of every member terminal
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dep_groups: Vec<DepGroup>,
+    /// Conditions on `blocked_by` entries outside dependency groups; blockers
+    /// not listed here are [`DepCondition::Always`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dep_conditions: BTreeMap<String, DepCondition>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub requires: Vec<String>,
+    /// Units of each required resource held while in progress (default 1 per resource)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resource_amounts: BTreeMap<String, f64>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
     /// Required skills/capabilities for this task
@@ -177,12 +440,19 @@ pub struct Task {
     /// Actual produced artifacts (paths/references)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub artifacts: Vec<String>,
+    /// Stored snapshots of artifacts, by path (`wg artifact add --snapshot`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub artifact_snapshots: BTreeMap<String, crate::artifacts::ArtifactSnapshot>,
     /// Shell command to execute for this task (optional, for wg exec)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exec: Option<String>,
     /// Task is not ready until this timestamp (ISO 8601 / RFC 3339)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub not_before: Option<String>,
+    /// Task should be done by this timestamp (ISO 8601 / RFC 3339); drives slack
+    /// in `wg at-risk`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<String>,
     /// Timestamp when the task was created (ISO 8601 / RFC 3339)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<String>,
@@ -201,6 +471,19 @@ pub struct Task {
     /// Maximum number of retries allowed (None = unlimited)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_retries: Option<u32>,
+    /// Automatic retry policy (overrides tag policies from config)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<crate::retry::RetryPolicy>,
+    /// One record per failed attempt, for retry classification and history
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub retry_history: Vec<crate::retry::FailureRecord>,
+    /// Tasks to generate from this task's output once it is done
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fan_out: Option<crate::fan_out::FanOut>,
+    /// Named values recorded with `wg output`, readable by dependents as
+    /// `{{deps.<task>.<key>}}`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outputs: BTreeMap<String, serde_json::Value>,
     /// Reason for failure or abandonment
     #[serde(skip_serializing_if = "Option::is_none")]
     pub failure_reason: Option<String>,
@@ -223,10 +506,28 @@ pub struct Task {
     /// Set by loop edges with a delay — prevents immediate dispatch after re-activation.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ready_after: Option<String>,
+    /// Cron recurrence: the coordinator reopens or copies the task at each fire
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<crate::schedule::Schedule>,
     /// When true, the task is paused and will not be dispatched by the coordinator.
     /// The task retains its status and loop state; `wg resume` clears this flag.
     #[serde(default, skip_serializing_if = "is_bool_false")]
     pub paused: bool,
+    /// Dispatch priority: ready tasks are handed out highest-priority first
+    #[serde(default, skip_serializing_if = "Priority::is_normal")]
+    pub priority: Priority,
+    /// Optional business value, used as a tiebreak between equal-priority tasks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    /// Parent task (epic) this task belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Mark this task done automatically once all its children are done or abandoned
+    #[serde(default, skip_serializing_if = "is_bool_false")]
+    pub auto_complete: bool,
+    /// Custom fields (`wg add --field k=v`), typed by the `[fields]` schema in config.toml
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, serde_json::Value>,
 }
 
 /// Legacy identity format: `{"role_id": "...", "objective_id": "..."}`.
@@ -255,8 +556,14 @@ struct TaskHelper {
     #[serde(default)]
     blocked_by: Vec<String>,
     #[serde(default)]
+    dep_groups: Vec<DepGroup>,
+    #[serde(default)]
+    dep_conditions: BTreeMap<String, DepCondition>,
+    #[serde(default)]
     requires: Vec<String>,
     #[serde(default)]
+    resource_amounts: BTreeMap<String, f64>,
+    #[serde(default)]
     tags: Vec<String>,
     #[serde(default)]
     skills: Vec<String>,
@@ -267,10 +574,14 @@ struct TaskHelper {
     #[serde(default)]
     artifacts: Vec<String>,
     #[serde(default)]
+    artifact_snapshots: BTreeMap<String, crate::artifacts::ArtifactSnapshot>,
+    #[serde(default)]
     exec: Option<String>,
     #[serde(default)]
     not_before: Option<String>,
     #[serde(default)]
+    deadline: Option<String>,
+    #[serde(default)]
     created_at: Option<String>,
     #[serde(default)]
     started_at: Option<String>,
@@ -283,6 +594,14 @@ struct TaskHelper {
     #[serde(default)]
     max_retries: Option<u32>,
     #[serde(default)]
+    retry_policy: Option<crate::retry::RetryPolicy>,
+    #[serde(default)]
+    retry_history: Vec<crate::retry::FailureRecord>,
+    #[serde(default)]
+    fan_out: Option<crate::fan_out::FanOut>,
+    #[serde(default)]
+    outputs: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
     failure_reason: Option<String>,
     #[serde(default)]
     model: Option<String>,
@@ -297,7 +616,19 @@ struct TaskHelper {
     #[serde(default)]
     ready_after: Option<String>,
     #[serde(default)]
+    schedule: Option<crate::schedule::Schedule>,
+    #[serde(default)]
     paused: bool,
+    #[serde(default)]
+    priority: Priority,
+    #[serde(default)]
+    value: Option<f64>,
+    #[serde(default)]
+    parent: Option<String>,
+    #[serde(default)]
+    auto_complete: bool,
+    #[serde(default)]
+    fields: BTreeMap<String, serde_json::Value>,
     /// Old format: inline identity object. Migrated to `agent` hash on read.
     #[serde(default)]
     identity: Option<LegacyIdentity>,
@@ -329,20 +660,29 @@ impl<'de> Deserialize<'de> for Task {
             estimate: helper.estimate,
             blocks: helper.blocks,
             blocked_by: helper.blocked_by,
+            dep_groups: helper.dep_groups,
+            dep_conditions: helper.dep_conditions,
             requires: helper.requires,
+            resource_amounts: helper.resource_amounts,
             tags: helper.tags,
             skills: helper.skills,
             inputs: helper.inputs,
             deliverables: helper.deliverables,
             artifacts: helper.artifacts,
+            artifact_snapshots: helper.artifact_snapshots,
             exec: helper.exec,
             not_before: helper.not_before,
+            deadline: helper.deadline,
             created_at: helper.created_at,
             started_at: helper.started_at,
             completed_at: helper.completed_at,
             log: helper.log,
             retry_count: helper.retry_count,
             max_retries: helper.max_retries,
+            retry_policy: helper.retry_policy,
+            retry_history: helper.retry_history,
+            fan_out: helper.fan_out,
+            outputs: helper.outputs,
             failure_reason: helper.failure_reason,
             model: helper.model,
             verify: helper.verify,
@@ -350,7 +690,13 @@ impl<'de> Deserialize<'de> for Task {
             loops_to: helper.loops_to,
             loop_iteration: helper.loop_iteration,
             ready_after: helper.ready_after,
+            schedule: helper.schedule,
             paused: helper.paused,
+            priority: helper.priority,
+            value: helper.value,
+            parent: helper.parent,
+            auto_complete: helper.auto_complete,
+            fields: helper.fields,
         })
     }
 }
@@ -491,6 +837,14 @@ impl WorkGraph {
         self.nodes.values()
     }
 
+    /// Remove a node without touching references to it from other nodes.
+    ///
+    /// Used when replaying stored state, where the referring nodes are
+    /// recorded separately.
+    pub(crate) fn take_node(&mut self, id: &str) -> Option<Node> {
+        self.nodes.remove(id)
+    }
+
     /// Iterate over all tasks in the graph, skipping resource nodes.
     pub fn tasks(&self) -> impl Iterator<Item = &Task> {
         self.nodes.values().filter_map(|n| match n {
@@ -510,22 +864,71 @@ impl WorkGraph {
     /// Remove a node by ID, returning the removed node if it existed.
     ///
     /// Also cleans up all references to the removed node from other tasks
-    /// (`blocked_by`, `blocks`, `requires`, and `loops_to` targets).
+    /// (`blocked_by`, `dep_groups`, `dep_conditions`, `blocks`, `requires`, and
+    /// `loops_to` targets).
     pub fn remove_node(&mut self, id: &str) -> Option<Node> {
         let removed = self.nodes.remove(id);
         if removed.is_some() {
             for node in self.nodes.values_mut() {
                 if let Node::Task(task) = node {
                     task.blocked_by.retain(|dep| dep != id);
+                    for group in &mut task.dep_groups {
+                        group.tasks.retain(|dep| dep != id);
+                    }
+                    task.dep_groups.retain(|group| !group.tasks.is_empty());
+                    task.dep_conditions.remove(id);
                     task.blocks.retain(|dep| dep != id);
                     task.requires.retain(|dep| dep != id);
                     task.loops_to.retain(|edge| edge.target != id);
+                    if task.parent.as_deref() == Some(id) {
+                        task.parent = None;
+                    }
                 }
             }
         }
         removed
     }
 
+    /// Direct children of a task (tasks whose `parent` is `id`), sorted by ID.
+    pub fn children(&self, id: &str) -> Vec<&Task> {
+        let mut children: Vec<&Task> = self
+            .tasks()
+            .filter(|t| t.parent.as_deref() == Some(id))
+            .collect();
+        children.sort_by(|a, b| a.id.cmp(&b.id));
+        children
+    }
+
+    /// All descendants of a task (children, grandchildren, ...), each once,
+    /// depth-first in ID order.
+    pub fn descendants(&self, id: &str) -> Vec<&Task> {
+        let mut seen = std::collections::HashSet::from([id.to_string()]);
+        let mut out = Vec::new();
+        let mut stack: Vec<&Task> = self.children(id).into_iter().rev().collect();
+        while let Some(task) = stack.pop() {
+            if !seen.insert(task.id.clone()) {
+                continue;
+            }
+            out.push(task);
+            stack.extend(self.children(&task.id).into_iter().rev());
+        }
+        out
+    }
+
+    /// Whether making `parent` the parent of `child` would create a cycle
+    /// (i.e. `parent` is `child` itself or one of its descendants).
+    pub fn would_create_parent_cycle(&self, child: &str, parent: &str) -> bool {
+        let mut seen = std::collections::HashSet::new();
+        let mut current = Some(parent);
+        while let Some(id) = current {
+            if id == child || !seen.insert(id) {
+                return true;
+            }
+            current = self.get_task(id).and_then(|t| t.parent.as_deref());
+        }
+        false
+    }
+
     /// Return the total number of nodes (tasks + resources) in the graph.
     pub fn len(&self) -> usize {
         self.nodes.len()
@@ -537,18 +940,254 @@ impl WorkGraph {
     }
 }
 
-/// Reward a loop guard condition against the current graph state.
-fn reward_guard(guard: &Option<LoopGuard>, graph: &WorkGraph) -> bool {
-    match guard {
-        None | Some(LoopGuard::Always) => true,
-        // IterationLessThan is checked in reward_loop_edges() where the
-        // target task's loop_iteration is available.
-        Some(LoopGuard::IterationLessThan(_)) => true,
-        Some(LoopGuard::TaskStatus { task, status }) => graph
-            .get_task(task)
-            .map(|t| t.status == *status)
-            .unwrap_or(false),
+/// Auto-complete the ancestors of `child_id` after it reaches a terminal status.
+///
+/// Walks up the parent chain. Each parent with `auto_complete` set whose
+/// children are all done or abandoned (at least one done) is marked done;
+/// a failed child holds the parent open until it is retried or abandoned.
+///
+/// Returns the IDs of the parents that were completed, nearest first.
+pub fn complete_parents(graph: &mut WorkGraph, child_id: &str) -> Vec<String> {
+    let mut completed = Vec::new();
+    let mut current = graph.get_task(child_id).and_then(|t| t.parent.clone());
+    while let Some(parent_id) = current.take() {
+        let Some(parent) = graph.get_task(&parent_id) else {
+            break;
+        };
+        if !parent.auto_complete || parent.status.is_terminal() {
+            break;
+        }
+        let children = graph.children(&parent_id);
+        let settled = children
+            .iter()
+            .all(|c| matches!(c.status, Status::Done | Status::Abandoned));
+        let any_done = children.iter().any(|c| c.status == Status::Done);
+        if !settled || !any_done || completed.contains(&parent_id) {
+            break;
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let parent = graph.get_task_mut(&parent_id).expect("parent looked up above");
+        parent.status = Status::Done;
+        parent.completed_at = Some(now.clone());
+        parent.log.push(LogEntry {
+            timestamp: now,
+            actor: None,
+            message: "Auto-completed: all child tasks finished".to_string(),
+        });
+        current = parent.parent.clone();
+        completed.push(parent_id);
     }
+    completed
+}
+
+/// Abandon the members left pending once `done_id` completes the quorum of a
+/// dependency group with `abandon_rest` set.
+///
+/// A pending member is only abandoned if nothing else still needs it: it must
+/// not be an ordinary blocker of any unfinished task, and every group it
+/// belongs to must have reached its quorum. Only members nobody has started
+/// are abandoned: an in-progress member has an agent working on it, so it is
+/// left to finish and just gets a log note saying it is no longer needed.
+///
+/// Returns the IDs of the abandoned tasks.
+pub fn abandon_quorum_siblings(graph: &mut WorkGraph, done_id: &str) -> Vec<String> {
+    let status = |graph: &WorkGraph, id: &str| graph.get_task(id).map(|t| t.status);
+    let mut quorums: Vec<(String, Vec<String>)> = Vec::new();
+    for task in graph.tasks() {
+        if task.status.is_terminal() {
+            continue;
+        }
+        for group in &task.dep_groups {
+            if group.abandon_rest
+                && group.tasks.iter().any(|t| t == done_id)
+                && group.done_count(|id| status(graph, id)) >= group.need as usize
+            {
+                quorums.push((task.id.clone(), group.tasks.clone()));
+            }
+        }
+    }
+
+    let mut abandoned = Vec::new();
+    for (dependent, members) in quorums {
+        for member in members {
+            let pending = status(graph, &member).is_some_and(|s| !s.is_terminal());
+            if !pending || still_needed(graph, &member) {
+                continue;
+            }
+            let Some(task) = graph.get_task_mut(&member) else {
+                continue;
+            };
+            if task.status == Status::InProgress {
+                let note = format!(
+                    "No longer needed: '{}' completed the quorum for '{}'",
+                    done_id, dependent
+                );
+                if !task.log.iter().any(|entry| entry.message == note) {
+                    task.log.push(LogEntry {
+                        timestamp: Utc::now().to_rfc3339(),
+                        actor: None,
+                        message: note,
+                    });
+                }
+                continue;
+            }
+            task.status = Status::Abandoned;
+            task.failure_reason = Some(format!("quorum for '{}' reached", dependent));
+            task.log.push(LogEntry {
+                timestamp: Utc::now().to_rfc3339(),
+                actor: None,
+                message: format!(
+                    "Task abandoned: '{}' completed the quorum for '{}'",
+                    done_id, dependent
+                ),
+            });
+            abandoned.push(member);
+        }
+    }
+    abandoned
+}
+
+/// Whether an unfinished task still waits on `id`: as an ordinary blocker,
+/// or through a dependency group that hasn't reached its quorum
+fn still_needed(graph: &WorkGraph, id: &str) -> bool {
+    graph.tasks().any(|task| {
+        if task.status.is_terminal() {
+            return false;
+        }
+        let groups: Vec<&DepGroup> = task
+            .dep_groups
+            .iter()
+            .filter(|g| g.tasks.iter().any(|t| t == id))
+            .collect();
+        if groups.is_empty() {
+            return task.blocked_by.iter().any(|b| b == id);
+        }
+        groups
+            .iter()
+            .any(|g| g.done_count(|t| graph.get_task(t).map(|t| t.status)) < g.need as usize)
+    })
+}
+
+/// The condition on one of a task's blockers. Members of dependency groups
+/// and blockers without an entry in `dep_conditions` are
+/// [`DepCondition::Always`].
+pub fn dep_condition(task: &Task, blocker: &str) -> DepCondition {
+    if task
+        .dep_groups
+        .iter()
+        .any(|g| g.tasks.iter().any(|t| t == blocker))
+    {
+        return DepCondition::Always;
+    }
+    task.dep_conditions
+        .get(blocker)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// The first conditional blocker of `task` that has ended without meeting
+/// its condition, with the condition and the blocker's status. `status`
+/// gives a blocker's status, `None` if it doesn't exist.
+pub fn unreachable_condition(
+    task: &Task,
+    status: impl Fn(&str) -> Option<Status>,
+) -> Option<(&str, DepCondition, Status)> {
+    task.blocked_by.iter().find_map(|blocker| {
+        let condition = dep_condition(task, blocker);
+        let blocker_status = status(blocker)?;
+        condition.is_unreachable(blocker_status).then_some((
+            blocker.as_str(),
+            condition,
+            blocker_status,
+        ))
+    })
+}
+
+/// Why a task was skipped, given the blocker whose outcome ruled it out
+fn skip_reason(blocker: &str, status: Status) -> String {
+    let outcome = match status {
+        Status::Done => "succeeded",
+        Status::Failed => "failed",
+        _ => "was abandoned",
+    };
+    format!("{}{}", skip_reason_prefix(blocker), outcome)
+}
+
+fn skip_reason_prefix(blocker: &str) -> String {
+    format!("skipped: dependency '{}' ", blocker)
+}
+
+/// Skip the open tasks whose conditional dependencies can no longer be met:
+/// an `on_success` blocker that failed or was abandoned, or an `on_failure`
+/// blocker that ended any other way. Skipped tasks are abandoned with a
+/// "skipped: ..." reason, so skips propagate down `on_success` chains.
+///
+/// Returns the IDs of the skipped tasks.
+pub fn skip_unreachable(graph: &mut WorkGraph) -> Vec<String> {
+    let mut skipped = Vec::new();
+    loop {
+        let found: Vec<(String, String)> = graph
+            .tasks()
+            .filter(|t| matches!(t.status, Status::Open | Status::Blocked))
+            .filter_map(|t| {
+                let (blocker, _, status) =
+                    unreachable_condition(t, |id| graph.get_task(id).map(|b| b.status))?;
+                Some((t.id.clone(), skip_reason(blocker, status)))
+            })
+            .collect();
+        if found.is_empty() {
+            return skipped;
+        }
+        for (id, reason) in found {
+            if let Some(task) = graph.get_task_mut(&id) {
+                task.status = Status::Abandoned;
+                task.log.push(LogEntry {
+                    timestamp: Utc::now().to_rfc3339(),
+                    actor: None,
+                    message: format!("Task {}", reason),
+                });
+                task.failure_reason = Some(reason);
+                skipped.push(id);
+            }
+        }
+    }
+}
+
+/// Reopen the tasks [`skip_unreachable`] skipped because of `id`, and those
+/// skipped in turn because of them, after `id` is reopened for another try.
+///
+/// Returns the IDs of the reopened tasks.
+pub fn unskip_dependents(graph: &mut WorkGraph, id: &str) -> Vec<String> {
+    let mut reopened = Vec::new();
+    let mut queue = vec![id.to_string()];
+    while let Some(blocker) = queue.pop() {
+        let prefix = skip_reason_prefix(&blocker);
+        let skipped: Vec<String> = graph
+            .tasks()
+            .filter(|t| {
+                t.status == Status::Abandoned
+                    && t.failure_reason
+                        .as_deref()
+                        .is_some_and(|r| r.starts_with(&prefix))
+            })
+            .map(|t| t.id.clone())
+            .collect();
+        for task_id in skipped {
+            if let Some(task) = graph.get_task_mut(&task_id) {
+                task.status = Status::Open;
+                task.failure_reason = None;
+                task.log.push(LogEntry {
+                    timestamp: Utc::now().to_rfc3339(),
+                    actor: None,
+                    message: format!("Task reopened: '{}' is being retried", id),
+                });
+            }
+            queue.push(task_id.clone());
+            reopened.push(task_id);
+        }
+    }
+    reopened
 }
 
 /// Reward loop edges after a task transitions to Done.
@@ -563,7 +1202,20 @@ fn reward_guard(guard: &Option<LoopGuard>, graph: &WorkGraph) -> bool {
 ///    blockers are no longer all Done (since the target was just re-opened).
 ///
 /// Returns the list of task IDs that were re-activated.
+///
+/// Reward, artifact and shell guards need readings taken outside the graph
+/// and never fire here; use [`reward_loop_edges_with`] to pass them in.
 pub fn reward_loop_edges(graph: &mut WorkGraph, source_id: &str) -> Vec<String> {
+    reward_loop_edges_with(graph, source_id, &crate::loop_guard::Readings::default())
+}
+
+/// [`reward_loop_edges`] with the `readings` of the source task's reward,
+/// artifact and shell guards, taken before the graph lock.
+pub fn reward_loop_edges_with(
+    graph: &mut WorkGraph,
+    source_id: &str,
+    readings: &crate::loop_guard::Readings,
+) -> Vec<String> {
     // Check if the source task signaled convergence — skip all loop firing
     if let Some(task) = graph.get_task(source_id)
         && task.tags.contains(&"converged".to_string()) {
@@ -571,17 +1223,28 @@ pub fn reward_loop_edges(graph: &mut WorkGraph, source_id: &str) -> Vec<String>
         }
 
     // Collect loop edges from the source task (clone to avoid borrow issues)
-    let loop_edges: Vec<LoopEdge> = match graph.get_task(source_id) {
+    let mut loop_edges: Vec<LoopEdge> = match graph.get_task(source_id) {
         Some(task) => task.loops_to.clone(),
         None => return vec![],
     };
 
     let mut reactivated = Vec::new();
 
-    for edge in &loop_edges {
+    for (index, edge) in loop_edges.iter_mut().enumerate() {
         // 1. Check guard condition
-        if !reward_guard(&edge.guard, graph) {
-            continue;
+        if let Some(guard) = edge.guard.as_mut() {
+            let before = guard.clone();
+            let passed = crate::loop_guard::evaluate(guard, graph, readings);
+            // Persist state the guard recorded (e.g. the last artifact hash)
+            if *guard != before
+                && let Some(source) = graph.get_task_mut(source_id)
+                && let Some(stored) = source.loops_to.get_mut(index)
+            {
+                stored.guard = Some(guard.clone());
+            }
+            if !passed {
+                continue;
+            }
         }
 
         // Also check IterationLessThan guard specifically
@@ -852,6 +1515,7 @@ mod tests {
         assert!(Status::Done.is_terminal());
         assert!(Status::Failed.is_terminal());
         assert!(Status::Abandoned.is_terminal());
+        assert!(!Status::InReview.is_terminal());
     }
 
     #[test]
@@ -975,6 +1639,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_priority_ordering_and_parse() {
+        assert!(Priority::Critical > Priority::High);
+        assert!(Priority::High > Priority::Normal);
+        assert!(Priority::Normal > Priority::Low);
+        assert_eq!("HIGH".parse::<Priority>().unwrap(), Priority::High);
+        assert_eq!("urgent".parse::<Priority>().unwrap(), Priority::Critical);
+        assert!("soon".parse::<Priority>().is_err());
+    }
+
+    #[test]
+    fn test_priority_serialization_skips_normal() {
+        let task = make_task("t1", "Test task");
+        let json = serde_json::to_string(&Node::Task(task)).unwrap();
+        assert!(!json.contains("priority"));
+        assert!(!json.contains("value"));
+
+        let mut task = make_task("t1", "Test task");
+        task.priority = Priority::High;
+        task.value = Some(2.5);
+        let json = serde_json::to_string(&Node::Task(task)).unwrap();
+        assert!(json.contains("\"priority\":\"high\""));
+        let node: Node = serde_json::from_str(&json).unwrap();
+        match node {
+            Node::Task(t) => {
+                assert_eq!(t.priority, Priority::High);
+                assert_eq!(t.value, Some(2.5));
+            }
+            _ => panic!("Expected Task"),
+        }
+    }
+
+    #[test]
+    fn test_parse_resource_requirement() {
+        assert_eq!(
+            parse_resource_requirement("gpu-box").unwrap(),
+            ("gpu-box".to_string(), 1.0)
+        );
+        assert_eq!(
+            parse_resource_requirement("gpu-box:2").unwrap(),
+            ("gpu-box".to_string(), 2.0)
+        );
+        assert_eq!(
+            parse_resource_requirement("api-quota:0.5").unwrap(),
+            ("api-quota".to_string(), 0.5)
+        );
+        assert!(parse_resource_requirement("gpu-box:0").is_err());
+        assert!(parse_resource_requirement("gpu-box:lots").is_err());
+        assert!(parse_resource_requirement(":2").is_err());
+    }
+
     #[test]
     fn test_timestamp_fields_serialization() {
         let mut task = make_task("t1", "Test task");
@@ -1494,6 +2209,50 @@ mod tests {
         assert_eq!(tgt.loop_iteration, 1);
     }
 
+    #[test]
+    fn test_reward_loop_edges_artifact_changed_records_hash() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join(".workgraph");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(tmp.path().join("draft.md"), "first draft").unwrap();
+
+        let mut graph = WorkGraph::new();
+        let mut write = make_task("write", "Write");
+        write.status = Status::Done;
+        write.loops_to.push(LoopEdge {
+            target: "write".to_string(),
+            guard: Some("artifact-changed:draft.md".parse().unwrap()),
+            max_iterations: 5,
+            delay: None,
+        });
+        graph.add_node(Node::Task(write));
+
+        // Without readings the guard can't be checked
+        assert!(reward_loop_edges(&mut graph, "write").is_empty());
+
+        // First check sees a new file and loops, remembering its hash
+        let read = |graph: &WorkGraph| {
+            crate::loop_guard::Readings::of(graph.get_task("write").unwrap(), &dir)
+        };
+        let readings = read(&graph);
+        let reactivated = reward_loop_edges_with(&mut graph, "write", &readings);
+        assert!(reactivated.contains(&"write".to_string()));
+        let recorded = graph.get_task("write").unwrap().loops_to[0].guard.clone();
+        assert!(matches!(
+            recorded,
+            Some(LoopGuard::ArtifactChanged {
+                last_hash: Some(_),
+                ..
+            })
+        ));
+
+        // Unchanged on the next completion: the loop settles
+        graph.get_task_mut("write").unwrap().status = Status::Done;
+        let readings = read(&graph);
+        assert!(reward_loop_edges_with(&mut graph, "write", &readings).is_empty());
+        assert_eq!(graph.get_task("write").unwrap().status, Status::Done);
+    }
+
     #[test]
     fn test_reward_loop_edges_multi_target_no_duplicate_source() {
         // Source task has two loop edges to different targets.
@@ -1634,4 +2393,187 @@ mod tests {
             msg
         );
     }
+
+    fn make_child(id: &str, parent: &str, status: Status) -> Task {
+        Task {
+            parent: Some(parent.to_string()),
+            status,
+            ..make_task(id, id)
+        }
+    }
+
+    #[test]
+    fn test_children_and_descendants() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("epic", "Epic")));
+        graph.add_node(Node::Task(make_child("b", "epic", Status::Open)));
+        graph.add_node(Node::Task(make_child("a", "epic", Status::Open)));
+        graph.add_node(Node::Task(make_child("a1", "a", Status::Open)));
+
+        let ids = |tasks: Vec<&Task>| tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(graph.children("epic")), vec!["a", "b"]);
+        assert_eq!(ids(graph.descendants("epic")), vec!["a", "a1", "b"]);
+        assert!(graph.would_create_parent_cycle("epic", "a1"));
+        assert!(graph.would_create_parent_cycle("a", "a"));
+        assert!(!graph.would_create_parent_cycle("b", "a"));
+
+        graph.remove_node("a");
+        assert_eq!(graph.get_task("a1").unwrap().parent, None);
+    }
+
+    #[test]
+    fn test_complete_parents_walks_up_auto_complete_chain() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(Task {
+            auto_complete: true,
+            ..make_task("epic", "Epic")
+        }));
+        graph.add_node(Node::Task(Task {
+            auto_complete: true,
+            ..make_child("story", "epic", Status::Open)
+        }));
+        graph.add_node(Node::Task(make_child("t1", "story", Status::Done)));
+        graph.add_node(Node::Task(make_child("t2", "story", Status::Failed)));
+
+        // A failed child holds the parent open
+        assert!(complete_parents(&mut graph, "t1").is_empty());
+
+        graph.get_task_mut("t2").unwrap().status = Status::Abandoned;
+        assert_eq!(complete_parents(&mut graph, "t2"), vec!["story", "epic"]);
+        assert_eq!(graph.get_task("epic").unwrap().status, Status::Done);
+    }
+
+    #[test]
+    fn test_parse_dep_group() {
+        let group = parse_dep_group("a, b", false).unwrap();
+        assert_eq!(group.tasks, vec!["a", "b"]);
+        assert_eq!(group.need, 1);
+        assert_eq!(group.to_string(), "any of a, b");
+
+        let group = parse_dep_group("2:a,b,c", true).unwrap();
+        assert_eq!(group.need, 2);
+        assert_eq!(group.to_string(), "2 of a, b, c (abandon rest)");
+
+        assert!(parse_dep_group("a", false).is_err());
+        assert!(parse_dep_group("a,a", false).is_err());
+        assert!(parse_dep_group("0:a,b", false).is_err());
+        assert!(parse_dep_group("3:a,b", false).is_err());
+    }
+
+    #[test]
+    fn test_abandon_quorum_siblings() {
+        let mut graph = WorkGraph::new();
+        for id in ["a", "b", "c"] {
+            graph.add_node(Node::Task(make_task(id, id)));
+        }
+        let mut merge = make_task("merge", "Merge");
+        merge.blocked_by = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        merge.dep_groups = vec![parse_dep_group("2:a,b,c", true).unwrap()];
+        graph.add_node(Node::Task(merge));
+        // Something else still needs 'c' in full
+        let mut audit = make_task("audit", "Audit");
+        audit.blocked_by = vec!["c".to_string()];
+        graph.add_node(Node::Task(audit));
+
+        graph.get_task_mut("a").unwrap().status = Status::Done;
+        assert!(abandon_quorum_siblings(&mut graph, "a").is_empty());
+
+        graph.get_task_mut("b").unwrap().status = Status::Done;
+        assert!(abandon_quorum_siblings(&mut graph, "b").is_empty());
+        assert_eq!(graph.get_task("c").unwrap().status, Status::Open);
+
+        graph.get_task_mut("audit").unwrap().status = Status::Abandoned;
+        graph.get_task_mut("b").unwrap().status = Status::Done;
+        assert_eq!(abandon_quorum_siblings(&mut graph, "b"), vec!["c"]);
+        let c = graph.get_task("c").unwrap();
+        assert_eq!(c.status, Status::Abandoned);
+        assert_eq!(
+            c.failure_reason.as_deref(),
+            Some("quorum for 'merge' reached")
+        );
+    }
+
+    #[test]
+    fn test_abandon_quorum_siblings_leaves_running_tasks() {
+        let mut graph = WorkGraph::new();
+        for id in ["a", "b", "c"] {
+            graph.add_node(Node::Task(make_task(id, id)));
+        }
+        graph.get_task_mut("b").unwrap().status = Status::InProgress;
+        let mut ship = make_task("ship", "Ship");
+        ship.dep_groups = vec![parse_dep_group("a,b,c", true).unwrap()];
+        graph.add_node(Node::Task(ship));
+
+        graph.get_task_mut("a").unwrap().status = Status::Done;
+        assert_eq!(abandon_quorum_siblings(&mut graph, "a"), vec!["c"]);
+
+        // 'b' has an agent on it: it keeps running, with a note
+        let b = graph.get_task("b").unwrap();
+        assert_eq!(b.status, Status::InProgress);
+        assert!(b.log.last().unwrap().message.starts_with("No longer needed"));
+        abandon_quorum_siblings(&mut graph, "a");
+        assert_eq!(graph.get_task("b").unwrap().log.len(), 1);
+    }
+
+    #[test]
+    fn test_skip_unreachable_and_unskip() {
+        let mut graph = WorkGraph::new();
+        let mut migrate = make_task("migrate", "Run migrations");
+        migrate.status = Status::Failed;
+        graph.add_node(Node::Task(migrate));
+        let conditional = |id: &str, blocker: &str, condition| {
+            let mut task = make_task(id, id);
+            task.blocked_by = vec![blocker.to_string()];
+            task.dep_conditions.insert(blocker.to_string(), condition);
+            Node::Task(task)
+        };
+        graph.add_node(conditional("deploy", "migrate", DepCondition::OnSuccess));
+        graph.add_node(conditional("announce", "deploy", DepCondition::OnSuccess));
+        graph.add_node(conditional("rollback", "migrate", DepCondition::OnFailure));
+        let mut cleanup = make_task("cleanup", "Cleanup");
+        cleanup.blocked_by = vec!["deploy".to_string()];
+        graph.add_node(Node::Task(cleanup));
+
+        let mut skipped = skip_unreachable(&mut graph);
+        skipped.sort();
+        assert_eq!(skipped, vec!["announce", "deploy"]);
+        let deploy = graph.get_task("deploy").unwrap();
+        assert_eq!(deploy.status, Status::Abandoned);
+        assert_eq!(
+            deploy.failure_reason.as_deref(),
+            Some("skipped: dependency 'migrate' failed")
+        );
+        assert_eq!(
+            graph
+                .get_task("announce")
+                .unwrap()
+                .failure_reason
+                .as_deref(),
+            Some("skipped: dependency 'deploy' was abandoned")
+        );
+        // Compensation and unconditional dependents are untouched
+        assert_eq!(graph.get_task("rollback").unwrap().status, Status::Open);
+        assert_eq!(graph.get_task("cleanup").unwrap().status, Status::Open);
+        assert!(skip_unreachable(&mut graph).is_empty());
+
+        // Retrying the blocker brings back everything it skipped
+        graph.get_task_mut("migrate").unwrap().status = Status::Open;
+        let mut reopened = unskip_dependents(&mut graph, "migrate");
+        reopened.sort();
+        assert_eq!(reopened, vec!["announce", "deploy"]);
+        assert!(graph.get_task("deploy").unwrap().failure_reason.is_none());
+
+        // Success skips the compensation task instead
+        graph.get_task_mut("migrate").unwrap().status = Status::Done;
+        assert_eq!(skip_unreachable(&mut graph), vec!["rollback"]);
+    }
+
+    #[test]
+    fn test_complete_parents_requires_opt_in() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("epic", "Epic")));
+        graph.add_node(Node::Task(make_child("t1", "epic", Status::Done)));
+        assert!(complete_parents(&mut graph, "t1").is_empty());
+        assert_eq!(graph.get_task("epic").unwrap().status, Status::Open);
+    }
 }
@@ -0,0 +1,431 @@
+//! Point-in-time reconstruction of the graph ("time travel")
+//!
+//! [`graph_at`] rebuilds the graph as it was at a given moment by folding the
+//! operations log (including rotated `.jsonl.zst` segments) over the nearest
+//! known graph state: a run snapshot under `.workgraph/runs/`, or the current
+//! graph. From a state taken before the requested time the task changes
+//! recorded with each operation (see [`crate::undo`]) are applied forwards;
+//! from a later one they are applied backwards.
+//!
+//! Operations that changed the graph but carry no change data (logs written
+//! before changes were recorded, or commands that do not record them) cannot
+//! be replayed; they are returned as [`GraphAt::skipped`] so callers can warn
+//! that the reconstruction may be incomplete.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde_json::Value;
+
+use crate::graph::{Node, WorkGraph};
+use crate::provenance::{self, OperationEntry};
+use crate::undo::{self, REDO_OP, UNDO_OP};
+
+/// Operations that never change tasks in the graph
+const NON_GRAPH_OPS: &[&str] = &["migrate"];
+
+/// The graph as reconstructed for a point in time
+#[derive(Debug)]
+pub struct GraphAt {
+    pub graph: WorkGraph,
+    /// Run snapshot the reconstruction started from, or `None` for the
+    /// current graph
+    pub base: Option<String>,
+    /// Number of operations folded into the base state
+    pub applied: usize,
+    /// Operations between the base and the requested time that could not be
+    /// replayed, as `op 'task' (timestamp)` descriptions
+    pub skipped: Vec<String>,
+}
+
+/// Parse a `--at` timestamp: RFC 3339 (`2026-10-01T12:00:00Z`), the same
+/// without seconds (`2026-10-01T12:00Z`), `2026-10-01 12:00[:00]` in UTC, or
+/// a bare date meaning midnight UTC.
+pub fn parse_timestamp(s: &str) -> Result<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M%#z", "%Y-%m-%d %H:%M%#z"] {
+        if let Ok(dt) = DateTime::parse_from_str(s, format) {
+            return Ok(dt.with_timezone(&Utc));
+        }
+    }
+    let naive = s.trim_end_matches(['Z', 'z']);
+    for format in [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(naive, format) {
+            return Ok(dt.and_utc());
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is always valid")
+            .and_utc());
+    }
+    anyhow::bail!(
+        "Invalid timestamp '{}', expected e.g. 2026-10-01T12:00Z or 2026-10-01",
+        s
+    )
+}
+
+fn op_time(entry: &OperationEntry) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&entry.timestamp)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Rebuild the graph of the workgraph in `dir` as it was at `at`.
+pub fn graph_at(dir: &Path, at: DateTime<Utc>) -> Result<GraphAt> {
+    let ops = provenance::read_all_operations(dir)?;
+    let times: Vec<Option<DateTime<Utc>>> = ops.iter().map(op_time).collect();
+    let count_between = |from: DateTime<Utc>, to: DateTime<Utc>| {
+        times
+            .iter()
+            .flatten()
+            .filter(|t| **t > from && **t <= to)
+            .count()
+    };
+
+    // The current graph is the latest state; a run snapshot wins if fewer
+    // operations separate it from `at`
+    let mut base: Option<(String, DateTime<Utc>)> = None;
+    let mut distance = times.iter().flatten().filter(|t| **t > at).count();
+    for id in crate::runs::list_runs(dir)? {
+        let Ok(meta) = crate::runs::load_run_meta(dir, &id) else {
+            continue;
+        };
+        let Ok(taken) = DateTime::parse_from_rfc3339(&meta.timestamp) else {
+            continue;
+        };
+        let taken = taken.with_timezone(&Utc);
+        let n = if taken <= at {
+            count_between(taken, at)
+        } else {
+            count_between(at, taken)
+        };
+        if n < distance || (n == distance && taken <= at) {
+            distance = n;
+            base = Some((id, taken));
+        }
+    }
+
+    let (mut graph, from) = match &base {
+        Some((id, taken)) => (load_run_graph(dir, id)?, Some(*taken)),
+        None => {
+            let path = dir.join("graph.jsonl");
+            let graph = crate::parser::load_graph(&path).context("Failed to load graph")?;
+            (graph, None)
+        }
+    };
+
+    let by_timestamp: HashMap<&str, &OperationEntry> = ops
+        .iter()
+        .filter(|e| e.op != UNDO_OP && e.op != REDO_OP)
+        .map(|e| (e.timestamp.as_str(), e))
+        .collect();
+    let mut folder = Folder {
+        dir,
+        by_timestamp,
+        archived: None,
+        skipped: Vec::new(),
+    };
+
+    let mut applied = 0;
+    match from {
+        Some(taken) if taken <= at => {
+            for (entry, time) in ops.iter().zip(&times) {
+                if time.is_some_and(|t| t > taken && t <= at) {
+                    folder.fold(&mut graph, entry, true)?;
+                    applied += 1;
+                }
+            }
+        }
+        _ => {
+            for (entry, time) in ops.iter().zip(&times).rev() {
+                if time.is_some_and(|t| t > at && from.is_none_or(|until| t <= until)) {
+                    folder.fold(&mut graph, entry, false)?;
+                    applied += 1;
+                }
+            }
+        }
+    }
+    for (entry, time) in ops.iter().zip(&times) {
+        if time.is_none() {
+            folder.skip(entry);
+        }
+    }
+
+    Ok(GraphAt {
+        graph,
+        base: base.map(|(id, _)| id),
+        applied,
+        skipped: folder.skipped,
+    })
+}
+
+fn load_run_graph(dir: &Path, run_id: &str) -> Result<WorkGraph> {
+    let path = crate::runs::run_dir(dir, run_id).join("graph.jsonl");
+    crate::parser::load_graph(&path)
+        .with_context(|| format!("Failed to load graph snapshot of run '{}'", run_id))
+}
+
+/// Applies operations to a graph, forwards or backwards
+struct Folder<'a> {
+    dir: &'a Path,
+    /// Undoable operations by timestamp, for resolving undo/redo targets
+    by_timestamp: HashMap<&'a str, &'a OperationEntry>,
+    /// Archived tasks, read on the first archive that has to be reversed
+    archived: Option<HashMap<String, crate::graph::Task>>,
+    skipped: Vec<String>,
+}
+
+impl Folder<'_> {
+    fn fold(
+        &mut self,
+        graph: &mut WorkGraph,
+        entry: &OperationEntry,
+        forward: bool,
+    ) -> Result<()> {
+        match entry.op.as_str() {
+            UNDO_OP | REDO_OP => {
+                let target = entry
+                    .detail
+                    .get("target")
+                    .and_then(Value::as_str)
+                    .and_then(|ts| self.by_timestamp.get(ts).copied());
+                match target {
+                    // An undo applies its target backwards, a redo forwards
+                    Some(target) => {
+                        let direction = (entry.op == REDO_OP) == forward;
+                        self.apply(graph, entry, &undo::changes(target), direction);
+                    }
+                    None => self.skip(entry),
+                }
+            }
+            "restore" => {
+                let key = if forward { "restored_from" } else { "safety_snapshot" };
+                match entry.detail.get(key).and_then(Value::as_str) {
+                    Some(run_id) => *graph = load_run_graph(self.dir, run_id)?,
+                    None => self.skip(entry),
+                }
+            }
+            "archive" => {
+                let ids: Vec<&str> = entry
+                    .detail
+                    .get("task_ids")
+                    .and_then(Value::as_array)
+                    .map(|ids| ids.iter().filter_map(Value::as_str).collect())
+                    .unwrap_or_default();
+                if forward {
+                    for id in ids {
+                        graph.remove_node(id);
+                    }
+                } else {
+                    let archived = self.archived()?;
+                    for id in ids {
+                        if let Some(task) = archived.get(id) {
+                            graph.add_node(Node::Task(task.clone()));
+                        }
+                    }
+                }
+            }
+            op if NON_GRAPH_OPS.contains(&op) => {}
+            _ => {
+                let changes = undo::changes(entry);
+                if changes.is_empty() {
+                    self.skip(entry);
+                } else {
+                    self.apply(graph, entry, &changes, forward);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply changes one task at a time, so a task that has drifted from
+    /// what the log expects does not stop the rest
+    fn apply(
+        &mut self,
+        graph: &mut WorkGraph,
+        entry: &OperationEntry,
+        changes: &[undo::TaskChange],
+        forward: bool,
+    ) {
+        let failed = changes
+            .iter()
+            .filter(|change| {
+                undo::apply(graph, std::slice::from_ref(*change), forward).is_err()
+            })
+            .count();
+        if failed > 0 {
+            self.skip(entry);
+        }
+    }
+
+    fn archived(&mut self) -> Result<&HashMap<String, crate::graph::Task>> {
+        if self.archived.is_none() {
+            // Later copies of a task win, like later lines in the graph file
+            let tasks = crate::search::archived_tasks(self.dir)?;
+            self.archived = Some(tasks.into_iter().map(|t| (t.id.clone(), t)).collect());
+        }
+        Ok(self.archived.as_ref().expect("archived tasks were just loaded"))
+    }
+
+    fn skip(&mut self, entry: &OperationEntry) {
+        let what = match &entry.task_id {
+            Some(id) => format!("{} '{}'", entry.op, id),
+            None => entry.op.clone(),
+        };
+        self.skipped.push(format!("{} ({})", what, entry.timestamp));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Status, Task};
+    use crate::parser::save_graph;
+    use crate::undo::TaskChange;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn record(dir: &Path, op: &str, ts: &str, detail: Value) {
+        let entry = OperationEntry {
+            timestamp: ts.to_string(),
+            op: op.to_string(),
+            task_id: None,
+            actor: None,
+            detail,
+        };
+        let threshold = provenance::DEFAULT_ROTATION_THRESHOLD;
+        provenance::append_operation(dir, &entry, threshold).unwrap();
+    }
+
+    fn status_change(id: &str, from: &str, to: &str) -> Value {
+        let change = TaskChange {
+            id: id.to_string(),
+            before: Some(serde_json::from_value(json!({ "status": from })).unwrap()),
+            after: Some(serde_json::from_value(json!({ "status": to })).unwrap()),
+            appended: Default::default(),
+        };
+        undo::with_changes(Value::Null, &[change])
+    }
+
+    fn status(graph: &WorkGraph, id: &str) -> Option<Status> {
+        graph.get_task(id).map(|t| t.status)
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let expected = parse_timestamp("2026-10-01T12:00:00Z").unwrap();
+        for s in [
+            "2026-10-01T12:00:00Z",
+            "2026-10-01T12:00Z",
+            "2026-10-01T14:00+02:00",
+            "2026-10-01 12:00",
+            "2026-10-01T12:00:00",
+        ] {
+            assert_eq!(parse_timestamp(s).unwrap(), expected, "{}", s);
+        }
+        assert_eq!(
+            parse_timestamp("2026-10-01").unwrap(),
+            parse_timestamp("2026-10-01T00:00Z").unwrap()
+        );
+        assert!(parse_timestamp("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_graph_at_rewinds_current_graph() {
+        let dir = tempdir().unwrap();
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(Task {
+            id: "a".to_string(),
+            title: "A".to_string(),
+            status: Status::Done,
+            ..Task::default()
+        }));
+        save_graph(&graph, dir.path().join("graph.jsonl")).unwrap();
+
+        let created = TaskChange {
+            id: "a".to_string(),
+            before: None,
+            after: Some(serde_json::from_value(json!({ "id": "a", "title": "A" })).unwrap()),
+            appended: Default::default(),
+        };
+        record(
+            dir.path(),
+            "add_task",
+            "2026-10-01T10:00:00+00:00",
+            undo::with_changes(Value::Null, &[created]),
+        );
+        let claim = status_change("a", "open", "in-progress");
+        record(dir.path(), "claim", "2026-10-01T11:00:00+00:00", claim);
+        let done = status_change("a", "in-progress", "done");
+        record(dir.path(), "done", "2026-10-01T13:00:00+00:00", done);
+        let target = json!({ "target": "2026-10-01T13:00:00+00:00" });
+        record(dir.path(), "undo", "2026-10-01T14:00:00+00:00", target.clone());
+        record(dir.path(), "redo", "2026-10-01T15:00:00+00:00", target);
+
+        let at = |s: &str| graph_at(dir.path(), parse_timestamp(s).unwrap()).unwrap();
+        let in_progress = Some(Status::InProgress);
+        assert_eq!(status(&at("2026-10-01T12:00Z").graph, "a"), in_progress);
+        // Undone, then redone
+        assert_eq!(status(&at("2026-10-01T14:30Z").graph, "a"), in_progress);
+        assert_eq!(status(&at("2026-10-01T16:00Z").graph, "a"), Some(Status::Done));
+        assert_eq!(status(&at("2026-10-01T09:00Z").graph, "a"), None);
+
+        let early = at("2026-10-01T09:00Z");
+        assert!(early.base.is_none());
+        assert_eq!(early.applied, 5);
+        assert!(early.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_graph_at_folds_forward_from_snapshot() {
+        let dir = tempdir().unwrap();
+        // Current graph knows nothing of 'a'; the snapshot does
+        save_graph(&WorkGraph::new(), dir.path().join("graph.jsonl")).unwrap();
+        let run = crate::runs::run_dir(dir.path(), "run-001");
+        std::fs::create_dir_all(&run).unwrap();
+        let mut snap = WorkGraph::new();
+        snap.add_node(Node::Task(Task {
+            id: "a".to_string(),
+            title: "A".to_string(),
+            ..Task::default()
+        }));
+        save_graph(&snap, run.join("graph.jsonl")).unwrap();
+        let meta = crate::runs::RunMeta {
+            id: "run-001".to_string(),
+            timestamp: "2026-10-01T10:00:00+00:00".to_string(),
+            model: None,
+            reset_tasks: Vec::new(),
+            preserved_tasks: Vec::new(),
+            filter: None,
+        };
+        let meta = serde_json::to_string(&meta).unwrap();
+        std::fs::write(run.join("meta.json"), meta).unwrap();
+
+        let claim = status_change("a", "open", "in-progress");
+        record(dir.path(), "claim", "2026-10-01T11:00:00+00:00", claim);
+        record(dir.path(), "fail", "2026-10-01T12:00:00+00:00", Value::Null);
+        let archived = json!({ "task_ids": ["a"] });
+        record(dir.path(), "archive", "2026-10-01T13:00:00+00:00", archived);
+        record(dir.path(), "migrate", "2026-10-01T14:00:00+00:00", Value::Null);
+        record(dir.path(), "migrate", "2026-10-01T15:00:00+00:00", Value::Null);
+
+        let result = graph_at(dir.path(), parse_timestamp("2026-10-01T12:30Z").unwrap()).unwrap();
+        assert_eq!(result.base.as_deref(), Some("run-001"));
+        assert_eq!(result.applied, 2);
+        assert_eq!(status(&result.graph, "a"), Some(Status::InProgress));
+        // The 'fail' entry has no change data to replay
+        assert_eq!(result.skipped, vec!["fail (2026-10-01T12:00:00+00:00)"]);
+    }
+}
@@ -1560,6 +1560,9 @@ pub struct ArtifactEntry {
     pub path: String,
     /// File size in bytes, or None if the file doesn't exist.
     pub size: Option<u64>,
+    /// SHA-256 of the snapshot taken with `wg artifact add --snapshot`, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
 }
 
 /// Capture a snapshot of what an agent produced for a completed task.
@@ -1715,7 +1718,7 @@ fn capture_git_diff(output_dir: &Path, task: &crate::graph::Task) {
 }
 
 /// Capture artifact manifest as artifacts.json — a JSON list of registered
-/// artifacts with their file paths and sizes.
+/// artifacts with their file paths, sizes and snapshot hashes.
 fn capture_artifact_manifest(output_dir: &Path, task: &crate::graph::Task) {
     let manifest_path = output_dir.join("artifacts.json");
 
@@ -1743,6 +1746,10 @@ fn capture_artifact_manifest(output_dir: &Path, task: &crate::graph::Task) {
             ArtifactEntry {
                 path: artifact_path.clone(),
                 size,
+                sha256: task
+                    .artifact_snapshots
+                    .get(artifact_path)
+                    .map(|s| s.sha256.clone()),
             }
         })
         .collect();
@@ -1,21 +1,36 @@
 pub mod gepa;
 pub mod identity;
+pub mod artifacts;
 pub mod check;
+pub mod completion;
 pub mod config;
+pub mod fan_out;
 pub mod federation;
+pub mod fields;
 pub mod graph;
+pub mod history;
+pub mod loop_guard;
 #[cfg(feature = "matrix")]
 pub mod matrix;
 pub mod matrix_commands;
 #[cfg(feature = "matrix-lite")]
 pub mod matrix_lite;
+pub mod merge;
+pub mod outputs;
 pub mod parser;
 pub mod provenance;
 pub mod query;
+pub mod retry;
 pub mod runs;
+pub mod schedule;
+pub mod schema;
+pub mod search;
 pub mod service;
+pub mod store;
 pub mod trace_function;
+pub mod undo;
 pub mod usage;
+pub mod verify;
 
 pub use config::MatrixConfig;
 pub use graph::WorkGraph;
@@ -39,7 +54,7 @@ pub use matrix_lite::{
     IncomingMessage as IncomingMessageLite, MatrixClient as MatrixClientLite, send_notification,
     send_notification_to_room,
 };
-pub use parser::{load_graph, save_graph};
+pub use parser::{load_graph, save_graph, with_graph_mut};
 pub use service::{AgentEntry, AgentRegistry, AgentStatus};
 
 #[cfg(any(test, feature = "test-support"))]
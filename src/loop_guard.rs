@@ -0,0 +1,587 @@
+//! Loop guard expressions
+//!
+//! A [`LoopGuard`] decides whether a loop edge fires when its source task
+//! completes. Guards have a one-line text form, used by `wg add --loop-guard`,
+//! trace function templates and every place that displays a guard:
+//!
+//! | Expression                  | Loops when                                        |
+//! |-----------------------------|---------------------------------------------------|
+//! | `always`                    | always (up to `max_iterations`)                   |
+//! | `iteration<N`               | the target has looped fewer than N times          |
+//! | `task:<id>=<status>`        | task `<id>` has that status                       |
+//! | `reward:<id><op><value>`    | the latest reward for `<id>` compares true        |
+//! | `artifact-exists:<path>`    | the file exists under the project root            |
+//! | `artifact-missing:<path>`   | the file does not exist                           |
+//! | `artifact-changed:<path>`   | the file's SHA-256 differs from the last check    |
+//! | `shell:<cmd>`               | `<cmd>` exits non-zero                            |
+//! | `shell=<N>:<cmd>`           | `<cmd>` exits with code N                         |
+//!
+//! `<op>` is one of `<`, `<=`, `>`, `>=`, `=`, `!=`. Reward, artifact and shell
+//! guards read state outside the graph, and a shell guard can run for up to
+//! [`SHELL_GUARD_TIMEOUT`], so they are read into [`Readings`] before the
+//! graph lock is taken and evaluated against those readings under it. A
+//! guard with no reading never fires.
+
+use crate::graph::{LoopGuard, Status, Task, WorkGraph};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// How long a `shell:` guard may run before it is killed and treated as false
+pub const SHELL_GUARD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Comparison operator of a `reward:` guard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Comparison {
+    /// Longest symbols first, so `<=` isn't read as `<`
+    const SYMBOLS: [(&'static str, Comparison); 6] = [
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("!=", Comparison::Ne),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+        ("=", Comparison::Eq),
+    ];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+            Comparison::Eq => "=",
+            Comparison::Ne => "!=",
+        }
+    }
+
+    /// Whether `lhs <op> rhs` holds
+    pub fn holds(self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Comparison::Lt => lhs < rhs,
+            Comparison::Le => lhs <= rhs,
+            Comparison::Gt => lhs > rhs,
+            Comparison::Ge => lhs >= rhs,
+            Comparison::Eq => (lhs - rhs).abs() < f64::EPSILON,
+            Comparison::Ne => (lhs - rhs).abs() >= f64::EPSILON,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+fn parse_status(s: &str) -> anyhow::Result<Status> {
+    Ok(match s.to_lowercase().as_str() {
+        "open" => Status::Open,
+        "in-progress" => Status::InProgress,
+        "done" => Status::Done,
+        "blocked" => Status::Blocked,
+        "failed" => Status::Failed,
+        "abandoned" => Status::Abandoned,
+        "in-review" => Status::InReview,
+        "pending-review" => Status::Done, // pending-review is deprecated, maps to done
+        _ => anyhow::bail!("Unknown status '{}' in guard expression", s),
+    })
+}
+
+fn parse_reward(expr: &str, rest: &str) -> anyhow::Result<LoopGuard> {
+    let found = rest
+        .char_indices()
+        .find(|(_, c)| matches!(c, '<' | '>' | '=' | '!'))
+        .map(|(i, _)| i);
+    let Some(at) = found else {
+        anyhow::bail!(
+            "Invalid guard format. Expected 'reward:<id><op><value>', got '{}'",
+            expr
+        );
+    };
+    let (task, tail) = rest.split_at(at);
+    let Some((symbol, op)) = Comparison::SYMBOLS
+        .iter()
+        .find(|(symbol, _)| tail.starts_with(symbol))
+    else {
+        anyhow::bail!("Unknown comparison in guard expression '{}'", expr);
+    };
+    let task = task.trim();
+    if task.is_empty() {
+        anyhow::bail!("Guard expression '{}' is missing a task id", expr);
+    }
+    let value = tail[symbol.len()..].trim();
+    let value: f64 = value
+        .parse()
+        .ok()
+        .filter(|v: &f64| v.is_finite())
+        .ok_or_else(|| anyhow::anyhow!("Invalid reward value '{}' in guard expression", value))?;
+    Ok(LoopGuard::RewardValue {
+        task: task.to_string(),
+        op: *op,
+        value,
+    })
+}
+
+fn non_empty<'a>(expr: &str, value: &'a str, what: &str) -> anyhow::Result<&'a str> {
+    let value = value.trim();
+    if value.is_empty() {
+        anyhow::bail!("Guard expression '{}' is missing {}", expr, what);
+    }
+    Ok(value)
+}
+
+impl FromStr for LoopGuard {
+    type Err = anyhow::Error;
+
+    fn from_str(expr: &str) -> anyhow::Result<Self> {
+        let expr = expr.trim();
+        if expr.eq_ignore_ascii_case("always") {
+            return Ok(LoopGuard::Always);
+        }
+        if let Some(n) = expr.strip_prefix("iteration<") {
+            let n = n.trim().parse().map_err(|_| {
+                anyhow::anyhow!("Invalid iteration count in guard expression '{}'", expr)
+            })?;
+            return Ok(LoopGuard::IterationLessThan(n));
+        }
+        if let Some(rest) = expr.strip_prefix("task:") {
+            let Some((task, status)) = rest.split_once('=') else {
+                anyhow::bail!(
+                    "Invalid guard format. Expected 'task:<id>=<status>', got '{}'",
+                    expr
+                );
+            };
+            return Ok(LoopGuard::TaskStatus {
+                task: task.to_string(),
+                status: parse_status(status)?,
+            });
+        }
+        if let Some(rest) = expr.strip_prefix("reward:") {
+            return parse_reward(expr, rest);
+        }
+        if let Some(path) = expr.strip_prefix("artifact-exists:") {
+            let path = non_empty(expr, path, "a path")?.to_string();
+            return Ok(LoopGuard::ArtifactExists { path });
+        }
+        if let Some(path) = expr.strip_prefix("artifact-missing:") {
+            let path = non_empty(expr, path, "a path")?.to_string();
+            return Ok(LoopGuard::ArtifactMissing { path });
+        }
+        if let Some(path) = expr.strip_prefix("artifact-changed:") {
+            let path = non_empty(expr, path, "a path")?.to_string();
+            return Ok(LoopGuard::ArtifactChanged {
+                path,
+                last_hash: None,
+            });
+        }
+        if let Some(command) = expr.strip_prefix("shell:") {
+            let command = non_empty(expr, command, "a command")?.to_string();
+            return Ok(LoopGuard::ShellExit {
+                command,
+                code: None,
+            });
+        }
+        if let Some(rest) = expr.strip_prefix("shell=") {
+            let Some((code, command)) = rest.split_once(':') else {
+                anyhow::bail!(
+                    "Invalid guard format. Expected 'shell=<code>:<command>', got '{}'",
+                    expr
+                );
+            };
+            let code = code
+                .trim()
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid exit code '{}' in guard expression", code))?;
+            let command = non_empty(expr, command, "a command")?.to_string();
+            return Ok(LoopGuard::ShellExit {
+                command,
+                code: Some(code),
+            });
+        }
+        anyhow::bail!(
+            "Invalid guard expression '{}'. Expected 'always', 'iteration<N', \
+             'task:<id>=<status>', 'reward:<id><op><value>', 'artifact-exists:<path>', \
+             'artifact-missing:<path>', 'artifact-changed:<path>' or 'shell[=<code>]:<command>'",
+            expr
+        );
+    }
+}
+
+impl fmt::Display for LoopGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoopGuard::Always => write!(f, "always"),
+            LoopGuard::IterationLessThan(n) => write!(f, "iteration<{}", n),
+            LoopGuard::TaskStatus { task, status } => write!(f, "task:{}={}", task, status),
+            LoopGuard::RewardValue { task, op, value } => {
+                write!(f, "reward:{}{}{}", task, op, value)
+            }
+            LoopGuard::ArtifactExists { path } => write!(f, "artifact-exists:{}", path),
+            LoopGuard::ArtifactMissing { path } => write!(f, "artifact-missing:{}", path),
+            LoopGuard::ArtifactChanged { path, .. } => write!(f, "artifact-changed:{}", path),
+            LoopGuard::ShellExit {
+                command,
+                code: None,
+            } => write!(f, "shell:{}", command),
+            LoopGuard::ShellExit {
+                command,
+                code: Some(code),
+            } => write!(f, "shell={}:{}", code, command),
+        }
+    }
+}
+
+/// A problem with a guard that parsing alone can't catch (guards deserialized
+/// from the graph never went through [`FromStr`]), or `None` if it is valid.
+pub fn validate(guard: &LoopGuard) -> Option<String> {
+    match guard {
+        LoopGuard::RewardValue { value, .. } if !value.is_finite() => {
+            Some(format!("reward threshold {} is not a number", value))
+        }
+        LoopGuard::ArtifactExists { path }
+        | LoopGuard::ArtifactMissing { path }
+        | LoopGuard::ArtifactChanged { path, .. } => {
+            let path = Path::new(path);
+            if path.as_os_str().is_empty() {
+                Some("artifact path is empty".to_string())
+            } else if path.is_absolute()
+                || path
+                    .components()
+                    .any(|c| matches!(c, std::path::Component::ParentDir))
+            {
+                Some(format!(
+                    "artifact path '{}' must be relative to the project root",
+                    path.display()
+                ))
+            } else {
+                None
+            }
+        }
+        LoopGuard::ShellExit { command, .. } if command.trim().is_empty() => {
+            Some("shell command is empty".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// The task a guard reads from the graph, if any
+pub fn referenced_task(guard: &LoopGuard) -> Option<&str> {
+    match guard {
+        LoopGuard::TaskStatus { task, .. } | LoopGuard::RewardValue { task, .. } => Some(task),
+        _ => None,
+    }
+}
+
+/// Mutable access to the task a guard reads, for remapping task ids
+pub fn referenced_task_mut(guard: &mut LoopGuard) -> Option<&mut String> {
+    match guard {
+        LoopGuard::TaskStatus { task, .. } | LoopGuard::RewardValue { task, .. } => Some(task),
+        _ => None,
+    }
+}
+
+/// What an external guard found outside the graph
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reading {
+    /// The guard's outcome (reward, artifact-exists/missing and shell guards)
+    Passed(bool),
+    /// Hash of the file an `ArtifactChanged` guard watches, if it exists;
+    /// compared against the guard's `last_hash` when evaluated
+    Hash(Option<String>),
+}
+
+/// Readings of the external guards on a task's loop edges, keyed by guard
+/// expression. Taken before the graph lock so slow guards don't hold it.
+#[derive(Debug, Clone, Default)]
+pub struct Readings(HashMap<String, Reading>);
+
+impl Readings {
+    /// Read every external guard on `task`'s loop edges, with `dir` the
+    /// workgraph directory.
+    pub fn of(task: &Task, dir: &Path) -> Self {
+        let mut readings = HashMap::new();
+        for guard in task.loops_to.iter().filter_map(|edge| edge.guard.as_ref()) {
+            let expr = guard.to_string();
+            if !readings.contains_key(&expr)
+                && let Some(reading) = read(guard, dir)
+            {
+                readings.insert(expr, reading);
+            }
+        }
+        Self(readings)
+    }
+
+    fn get(&self, guard: &LoopGuard) -> Option<&Reading> {
+        self.0.get(&guard.to_string())
+    }
+}
+
+/// Read an external guard, or `None` for guards that only look at the graph
+pub fn read(guard: &LoopGuard, dir: &Path) -> Option<Reading> {
+    let root = project_root(dir);
+    Some(match guard {
+        LoopGuard::Always | LoopGuard::IterationLessThan(_) | LoopGuard::TaskStatus { .. } => {
+            return None;
+        }
+        LoopGuard::RewardValue { task, op, value } => {
+            Reading::Passed(latest_reward(dir, task).is_some_and(|reward| op.holds(reward, *value)))
+        }
+        LoopGuard::ArtifactExists { path } => Reading::Passed(root.join(path).is_file()),
+        LoopGuard::ArtifactMissing { path } => Reading::Passed(!root.join(path).exists()),
+        LoopGuard::ArtifactChanged { path, .. } => Reading::Hash(file_hash(&root.join(path))),
+        LoopGuard::ShellExit { command, code } => Reading::Passed(
+            shell_exit_code(root, command).is_some_and(|exit| match code {
+                Some(code) => exit == *code,
+                None => exit != 0,
+            }),
+        ),
+    })
+}
+
+/// Evaluate a guard against the graph and, for external guards, the
+/// `readings` taken beforehand. `IterationLessThan` is left to the caller,
+/// which knows the target's iteration.
+///
+/// An `ArtifactChanged` guard records the hash it saw in `last_hash`; the
+/// caller persists the updated guard so the next evaluation compares
+/// against it.
+pub fn evaluate(guard: &mut LoopGuard, graph: &WorkGraph, readings: &Readings) -> bool {
+    let reading = readings.get(guard).cloned();
+    match guard {
+        LoopGuard::Always | LoopGuard::IterationLessThan(_) => true,
+        LoopGuard::TaskStatus { task, status } => graph
+            .get_task(task)
+            .map(|t| t.status == *status)
+            .unwrap_or(false),
+        LoopGuard::ArtifactChanged { last_hash, .. } => {
+            let Some(Reading::Hash(hash)) = reading else {
+                return false;
+            };
+            let changed = hash.is_some() && hash != *last_hash;
+            *last_hash = hash;
+            changed
+        }
+        LoopGuard::RewardValue { .. }
+        | LoopGuard::ArtifactExists { .. }
+        | LoopGuard::ArtifactMissing { .. }
+        | LoopGuard::ShellExit { .. } => reading == Some(Reading::Passed(true)),
+    }
+}
+
+/// The project root a `.workgraph` directory belongs to
+fn project_root(dir: &Path) -> &Path {
+    dir.parent().unwrap_or(dir)
+}
+
+/// Value of the most recent reward recorded for `task_id`
+fn latest_reward(dir: &Path, task_id: &str) -> Option<f64> {
+    crate::identity::load_all_rewards_or_warn(&dir.join("identity").join("rewards"))
+        .into_iter()
+        .filter(|r| r.task_id == task_id)
+        .max_by(|a, b| a.timestamp.cmp(&b.timestamp))
+        .map(|r| r.value)
+}
+
+/// Hex SHA-256 of a file's contents, or `None` if it can't be read
+fn file_hash(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    Some(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Run `command` with `sh -c` in `root` and return its exit code. Returns
+/// `None` (with a warning) if it can't be started, is killed by a signal or
+/// outlives [`SHELL_GUARD_TIMEOUT`].
+fn shell_exit_code(root: &Path, command: &str) -> Option<i32> {
+    let mut child = match Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Warning: failed to run loop guard '{}': {}", command, e);
+            return None;
+        }
+    };
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return status.code(),
+            Ok(None) if started.elapsed() < SHELL_GUARD_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                eprintln!(
+                    "Warning: loop guard '{}' timed out after {}s, treating as false",
+                    command,
+                    SHELL_GUARD_TIMEOUT.as_secs()
+                );
+                return None;
+            }
+            Err(e) => {
+                eprintln!(
+                    "Warning: failed to wait for loop guard '{}': {}",
+                    command, e
+                );
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Node, Task};
+
+    #[test]
+    fn test_parse_display_round_trip() {
+        for expr in [
+            "always",
+            "iteration<3",
+            "task:gate=done",
+            "reward:review<0.8",
+            "reward:review>=0.75",
+            "reward:review!=1",
+            "artifact-exists:out/report.md",
+            "artifact-missing:out/report.md",
+            "artifact-changed:out/report.md",
+            "shell:cargo test --quiet",
+            "shell=2:grep -q TODO src/lib.rs",
+        ] {
+            let guard: LoopGuard = expr.parse().unwrap();
+            assert_eq!(guard.to_string(), expr);
+            assert_eq!(validate(&guard), None, "{}", expr);
+        }
+        assert_eq!(
+            "reward:review <= 0.5".parse::<LoopGuard>().unwrap(),
+            LoopGuard::RewardValue {
+                task: "review".to_string(),
+                op: Comparison::Le,
+                value: 0.5,
+            }
+        );
+        for bad in [
+            "reward:review",
+            "reward:<0.5",
+            "reward:review<high",
+            "reward:review=<1",
+            "artifact-exists:",
+            "shell:",
+            "shell=x:true",
+            "iteration<many",
+        ] {
+            assert!(bad.parse::<LoopGuard>().is_err(), "{}", bad);
+        }
+        let escaping: LoopGuard = "artifact-exists:../secrets".parse().unwrap();
+        assert!(validate(&escaping).is_some());
+    }
+
+    #[test]
+    fn test_external_guards() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join(".workgraph");
+        std::fs::create_dir_all(&dir).unwrap();
+        let graph = WorkGraph::new();
+        let eval_guard = |guard: &mut LoopGuard| {
+            let task = task_with_guard(guard);
+            evaluate(guard, &graph, &Readings::of(&task, &dir))
+        };
+        let eval = |expr: &str| eval_guard(&mut expr.parse().unwrap());
+
+        assert!(eval("artifact-missing:report.md"));
+        assert!(!eval("artifact-exists:report.md"));
+        std::fs::write(tmp.path().join("report.md"), "v1").unwrap();
+        assert!(eval("artifact-exists:report.md"));
+
+        let mut changed: LoopGuard = "artifact-changed:report.md".parse().unwrap();
+        assert!(eval_guard(&mut changed));
+        assert!(!eval_guard(&mut changed));
+        std::fs::write(tmp.path().join("report.md"), "v2").unwrap();
+        assert!(eval_guard(&mut changed));
+
+        assert!(eval("shell:false"));
+        assert!(!eval("shell:true"));
+        assert!(eval("shell=3:exit 3"));
+        assert!(eval("shell:test -f report.md && exit 1"));
+
+        // Without a reading, external guards never fire
+        let mut shell: LoopGuard = "shell:false".parse().unwrap();
+        assert!(!evaluate(&mut shell, &graph, &Readings::default()));
+    }
+
+    fn task_with_guard(guard: &LoopGuard) -> Task {
+        Task {
+            id: "t".to_string(),
+            loops_to: vec![crate::graph::LoopEdge {
+                target: "t".to_string(),
+                guard: Some(guard.clone()),
+                max_iterations: 1,
+                delay: None,
+            }],
+            ..Task::default()
+        }
+    }
+
+    #[test]
+    fn test_reward_guard_uses_latest_reward() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join(".workgraph");
+        let rewards = dir.join("identity").join("rewards");
+        for (id, value, timestamp) in [
+            ("r1", 0.3, "2026-01-01T00:00:00Z"),
+            ("r2", 0.9, "2026-01-02T00:00:00Z"),
+        ] {
+            let reward = crate::identity::Reward {
+                id: id.to_string(),
+                task_id: "draft".to_string(),
+                agent_id: String::new(),
+                role_id: "role".to_string(),
+                objective_id: "objective".to_string(),
+                value,
+                dimensions: Default::default(),
+                notes: String::new(),
+                evaluator: "test".to_string(),
+                timestamp: timestamp.to_string(),
+                model: None,
+                source: "manual".to_string(),
+            };
+            crate::identity::save_reward(&reward, &rewards).unwrap();
+        }
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(Task {
+            id: "draft".to_string(),
+            ..Task::default()
+        }));
+
+        let eval = |expr: &str| {
+            let mut guard: LoopGuard = expr.parse().unwrap();
+            let readings = Readings::of(&task_with_guard(&guard), &dir);
+            evaluate(&mut guard, &graph, &readings)
+        };
+        assert!(eval("reward:draft>=0.8"));
+        assert!(!eval("reward:draft<0.8"));
+        assert!(!eval("reward:other<0.8"));
+    }
+}
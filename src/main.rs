@@ -39,6 +39,7 @@ struct Cli {
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 enum Commands {
     /// Initialize a new workgraph in the current directory
     Init,
@@ -64,6 +65,26 @@ enum Commands {
         #[arg(long = "blocked-by", value_delimiter = ',', num_args = 1..)]
         blocked_by: Vec<String>,
 
+        /// Start once any one of these comma-separated tasks is done (can repeat)
+        #[arg(long = "any-of", value_name = "IDS")]
+        any_of: Vec<String>,
+
+        /// Start once K of these comma-separated tasks are done, as K:IDS (can repeat)
+        #[arg(long, value_name = "K:IDS")]
+        quorum: Vec<String>,
+
+        /// Abandon the unstarted rest of an --any-of/--quorum group once it is reached
+        #[arg(long = "abandon-rest")]
+        abandon_rest: bool,
+
+        /// Blocked by a task that must succeed; skipped if it fails or is abandoned
+        #[arg(long = "on-success", value_delimiter = ',', num_args = 1..)]
+        on_success: Vec<String>,
+
+        /// Compensation for a task: runs only if it fails, skipped otherwise
+        #[arg(long = "on-failure", value_delimiter = ',', num_args = 1..)]
+        on_failure: Vec<String>,
+
         /// Assign to an actor
         #[arg(long)]
         assign: Option<String>,
@@ -100,9 +121,10 @@ enum Commands {
         #[arg(long)]
         model: Option<String>,
 
-        /// Verification criteria - task requires review before done
+        /// Verification criteria for reviewers; lines 'shell:<cmd>', 'exists:<path>',
+        /// 'deliverables' and 'match:<path>:<regex>' are also run by 'wg done' (can repeat)
         #[arg(long)]
-        verify: Option<String>,
+        verify: Vec<String>,
 
         /// Create a loop edge back to target task (re-activates on completion)
         #[arg(long = "loops-to")]
@@ -112,13 +134,67 @@ enum Commands {
         #[arg(long = "loop-max")]
         loop_max: Option<u32>,
 
-        /// Guard condition for loop: 'task:<id>=<status>' or 'always'
+        /// Guard condition for loop: 'always', 'task:<id>=<status>', 'reward:<id><op><value>',
+        /// 'artifact-exists|artifact-missing|artifact-changed:<path>' or 'shell[=<code>]:<cmd>'
         #[arg(long = "loop-guard")]
         loop_guard: Option<String>,
 
         /// Delay between loop iterations (e.g., 30s, 5m, 1h, 24h, 7d)
         #[arg(long = "loop-delay")]
         loop_delay: Option<String>,
+
+        /// Dispatch priority: critical, high, normal (default), low
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Business value, used to break ties between equal-priority tasks
+        #[arg(long)]
+        value: Option<f64>,
+
+        /// Required resource, optionally with amount: gpu-box or gpu-box:2 (can specify multiple)
+        #[arg(long, value_delimiter = ',', num_args = 1..)]
+        requires: Vec<String>,
+
+        /// Parent task (epic) this task belongs to
+        #[arg(long)]
+        parent: Option<String>,
+
+        /// Mark this task done automatically once all its children are done or abandoned
+        #[arg(long = "auto-complete")]
+        auto_complete: bool,
+
+        /// Custom field as key=value, typed by the [fields] schema in config.toml (can repeat)
+        #[arg(long = "field", value_name = "KEY=VALUE")]
+        field: Vec<String>,
+
+        /// Finish-by time (RFC 3339, or YYYY-MM-DD [HH:MM] in UTC); see `wg at-risk`
+        #[arg(long)]
+        deadline: Option<String>,
+
+        /// Recur on a cron schedule (UTC), e.g. '0 9 * * mon-fri'; see `wg schedule`
+        #[arg(long)]
+        schedule: Option<String>,
+
+        /// Retry automatically on failure, e.g. 'max=5,backoff=30s,cap=1h,on=rate-limit+timeout'
+        #[arg(long = "retry-policy", value_name = "SPEC")]
+        retry_policy: Option<String>,
+
+        /// When done, add one task per item of the task's JSON output, titled from this
+        /// template, e.g. 'Review {{input.item}}'
+        #[arg(long = "fan-out", value_name = "TITLE")]
+        fan_out: Option<String>,
+
+        /// Description template for the fan-out tasks
+        #[arg(long = "fan-out-description", value_name = "TEXT")]
+        fan_out_description: Option<String>,
+
+        /// JSON file with the fan-out items (default: the task's .json artifact)
+        #[arg(long = "fan-out-items", value_name = "PATH")]
+        fan_out_items: Option<String>,
+
+        /// Title of the join task that waits for all fan-out tasks
+        #[arg(long, value_name = "TITLE")]
+        join: Option<String>,
     },
 
     /// Edit an existing task
@@ -170,7 +246,8 @@ enum Commands {
         #[arg(long = "loop-max")]
         loop_max: Option<u32>,
 
-        /// Guard condition for loop: 'task:<id>=<status>' or 'always'
+        /// Guard condition for loop: 'always', 'task:<id>=<status>', 'reward:<id><op><value>',
+        /// 'artifact-exists|artifact-missing|artifact-changed:<path>' or 'shell[=<code>]:<cmd>'
         #[arg(long = "loop-guard")]
         loop_guard: Option<String>,
 
@@ -185,9 +262,69 @@ enum Commands {
         /// Manually override the loop iteration counter on this task
         #[arg(long = "loop-iteration")]
         loop_iteration: Option<u32>,
+
+        /// Update dispatch priority: critical, high, normal, low
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Update business value
+        #[arg(long)]
+        value: Option<f64>,
+
+        /// Add a required resource, optionally with amount (gpu-box or gpu-box:2)
+        #[arg(long = "add-requires")]
+        add_requires: Vec<String>,
+
+        /// Remove a required resource
+        #[arg(long = "remove-requires")]
+        remove_requires: Vec<String>,
+
+        /// Move the task under a parent task (epic)
+        #[arg(long, conflicts_with = "no_parent")]
+        parent: Option<String>,
+
+        /// Detach the task from its parent
+        #[arg(long = "no-parent")]
+        no_parent: bool,
+
+        /// Auto-complete this task once all its children are done or abandoned
+        #[arg(long = "auto-complete", conflicts_with = "no_auto_complete")]
+        auto_complete: bool,
+
+        /// Stop auto-completing this task
+        #[arg(long = "no-auto-complete")]
+        no_auto_complete: bool,
+
+        /// Set a custom field as key=value (can repeat)
+        #[arg(long = "field", value_name = "KEY=VALUE")]
+        field: Vec<String>,
+
+        /// Remove a custom field (can repeat)
+        #[arg(long = "remove-field", value_name = "KEY")]
+        remove_field: Vec<String>,
+
+        /// Set the finish-by time (RFC 3339, or YYYY-MM-DD [HH:MM] in UTC)
+        #[arg(long, conflicts_with = "no_deadline")]
+        deadline: Option<String>,
+
+        /// Clear the deadline
+        #[arg(long = "no-deadline")]
+        no_deadline: bool,
+
+        /// Set the automatic retry policy, e.g. 'max=5,backoff=30s,cap=1h,on=rate-limit+timeout'
+        #[arg(
+            long = "retry-policy",
+            value_name = "SPEC",
+            conflicts_with = "no_retry_policy"
+        )]
+        retry_policy: Option<String>,
+
+        /// Remove the task's own retry policy (tag policies from config still apply)
+        #[arg(long = "no-retry-policy")]
+        no_retry_policy: bool,
     },
 
-    /// Mark a task as done
+    /// Mark a task as done (an agent finishing a task with --verify submits it for review)
     Done {
         /// Task ID to mark as done
         id: String,
@@ -195,6 +332,38 @@ enum Commands {
         /// Signal that the task's iterative loop has converged (stops loop edges from firing)
         #[arg(long)]
         converged: bool,
+
+        /// Mark done without running the task's verification checks (not allowed for agents)
+        #[arg(long)]
+        skip_verify: bool,
+
+        /// Who is marking the task done (recorded in the log and provenance)
+        #[arg(long)]
+        actor: Option<String>,
+    },
+
+    /// Approve a task awaiting review, marking it done and unblocking dependents
+    Approve {
+        /// Task ID to approve
+        id: String,
+
+        /// Reviewer (recorded in the log and provenance)
+        #[arg(long)]
+        actor: Option<String>,
+    },
+
+    /// Reject a task awaiting review, reopening it with the reviewer's notes
+    Reject {
+        /// Task ID to reject
+        id: String,
+
+        /// What needs to change, appended to the task description
+        #[arg(long)]
+        reason: String,
+
+        /// Reviewer (recorded in the log and provenance)
+        #[arg(long)]
+        actor: Option<String>,
     },
 
     /// Mark a task as failed (can be retried)
@@ -205,6 +374,10 @@ enum Commands {
         /// Reason for failure
         #[arg(long)]
         reason: Option<String>,
+
+        /// Exit code of the failed command, used to classify the failure for retry policies
+        #[arg(long, allow_hyphen_values = true)]
+        exit_code: Option<i32>,
     },
 
     /// Mark a task as abandoned (will not be retried)
@@ -266,7 +439,11 @@ enum Commands {
     },
 
     /// List tasks that are ready to work on
-    Ready,
+    Ready {
+        /// Filter expression, e.g. 'tag:backend and priority>=high' or '@saved-query'
+        #[arg(long)]
+        filter: Option<String>,
+    },
 
     /// Show what's blocking a task
     Blocked {
@@ -292,6 +469,22 @@ enum Commands {
         /// Only show paused tasks
         #[arg(long)]
         paused: bool,
+
+        /// Show tasks nested under their parents, with rolled-up progress
+        #[arg(long)]
+        tree: bool,
+
+        /// Only show tasks whose custom field matches key=value (can repeat)
+        #[arg(long = "field", value_name = "KEY=VALUE")]
+        field: Vec<String>,
+
+        /// Filter expression, e.g. 'status:open and tag:backend' or '@saved-query'
+        #[arg(long)]
+        filter: Option<String>,
+
+        /// List tasks as they were at this time (e.g. 2026-10-01T12:00Z)
+        #[arg(long, value_name = "TIMESTAMP")]
+        at: Option<String>,
     },
 
     /// Visualize the dependency graph (ASCII tree by default)
@@ -327,6 +520,26 @@ enum Commands {
         /// Show internal tasks (assign-*, reward-*) normally hidden
         #[arg(long)]
         show_internal: bool,
+
+        /// Draw parent tasks as clusters around their children (dot/mermaid)
+        #[arg(long)]
+        clusters: bool,
+
+        /// Only show tasks whose custom field matches key=value (can repeat)
+        #[arg(long = "field", value_name = "KEY=VALUE")]
+        field: Vec<String>,
+
+        /// Filter expression, e.g. 'status:open and tag:backend' or '@saved-query'
+        #[arg(long)]
+        filter: Option<String>,
+
+        /// Draw the graph as it was at this time (e.g. 2026-10-01T12:00Z)
+        #[arg(long, value_name = "TIMESTAMP")]
+        at: Option<String>,
+
+        /// Draw every fan-out task instead of one node per fan-out
+        #[arg(long = "expand-fan-out")]
+        expand_fan_out: bool,
     },
 
     /// Output the full graph data (DOT format with archive support)
@@ -343,6 +556,10 @@ enum Commands {
         /// Only show tasks completed/archived before this date (YYYY-MM-DD)
         #[arg(long)]
         until: Option<String>,
+
+        /// Filter expression, e.g. 'status:open and tag:backend' or '@saved-query'
+        #[arg(long)]
+        filter: Option<String>,
     },
 
     /// Calculate cost of a task including dependencies
@@ -419,6 +636,14 @@ enum Commands {
     /// remaining open tasks. Uses linear extrapolation.
     Forecast,
 
+    /// List tasks at risk of missing a deadline: backward-schedules deadlines
+    /// over the dependency graph and shows tasks with too little slack.
+    AtRisk {
+        /// Also list tasks with less than this many hours of slack (default: 0)
+        #[arg(long, value_name = "HOURS")]
+        within: Option<f64>,
+    },
+
     /// Show agent workload balance: how many tasks each agent has claimed
     /// or completed, to identify over/under-utilization.
     Workload,
@@ -445,6 +670,10 @@ enum Commands {
         /// List archived tasks instead of archiving
         #[arg(long)]
         list: bool,
+
+        /// Only archive (or list) done tasks matching this filter expression
+        #[arg(long)]
+        filter: Option<String>,
     },
 
     /// Garbage collect terminal tasks (failed, abandoned) from the graph
@@ -456,12 +685,84 @@ enum Commands {
         /// Also remove done tasks (by default only failed+abandoned)
         #[arg(long)]
         include_done: bool,
+
+        /// Only remove tasks matching this filter expression
+        #[arg(long)]
+        filter: Option<String>,
+    },
+
+    /// Convert the graph between storage formats (jsonl, journal, sqlite) or upgrade its schema
+    Migrate {
+        /// Target storage format: jsonl, journal or sqlite (needs the `sqlite` build feature)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Fold the journal into the snapshot now
+        #[arg(long)]
+        compact: bool,
+
+        /// List pending schema migrations (fails if there are any)
+        #[arg(long, conflicts_with_all = ["to", "compact", "apply"])]
+        check: bool,
+
+        /// Apply pending schema migrations now, keeping a backup
+        #[arg(long)]
+        apply: bool,
+    },
+
+    /// Three-way merge of graph.jsonl, for use as a git merge driver
+    MergeDriver {
+        /// Common ancestor version (%O)
+        base: PathBuf,
+
+        /// Our version (%A); the merged graph is written here
+        ours: PathBuf,
+
+        /// Their version (%B)
+        theirs: PathBuf,
     },
 
     /// Show detailed information about a single task
     Show {
         /// Task ID
         id: String,
+
+        /// Show the task as it was at this time (e.g. 2026-10-01T12:00Z)
+        #[arg(long, value_name = "TIMESTAMP")]
+        at: Option<String>,
+    },
+
+    /// Full-text search over task titles, descriptions, logs and the archive
+    Search {
+        /// Search terms
+        #[arg(required_unless_present = "reindex")]
+        terms: Vec<String>,
+
+        /// Rebuild the search index from scratch
+        #[arg(long)]
+        reindex: bool,
+
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Revert the last N graph operations (done, edit, add, log, ...)
+    Undo {
+        /// Number of operations to undo
+        #[arg(default_value = "1")]
+        count: usize,
+
+        /// Undo even if later operations touched the same tasks
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Re-apply the most recently undone operation
+    Redo {
+        /// Redo even if the affected tasks have changed since the undo
+        #[arg(long)]
+        force: bool,
     },
 
     /// Trace commands: execution history and trace functions
@@ -499,6 +800,10 @@ enum Commands {
         /// Only replay tasks in this subgraph (rooted at given task)
         #[arg(long)]
         subgraph: Option<String>,
+
+        /// Only reset tasks matching this filter expression
+        #[arg(long)]
+        filter: Option<String>,
     },
 
     /// Manage run snapshots (list, show, restore, diff)
@@ -538,6 +843,12 @@ enum Commands {
         command: ResourceCommands,
     },
 
+    /// Manage cron-style recurring tasks
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommands,
+    },
+
     /// Manage skills (Claude Code skill installation, task skill queries)
     Skill {
         #[command(subcommand)]
@@ -603,9 +914,14 @@ enum Commands {
     },
 
     /// Manage task artifacts (produced outputs)
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Artifact {
+        #[command(subcommand)]
+        command: Option<ArtifactCommands>,
+
         /// Task ID
-        task: String,
+        #[arg(required = true)]
+        task: Option<String>,
 
         /// Artifact path to add (omit to list)
         path: Option<String>,
@@ -615,6 +931,20 @@ enum Commands {
         remove: bool,
     },
 
+    /// Record named outputs on a task for its dependents ({{deps.<task>.<key>}}), or list them
+    Output {
+        /// Task ID
+        task: String,
+
+        /// Outputs as key=value (a string), key:=JSON (a typed value) or one JSON object; omit to list
+        #[arg(value_name = "KEY=VALUE")]
+        values: Vec<String>,
+
+        /// Remove an output (can repeat)
+        #[arg(long, value_name = "KEY")]
+        remove: Vec<String>,
+    },
+
     /// Show available context for a task from its dependencies
     Context {
         /// Task ID
@@ -679,7 +1009,7 @@ enum Commands {
         #[arg(long)]
         executor: String,
 
-        /// Timeout duration (e.g., 30m, 1h, 90s)
+        /// Kill the agent after this long (e.g., 30m, 1h, 90s); overrides the executor timeout
         #[arg(long)]
         timeout: Option<String>,
 
@@ -1134,6 +1464,75 @@ enum ResourceCommands {
     List,
 }
 
+#[derive(Subcommand)]
+enum ArtifactCommands {
+    /// Register an artifact for a task
+    Add {
+        /// Task ID
+        task: String,
+
+        /// Artifact path (relative to the project root)
+        path: String,
+
+        /// Also copy the file into .workgraph/artifacts/<sha256> and record its hash
+        #[arg(long)]
+        snapshot: bool,
+    },
+
+    /// Check snapshotted artifacts against their recorded hashes
+    Verify {
+        /// Task ID (default: all tasks)
+        task: Option<String>,
+    },
+
+    /// Restore a task's snapshotted artifacts to what it produced
+    Checkout {
+        /// Task ID
+        task: String,
+
+        /// Artifact paths to restore (default: all the task's snapshots)
+        paths: Vec<String>,
+
+        /// Also overwrite files modified since the snapshot
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ScheduleCommands {
+    /// List scheduled tasks and their next fire times
+    List {
+        /// Number of upcoming fire times to show per task
+        #[arg(long, short = 'n', default_value = "1")]
+        count: usize,
+    },
+
+    /// Make a task recur on a cron schedule (replaces any existing schedule)
+    Set {
+        /// Task ID
+        id: String,
+
+        /// Cron expression in UTC: 'min hour day month weekday' or @hourly/@daily/@weekly/@monthly/@yearly
+        cron: String,
+
+        /// Add a fresh copy of the task at each fire instead of reopening it
+        /// (the task itself is paused and kept as the template)
+        #[arg(long)]
+        copy: bool,
+
+        /// Missed-fire policy: skip, catch-up-one, catch-up-all (default: coordinator.missed_fire)
+        #[arg(long)]
+        missed: Option<String>,
+    },
+
+    /// Stop a task recurring
+    Clear {
+        /// Task ID
+        id: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum SkillCommands {
     /// List all skills used across tasks
@@ -1858,6 +2257,8 @@ fn command_name(cmd: &Commands) -> &'static str {
         Commands::Add { .. } => "add",
         Commands::Edit { .. } => "edit",
         Commands::Done { .. } => "done",
+        Commands::Approve { .. } => "approve",
+        Commands::Reject { .. } => "reject",
         Commands::Fail { .. } => "fail",
         Commands::Abandon { .. } => "abandon",
         Commands::Retry { .. } => "retry",
@@ -1866,7 +2267,7 @@ fn command_name(cmd: &Commands) -> &'static str {
         Commands::Pause { .. } => "pause",
         Commands::Resume { .. } => "resume",
         Commands::Reclaim { .. } => "reclaim",
-        Commands::Ready => "ready",
+        Commands::Ready { .. } => "ready",
         Commands::Blocked { .. } => "blocked",
         Commands::WhyBlocked { .. } => "why-blocked",
         Commands::Check => "check",
@@ -1884,18 +2285,25 @@ fn command_name(cmd: &Commands) -> &'static str {
         Commands::Velocity { .. } => "velocity",
         Commands::Aging => "aging",
         Commands::Forecast => "forecast",
+        Commands::AtRisk { .. } => "at-risk",
         Commands::Workload => "workload",
         Commands::Resources => "resources",
         Commands::CriticalPath => "critical-path",
         Commands::Analyze => "analyze",
         Commands::Archive { .. } => "archive",
         Commands::Gc { .. } => "gc",
+        Commands::Migrate { .. } => "migrate",
+        Commands::MergeDriver { .. } => "merge-driver",
         Commands::Show { .. } => "show",
+        Commands::Search { .. } => "search",
+        Commands::Undo { .. } => "undo",
+        Commands::Redo { .. } => "redo",
         Commands::Trace { .. } => "trace",
         Commands::Replay { .. } => "replay",
         Commands::Runs { .. } => "runs",
         Commands::Log { .. } => "log",
         Commands::Resource { .. } => "resource",
+        Commands::Schedule { .. } => "schedule",
         Commands::Skill { .. } => "skill",
         Commands::Identity { .. } => "identity",
         Commands::Peer { .. } => "peer",
@@ -1905,6 +2313,7 @@ fn command_name(cmd: &Commands) -> &'static str {
         Commands::Match { .. } => "match",
         Commands::Heartbeat { .. } => "heartbeat",
         Commands::Artifact { .. } => "artifact",
+        Commands::Output { .. } => "output",
         Commands::Context { .. } => "context",
         Commands::Next { .. } => "next",
         Commands::Trajectory { .. } => "trajectory",
@@ -1933,7 +2342,7 @@ fn command_name(cmd: &Commands) -> &'static str {
 fn supports_json(cmd: &Commands) -> bool {
     matches!(
         cmd,
-        Commands::Ready
+        Commands::Ready { .. }
             | Commands::Blocked { .. }
             | Commands::WhyBlocked { .. }
             | Commands::List { .. }
@@ -1946,6 +2355,7 @@ fn supports_json(cmd: &Commands) -> bool {
             | Commands::Velocity { .. }
             | Commands::Aging
             | Commands::Forecast
+            | Commands::AtRisk { .. }
             | Commands::Workload
             | Commands::Resources
             | Commands::CriticalPath
@@ -1953,11 +2363,13 @@ fn supports_json(cmd: &Commands) -> bool {
             | Commands::Archive { .. }
             | Commands::Gc { .. }
             | Commands::Show { .. }
+            | Commands::Search { .. }
             | Commands::Trace { .. }
             | Commands::Replay { .. }
             | Commands::Runs { .. }
             | Commands::Log { .. }
             | Commands::Resource { .. }
+            | Commands::Schedule { .. }
             | Commands::Skill { .. }
             | Commands::Identity { .. }
             | Commands::Peer { .. }
@@ -1966,6 +2378,7 @@ fn supports_json(cmd: &Commands) -> bool {
             | Commands::Match { .. }
             | Commands::Heartbeat { .. }
             | Commands::Artifact { .. }
+            | Commands::Output { .. }
             | Commands::Context { .. }
             | Commands::Next { .. }
             | Commands::Trajectory { .. }
@@ -2087,7 +2500,26 @@ fn main() -> Result<()> {
             loop_max,
             loop_guard,
             loop_delay,
+            priority,
+            value,
+            requires,
+            parent,
+            auto_complete,
+            field,
+            deadline,
+            schedule,
+            any_of,
+            quorum,
+            abandon_rest,
+            on_success,
+            on_failure,
+            retry_policy,
+            fan_out,
+            fan_out_description,
+            fan_out_items,
+            join,
         } => {
+            let verify = (!verify.is_empty()).then(|| verify.join("\n"));
             if let Some(ref peer_ref) = repo {
                 commands::add::run_remote(
                     &workgraph_dir,
@@ -2123,6 +2555,24 @@ fn main() -> Result<()> {
                     loop_max,
                     loop_guard.as_deref(),
                     loop_delay.as_deref(),
+                    priority.as_deref(),
+                    value,
+                    &requires,
+                    parent.as_deref(),
+                    auto_complete,
+                    &field,
+                    deadline.as_deref(),
+                    schedule.as_deref(),
+                    &any_of,
+                    &quorum,
+                    abandon_rest,
+                    &on_success,
+                    &on_failure,
+                    retry_policy.as_deref(),
+                    fan_out.as_deref(),
+                    fan_out_description.as_deref(),
+                    fan_out_items.as_deref(),
+                    join.as_deref(),
                 )
             }
         }
@@ -2143,6 +2593,20 @@ fn main() -> Result<()> {
             loop_delay,
             remove_loops_to,
             loop_iteration,
+            priority,
+            value,
+            add_requires,
+            remove_requires,
+            parent,
+            no_parent,
+            auto_complete,
+            no_auto_complete,
+            field,
+            remove_field,
+            deadline,
+            no_deadline,
+            retry_policy,
+            no_retry_policy,
         } => commands::edit::run(
             &workgraph_dir,
             &id,
@@ -2161,11 +2625,47 @@ fn main() -> Result<()> {
             loop_delay.as_deref(),
             remove_loops_to.as_deref(),
             loop_iteration,
+            priority.as_deref(),
+            value,
+            &add_requires,
+            &remove_requires,
+            parent.as_deref(),
+            no_parent,
+            match (auto_complete, no_auto_complete) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            },
+            &field,
+            &remove_field,
+            deadline.as_deref(),
+            no_deadline,
+            retry_policy.as_deref(),
+            no_retry_policy,
         ),
-        Commands::Done { id, converged } => commands::done::run(&workgraph_dir, &id, converged),
-        Commands::Fail { id, reason } => {
-            commands::fail::run(&workgraph_dir, &id, reason.as_deref())
+        Commands::Done {
+            id,
+            converged,
+            skip_verify,
+            actor,
+        } => commands::done::run(
+            &workgraph_dir,
+            &id,
+            converged,
+            skip_verify,
+            actor.as_deref(),
+        ),
+        Commands::Approve { id, actor } => {
+            commands::review::run_approve(&workgraph_dir, &id, actor.as_deref())
+        }
+        Commands::Reject { id, reason, actor } => {
+            commands::review::run_reject(&workgraph_dir, &id, &reason, actor.as_deref())
         }
+        Commands::Fail {
+            id,
+            reason,
+            exit_code,
+        } => commands::fail::run(&workgraph_dir, &id, reason.as_deref(), exit_code),
         Commands::Abandon { id, reason } => {
             commands::abandon::run(&workgraph_dir, &id, reason.as_deref())
         }
@@ -2179,13 +2679,29 @@ fn main() -> Result<()> {
         Commands::Reclaim { id, from, to } => {
             commands::reclaim::run(&workgraph_dir, &id, &from, &to)
         }
-        Commands::Ready => commands::ready::run(&workgraph_dir, cli.json),
+        Commands::Ready { filter } => {
+            commands::ready::run(&workgraph_dir, filter.as_deref(), cli.json)
+        }
         Commands::Blocked { id } => commands::blocked::run(&workgraph_dir, &id, cli.json),
         Commands::WhyBlocked { id } => commands::why_blocked::run(&workgraph_dir, &id, cli.json),
         Commands::Check => commands::check::run(&workgraph_dir, cli.json),
-        Commands::List { status, paused } => {
-            commands::list::run(&workgraph_dir, status.as_deref(), paused, cli.json)
-        }
+        Commands::List {
+            status,
+            paused,
+            tree,
+            field,
+            filter,
+            at,
+        } => commands::list::run(
+            &workgraph_dir,
+            status.as_deref(),
+            paused,
+            tree,
+            &field,
+            filter.as_deref(),
+            at.as_deref(),
+            cli.json,
+        ),
         Commands::Viz {
             all,
             status,
@@ -2195,6 +2711,11 @@ fn main() -> Result<()> {
             graph,
             output,
             show_internal,
+            clusters,
+            field,
+            filter,
+            at,
+            expand_fan_out,
         } => {
             let fmt = if dot {
                 commands::viz::OutputFormat::Dot
@@ -2212,6 +2733,11 @@ fn main() -> Result<()> {
                 format: fmt,
                 output,
                 show_internal,
+                clusters,
+                fields: field,
+                filter,
+                at,
+                expand_fan_out,
             };
             commands::viz::run(&workgraph_dir, &options)
         }
@@ -2219,7 +2745,14 @@ fn main() -> Result<()> {
             archive,
             since,
             until,
-        } => commands::graph::run(&workgraph_dir, archive, since.as_deref(), until.as_deref()),
+            filter,
+        } => commands::graph::run(
+            &workgraph_dir,
+            archive,
+            since.as_deref(),
+            until.as_deref(),
+            filter.as_deref(),
+        ),
         Commands::Cost { id } => commands::cost::run(&workgraph_dir, &id, cli.json),
         Commands::Coordinate { max_parallel } => {
             commands::coordinate::run(&workgraph_dir, cli.json, max_parallel)
@@ -2237,6 +2770,7 @@ fn main() -> Result<()> {
         Commands::Velocity { weeks } => commands::velocity::run(&workgraph_dir, cli.json, weeks),
         Commands::Aging => commands::aging::run(&workgraph_dir, cli.json),
         Commands::Forecast => commands::forecast::run(&workgraph_dir, cli.json),
+        Commands::AtRisk { within } => commands::at_risk::run(&workgraph_dir, within, cli.json),
         Commands::Workload => commands::workload::run(&workgraph_dir, cli.json),
         Commands::Resources => commands::resources::run(&workgraph_dir, cli.json),
         Commands::CriticalPath => commands::critical_path::run(&workgraph_dir, cli.json),
@@ -2245,12 +2779,39 @@ fn main() -> Result<()> {
             dry_run,
             older,
             list,
-        } => commands::archive::run(&workgraph_dir, dry_run, older.as_deref(), list, cli.json),
+            filter,
+        } => commands::archive::run(
+            &workgraph_dir,
+            dry_run,
+            older.as_deref(),
+            list,
+            filter.as_deref(),
+            cli.json,
+        ),
         Commands::Gc {
             dry_run,
             include_done,
-        } => commands::gc::run(&workgraph_dir, dry_run, include_done),
-        Commands::Show { id } => commands::show::run(&workgraph_dir, &id, cli.json),
+            filter,
+        } => commands::gc::run(&workgraph_dir, dry_run, include_done, filter.as_deref()),
+        Commands::Migrate {
+            to,
+            compact,
+            check,
+            apply,
+        } => commands::migrate::run(&workgraph_dir, to.as_deref(), compact, check, apply),
+        Commands::MergeDriver { base, ours, theirs } => {
+            commands::merge_driver::run(&base, &ours, &theirs)
+        }
+        Commands::Show { id, at } => {
+            commands::show::run(&workgraph_dir, &id, at.as_deref(), cli.json)
+        }
+        Commands::Search {
+            terms,
+            reindex,
+            limit,
+        } => commands::search::run(&workgraph_dir, &terms, reindex, limit, cli.json),
+        Commands::Undo { count, force } => commands::undo::run_undo(&workgraph_dir, count, force),
+        Commands::Redo { force } => commands::undo::run_redo(&workgraph_dir, force),
         Commands::Trace { command } => match command {
             TraceCommands::Show { id, full, ops_only, recursive, timeline } => {
                 if recursive || timeline {
@@ -2321,6 +2882,7 @@ fn main() -> Result<()> {
             keep_done,
             plan_only,
             subgraph,
+            filter,
         } => {
             let opts = commands::replay::ReplayOptions {
                 model,
@@ -2330,6 +2892,7 @@ fn main() -> Result<()> {
                 keep_done,
                 plan_only,
                 subgraph,
+                filter,
             };
             commands::replay::run(&workgraph_dir, &opts, cli.json)
         }
@@ -2387,6 +2950,18 @@ fn main() -> Result<()> {
             ),
             ResourceCommands::List => commands::resource::run_list(&workgraph_dir, cli.json),
         },
+        Commands::Schedule { command } => match command {
+            ScheduleCommands::List { count } => {
+                commands::schedule::run_list(&workgraph_dir, count, cli.json)
+            }
+            ScheduleCommands::Set {
+                id,
+                cron,
+                copy,
+                missed,
+            } => commands::schedule::run_set(&workgraph_dir, &id, &cron, copy, missed.as_deref()),
+            ScheduleCommands::Clear { id } => commands::schedule::run_clear(&workgraph_dir, &id),
+        },
         Commands::Skill { command } => match command {
             SkillCommands::List => commands::skills::run_list(&workgraph_dir, cli.json),
             SkillCommands::Task { id } => commands::skills::run_task(&workgraph_dir, &id, cli.json),
@@ -2566,17 +3141,49 @@ fn main() -> Result<()> {
                 commands::heartbeat::run_check_agents(&workgraph_dir, threshold, cli.json)
             }
         }
-        Commands::Artifact { task, path, remove } => {
-            if let Some(artifact_path) = path {
-                if remove {
-                    commands::artifact::run_remove(&workgraph_dir, &task, &artifact_path)
-                } else {
-                    commands::artifact::run_add(&workgraph_dir, &task, &artifact_path)
-                }
+        Commands::Output {
+            task,
+            values,
+            remove,
+        } => {
+            if values.is_empty() && remove.is_empty() {
+                commands::output::run_list(&workgraph_dir, &task, cli.json)
             } else {
-                commands::artifact::run_list(&workgraph_dir, &task, cli.json)
+                commands::output::run_set(&workgraph_dir, &task, &values, &remove)
             }
         }
+        Commands::Artifact {
+            command,
+            task,
+            path,
+            remove,
+        } => match command {
+            Some(ArtifactCommands::Add {
+                task,
+                path,
+                snapshot,
+            }) => commands::artifact::run_add(&workgraph_dir, &task, &path, snapshot),
+            Some(ArtifactCommands::Verify { task }) => {
+                commands::artifact::run_verify(&workgraph_dir, task.as_deref(), cli.json)
+            }
+            Some(ArtifactCommands::Checkout { task, paths, force }) => {
+                commands::artifact::run_checkout(&workgraph_dir, &task, &paths, force)
+            }
+            None => {
+                let Some(task) = task else {
+                    anyhow::bail!("Usage: wg artifact <TASK> [PATH]");
+                };
+                if let Some(artifact_path) = path {
+                    if remove {
+                        commands::artifact::run_remove(&workgraph_dir, &task, &artifact_path)
+                    } else {
+                        commands::artifact::run_add(&workgraph_dir, &task, &artifact_path, false)
+                    }
+                } else {
+                    commands::artifact::run_list(&workgraph_dir, &task, cli.json)
+                }
+            }
+        },
         Commands::Context { task, dependents } => {
             if dependents {
                 commands::context::run_dependents(&workgraph_dir, &task, cli.json)
@@ -19,8 +19,9 @@ use std::path::Path;
 
 use chrono::Utc;
 
-use crate::graph::{LogEntry, Status};
-use crate::parser::{load_graph, save_graph};
+use crate::completion::{check_completable, complete, read_guards};
+use crate::graph::{LogEntry, Status, Task, WorkGraph, skip_unreachable};
+use crate::parser::{ParseError, load_graph, with_graph_mut};
 
 /// A parsed command from a Matrix message
 #[derive(Debug, Clone, PartialEq)]
@@ -306,62 +307,95 @@ pub fn execute_command(workgraph_dir: &Path, command: &MatrixCommand, sender: &s
     }
 }
 
-/// Execute claim command
-pub fn execute_claim(workgraph_dir: &Path, task_id: &str, actor: Option<&str>) -> String {
+/// Why a command's transaction ended without writing the graph
+enum Aborted {
+    /// The command was refused; the reply explains why
+    Reply(String),
+    Graph(ParseError),
+}
+
+impl From<ParseError> for Aborted {
+    fn from(e: ParseError) -> Self {
+        Aborted::Graph(e)
+    }
+}
+
+/// Run a mutating command as a single graph transaction.
+///
+/// `f` returns the reply for the chat either way; an `Err` reply aborts the
+/// transaction, so nothing is written.
+fn with_graph(
+    workgraph_dir: &Path,
+    f: impl FnOnce(&mut WorkGraph) -> Result<String, String>,
+) -> String {
     let graph_path = workgraph_dir.join("graph.jsonl");
 
     if !graph_path.exists() {
         return "Error: Workgraph not initialized".to_string();
     }
 
-    let mut graph = match load_graph(&graph_path) {
-        Ok(g) => g,
-        Err(e) => return format!("Error loading graph: {}", e),
-    };
+    match with_graph_mut(&graph_path, |graph| f(graph).map_err(Aborted::Reply)) {
+        Ok(reply) | Err(Aborted::Reply(reply)) => reply,
+        Err(Aborted::Graph(e)) => format!("Error updating graph: {}", e),
+    }
+}
 
-    let task = match graph.get_task_mut(task_id) {
-        Some(t) => t,
-        None => return format!("Error: Task '{}' not found", task_id),
-    };
+/// Look up a task for a command, with the chat reply if it doesn't exist
+fn task_mut<'a>(graph: &'a mut WorkGraph, task_id: &str) -> Result<&'a mut Task, String> {
+    graph
+        .get_task_mut(task_id)
+        .ok_or_else(|| format!("Error: Task '{}' not found", task_id))
+}
 
-    match task.status {
-        Status::Open | Status::Blocked => {}
-        Status::InProgress => {
-            let holder = task
-                .assigned
-                .as_ref()
-                .map(|a| format!(" by {}", a))
-                .unwrap_or_default();
-            return format!("Task '{}' is already claimed{}", task_id, holder);
-        }
-        Status::Done => {
-            return format!("Task '{}' is already done", task_id);
-        }
-        Status::Failed => {
-            return format!(
-                "Cannot claim task '{}': task is Failed. Use 'wg retry' first.",
-                task_id
-            );
-        }
-        Status::Abandoned => {
-            return format!("Cannot claim task '{}': task is Abandoned", task_id);
+/// Execute claim command
+pub fn execute_claim(workgraph_dir: &Path, task_id: &str, actor: Option<&str>) -> String {
+    with_graph(workgraph_dir, |graph| {
+        let task = task_mut(graph, task_id)?;
+
+        match task.status {
+            Status::Open | Status::Blocked => {}
+            Status::InProgress => {
+                let holder = task
+                    .assigned
+                    .as_ref()
+                    .map(|a| format!(" by {}", a))
+                    .unwrap_or_default();
+                return Err(format!("Task '{}' is already claimed{}", task_id, holder));
+            }
+            Status::Done => {
+                return Err(format!("Task '{}' is already done", task_id));
+            }
+            Status::Failed => {
+                return Err(format!(
+                    "Cannot claim task '{}': task is Failed. Use 'wg retry' first.",
+                    task_id
+                ));
+            }
+            Status::Abandoned => {
+                return Err(format!(
+                    "Cannot claim task '{}': task is Abandoned",
+                    task_id
+                ));
+            }
+            Status::InReview => {
+                return Err(format!(
+                    "Cannot claim task '{}': task is awaiting review",
+                    task_id
+                ));
+            }
         }
-    }
 
-    task.status = Status::InProgress;
-    task.started_at = Some(Utc::now().to_rfc3339());
-    if let Some(actor_id) = actor {
-        task.assigned = Some(actor_id.to_string());
-    }
-
-    if let Err(e) = save_graph(&graph, &graph_path) {
-        return format!("Error saving graph: {}", e);
-    }
+        task.status = Status::InProgress;
+        task.started_at = Some(Utc::now().to_rfc3339());
+        if let Some(actor_id) = actor {
+            task.assigned = Some(actor_id.to_string());
+        }
 
-    match actor {
-        Some(actor_id) => format!("Claimed '{}' for '{}'", task_id, actor_id),
-        None => format!("Claimed '{}'", task_id),
-    }
+        Ok(match actor {
+            Some(actor_id) => format!("Claimed '{}' for '{}'", task_id, actor_id),
+            None => format!("Claimed '{}'", task_id),
+        })
+    })
 }
 
 /// Execute done command
@@ -372,135 +406,120 @@ pub fn execute_done(workgraph_dir: &Path, task_id: &str) -> String {
         return "Error: Workgraph not initialized".to_string();
     }
 
-    let mut graph = match load_graph(&graph_path) {
+    // Verification checks apply here as for 'wg done'; there's no skipping
+    // from chat. They and the loop guards can take as long as a build, so
+    // they run before the graph lock is taken.
+    let graph = match load_graph(&graph_path) {
         Ok(g) => g,
         Err(e) => return format!("Error loading graph: {}", e),
     };
-
-    let task = match graph.get_task_mut(task_id) {
-        Some(t) => t,
-        None => return format!("Error: Task '{}' not found", task_id),
-    };
-
-    if task.status == Status::Done {
-        return format!("Task '{}' is already done", task_id);
+    if let Some(task) = graph.get_task(task_id)
+        && !matches!(task.status, Status::Done | Status::InReview)
+    {
+        match crate::verify::run(task, workgraph_dir) {
+            Ok(failures) if failures.is_empty() => {}
+            Ok(failures) => {
+                let failed: Vec<String> = failures
+                    .iter()
+                    .map(|f| format!("{} ({})", f.check, f.reason))
+                    .collect();
+                return format!(
+                    "Cannot mark '{}' as done: verification failed: {}",
+                    task_id,
+                    failed.join("; ")
+                );
+            }
+            Err(e) => return format!("Error: {}", e),
+        }
     }
+    let guards = read_guards(&graph, workgraph_dir, task_id);
+    drop(graph);
 
-    task.status = Status::Done;
-    task.completed_at = Some(Utc::now().to_rfc3339());
+    with_graph(workgraph_dir, |graph| {
+        let task = task_mut(graph, task_id)?;
 
-    if let Err(e) = save_graph(&graph, &graph_path) {
-        return format!("Error saving graph: {}", e);
-    }
+        if task.status == Status::Done {
+            return Err(format!("Task '{}' is already done", task_id));
+        }
+        if task.status == Status::InReview {
+            return Err(format!(
+                "Task '{}' is awaiting review. Use 'wg approve' or 'wg reject'",
+                task_id
+            ));
+        }
 
-    format!("Marked '{}' as done", task_id)
+        check_completable(graph, task_id).map_err(|e| e.to_string())?;
+        let completed = complete(graph, workgraph_dir, task_id, &guards)
+            .map_err(|e| format!("Error: {}", e))?;
+
+        let mut reply = format!("Marked '{}' as done", task_id);
+        for parent_id in &completed.completed_parents {
+            reply.push_str(&format!("\n• Parent '{}' auto-completed", parent_id));
+        }
+        if let Some(children) = &completed.fanned_out {
+            reply.push_str(&format!("\n• Fanned out into {} task(s)", children.len()));
+        }
+        Ok(reply)
+    })
 }
 
 /// Execute fail command
 pub fn execute_fail(workgraph_dir: &Path, task_id: &str, reason: Option<&str>) -> String {
-    let graph_path = workgraph_dir.join("graph.jsonl");
+    with_graph(workgraph_dir, |graph| {
+        let task = task_mut(graph, task_id)?;
 
-    if !graph_path.exists() {
-        return "Error: Workgraph not initialized".to_string();
-    }
-
-    let mut graph = match load_graph(&graph_path) {
-        Ok(g) => g,
-        Err(e) => return format!("Error loading graph: {}", e),
-    };
-
-    let task = match graph.get_task_mut(task_id) {
-        Some(t) => t,
-        None => return format!("Error: Task '{}' not found", task_id),
-    };
-
-    if task.status == Status::Done {
-        return format!(
-            "Task '{}' is already done and cannot be marked as failed",
-            task_id
-        );
-    }
-
-    if task.status == Status::Failed {
-        return format!("Task '{}' is already failed", task_id);
-    }
+        if task.status == Status::Done {
+            return Err(format!(
+                "Task '{}' is already done and cannot be marked as failed",
+                task_id
+            ));
+        }
 
-    task.status = Status::Failed;
-    task.retry_count += 1;
-    task.failure_reason = reason.map(String::from);
+        if task.status == Status::Failed {
+            return Err(format!("Task '{}' is already failed", task_id));
+        }
 
-    let retry_count = task.retry_count;
+        task.status = Status::Failed;
+        task.retry_count += 1;
+        task.failure_reason = reason.map(String::from);
+        crate::retry::record_failure(task, None, None);
 
-    if let Err(e) = save_graph(&graph, &graph_path) {
-        return format!("Error saving graph: {}", e);
-    }
+        let retry_count = task.retry_count;
+        skip_unreachable(graph);
 
-    let reason_msg = reason.map(|r| format!(" ({})", r)).unwrap_or_default();
-    format!(
-        "Marked '{}' as failed{} (retry #{})",
-        task_id, reason_msg, retry_count
-    )
+        let reason_msg = reason.map(|r| format!(" ({})", r)).unwrap_or_default();
+        Ok(format!(
+            "Marked '{}' as failed{} (retry #{})",
+            task_id, reason_msg, retry_count
+        ))
+    })
 }
 
 /// Execute input/log command
 pub fn execute_input(workgraph_dir: &Path, task_id: &str, text: &str, actor: &str) -> String {
-    let graph_path = workgraph_dir.join("graph.jsonl");
-
-    if !graph_path.exists() {
-        return "Error: Workgraph not initialized".to_string();
-    }
-
-    let mut graph = match load_graph(&graph_path) {
-        Ok(g) => g,
-        Err(e) => return format!("Error loading graph: {}", e),
-    };
-
-    let task = match graph.get_task_mut(task_id) {
-        Some(t) => t,
-        None => return format!("Error: Task '{}' not found", task_id),
-    };
+    with_graph(workgraph_dir, |graph| {
+        let task = task_mut(graph, task_id)?;
 
-    let entry = LogEntry {
-        timestamp: Utc::now().to_rfc3339(),
-        actor: Some(actor.to_string()),
-        message: text.to_string(),
-    };
+        task.log.push(LogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            actor: Some(actor.to_string()),
+            message: text.to_string(),
+        });
 
-    task.log.push(entry);
-
-    if let Err(e) = save_graph(&graph, &graph_path) {
-        return format!("Error saving graph: {}", e);
-    }
-
-    format!("Added log entry to '{}' from {}", task_id, actor)
+        Ok(format!("Added log entry to '{}' from {}", task_id, actor))
+    })
 }
 
 /// Execute unclaim command
 pub fn execute_unclaim(workgraph_dir: &Path, task_id: &str) -> String {
-    let graph_path = workgraph_dir.join("graph.jsonl");
+    with_graph(workgraph_dir, |graph| {
+        let task = task_mut(graph, task_id)?;
 
-    if !graph_path.exists() {
-        return "Error: Workgraph not initialized".to_string();
-    }
+        task.status = Status::Open;
+        task.assigned = None;
 
-    let mut graph = match load_graph(&graph_path) {
-        Ok(g) => g,
-        Err(e) => return format!("Error loading graph: {}", e),
-    };
-
-    let task = match graph.get_task_mut(task_id) {
-        Some(t) => t,
-        None => return format!("Error: Task '{}' not found", task_id),
-    };
-
-    task.status = Status::Open;
-    task.assigned = None;
-
-    if let Err(e) = save_graph(&graph, &graph_path) {
-        return format!("Error saving graph: {}", e);
-    }
-
-    format!("Unclaimed '{}'", task_id)
+        Ok(format!("Unclaimed '{}'", task_id))
+    })
 }
 
 /// Execute status command
@@ -528,11 +547,28 @@ pub fn execute_status(workgraph_dir: &Path) -> String {
         .filter(|t| t.status == Status::Blocked)
         .count();
     let failed = graph.tasks().filter(|t| t.status == Status::Failed).count();
+    let in_review: Vec<_> = graph
+        .tasks()
+        .filter(|t| t.status == Status::InReview)
+        .collect();
 
-    format!(
-        "**Project Status**\n• Total: {} tasks\n• Done: {}\n• In Progress: {}\n• Open: {}\n• Blocked: {}\n• Failed: {}",
-        total, done, in_progress, open, blocked, failed
-    )
+    let mut response = format!(
+        "**Project Status**\n• Total: {} tasks\n• Done: {}\n• In Progress: {}\n• In Review: {}\n• Open: {}\n• Blocked: {}\n• Failed: {}",
+        total,
+        done,
+        in_progress,
+        in_review.len(),
+        open,
+        blocked,
+        failed
+    );
+    if !in_review.is_empty() {
+        response.push_str("\n\n**Awaiting Review**");
+        for task in &in_review {
+            response.push_str(&format!("\n• `{}`: {}", task.id, task.title));
+        }
+    }
+    response
 }
 
 /// Execute ready command
@@ -548,19 +584,8 @@ pub fn execute_ready(workgraph_dir: &Path) -> String {
         Err(e) => return format!("Error loading graph: {}", e),
     };
 
-    // Find ready tasks (open, not blocked)
-    let ready_tasks: Vec<_> = graph
-        .tasks()
-        .filter(|t| {
-            t.status == Status::Open
-                && t.blocked_by.iter().all(|dep| {
-                    graph
-                        .get_task(dep)
-                        .map(|d| d.status.is_terminal())
-                        .unwrap_or(true)
-                })
-        })
-        .collect();
+    // Ready tasks, in the order the coordinator would dispatch them
+    let ready_tasks = crate::query::ready_tasks(&graph);
 
     if ready_tasks.is_empty() {
         return "No tasks ready to work on".to_string();
@@ -783,4 +808,54 @@ mod tests {
         assert_eq!(extract_localpart("plainuser"), "plainuser");
         assert_eq!(extract_localpart("@bot:matrix.org"), "bot");
     }
+
+    #[test]
+    fn test_execute_claim_and_done() {
+        use crate::test_helpers::{make_task, setup_workgraph};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = setup_workgraph(dir.path(), vec![make_task("t1", "Task 1")]);
+        let before = std::fs::read_to_string(&path).unwrap();
+
+        // A rejected command leaves the graph file alone
+        assert_eq!(
+            execute_claim(dir.path(), "missing", None),
+            "Error: Task 'missing' not found"
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), before);
+
+        assert_eq!(
+            execute_claim(dir.path(), "t1", Some("erik")),
+            "Claimed 't1' for 'erik'"
+        );
+        assert_eq!(
+            execute_claim(dir.path(), "t1", None),
+            "Task 't1' is already claimed by erik"
+        );
+        assert_eq!(execute_done(dir.path(), "t1"), "Marked 't1' as done");
+
+        let graph = load_graph(&path).unwrap();
+        assert_eq!(graph.get_task("t1").unwrap().status, Status::Done);
+    }
+
+    #[test]
+    fn test_execute_ready_lists_tasks_in_dispatch_order() {
+        use crate::graph::Priority;
+        use crate::test_helpers::{make_task, setup_workgraph};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut urgent = make_task("urgent", "Urgent");
+        urgent.priority = Priority::Critical;
+        let mut paused = make_task("paused", "Paused");
+        paused.paused = true;
+        setup_workgraph(
+            dir.path(),
+            vec![make_task("plain", "Plain"), urgent, paused],
+        );
+
+        assert_eq!(
+            execute_ready(dir.path()),
+            "**Ready Tasks** (2)\n• `urgent`: Urgent\n• `plain`: Plain\n"
+        );
+    }
 }
@@ -0,0 +1,427 @@
+//! Semantic three-way merge of `graph.jsonl`
+//!
+//! Used by `wg merge-driver %O %A %B` as a git merge driver. Instead of
+//! merging lines, the base, ours and theirs graphs are merged node by node and
+//! field by field:
+//!
+//! - a field changed on one side only takes that side's value;
+//! - list fields (`log`, `artifacts`, `tags`, `blocked_by`, ...) keep the
+//!   items either side added and drop the ones either side removed, and
+//!   `log` stays in timestamp order;
+//! - `status` takes the furthest-progressed value, and the fields that go
+//!   with it (`assigned`, `started_at`, `completed_at`, `failure_reason`)
+//!   follow the winning side;
+//! - counters (`retry_count`, `loop_iteration`) take the larger value;
+//! - maps (`fields`, `resource_amounts`, `estimate`) merge key by key.
+//!
+//! Anything else changed differently on both sides is a real conflict: ours
+//! is kept, the task is tagged [`CONFLICT_TAG`] and a log entry records the
+//! other side's value, so the result is always a valid graph.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+
+use crate::graph::{Node, WorkGraph};
+
+/// Tag added to tasks whose merge needs a human decision
+pub const CONFLICT_TAG: &str = "conflict";
+
+/// Fields that follow the side whose `status` wins
+const STATUS_FIELDS: &[&str] = &["assigned", "started_at", "completed_at", "failure_reason"];
+/// Counters that only ever grow
+const COUNTER_FIELDS: &[&str] = &["retry_count", "loop_iteration"];
+
+/// A node that could not be merged cleanly
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub id: String,
+    /// Conflicting fields (`fields.<key>` for custom fields), or `deleted`
+    /// when one side removed a node the other changed
+    pub fields: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    Ours,
+    Theirs,
+}
+
+/// Merge `ours` and `theirs`, both derived from `base`.
+pub fn merge(
+    base: &WorkGraph,
+    ours: &WorkGraph,
+    theirs: &WorkGraph,
+) -> Result<(WorkGraph, Vec<Conflict>)> {
+    let ids: BTreeSet<&str> = base
+        .nodes()
+        .chain(ours.nodes())
+        .chain(theirs.nodes())
+        .map(|n| n.id())
+        .collect();
+
+    let mut merged = WorkGraph::new();
+    let mut conflicts = Vec::new();
+    for id in ids {
+        let o = base.get_node(id);
+        let a = ours.get_node(id);
+        let b = theirs.get_node(id);
+        let node = match (o, a, b) {
+            (_, Some(a), Some(b)) if a == b => Some(a.clone()),
+            (Some(o), Some(a), None) | (Some(o), None, Some(a)) if a == o => None,
+            (None, Some(a), None) | (None, None, Some(a)) => Some(a.clone()),
+            (Some(_), Some(kept), None) | (Some(_), None, Some(kept)) => {
+                // Removed on one side (e.g. archived), changed on the other
+                let note =
+                    "removed on one branch and changed on the other; kept the changed version";
+                let node = flag(kept.clone(), &["deleted".to_string()], note)?;
+                conflicts.push(Conflict {
+                    id: id.to_string(),
+                    fields: vec!["deleted".to_string()],
+                });
+                Some(node)
+            }
+            (o, Some(a), Some(b)) => {
+                let (node, fields) = merge_node(o, a, b)?;
+                if !fields.is_empty() {
+                    conflicts.push(Conflict {
+                        id: id.to_string(),
+                        fields,
+                    });
+                }
+                Some(node)
+            }
+            (_, None, None) => None,
+        };
+        if let Some(node) = node {
+            merged.add_node(node);
+        }
+    }
+    Ok((merged, conflicts))
+}
+
+/// Run the merge driver: merge the graph files git hands over and write the
+/// result to `ours`, as git expects of a merge driver.
+pub fn merge_files(base: &Path, ours: &Path, theirs: &Path) -> Result<Vec<Conflict>> {
+    let read = |path: &Path| {
+        crate::parser::read_graph_file(path)
+            .with_context(|| format!("Failed to read graph {}", path.display()))
+    };
+    let (merged, conflicts) = merge(&read(base)?, &read(ours)?, &read(theirs)?)?;
+    crate::parser::write_snapshot_unlocked(&merged, ours)
+        .with_context(|| format!("Failed to write merged graph to {}", ours.display()))?;
+    Ok(conflicts)
+}
+
+fn to_map(node: &Node) -> Result<Map<String, Value>> {
+    match serde_json::to_value(node)? {
+        Value::Object(map) => Ok(map),
+        _ => anyhow::bail!("Node '{}' does not serialize to an object", node.id()),
+    }
+}
+
+fn from_map(map: Map<String, Value>) -> Result<Node> {
+    serde_json::from_value(Value::Object(map)).context("Merged node is not valid")
+}
+
+fn merge_node(base: Option<&Node>, ours: &Node, theirs: &Node) -> Result<(Node, Vec<String>)> {
+    let o = base.map(to_map).transpose()?.unwrap_or_default();
+    let a = to_map(ours)?;
+    let b = to_map(theirs)?;
+
+    let status_winner = match (a.get("status"), b.get("status")) {
+        (Some(x), Some(y))
+            if x != y && o.get("status") != Some(x) && o.get("status") != Some(y) =>
+        {
+            match status_rank(x).cmp(&status_rank(y)) {
+                std::cmp::Ordering::Greater => Some(Side::Ours),
+                std::cmp::Ordering::Less => Some(Side::Theirs),
+                std::cmp::Ordering::Equal => None,
+            }
+        }
+        _ => None,
+    };
+
+    let mut merged = Map::new();
+    let mut conflicts = Vec::new();
+    let mut notes = Vec::new();
+    let keys: BTreeSet<&String> = o.keys().chain(a.keys()).chain(b.keys()).collect();
+    for key in keys {
+        let value = match merge_value(key, o.get(key), a.get(key), b.get(key)) {
+            Merged::Value(value) => value,
+            Merged::Conflict => {
+                let side = if key == "status" || STATUS_FIELDS.contains(&key.as_str()) {
+                    status_winner
+                } else {
+                    None
+                };
+                match side {
+                    Some(Side::Theirs) => b.get(key).cloned(),
+                    Some(Side::Ours) => a.get(key).cloned(),
+                    None => {
+                        conflicts.push(key.clone());
+                        notes.push(format!("{} (theirs: {})", key, show(b.get(key))));
+                        a.get(key).cloned()
+                    }
+                }
+            }
+            Merged::Nested(value, keys) => {
+                for (sub, theirs) in keys {
+                    conflicts.push(format!("{}.{}", key, sub));
+                    notes.push(format!(
+                        "{}.{} (theirs: {})",
+                        key,
+                        sub,
+                        show(theirs.as_ref())
+                    ));
+                }
+                Some(value)
+            }
+        };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value);
+        }
+    }
+
+    let node = from_map(merged)?;
+    if conflicts.is_empty() {
+        return Ok((node, conflicts));
+    }
+    let note = format!("kept ours for {}", notes.join(", "));
+    Ok((flag(node, &conflicts, &note)?, conflicts))
+}
+
+enum Merged {
+    /// The merged value, or `None` for a field absent from the result
+    Value(Option<Value>),
+    /// Both sides changed the field differently
+    Conflict,
+    /// A map merged key by key, with the conflicting keys and their values
+    /// on the other side
+    Nested(Value, Vec<(String, Option<Value>)>),
+}
+
+fn merge_value(key: &str, o: Option<&Value>, a: Option<&Value>, b: Option<&Value>) -> Merged {
+    if a == b || b == o {
+        return Merged::Value(a.cloned());
+    }
+    if a == o {
+        return Merged::Value(b.cloned());
+    }
+    match (a, b) {
+        (Some(Value::Array(_)) | None, Some(Value::Array(_)) | None) => {
+            let items = merge_list(key, o, a, b);
+            Merged::Value((!items.is_empty()).then_some(Value::Array(items)))
+        }
+        (Some(Value::Number(x)), Some(Value::Number(y))) if COUNTER_FIELDS.contains(&key) => {
+            let larger = if x.as_f64() >= y.as_f64() { x } else { y };
+            Merged::Value(Some(Value::Number(larger.clone())))
+        }
+        (Some(Value::Object(x)), Some(Value::Object(y))) => {
+            let empty = Map::new();
+            let base = match o {
+                Some(Value::Object(map)) => map,
+                _ => &empty,
+            };
+            let mut merged = Map::new();
+            let mut conflicts = Vec::new();
+            let keys: BTreeSet<&String> = base.keys().chain(x.keys()).chain(y.keys()).collect();
+            for sub in keys {
+                let (o, a, b) = (base.get(sub), x.get(sub), y.get(sub));
+                let value = if a == b || b == o {
+                    a
+                } else if a == o {
+                    b
+                } else {
+                    conflicts.push((sub.clone(), b.cloned()));
+                    a
+                };
+                if let Some(value) = value {
+                    merged.insert(sub.clone(), value.clone());
+                }
+            }
+            Merged::Nested(Value::Object(merged), conflicts)
+        }
+        _ => Merged::Conflict,
+    }
+}
+
+/// Three-way merge of a list: ours in order, then theirs' additions; items
+/// either side removed from the base are dropped. `log` is kept in
+/// timestamp order.
+fn merge_list(key: &str, o: Option<&Value>, a: Option<&Value>, b: Option<&Value>) -> Vec<Value> {
+    let items = |v: Option<&Value>| match v {
+        Some(Value::Array(items)) => items.clone(),
+        _ => Vec::new(),
+    };
+    let (o, a, b) = (items(o), items(a), items(b));
+    let removed = |item: &Value| o.contains(item) && (!a.contains(item) || !b.contains(item));
+
+    let mut merged: Vec<Value> = Vec::new();
+    for item in a.iter().chain(b.iter()) {
+        if !removed(item) && !merged.contains(item) {
+            merged.push(item.clone());
+        }
+    }
+    if key == "log" {
+        merged.sort_by(|x, y| {
+            let ts = |v: &Value| {
+                v.get("timestamp")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            };
+            ts(x).cmp(&ts(y))
+        });
+    }
+    merged
+}
+
+/// How far a status is along the task lifecycle
+fn status_rank(status: &Value) -> u8 {
+    match status.as_str() {
+        Some("blocked") => 1,
+        Some("in-progress") => 2,
+        Some("in-review") => 3,
+        Some("failed") => 4,
+        Some("done") | Some("abandoned") => 5,
+        _ => 0,
+    }
+}
+
+fn show(value: Option<&Value>) -> String {
+    value.map_or_else(|| "unset".to_string(), Value::to_string)
+}
+
+/// Tag a task as conflicted and log what happened
+fn flag(node: Node, fields: &[String], note: &str) -> Result<Node> {
+    let Node::Task(mut task) = node else {
+        return Ok(node);
+    };
+    if !task.tags.iter().any(|t| t == CONFLICT_TAG) {
+        task.tags.push(CONFLICT_TAG.to_string());
+    }
+    task.log.push(crate::graph::LogEntry {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        actor: Some("merge-driver".to_string()),
+        message: format!("Merge conflict in {}: {}", fields.join(", "), note),
+    });
+    Ok(Node::Task(task))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{LogEntry, Status, Task};
+
+    fn task(id: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            title: id.to_string(),
+            ..Task::default()
+        }
+    }
+
+    fn graph(tasks: Vec<Task>) -> WorkGraph {
+        let mut graph = WorkGraph::new();
+        for task in tasks {
+            graph.add_node(Node::Task(task));
+        }
+        graph
+    }
+
+    fn log(ts: &str, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: ts.to_string(),
+            actor: None,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_merge_unions_lists_and_takes_furthest_status() {
+        let mut t = task("t");
+        t.log.push(log("2026-01-01T00:00:00Z", "created"));
+        t.artifacts.push("a.txt".to_string());
+        let base = graph(vec![t.clone(), task("gone")]);
+
+        let mut ours = t.clone();
+        ours.status = Status::InProgress;
+        ours.assigned = Some("alice".to_string());
+        ours.log.push(log("2026-01-03T00:00:00Z", "started"));
+        ours.artifacts.push("b.txt".to_string());
+        let mut theirs = t.clone();
+        theirs.status = Status::Done;
+        theirs.assigned = Some("bob".to_string());
+        theirs.log.push(log("2026-01-02T00:00:00Z", "finished"));
+        theirs.artifacts.clear();
+        theirs.artifacts.push("c.txt".to_string());
+        theirs.retry_count = 2;
+
+        // 'gone' is removed on our side, unchanged on theirs; 'new' only on theirs
+        let ours = graph(vec![ours]);
+        let theirs = graph(vec![theirs, task("gone"), task("new")]);
+
+        let (merged, conflicts) = merge(&base, &ours, &theirs).unwrap();
+        assert!(conflicts.is_empty(), "{:?}", conflicts);
+        assert!(merged.get_task("gone").is_none());
+        assert!(merged.get_task("new").is_some());
+
+        let t = merged.get_task("t").unwrap();
+        assert_eq!(t.status, Status::Done);
+        assert_eq!(t.assigned.as_deref(), Some("bob"));
+        assert_eq!(t.retry_count, 2);
+        assert_eq!(t.artifacts, vec!["b.txt", "c.txt"]);
+        let messages: Vec<&str> = t.log.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["created", "finished", "started"]);
+        assert!(!t.tags.iter().any(|t| t == CONFLICT_TAG));
+    }
+
+    #[test]
+    fn test_merge_flags_real_conflicts() {
+        let mut t = task("t");
+        t.fields.insert("risk".to_string(), Value::from("low"));
+        let base = graph(vec![t.clone(), task("archived")]);
+
+        let mut ours = t.clone();
+        ours.title = "Our title".to_string();
+        ours.fields.insert("risk".to_string(), Value::from("high"));
+        ours.fields.insert("owner".to_string(), Value::from("ops"));
+        let mut theirs = t.clone();
+        theirs.title = "Their title".to_string();
+        theirs
+            .fields
+            .insert("risk".to_string(), Value::from("medium"));
+        let mut changed = task("archived");
+        changed.description = Some("still needed".to_string());
+
+        let ours = graph(vec![ours]);
+        let theirs = graph(vec![theirs, changed]);
+        let (merged, conflicts) = merge(&base, &ours, &theirs).unwrap();
+
+        assert_eq!(
+            conflicts,
+            vec![
+                Conflict {
+                    id: "archived".to_string(),
+                    fields: vec!["deleted".to_string()],
+                },
+                Conflict {
+                    id: "t".to_string(),
+                    fields: vec!["fields.risk".to_string(), "title".to_string()],
+                },
+            ]
+        );
+        let t = merged.get_task("t").unwrap();
+        assert_eq!(t.title, "Our title");
+        assert_eq!(t.fields["risk"], "high");
+        assert_eq!(t.fields["owner"], "ops");
+        assert!(t.tags.contains(&CONFLICT_TAG.to_string()));
+        assert!(t.log.last().unwrap().message.contains("Their title"));
+
+        let archived = merged.get_task("archived").unwrap();
+        assert_eq!(archived.description.as_deref(), Some("still needed"));
+        assert!(archived.tags.contains(&CONFLICT_TAG.to_string()));
+    }
+}
@@ -0,0 +1,207 @@
+//! Structured task outputs
+//!
+//! A task records named values with `wg output <task> key=value` (a string),
+//! `key:=<json>` (a typed value) or a JSON object, stored in its `outputs`. Dependents read them as
+//! `{{deps.<task>.<key>}}` in their description and executor prompt (see
+//! `TemplateVars`), and `wg context` lists them.
+
+use crate::graph::{Task, WorkGraph};
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Outputs of a task's dependencies, by dependency ID
+pub type DepOutputs = BTreeMap<String, BTreeMap<String, Value>>;
+
+/// Parse `wg output` arguments: `key=value` pairs, stored as strings, and
+/// `key:=<json>` pairs, stored as the JSON value (`count:=3`, `ok:=true`,
+/// `ids:=[1,2]`); or a single JSON object
+pub fn parse_args(args: &[String]) -> Result<BTreeMap<String, Value>> {
+    if let [arg] = args
+        && arg.trim_start().starts_with('{')
+    {
+        let Value::Object(map) = serde_json::from_str(arg)? else {
+            anyhow::bail!("Expected a JSON object");
+        };
+        for key in map.keys() {
+            validate_key(key)?;
+        }
+        return Ok(map.into_iter().collect());
+    }
+    let mut outputs = BTreeMap::new();
+    for arg in args {
+        let (key, value) = arg.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!("Invalid output '{}'. Use key=value or key:=<json>", arg)
+        })?;
+        let (key, value) = match key.strip_suffix(':') {
+            Some(key) => (
+                key,
+                serde_json::from_str(value)
+                    .map_err(|e| anyhow::anyhow!("Invalid JSON value in '{}': {}", arg, e))?,
+            ),
+            None => (key, Value::String(value.to_string())),
+        };
+        let key = key.trim();
+        validate_key(key)?;
+        outputs.insert(key.to_string(), value);
+    }
+    Ok(outputs)
+}
+
+/// Output keys are referenced in `{{deps.<task>.<key>}}`, so they can't
+/// contain dots, braces or whitespace
+pub fn validate_key(key: &str) -> Result<()> {
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        anyhow::bail!(
+            "Invalid output key '{}'. Use letters, digits, '_' and '-'",
+            key
+        );
+    }
+    Ok(())
+}
+
+/// A value as substituted into templates: strings as-is, anything else as JSON
+pub fn render(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The outputs of `task`'s dependencies that have any
+pub fn dep_outputs(graph: &WorkGraph, task: &Task) -> DepOutputs {
+    task.blocked_by
+        .iter()
+        .filter_map(|dep| graph.get_task(dep))
+        .filter(|dep| !dep.outputs.is_empty())
+        .map(|dep| (dep.id.clone(), dep.outputs.clone()))
+        .collect()
+}
+
+/// Replace each `{{deps.<task>.<key>}}` in `template` with the value, leaving
+/// references to missing values in place. Substituted values are not scanned
+/// again, so a value containing a reference is inserted as-is.
+pub fn substitute(template: &str, outputs: &DepOutputs) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{deps.") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + "{{deps.".len()..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let value = after[..end]
+            .rsplit_once('.')
+            .and_then(|(task_id, key)| outputs.get(task_id)?.get(key));
+        match value {
+            Some(value) => result.push_str(&render(value)),
+            None => result.push_str(&rest[start..start + "{{deps.".len() + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// The `<task>.<key>` of each `{{deps.<task>.<key>}}` reference in `template`
+pub fn references(template: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{deps.") {
+        rest = &rest[start + "{{deps.".len()..];
+        let Some(end) = rest.find("}}") else {
+            break;
+        };
+        let reference = &rest[..end];
+        if reference.contains('.') && !refs.iter().any(|r| r == reference) {
+            refs.push(reference.to_string());
+        }
+        rest = &rest[end..];
+    }
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+
+    #[test]
+    fn test_parse_args() {
+        let args: Vec<String> = [
+            "count:=3",
+            "ok:=true",
+            "name=report.md",
+            "tags:=[\"a\"]",
+            "zip=01234",
+            "flag=true",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let outputs = parse_args(&args).unwrap();
+        assert_eq!(outputs["count"], Value::from(3));
+        assert_eq!(outputs["ok"], Value::Bool(true));
+        assert_eq!(outputs["name"], Value::from("report.md"));
+        assert_eq!(outputs["tags"], serde_json::json!(["a"]));
+        // Plain values stay strings, even when they look like JSON
+        assert_eq!(outputs["zip"], Value::from("01234"));
+        assert_eq!(outputs["flag"], Value::from("true"));
+
+        let outputs = parse_args(&[r#"{"url": "https://x", "n": 1}"#.to_string()]).unwrap();
+        assert_eq!(outputs.len(), 2);
+
+        assert!(parse_args(&["novalue".to_string()]).is_err());
+        assert!(parse_args(&["a.b=1".to_string()]).is_err());
+        assert!(parse_args(&["n:=three".to_string()]).is_err());
+        assert!(parse_args(&["[1]".to_string()]).is_err());
+        assert!(parse_args(&[r#"{"a.b": 1}"#.to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_substitute_dep_outputs() {
+        let mut graph = WorkGraph::new();
+        let mut build = Task {
+            id: "build".to_string(),
+            ..Task::default()
+        };
+        build
+            .outputs
+            .insert("version".to_string(), Value::from("1.2.0"));
+        build.outputs.insert("size".to_string(), Value::from(42));
+        graph.add_node(Node::Task(build));
+        let deploy = Task {
+            id: "deploy".to_string(),
+            blocked_by: vec!["build".to_string()],
+            ..Task::default()
+        };
+        graph.add_node(Node::Task(deploy.clone()));
+
+        let outputs = dep_outputs(&graph, &deploy);
+        let template = "Ship {{deps.build.version}} ({{deps.build.size}} MB), {{deps.build.notes}}";
+        assert_eq!(
+            substitute(template, &outputs),
+            "Ship 1.2.0 (42 MB), {{deps.build.notes}}"
+        );
+        assert_eq!(
+            references(template),
+            vec!["build.version", "build.size", "build.notes"]
+        );
+
+        // A value that looks like a reference is not expanded again
+        let mut outputs = outputs;
+        outputs
+            .get_mut("build")
+            .unwrap()
+            .insert("version".to_string(), Value::from("{{deps.build.size}}"));
+        assert_eq!(
+            substitute("v{{deps.build.version}} {{deps.build", &outputs),
+            "v{{deps.build.size}} {{deps.build"
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use crate::graph::{Node, WorkGraph};
+use crate::schema::{self, SCHEMA_VERSION};
+use serde::Deserialize;
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
@@ -18,10 +20,23 @@ pub enum ParseError {
     },
     #[error("Lock error: {0}")]
     Lock(String),
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error(
+        "Graph is stored in SQLite ({0}) but this build lacks the `sqlite` feature; rebuild with `--features sqlite`"
+    )]
+    SqliteUnsupported(PathBuf),
+    #[error("Task not found: {0}")]
+    TaskNotFound(String),
+    #[error(
+        "Graph uses schema version {found}, newer than this wg supports ({supported}); upgrade wg to open it"
+    )]
+    SchemaTooNew { found: u32, supported: u32 },
 }
 
 /// RAII guard for file locks - automatically releases lock on drop
-struct FileLock {
+pub(crate) struct FileLock {
     #[cfg(unix)]
     file: File,
 }
@@ -29,7 +44,7 @@ struct FileLock {
 impl FileLock {
     /// Acquire an exclusive lock on a lock file
     #[cfg(unix)]
-    fn acquire<P: AsRef<Path>>(lock_path: P) -> Result<Self, ParseError> {
+    pub(crate) fn acquire<P: AsRef<Path>>(lock_path: P) -> Result<Self, ParseError> {
         use std::os::unix::io::AsRawFd;
 
         // Ensure the .workgraph directory exists
@@ -61,7 +76,7 @@ impl FileLock {
     }
 
     #[cfg(not(unix))]
-    fn acquire<P: AsRef<Path>>(_lock_path: P) -> Result<Self, ParseError> {
+    pub(crate) fn acquire<P: AsRef<Path>>(_lock_path: P) -> Result<Self, ParseError> {
         // On non-Unix systems, we can't use flock - return a no-op lock
         // This is a limitation but workgraph is primarily for Unix systems
         Ok(FileLock {})
@@ -82,7 +97,7 @@ impl Drop for FileLock {
 }
 
 /// Get the lock file path for a given graph file
-fn get_lock_path<P: AsRef<Path>>(graph_path: P) -> PathBuf {
+pub(crate) fn get_lock_path<P: AsRef<Path>>(graph_path: P) -> PathBuf {
     let graph_path = graph_path.as_ref();
     if let Some(parent) = graph_path.parent() {
         parent.join("graph.lock")
@@ -91,15 +106,55 @@ fn get_lock_path<P: AsRef<Path>>(graph_path: P) -> PathBuf {
     }
 }
 
-/// Load a work graph from a JSONL file
-/// Uses advisory file locking to prevent concurrent access corruption
-pub fn load_graph<P: AsRef<Path>>(path: P) -> Result<WorkGraph, ParseError> {
-    let lock_path = get_lock_path(&path);
-    let _lock = FileLock::acquire(&lock_path)?;
+/// Number of journal records after which `save_graph` folds the journal back
+/// into the snapshot.
+pub const JOURNAL_COMPACT_THRESHOLD: usize = 1000;
+
+/// A delta record in the graph journal.
+///
+/// Replaying records is idempotent: `put` stores the full node and `remove`
+/// drops it, so applying a record that is already reflected in the snapshot
+/// (e.g. after a crash mid-compaction) leaves the graph unchanged.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+#[allow(clippy::large_enum_variant)]
+enum JournalEntry {
+    Put { node: Node },
+    Remove { id: String },
+}
+
+/// Get the journal file path for a given graph file (`graph.jsonl` -> `graph.journal`)
+pub fn journal_path<P: AsRef<Path>>(graph_path: P) -> PathBuf {
+    graph_path.as_ref().with_extension("journal")
+}
 
+/// Whether the graph at `graph_path` uses journaled storage.
+///
+/// In journaled mode `graph.jsonl` is the last compacted snapshot and
+/// mutations are appended to `graph.journal` as delta records.
+pub fn is_journaled<P: AsRef<Path>>(graph_path: P) -> bool {
+    journal_path(graph_path).exists()
+}
+
+/// Refuse graphs written by a newer `wg`
+pub(crate) fn check_schema_version(version: u32) -> Result<(), ParseError> {
+    if version > SCHEMA_VERSION {
+        return Err(ParseError::SchemaTooNew {
+            found: version,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    Ok(())
+}
+
+/// Read the snapshot file (plain JSONL, one node per line, after the schema
+/// header). Nodes written in an older schema version are migrated. Returns
+/// the graph and the version the file was written in.
+fn read_snapshot(path: &Path) -> Result<(WorkGraph, u32), ParseError> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
-    let mut graph = WorkGraph::new();
+    let mut header = None;
+    let mut values = Vec::new();
 
     for (line_num, line) in reader.lines().enumerate() {
         let line = line?;
@@ -107,39 +162,144 @@ pub fn load_graph<P: AsRef<Path>>(path: P) -> Result<WorkGraph, ParseError> {
         if trimmed.is_empty() || trimmed.starts_with('#') {
             continue;
         }
-        // Skip legacy Actor nodes (removed in actor-system cleanup)
-        if let Ok(v) = serde_json::from_str::<serde_json::Value>(trimmed)
-            && v.get("kind").and_then(|k| k.as_str()) == Some("actor")
-        {
+        let value: serde_json::Value =
+            serde_json::from_str(trimmed).map_err(|e| ParseError::Json {
+                line: line_num + 1,
+                source: e,
+            })?;
+        match schema::header_version(&value) {
+            Some(version) => header = Some(version),
+            None => values.push((line_num + 1, value)),
+        }
+    }
+
+    // A file without a header predates schema versions
+    let version = header.unwrap_or(if values.is_empty() { SCHEMA_VERSION } else { 0 });
+    check_schema_version(version)?;
+
+    let mut graph = WorkGraph::new();
+    for (line, mut value) in values {
+        if !schema::migrate_node(version, &mut value) {
             continue;
         }
-        let node: Node = serde_json::from_str(trimmed).map_err(|e| ParseError::Json {
-            line: line_num + 1,
-            source: e,
-        })?;
+        let node: Node =
+            serde_json::from_value(value).map_err(|e| ParseError::Json { line, source: e })?;
         let node_id = node.id().to_string();
         if graph.get_node(&node_id).is_some() {
             eprintln!(
                 "Warning: duplicate node ID '{}' at line {} (overwriting previous definition)",
-                node_id,
-                line_num + 1
+                node_id, line
             );
         }
         graph.add_node(node);
     }
 
-    Ok(graph)
-    // Lock is automatically released when _lock goes out of scope
+    Ok((graph, version))
 }
 
-/// Save a work graph to a JSONL file
-/// Uses advisory file locking and atomic write (temp file + rename) to
-/// prevent data loss on crash.
-pub fn save_graph<P: AsRef<Path>>(graph: &WorkGraph, path: P) -> Result<(), ParseError> {
+/// Replay journal records over `graph`, migrating nodes written before
+/// schema `version`. Returns the number of records applied.
+///
+/// A trailing record without a newline is the remains of an interrupted
+/// append and is skipped with a warning.
+fn replay_journal(graph: &mut WorkGraph, journal: &Path, version: u32) -> Result<usize, ParseError> {
+    let content = match std::fs::read_to_string(journal) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut lines: Vec<&str> = content.split('\n').collect();
+    // split() yields a final "" when the file ends with a newline
+    if let Some(last) = lines.pop()
+        && !last.trim().is_empty()
+    {
+        eprintln!(
+            "Warning: ignoring incomplete trailing record in {:?}",
+            journal
+        );
+    }
+
+    let mut applied = 0;
+    for (line_num, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let json_error = |e| ParseError::Json {
+            line: line_num + 1,
+            source: e,
+        };
+        let mut value: serde_json::Value = serde_json::from_str(trimmed).map_err(json_error)?;
+        applied += 1;
+        if let Some(node) = value.get_mut("node")
+            && !schema::migrate_node(version, node)
+        {
+            continue;
+        }
+        match serde_json::from_value(value).map_err(json_error)? {
+            JournalEntry::Put { node } => graph.add_node(node),
+            JournalEntry::Remove { id } => {
+                graph.take_node(&id);
+            }
+        }
+    }
+
+    Ok(applied)
+}
+
+/// Get the SQLite database path for a given graph file (`graph.jsonl` -> `graph.db`)
+pub fn sqlite_path<P: AsRef<Path>>(graph_path: P) -> PathBuf {
+    graph_path.as_ref().with_extension("db")
+}
+
+/// Whether the graph at `graph_path` is stored in SQLite.
+///
+/// In SQLite mode `graph.jsonl` only holds a comment pointing at `graph.db`,
+/// and every load and save goes through the database.
+pub fn is_sqlite<P: AsRef<Path>>(graph_path: P) -> bool {
+    sqlite_path(graph_path).exists()
+}
+
+/// Read a plain JSONL graph file without locking it or replaying a journal,
+/// e.g. a version of `graph.jsonl` handed over by git.
+pub fn read_graph_file<P: AsRef<Path>>(path: P) -> Result<WorkGraph, ParseError> {
+    read_snapshot(path.as_ref()).map(|(graph, _)| graph)
+}
+
+/// Read the current graph state (snapshot plus journal) without locking.
+/// Returns the graph and the number of journal records replayed.
+pub(crate) fn read_graph_unlocked(path: &Path) -> Result<(WorkGraph, usize), ParseError> {
+    let db = sqlite_path(path);
+    if db.exists() {
+        #[cfg(feature = "sqlite")]
+        return crate::store::sqlite::read_graph(&db).map(|graph| (graph, 0));
+        #[cfg(not(feature = "sqlite"))]
+        return Err(ParseError::SqliteUnsupported(db));
+    }
+
+    let (mut graph, version) = read_snapshot(path)?;
+    let journal_len = replay_journal(&mut graph, &journal_path(path), version)?;
+    Ok((graph, journal_len))
+}
+
+/// Load a work graph from a JSONL file
+/// Uses advisory file locking to prevent concurrent access corruption
+///
+/// If the graph is journaled, journal records are replayed over the snapshot.
+pub fn load_graph<P: AsRef<Path>>(path: P) -> Result<WorkGraph, ParseError> {
     let path = path.as_ref();
     let lock_path = get_lock_path(path);
     let _lock = FileLock::acquire(&lock_path)?;
 
+    read_graph_unlocked(path).map(|(graph, _)| graph)
+    // Lock is automatically released when _lock goes out of scope
+}
+
+/// Write the full graph as a snapshot using an atomic write (temp file + rename).
+/// The schema header comes first, then nodes in ID order so the file diffs (and merges) minimally.
+/// Caller must hold the graph lock.
+pub(crate) fn write_snapshot_unlocked(graph: &WorkGraph, path: &Path) -> Result<(), ParseError> {
     // Write to a temporary file in the same directory, then atomically rename.
     // This ensures a crash mid-write leaves the original file intact.
     let parent = path.parent().unwrap_or(Path::new("."));
@@ -152,22 +312,17 @@ pub fn save_graph<P: AsRef<Path>>(graph: &WorkGraph, path: P) -> Result<(), Pars
             .truncate(true)
             .open(&tmp_path)?;
 
-        for node in graph.nodes() {
+        writeln!(file, "{}", schema::header_line())?;
+        let mut nodes: Vec<&Node> = graph.nodes().collect();
+        nodes.sort_by(|a, b| a.id().cmp(b.id()));
+        for node in nodes {
             let json =
                 serde_json::to_string(node).map_err(|e| ParseError::Json { line: 0, source: e })?;
             writeln!(file, "{}", json)?;
         }
 
         file.flush()?;
-        #[cfg(unix)]
-        {
-            use std::os::unix::io::AsRawFd;
-            // fsync to ensure data is on disk before rename
-            let rc = unsafe { libc::fsync(file.as_raw_fd()) };
-            if rc != 0 {
-                return Err(ParseError::Io(std::io::Error::last_os_error()));
-            }
-        }
+        sync_file(&file)?;
 
         Ok(())
     })();
@@ -180,7 +335,345 @@ pub fn save_graph<P: AsRef<Path>>(graph: &WorkGraph, path: P) -> Result<(), Pars
     }
 
     result
-    // Lock is automatically released when _lock goes out of scope
+}
+
+/// fsync a file so its contents are on disk before we rely on them
+fn sync_file(file: &File) -> Result<(), ParseError> {
+    #[cfg(unix)]
+    {
+        let rc = unsafe { libc::fsync(file.as_raw_fd()) };
+        if rc != 0 {
+            return Err(ParseError::Io(std::io::Error::last_os_error()));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = file;
+    Ok(())
+}
+
+/// Drop an incomplete trailing record left by an interrupted append, so the
+/// next record starts on its own line
+fn truncate_incomplete_record(file: &mut File) -> Result<(), ParseError> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Ok(());
+    }
+    let mut last = [0u8; 1];
+    file.seek(SeekFrom::Start(len - 1))?;
+    file.read_exact(&mut last)?;
+    if last[0] == b'\n' {
+        return Ok(());
+    }
+
+    let mut content = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut content)?;
+    let keep = content
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    file.set_len(keep as u64)?;
+    Ok(())
+}
+
+/// Journal records that turn `current` into `target`, in deterministic order
+fn journal_delta(current: &WorkGraph, target: &WorkGraph) -> Result<Vec<String>, ParseError> {
+    let to_json = |value: serde_json::Value| {
+        serde_json::to_string(&value).map_err(|e| ParseError::Json { line: 0, source: e })
+    };
+
+    let mut target_ids: Vec<&str> = target.nodes().map(|n| n.id()).collect();
+    target_ids.sort_unstable();
+    let mut removed_ids: Vec<&str> = current
+        .nodes()
+        .map(|n| n.id())
+        .filter(|id| target.get_node(id).is_none())
+        .collect();
+    removed_ids.sort_unstable();
+
+    let mut records = Vec::new();
+    for id in target_ids {
+        let node = target.get_node(id).expect("id collected from target");
+        if current.get_node(id) != Some(node) {
+            records.push(to_json(serde_json::json!({ "op": "put", "node": node }))?);
+        }
+    }
+    for id in removed_ids {
+        records.push(to_json(serde_json::json!({ "op": "remove", "id": id }))?);
+    }
+    Ok(records)
+}
+
+/// Save a work graph to a JSONL file
+/// Uses advisory file locking and atomic write (temp file + rename) to
+/// prevent data loss on crash.
+///
+/// If the graph is journaled, only the nodes that differ from the stored state
+/// are appended to the journal; once the journal exceeds
+/// [`JOURNAL_COMPACT_THRESHOLD`] records it is folded into a fresh snapshot.
+pub fn save_graph<P: AsRef<Path>>(graph: &WorkGraph, path: P) -> Result<(), ParseError> {
+    let path = path.as_ref();
+    let lock_path = get_lock_path(path);
+    let lock = FileLock::acquire(&lock_path)?;
+
+    write_graph_unlocked(graph, path)?;
+    // Other writers needn't wait for the search index
+    drop(lock);
+    crate::search::update_after_save(path, graph);
+    Ok(())
+}
+
+/// Load, modify and save the graph as a single transaction.
+///
+/// The graph lock is held from load to save, so no other `wg` process can
+/// write in between and have its changes silently overwritten. The closure's
+/// value is returned; if it returns an error or leaves the graph unchanged,
+/// nothing is written.
+///
+/// The closure must not call `load_graph`/`save_graph` on the same graph (the
+/// lock is not reentrant), and should leave anything that waits on other `wg`
+/// processes, such as notifying the service daemon, until after it returns.
+pub fn with_graph_mut<P, T, E, F>(path: P, f: F) -> Result<T, E>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut WorkGraph) -> Result<T, E>,
+    E: From<ParseError>,
+{
+    let path = path.as_ref();
+    let lock_path = get_lock_path(path);
+    let lock = FileLock::acquire(&lock_path)?;
+
+    let fingerprint = StoreFingerprint::of(path)?;
+    let (mut graph, journal_len) = read_graph_unlocked(path)?;
+    let before = graph.clone();
+    let value = f(&mut graph)?;
+    if !same_nodes(&before, &graph) {
+        let loaded = Loaded {
+            graph: &before,
+            journal_len,
+            fingerprint,
+        };
+        write_graph_from(&graph, path, Some(loaded))?;
+        // Other writers needn't wait for the search index
+        drop(lock);
+        crate::search::update_after_save(path, &graph);
+    }
+    Ok(value)
+}
+
+/// Whether two graphs hold exactly the same nodes
+fn same_nodes(a: &WorkGraph, b: &WorkGraph) -> bool {
+    a.nodes().count() == b.nodes().count() && a.nodes().all(|n| b.get_node(n.id()) == Some(n))
+}
+
+/// Sizes and modification times of the snapshot and journal, used to tell
+/// whether the stored graph changed since it was read
+#[derive(Debug, Clone, PartialEq)]
+struct StoreFingerprint {
+    snapshot: Option<(u64, std::time::SystemTime)>,
+    journal: Option<(u64, std::time::SystemTime)>,
+}
+
+impl StoreFingerprint {
+    fn of(path: &Path) -> Result<Self, ParseError> {
+        let stat = |p: &Path| -> Result<_, ParseError> {
+            match std::fs::metadata(p) {
+                Ok(meta) => Ok(Some((meta.len(), meta.modified()?))),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        };
+        Ok(Self {
+            snapshot: stat(path)?,
+            journal: stat(&journal_path(path))?,
+        })
+    }
+}
+
+/// The stored graph as a transaction read it, so saving can diff against it
+/// instead of reading the snapshot and journal again
+struct Loaded<'a> {
+    graph: &'a WorkGraph,
+    journal_len: usize,
+    fingerprint: StoreFingerprint,
+}
+
+/// Persist `graph`, appending to the journal if the graph is journaled.
+/// Caller must hold the graph lock.
+pub(crate) fn write_graph_unlocked(graph: &WorkGraph, path: &Path) -> Result<(), ParseError> {
+    write_graph_from(graph, path, None)
+}
+
+/// Persist `graph`, diffing journal records against `loaded` if the files
+/// haven't changed since it was read, or against a fresh read otherwise.
+/// Caller must hold the graph lock.
+fn write_graph_from(
+    graph: &WorkGraph,
+    path: &Path,
+    loaded: Option<Loaded>,
+) -> Result<(), ParseError> {
+    let db = sqlite_path(path);
+    if db.exists() {
+        #[cfg(feature = "sqlite")]
+        return crate::store::sqlite::write_graph(&db, graph);
+        #[cfg(not(feature = "sqlite"))]
+        return Err(ParseError::SqliteUnsupported(db));
+    }
+
+    let journal = journal_path(path);
+    if !journal.exists() {
+        return write_snapshot_unlocked(graph, path);
+    }
+
+    // Everyone writes under the graph lock, so the files only change between
+    // load and save if something bypassed it (a git checkout, a hand edit)
+    let reread;
+    let (current, journal_len) = match loaded {
+        Some(loaded) if StoreFingerprint::of(path)? == loaded.fingerprint => {
+            (loaded.graph, loaded.journal_len)
+        }
+        _ => {
+            reread = read_graph_unlocked(path)?;
+            (&reread.0, reread.1)
+        }
+    };
+    let records = journal_delta(current, graph)?;
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    if journal_len + records.len() > JOURNAL_COMPACT_THRESHOLD {
+        write_snapshot_unlocked(graph, path)?;
+        let file = File::create(&journal)?;
+        return sync_file(&file);
+    }
+
+    let mut file = OpenOptions::new().read(true).append(true).open(&journal)?;
+    truncate_incomplete_record(&mut file)?;
+    let mut buf = records.join("\n");
+    buf.push('\n');
+    file.write_all(buf.as_bytes())?;
+    file.flush()?;
+    sync_file(&file)
+}
+
+/// Fold the journal into the snapshot and truncate it. No-op for plain JSONL graphs.
+pub fn compact_graph<P: AsRef<Path>>(path: P) -> Result<(), ParseError> {
+    let path = path.as_ref();
+    let lock_path = get_lock_path(path);
+    let _lock = FileLock::acquire(&lock_path)?;
+
+    let journal = journal_path(path);
+    if !journal.exists() {
+        return Ok(());
+    }
+    let (graph, _) = read_graph_unlocked(path)?;
+    write_snapshot_unlocked(&graph, path)?;
+    let file = File::create(&journal)?;
+    sync_file(&file)
+}
+
+/// Switch a graph between plain JSONL and journaled storage.
+///
+/// Enabling creates an empty journal next to the snapshot. Disabling folds the
+/// journal into `graph.jsonl` and removes it, leaving a plain JSONL graph.
+pub fn set_journaled<P: AsRef<Path>>(path: P, enabled: bool) -> Result<(), ParseError> {
+    let path = path.as_ref();
+    let lock_path = get_lock_path(path);
+    let _lock = FileLock::acquire(&lock_path)?;
+
+    let journal = journal_path(path);
+    if enabled {
+        if !journal.exists() {
+            let file = File::create(&journal)?;
+            sync_file(&file)?;
+        }
+    } else if journal.exists() {
+        let (graph, _) = read_graph_unlocked(path)?;
+        write_snapshot_unlocked(&graph, path)?;
+        std::fs::remove_file(&journal)?;
+    }
+    Ok(())
+}
+
+/// Schema version the graph at `path` is stored in (see [`crate::schema`]).
+pub fn schema_version<P: AsRef<Path>>(path: P) -> Result<u32, ParseError> {
+    let path = path.as_ref();
+    let lock_path = get_lock_path(path);
+    let _lock = FileLock::acquire(&lock_path)?;
+
+    stored_schema_version(path)
+}
+
+fn stored_schema_version(path: &Path) -> Result<u32, ParseError> {
+    let db = sqlite_path(path);
+    if db.exists() {
+        #[cfg(feature = "sqlite")]
+        return crate::store::sqlite::schema_version(&db);
+        #[cfg(not(feature = "sqlite"))]
+        return Err(ParseError::SqliteUnsupported(db));
+    }
+    // Only the header is needed, but a headerless file's version depends on
+    // whether it holds any nodes
+    let file = File::open(path)?;
+    let mut has_nodes = false;
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed)
+            && let Some(version) = schema::header_version(&value)
+        {
+            return Ok(version);
+        }
+        has_nodes = true;
+    }
+    Ok(if has_nodes { 0 } else { SCHEMA_VERSION })
+}
+
+/// Rewrite the graph at `path` in the current schema version, after copying
+/// the stored files to `<name>.v<old version>.bak`. Returns the version
+/// migrated from and the backup of the main file, or `None` if the graph is
+/// already current.
+pub fn migrate_schema<P: AsRef<Path>>(path: P) -> Result<Option<(u32, PathBuf)>, ParseError> {
+    let path = path.as_ref();
+    let lock_path = get_lock_path(path);
+    let _lock = FileLock::acquire(&lock_path)?;
+
+    let version = stored_schema_version(path)?;
+    check_schema_version(version)?;
+    if version == SCHEMA_VERSION {
+        return Ok(None);
+    }
+
+    let backup = |file: &Path| -> Result<PathBuf, ParseError> {
+        let mut name = file.as_os_str().to_owned();
+        name.push(format!(".v{}.bak", version));
+        let dest = PathBuf::from(name);
+        std::fs::copy(file, &dest)?;
+        Ok(dest)
+    };
+
+    let (graph, _) = read_graph_unlocked(path)?;
+    let db = sqlite_path(path);
+    if db.exists() {
+        let saved = backup(&db)?;
+        write_graph_unlocked(&graph, path)?;
+        return Ok(Some((version, saved)));
+    }
+    let saved = backup(path)?;
+    write_snapshot_unlocked(&graph, path)?;
+    let journal = journal_path(path);
+    if journal.exists() {
+        backup(&journal)?;
+        let file = File::create(&journal)?;
+        sync_file(&file)?;
+    }
+    Ok(Some((version, saved)))
 }
 
 #[cfg(test)]
@@ -259,6 +752,32 @@ mod tests {
         assert!(graph.get_task("t2").is_some());
     }
 
+    #[test]
+    fn test_schema_header_written_and_checked() {
+        let file = NamedTempFile::new().unwrap();
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("t1", "Task 1")));
+        save_graph(&graph, file.path()).unwrap();
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(content.lines().next(), Some(schema::header_line().as_str()));
+        assert_eq!(schema_version(file.path()).unwrap(), SCHEMA_VERSION);
+        assert_eq!(load_graph(file.path()).unwrap().len(), 1);
+        assert!(migrate_schema(file.path()).unwrap().is_none());
+
+        // A graph from a newer wg is refused, not loaded with fields dropped
+        let newer = content.replace(
+            &schema::header_line(),
+            &format!(r#"{{"kind":"schema","version":{}}}"#, SCHEMA_VERSION + 1),
+        );
+        std::fs::write(file.path(), newer).unwrap();
+        assert!(matches!(
+            load_graph(file.path()),
+            Err(ParseError::SchemaTooNew { .. })
+        ));
+        assert!(migrate_schema(file.path()).is_err());
+    }
+
     #[test]
     fn test_load_skips_empty_lines_and_comments() {
         let mut file = NamedTempFile::new().unwrap();
@@ -853,4 +1372,199 @@ mod tests {
         let task = graph.get_task("dup").unwrap();
         assert_eq!(task.title, "Second version");
     }
+
+    fn journaled_graph(dir: &Path) -> PathBuf {
+        let path = dir.join("graph.jsonl");
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("t1", "Task 1")));
+        graph.add_node(Node::Task(make_task("t2", "Task 2")));
+        save_graph(&graph, &path).unwrap();
+        set_journaled(&path, true).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_journaled_save_appends_deltas() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = journaled_graph(dir.path());
+        assert!(is_journaled(&path));
+        let snapshot_before = std::fs::read_to_string(&path).unwrap();
+
+        let mut graph = load_graph(&path).unwrap();
+        graph.get_task_mut("t1").unwrap().title = "Renamed".to_string();
+        graph.remove_node("t2");
+        graph.add_node(Node::Task(make_task("t3", "Task 3")));
+        save_graph(&graph, &path).unwrap();
+
+        // Snapshot untouched; only changed nodes went to the journal
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), snapshot_before);
+        let journal = std::fs::read_to_string(journal_path(&path)).unwrap();
+        let ops: Vec<&str> = journal.lines().collect();
+        assert_eq!(ops.len(), 3);
+        assert!(ops[0].contains(r#""op":"put""#) && ops[0].contains("Renamed"));
+        assert!(ops[1].contains(r#""id":"t3""#));
+        assert!(ops[2].contains(r#""op":"remove""#) && ops[2].contains("t2"));
+
+        let reloaded = load_graph(&path).unwrap();
+        assert_eq!(reloaded.get_task("t1").unwrap().title, "Renamed");
+        assert!(reloaded.get_task("t2").is_none());
+        assert!(reloaded.get_task("t3").is_some());
+
+        // Saving an unchanged graph appends nothing
+        save_graph(&reloaded, &path).unwrap();
+        let journal_after = std::fs::read_to_string(journal_path(&path)).unwrap();
+        assert_eq!(journal_after, journal);
+    }
+
+    #[test]
+    fn test_compact_and_disable_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = journaled_graph(dir.path());
+
+        let mut graph = load_graph(&path).unwrap();
+        graph.get_task_mut("t2").unwrap().title = "Changed".to_string();
+        save_graph(&graph, &path).unwrap();
+
+        compact_graph(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(journal_path(&path)).unwrap(), "");
+        assert!(std::fs::read_to_string(&path).unwrap().contains("Changed"));
+
+        graph.get_task_mut("t1").unwrap().title = "Also changed".to_string();
+        save_graph(&graph, &path).unwrap();
+        set_journaled(&path, false).unwrap();
+        assert!(!is_journaled(&path));
+
+        let reloaded = load_graph(&path).unwrap();
+        assert_eq!(reloaded.get_task("t1").unwrap().title, "Also changed");
+        assert_eq!(reloaded.get_task("t2").unwrap().title, "Changed");
+    }
+
+    #[test]
+    fn test_journal_ignores_incomplete_trailing_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = journaled_graph(dir.path());
+
+        // Simulate a crash mid-append
+        std::fs::write(journal_path(&path), r#"{"op":"put","node":{"kind":"ta"#).unwrap();
+        let mut graph = load_graph(&path).unwrap();
+        assert_eq!(graph.len(), 2);
+
+        // The next save drops the partial record before appending
+        graph.get_task_mut("t1").unwrap().title = "After crash".to_string();
+        save_graph(&graph, &path).unwrap();
+        let journal = std::fs::read_to_string(journal_path(&path)).unwrap();
+        assert_eq!(journal.lines().count(), 1);
+        assert_eq!(
+            load_graph(&path).unwrap().get_task("t1").unwrap().title,
+            "After crash"
+        );
+    }
+
+    #[test]
+    fn test_with_graph_mut_has_no_lost_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.jsonl");
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("t1", "Shared")));
+        save_graph(&graph, &path).unwrap();
+
+        let mut handles = Vec::new();
+        for i in 0..8 {
+            let path = path.clone();
+            handles.push(std::thread::spawn(move || {
+                for j in 0..5 {
+                    let entry = crate::graph::LogEntry {
+                        timestamp: String::new(),
+                        actor: None,
+                        message: format!("{}-{}", i, j),
+                    };
+                    with_graph_mut(&path, |graph| -> Result<(), ParseError> {
+                        graph.get_task_mut("t1").unwrap().log.push(entry);
+                        Ok(())
+                    })
+                    .unwrap();
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let graph = load_graph(&path).unwrap();
+        assert_eq!(graph.get_task("t1").unwrap().log.len(), 40);
+    }
+
+    #[test]
+    fn test_with_graph_mut_error_skips_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.jsonl");
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("t1", "Original")));
+        save_graph(&graph, &path).unwrap();
+
+        let result = with_graph_mut(&path, |graph| -> anyhow::Result<()> {
+            graph.get_task_mut("t1").unwrap().title = "Changed".to_string();
+            anyhow::bail!("abort")
+        });
+        assert!(result.is_err());
+        assert_eq!(
+            load_graph(&path).unwrap().get_task("t1").unwrap().title,
+            "Original"
+        );
+    }
+
+    #[test]
+    fn test_with_graph_mut_unchanged_skips_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.jsonl");
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("t1", "Original")));
+        save_graph(&graph, &path).unwrap();
+        let mut content = std::fs::read_to_string(&path).unwrap();
+        content.push_str("# kept unless the graph is rewritten\n");
+        std::fs::write(&path, &content).unwrap();
+
+        let title = with_graph_mut(&path, |graph| -> Result<String, ParseError> {
+            Ok(graph.get_task("t1").unwrap().title.clone())
+        })
+        .unwrap();
+        assert_eq!(title, "Original");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+    }
+
+    #[test]
+    fn test_with_graph_mut_journals_against_loaded_graph() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = journaled_graph(dir.path());
+
+        with_graph_mut(&path, |graph| -> Result<(), ParseError> {
+            graph.get_task_mut("t1").unwrap().title = "Renamed".to_string();
+            Ok(())
+        })
+        .unwrap();
+        let journal = std::fs::read_to_string(journal_path(&path)).unwrap();
+        assert_eq!(journal.lines().count(), 1);
+        assert!(journal.contains("Renamed"));
+
+        // A write that bypasses the lock changes the fingerprint, so the save
+        // diffs against what is actually stored and the result is still exact
+        with_graph_mut(&path, |graph| -> Result<(), ParseError> {
+            let stray = serde_json::json!({
+                "op": "put",
+                "node": Node::Task(make_task("stray", "Written behind our back")),
+            });
+            let mut file = OpenOptions::new()
+                .append(true)
+                .open(journal_path(&path))
+                .unwrap();
+            writeln!(file, "{}", stray).unwrap();
+            graph.get_task_mut("t2").unwrap().title = "Also renamed".to_string();
+            Ok(())
+        })
+        .unwrap();
+        let reloaded = load_graph(&path).unwrap();
+        assert!(reloaded.get_task("stray").is_none());
+        assert_eq!(reloaded.get_task("t1").unwrap().title, "Renamed");
+        assert_eq!(reloaded.get_task("t2").unwrap().title, "Also renamed");
+    }
 }
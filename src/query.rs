@@ -1,4 +1,6 @@
-use crate::graph::{Status, Task, WorkGraph};
+pub mod filter;
+
+use crate::graph::{Status, Task, WorkGraph, dep_condition};
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
@@ -85,7 +87,8 @@ pub fn project_summary(graph: &WorkGraph) -> ProjectSummary {
                 }
             }
             Status::Done => done += 1,
-            Status::InProgress => in_progress += 1,
+            // Work awaiting review is still in flight
+            Status::InProgress | Status::InReview => in_progress += 1,
             Status::Blocked => {
                 // Explicit blocked status also counts
                 blocked_count += 1;
@@ -191,12 +194,12 @@ where
             }
 
             // Check if all blockers are now resolved (in our plan or terminal)
-            let blockers_done = task.blocked_by.iter().all(|blocker_id| {
-                completed_in_plan.contains(blocker_id.as_str())
-                    || graph
-                        .get_task(blocker_id)
-                        .map(|t| t.status.is_terminal())
-                        .unwrap_or(true)
+            let blockers_done = dependencies_met(task, |blocker_id| {
+                if completed_in_plan.contains(blocker_id) {
+                    Some(Status::Done)
+                } else {
+                    graph.get_task(blocker_id).map(|t| t.status)
+                }
             });
 
             if blockers_done {
@@ -244,9 +247,175 @@ pub fn build_reverse_index(graph: &WorkGraph) -> HashMap<String, Vec<String>> {
     index
 }
 
-/// Find all tasks that are ready to work on (no open blockers, past not_before)
-pub fn ready_tasks(graph: &WorkGraph) -> Vec<&Task> {
+/// Rolled-up status of a task's descendants (children, grandchildren, ...).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Rollup {
+    pub total: usize,
+    pub open: usize,
+    pub in_progress: usize,
+    pub blocked: usize,
+    pub done: usize,
+    pub failed: usize,
+    pub abandoned: usize,
+    pub in_review: usize,
+    /// Sum of descendants' estimated hours
+    pub hours: f64,
+    /// Sum of descendants' estimated cost
+    pub cost: f64,
+    /// Percentage of non-abandoned descendants that are done (0-100)
+    pub progress: f64,
+}
+
+/// Roll up status counts, estimates and progress over the descendants of
+/// `task_id`. Returns `None` if the task has no children.
+pub fn rollup(graph: &WorkGraph, task_id: &str) -> Option<Rollup> {
+    let descendants = graph.descendants(task_id);
+    if descendants.is_empty() {
+        return None;
+    }
+    let mut r = Rollup {
+        total: descendants.len(),
+        ..Rollup::default()
+    };
+    for task in descendants {
+        match task.status {
+            Status::Open => r.open += 1,
+            Status::InProgress => r.in_progress += 1,
+            Status::Blocked => r.blocked += 1,
+            Status::Done => r.done += 1,
+            Status::Failed => r.failed += 1,
+            Status::Abandoned => r.abandoned += 1,
+            Status::InReview => r.in_review += 1,
+        }
+        if let Some(est) = &task.estimate {
+            r.hours += est.hours.unwrap_or(0.0);
+            r.cost += est.cost.unwrap_or(0.0);
+        }
+    }
+    let counted = r.total - r.abandoned;
+    r.progress = if counted == 0 {
+        100.0
+    } else {
+        r.done as f64 * 100.0 / counted as f64
+    };
+    Some(r)
+}
+
+/// IDs of parent tasks that still have a non-terminal child. A parent is not
+/// ready until all of its children have finished.
+pub fn parents_with_open_children(graph: &WorkGraph) -> HashSet<&str> {
     graph
+        .tasks()
+        .filter(|t| !t.status.is_terminal())
+        .filter_map(|t| t.parent.as_deref())
+        .collect()
+}
+
+/// Amount of a required resource a task holds while in progress (default 1).
+pub fn resource_amount(task: &Task, resource_id: &str) -> f64 {
+    task.resource_amounts
+        .get(resource_id)
+        .copied()
+        .unwrap_or(1.0)
+}
+
+/// Units of each resource currently held by in-progress tasks.
+pub fn resource_usage(graph: &WorkGraph) -> HashMap<String, f64> {
+    let mut usage: HashMap<String, f64> = HashMap::new();
+    for task in graph.tasks().filter(|t| t.status == Status::InProgress) {
+        for resource_id in &task.requires {
+            *usage.entry(resource_id.clone()).or_default() += resource_amount(task, resource_id);
+        }
+    }
+    usage
+}
+
+/// A resource a task cannot acquire because it is at capacity.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResourceWait {
+    pub resource: String,
+    /// Units the task needs
+    pub needed: f64,
+    /// Units held by in-progress tasks
+    pub in_use: f64,
+    /// Capacity of the resource (`available`)
+    pub available: f64,
+}
+
+/// Resources whose remaining capacity cannot cover what `task` needs, given
+/// the current `usage` (see [`resource_usage`]).
+///
+/// Resources act as counting semaphores: only resources with `available` set
+/// are enforced, and requirements on unknown nodes are ignored (`wg check`
+/// reports those).
+pub fn resource_waits(
+    graph: &WorkGraph,
+    task: &Task,
+    usage: &HashMap<String, f64>,
+) -> Vec<ResourceWait> {
+    task.requires
+        .iter()
+        .filter_map(|resource_id| {
+            let available = graph.get_resource(resource_id)?.available?;
+            let needed = resource_amount(task, resource_id);
+            let in_use = usage.get(resource_id).copied().unwrap_or(0.0);
+            (in_use + needed > available + f64::EPSILON).then(|| ResourceWait {
+                resource: resource_id.clone(),
+                needed,
+                in_use,
+                available,
+            })
+        })
+        .collect()
+}
+
+/// A resource requirement larger than the resource's total capacity. Unlike a
+/// [`ResourceWait`], no amount of waiting will satisfy it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResourceShortfall {
+    pub resource: String,
+    /// Units the task needs
+    pub needed: f64,
+    /// Capacity of the resource (`available`)
+    pub available: f64,
+}
+
+/// Required resources whose total capacity is smaller than what `task` needs,
+/// so the task can never be dispatched until the requirement or capacity
+/// changes.
+pub fn resource_shortfalls(graph: &WorkGraph, task: &Task) -> Vec<ResourceShortfall> {
+    task.requires
+        .iter()
+        .filter_map(|resource_id| {
+            let available = graph.get_resource(resource_id)?.available?;
+            let needed = resource_amount(task, resource_id);
+            (needed > available + f64::EPSILON).then(|| ResourceShortfall {
+                resource: resource_id.clone(),
+                needed,
+                available,
+            })
+        })
+        .collect()
+}
+
+/// Record that `task` now holds its required resources.
+///
+/// The coordinator uses this to keep `usage` accurate while it spawns several
+/// tasks within a single tick.
+pub fn reserve_resources(task: &Task, usage: &mut HashMap<String, f64>) {
+    for resource_id in &task.requires {
+        *usage.entry(resource_id.clone()).or_default() += resource_amount(task, resource_id);
+    }
+}
+
+/// Find all tasks that are ready to work on (no open blockers or children,
+/// past not_before, required resources have spare capacity)
+///
+/// Results are sorted in dispatch order (see [`sort_by_dispatch_order`]).
+pub fn ready_tasks(graph: &WorkGraph) -> Vec<&Task> {
+    let usage = resource_usage(graph);
+    let waiting_parents = parents_with_open_children(graph);
+    let mut ready: Vec<&Task> = graph
         .tasks()
         .filter(|task| {
             // Must be open
@@ -261,49 +430,131 @@ pub fn ready_tasks(graph: &WorkGraph) -> Vec<&Task> {
             if !is_time_ready(task) {
                 return false;
             }
-            // All blockers must be terminal (done, failed, or abandoned)
-            task.blocked_by.iter().all(|blocker_id| {
-                graph
-                    .get_task(blocker_id)
-                    .map(|t| t.status.is_terminal())
-                    .unwrap_or(true) // If blocker doesn't exist, treat as unblocked
-            })
+            // Parents wait for their children
+            if waiting_parents.contains(task.id.as_str()) {
+                return false;
+            }
+            // All blockers must be terminal (done, failed, or abandoned), or
+            // for dependency groups, their quorum done
+            let unblocked = dependencies_met(task, |blocker_id| {
+                graph.get_task(blocker_id).map(|t| t.status) // Missing blockers don't block
+            });
+            // Required resources must have spare capacity
+            unblocked
+                && !awaits_fan_out(graph, task)
+                && resource_waits(graph, task, &usage).is_empty()
         })
-        .collect()
+        .collect();
+    sort_by_dispatch_order(graph, &mut ready);
+    ready
 }
 
-/// Check whether a single blocked_by dependency is satisfied (terminal).
+/// Whether `task` waits on a fan-out task that is done but hasn't expanded
+/// yet: the children its join will wait on don't exist until the coordinator
+/// expands it (see [`crate::fan_out::expand_pending`]).
+fn awaits_fan_out(graph: &WorkGraph, task: &Task) -> bool {
+    task.blocked_by
+        .iter()
+        .filter_map(|blocker_id| graph.get_task(blocker_id))
+        .any(|blocker| {
+            blocker.status == Status::Done && blocker.fan_out.as_ref().is_some_and(|f| !f.expanded)
+        })
+}
+
+/// Status of a single blocked_by dependency, or `None` if it doesn't exist
+/// (missing blockers don't block).
 ///
 /// Handles both local and remote (`peer:task-id`) references.
 /// For remote refs, resolves via federation config using IPC or direct file access.
-pub fn is_blocker_satisfied(
+pub fn blocker_status(
     blocker_id: &str,
     graph: &WorkGraph,
     workgraph_dir: Option<&Path>,
-) -> bool {
+) -> Option<Status> {
     if let Some((peer_name, remote_task_id)) = crate::federation::parse_remote_ref(blocker_id) {
         // Cross-repo dependency
         let Some(wg_dir) = workgraph_dir else {
-            return false; // Can't resolve without workgraph dir; treat as blocked
+            return Some(Status::Open); // Can't resolve without workgraph dir; treat as blocked
         };
-        let remote = crate::federation::resolve_remote_task_status(peer_name, remote_task_id, wg_dir);
-        remote.status.is_terminal()
+        let remote =
+            crate::federation::resolve_remote_task_status(peer_name, remote_task_id, wg_dir);
+        Some(remote.status)
     } else {
         // Local dependency
-        graph
-            .get_task(blocker_id)
-            .map(|t| t.status.is_terminal())
-            .unwrap_or(true) // If blocker doesn't exist, treat as unblocked
+        graph.get_task(blocker_id).map(|t| t.status)
     }
 }
 
+/// Check whether a single blocked_by dependency is satisfied (terminal).
+///
+/// Handles both local and remote (`peer:task-id`) references, as
+/// [`blocker_status`] does.
+pub fn is_blocker_satisfied(
+    blocker_id: &str,
+    graph: &WorkGraph,
+    workgraph_dir: Option<&Path>,
+) -> bool {
+    blocker_status(blocker_id, graph, workgraph_dir).is_none_or(|s| s.is_terminal())
+}
+
+/// Members of a task's dependency groups, which don't block individually
+fn grouped_blockers(task: &Task) -> HashSet<&str> {
+    task.dep_groups
+        .iter()
+        .flat_map(|g| g.tasks.iter().map(String::as_str))
+        .collect()
+}
+
+/// Whether a task's dependencies let it start: every `blocked_by` entry
+/// outside a dependency group is terminal (in the outcome its
+/// [`crate::graph::DepCondition`] asks for), and every group is met (see
+/// [`crate::graph::DepGroup::is_met`]). `status` gives a blocker's status, `None` if it
+/// doesn't exist.
+pub fn dependencies_met(task: &Task, status: impl Fn(&str) -> Option<Status>) -> bool {
+    let grouped = grouped_blockers(task);
+    task.blocked_by
+        .iter()
+        .filter(|b| !grouped.contains(b.as_str()))
+        .all(|b| status(b).is_none_or(|s| dep_condition(task, b).is_met(s)))
+        && task.dep_groups.iter().all(|g| g.is_met(&status))
+}
+
+/// The blockers a task's start actually waits on, given when each blocker
+/// finishes: all of its ungrouped blockers and, for each dependency group,
+/// the members that complete the quorum (earliest finishers first).
+pub fn gating_blockers<T: PartialOrd + Copy>(
+    task: &Task,
+    mut finish: impl FnMut(&str) -> T,
+) -> Vec<(&str, T)> {
+    let grouped = grouped_blockers(task);
+    let mut gates: Vec<(&str, T)> = task
+        .blocked_by
+        .iter()
+        .filter(|b| !grouped.contains(b.as_str()))
+        .map(|b| (b.as_str(), finish(b)))
+        .collect();
+    for group in &task.dep_groups {
+        let mut members: Vec<(&str, T)> = group
+            .tasks
+            .iter()
+            .map(|m| (m.as_str(), finish(m)))
+            .collect();
+        members.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        members.truncate((group.need as usize).max(1));
+        gates.extend(members);
+    }
+    gates
+}
+
 /// Find all tasks that are ready to work on, resolving cross-repo dependencies.
 ///
 /// This is the cross-repo-aware variant of `ready_tasks()`. The coordinator
 /// should use this version so that tasks blocked by remote `peer:task-id`
 /// references are correctly resolved.
 pub fn ready_tasks_with_peers<'a>(graph: &'a WorkGraph, workgraph_dir: &Path) -> Vec<&'a Task> {
-    graph
+    let usage = resource_usage(graph);
+    let waiting_parents = parents_with_open_children(graph);
+    let mut ready: Vec<&Task> = graph
         .tasks()
         .filter(|task| {
             if task.status != Status::Open {
@@ -315,11 +566,379 @@ pub fn ready_tasks_with_peers<'a>(graph: &'a WorkGraph, workgraph_dir: &Path) ->
             if !is_time_ready(task) {
                 return false;
             }
-            task.blocked_by
-                .iter()
-                .all(|blocker_id| is_blocker_satisfied(blocker_id, graph, Some(workgraph_dir)))
+            if waiting_parents.contains(task.id.as_str()) {
+                return false;
+            }
+            dependencies_met(task, |blocker_id| {
+                blocker_status(blocker_id, graph, Some(workgraph_dir))
+            }) && !awaits_fan_out(graph, task)
+                && resource_waits(graph, task, &usage).is_empty()
         })
-        .collect()
+        .collect();
+    sort_by_dispatch_order(graph, &mut ready);
+    ready
+}
+
+/// Sort tasks into dispatch order: highest priority first, then least
+/// deadline slack (tasks without a deadline downstream go last), then longest
+/// remaining critical path, then highest value, then task ID.
+///
+/// This is the single ordering shared by `wg ready`, `wg next` and the
+/// coordinator, so that when there are more ready tasks than agent slots the
+/// most urgent work is always picked first.
+pub fn sort_by_dispatch_order(graph: &WorkGraph, tasks: &mut [&Task]) {
+    sort_by_dispatch_order_then(graph, tasks, |_, _| std::cmp::Ordering::Equal);
+}
+
+/// Like [`sort_by_dispatch_order`], but breaks ties with `tiebreak` before
+/// falling back to task ID. `wg next` uses this to rank equally urgent tasks
+/// by how well they fit an agent's skills.
+pub fn sort_by_dispatch_order_then<F>(graph: &WorkGraph, tasks: &mut [&Task], tiebreak: F)
+where
+    F: Fn(&Task, &Task) -> std::cmp::Ordering,
+{
+    if tasks.len() < 2 {
+        return;
+    }
+    let path_hours = critical_path_hours(graph);
+    let hours_of = |t: &Task| path_hours.get(&t.id).copied().unwrap_or(0.0);
+    let slack = compute_slack(graph, Utc::now());
+    let slack_of = |t: &Task| slack.get(&t.id).map_or(f64::INFINITY, |s| s.slack_hours);
+
+    tasks.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| {
+                slack_of(a)
+                    .partial_cmp(&slack_of(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .then_with(|| {
+                hours_of(b)
+                    .partial_cmp(&hours_of(a))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .then_with(|| {
+                let (va, vb) = (a.value.unwrap_or(0.0), b.value.unwrap_or(0.0));
+                vb.partial_cmp(&va).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .then_with(|| tiebreak(a, b))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+}
+
+/// Compute, for every non-terminal task, the length (in estimated hours) of
+/// the longest chain of non-terminal dependents starting at that task,
+/// including the task itself.
+///
+/// Tasks without an hours estimate count as 1h; negative estimates are
+/// clamped to zero. A dependency cycle counts as one step holding the hours
+/// of all its tasks, so every task on it gets the same length whatever order
+/// the graph is walked in.
+pub fn critical_path_hours(graph: &WorkGraph) -> HashMap<String, f64> {
+    let reverse = build_reverse_index(graph);
+    let mut active: Vec<&str> = graph
+        .tasks()
+        .filter(|task| !task.status.is_terminal())
+        .map(|task| task.id.as_str())
+        .collect();
+    active.sort_unstable();
+
+    let mut hours: HashMap<String, f64> = HashMap::new();
+    // Components come out dependents first, so theirs are already known
+    for mut component in dependent_components(&active, &reverse) {
+        component.sort_unstable();
+        let own_hours: f64 = component
+            .iter()
+            .filter_map(|id| graph.get_task(id))
+            .map(estimated_hours)
+            .sum();
+        let longest_dependent = component
+            .iter()
+            .filter_map(|id| reverse.get(*id))
+            .flatten()
+            .filter(|dep| !component.contains(&dep.as_str()))
+            .filter_map(|dep| hours.get(dep))
+            .fold(0.0, |longest, dep_hours| f64::max(longest, *dep_hours));
+        for id in component {
+            hours.insert(id.to_string(), own_hours + longest_dependent);
+        }
+    }
+    hours
+}
+
+/// Strongly connected components of the `active` tasks over "is blocked by"
+/// edges (Tarjan's algorithm), each emitted after every component that
+/// depends on it.
+fn dependent_components<'a>(
+    active: &[&'a str],
+    reverse: &'a HashMap<String, Vec<String>>,
+) -> Vec<Vec<&'a str>> {
+    struct Walk<'a> {
+        active: HashSet<&'a str>,
+        reverse: &'a HashMap<String, Vec<String>>,
+        index: HashMap<&'a str, usize>,
+        low: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: HashSet<&'a str>,
+        components: Vec<Vec<&'a str>>,
+    }
+
+    impl<'a> Walk<'a> {
+        fn visit(&mut self, id: &'a str) {
+            let index = self.index.len();
+            self.index.insert(id, index);
+            self.low.insert(id, index);
+            self.stack.push(id);
+            self.on_stack.insert(id);
+
+            let mut dependents: Vec<&'a str> = self
+                .reverse
+                .get(id)
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .filter(|dep| self.active.contains(dep))
+                .collect();
+            dependents.sort_unstable();
+            dependents.dedup();
+            for dep in dependents {
+                if !self.index.contains_key(dep) {
+                    self.visit(dep);
+                    let low = self.low[id].min(self.low[dep]);
+                    self.low.insert(id, low);
+                } else if self.on_stack.contains(dep) {
+                    let low = self.low[id].min(self.index[dep]);
+                    self.low.insert(id, low);
+                }
+            }
+
+            if self.low[id] == self.index[id] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    component.push(member);
+                    if member == id {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    let mut walk = Walk {
+        active: active.iter().copied().collect(),
+        reverse,
+        index: HashMap::new(),
+        low: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+    for id in active {
+        if !walk.index.contains_key(id) {
+            walk.visit(id);
+        }
+    }
+    walk.components
+}
+
+/// Earliest finish, in estimated hours from now, of every task for which
+/// `include` is true, assuming unlimited parallelism. Blockers that are not
+/// included count as finished, and a dependency group only waits for the
+/// members that complete its quorum (see [`gating_blockers`]). Cycles are
+/// broken by ignoring the edge that closes them.
+///
+/// Critical path analyses use this to decide which members of a group are on
+/// a path: the others don't hold anything up.
+pub fn finish_hours(graph: &WorkGraph, include: impl Fn(&Task) -> bool) -> HashMap<String, f64> {
+    let mut memo: HashMap<String, f64> = HashMap::new();
+    let mut visiting: HashSet<String> = HashSet::new();
+    for task in graph.tasks() {
+        finish_hours_from(graph, &task.id, &include, &mut memo, &mut visiting);
+    }
+    memo
+}
+
+fn finish_hours_from(
+    graph: &WorkGraph,
+    task_id: &str,
+    include: &impl Fn(&Task) -> bool,
+    memo: &mut HashMap<String, f64>,
+    visiting: &mut HashSet<String>,
+) -> f64 {
+    if let Some(finish) = memo.get(task_id) {
+        return *finish;
+    }
+    let Some(task) = graph.get_task(task_id) else {
+        return 0.0;
+    };
+    if !include(task) || !visiting.insert(task_id.to_string()) {
+        return 0.0;
+    }
+
+    let start = gating_blockers(task, |blocker| {
+        finish_hours_from(graph, blocker, include, memo, visiting)
+    })
+    .into_iter()
+    .map(|(_, finish)| finish)
+    .fold(0.0, f64::max);
+
+    visiting.remove(task_id);
+    let finish = start + estimated_hours(task);
+    memo.insert(task_id.to_string(), finish);
+    finish
+}
+
+/// Estimated hours for a task: its hours estimate, or 1h without one.
+/// Negative estimates are clamped to zero.
+pub fn estimated_hours(task: &Task) -> f64 {
+    task.estimate
+        .as_ref()
+        .and_then(|e| e.hours)
+        .unwrap_or(1.0)
+        .max(0.0)
+}
+
+/// Deadline slack of one task, from [`compute_slack`]
+#[derive(Debug, Clone, Serialize)]
+pub struct Slack {
+    /// Latest time the task can start without pushing a deadline back
+    pub latest_start: DateTime<Utc>,
+    /// Latest time the task can finish
+    pub latest_finish: DateTime<Utc>,
+    /// Earliest time the task can finish, given its unfinished blockers
+    pub earliest_finish: DateTime<Utc>,
+    /// `latest_finish - earliest_finish`; negative means a deadline will be missed
+    pub slack_hours: f64,
+    /// Task whose deadline sets `latest_finish` (this task or a dependent)
+    pub deadline_task: String,
+}
+
+/// Backward-schedule every non-terminal task against the deadlines in the
+/// graph.
+///
+/// A task's latest finish is the earliest of its own deadline and the latest
+/// starts of its non-terminal dependents; its latest start is that minus its
+/// estimated hours (as in [`critical_path_hours`]). The earliest finish is
+/// forward-scheduled from `now`, the task's `not_before` and its unfinished
+/// blockers, less the time an in-progress task has already run. Only tasks
+/// with a deadline at or downstream of them get an entry. Cycles are broken
+/// by ignoring the edge that closes them.
+pub fn compute_slack(graph: &WorkGraph, now: DateTime<Utc>) -> HashMap<String, Slack> {
+    let reverse = build_reverse_index(graph);
+    let mut latest: HashMap<String, Option<(DateTime<Utc>, String)>> = HashMap::new();
+    let mut earliest: HashMap<String, DateTime<Utc>> = HashMap::new();
+    let mut visiting: HashSet<String> = HashSet::new();
+
+    let mut slack = HashMap::new();
+    for task in graph.tasks() {
+        if task.status.is_terminal() {
+            continue;
+        }
+        let Some((latest_finish, deadline_task)) =
+            latest_finish_from(graph, &task.id, &reverse, &mut latest, &mut visiting)
+        else {
+            continue;
+        };
+        let earliest_finish =
+            earliest_finish_from(graph, &task.id, now, &mut earliest, &mut visiting);
+        slack.insert(
+            task.id.clone(),
+            Slack {
+                latest_start: latest_finish - hours_duration(estimated_hours(task)),
+                latest_finish,
+                earliest_finish,
+                slack_hours: (latest_finish - earliest_finish).num_seconds() as f64 / 3600.0,
+                deadline_task,
+            },
+        );
+    }
+    slack
+}
+
+fn hours_duration(hours: f64) -> chrono::Duration {
+    chrono::Duration::seconds((hours * 3600.0).round() as i64)
+}
+
+fn parse_time(timestamp: Option<&String>) -> Option<DateTime<Utc>> {
+    timestamp.and_then(|t| t.parse::<DateTime<Utc>>().ok())
+}
+
+fn latest_finish_from(
+    graph: &WorkGraph,
+    task_id: &str,
+    reverse: &HashMap<String, Vec<String>>,
+    memo: &mut HashMap<String, Option<(DateTime<Utc>, String)>>,
+    visiting: &mut HashSet<String>,
+) -> Option<(DateTime<Utc>, String)> {
+    if let Some(latest) = memo.get(task_id) {
+        return latest.clone();
+    }
+    let task = graph.get_task(task_id)?;
+    if task.status.is_terminal() || !visiting.insert(task_id.to_string()) {
+        return None;
+    }
+
+    let mut latest = parse_time(task.deadline.as_ref()).map(|d| (d, task.id.clone()));
+    for dependent_id in reverse.get(task_id).into_iter().flatten() {
+        let Some((finish, deadline_task)) =
+            latest_finish_from(graph, dependent_id, reverse, memo, visiting)
+        else {
+            continue;
+        };
+        let Some(dependent) = graph.get_task(dependent_id) else {
+            continue;
+        };
+        let start = finish - hours_duration(estimated_hours(dependent));
+        if latest.as_ref().is_none_or(|(l, _)| start < *l) {
+            latest = Some((start, deadline_task));
+        }
+    }
+
+    visiting.remove(task_id);
+    memo.insert(task_id.to_string(), latest.clone());
+    latest
+}
+
+fn earliest_finish_from(
+    graph: &WorkGraph,
+    task_id: &str,
+    now: DateTime<Utc>,
+    memo: &mut HashMap<String, DateTime<Utc>>,
+    visiting: &mut HashSet<String>,
+) -> DateTime<Utc> {
+    if let Some(finish) = memo.get(task_id) {
+        return *finish;
+    }
+    let Some(task) = graph.get_task(task_id) else {
+        return now;
+    };
+    if task.status.is_terminal() || !visiting.insert(task_id.to_string()) {
+        return now;
+    }
+
+    let mut start = now;
+    if let Some(not_before) = parse_time(task.not_before.as_ref()) {
+        start = start.max(not_before);
+    }
+    for (_, finish) in gating_blockers(task, |blocker| {
+        earliest_finish_from(graph, blocker, now, memo, visiting)
+    }) {
+        start = start.max(finish);
+    }
+    let mut remaining = hours_duration(estimated_hours(task));
+    if task.status == Status::InProgress
+        && let Some(started) = parse_time(task.started_at.as_ref())
+    {
+        remaining = (remaining - (now - started)).max(chrono::Duration::zero());
+    }
+
+    visiting.remove(task_id);
+    let finish = start + remaining;
+    memo.insert(task_id.to_string(), finish);
+    finish
 }
 
 /// Find what tasks are blocking a given task
@@ -369,7 +988,7 @@ fn cost_of_recursive(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::graph::{Estimate, Node};
+    use crate::graph::{DepCondition, Estimate, Node, parse_dep_group};
 
     fn make_task(id: &str, title: &str) -> Task {
         Task {
@@ -441,6 +1060,37 @@ mod tests {
         assert_eq!(ready[0].id, "blocked");
     }
 
+    #[test]
+    fn test_ready_tasks_wait_for_fan_out_expansion() {
+        let mut graph = WorkGraph::new();
+
+        // Marked done without going through completion, so not yet expanded
+        let mut split = make_task("split", "Split");
+        split.status = Status::Done;
+        split.fan_out = Some(crate::fan_out::FanOut::new(
+            "split",
+            "Handle {{input.item}}",
+            None,
+            None,
+            "split-join",
+        ));
+        let mut join = make_task("split-join", "Join");
+        join.blocked_by = vec!["split".to_string()];
+        graph.add_node(Node::Task(split));
+        graph.add_node(Node::Task(join));
+        assert!(ready_tasks(&graph).is_empty());
+
+        graph
+            .get_task_mut("split")
+            .unwrap()
+            .fan_out
+            .as_mut()
+            .unwrap()
+            .expanded = true;
+        let ids: Vec<&str> = ready_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["split-join"]);
+    }
+
     #[test]
     fn test_blocked_by_returns_blockers() {
         let mut graph = WorkGraph::new();
@@ -901,6 +1551,70 @@ mod tests {
         assert_eq!(ready[0].id, "c");
     }
 
+    #[test]
+    fn test_ready_tasks_dep_groups() {
+        let mut graph = WorkGraph::new();
+        for id in ["a", "b", "c"] {
+            graph.add_node(Node::Task(make_task(id, id)));
+        }
+        let mut any = make_task("any", "Any of a, b");
+        any.blocked_by = vec!["a".to_string(), "b".to_string()];
+        any.dep_groups = vec![parse_dep_group("a,b", false).unwrap()];
+        graph.add_node(Node::Task(any));
+        let mut two = make_task("two", "Two of a, b, c");
+        two.blocked_by = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        two.dep_groups = vec![parse_dep_group("2:a,b,c", false).unwrap()];
+        graph.add_node(Node::Task(two));
+
+        let ready_ids = |graph: &WorkGraph| -> Vec<String> {
+            ready_tasks(graph)
+                .into_iter()
+                .map(|t| t.id.clone())
+                .collect()
+        };
+        assert!(!ready_ids(&graph).contains(&"any".to_string()));
+
+        graph.get_task_mut("b").unwrap().status = Status::Done;
+        let ready = ready_ids(&graph);
+        assert!(ready.contains(&"any".to_string()));
+        assert!(!ready.contains(&"two".to_string()));
+
+        // A failed member can't reach the quorum, but doesn't count towards it
+        graph.get_task_mut("a").unwrap().status = Status::Failed;
+        assert!(!ready_ids(&graph).contains(&"two".to_string()));
+        graph.get_task_mut("c").unwrap().status = Status::Done;
+        assert!(ready_ids(&graph).contains(&"two".to_string()));
+    }
+
+    #[test]
+    fn test_ready_tasks_dep_conditions() {
+        let mut graph = WorkGraph::new();
+        let mut migrate = make_task("migrate", "Run migrations");
+        migrate.status = Status::Failed;
+        graph.add_node(Node::Task(migrate));
+        let mut deploy = make_task("deploy", "Deploy");
+        deploy.blocked_by = vec!["migrate".to_string()];
+        deploy
+            .dep_conditions
+            .insert("migrate".to_string(), DepCondition::OnSuccess);
+        graph.add_node(Node::Task(deploy));
+        let mut rollback = make_task("rollback", "Roll back");
+        rollback.blocked_by = vec!["migrate".to_string()];
+        rollback
+            .dep_conditions
+            .insert("migrate".to_string(), DepCondition::OnFailure);
+        graph.add_node(Node::Task(rollback));
+
+        let ready = ready_tasks(&graph);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].id, "rollback");
+
+        graph.get_task_mut("migrate").unwrap().status = Status::Done;
+        let ready = ready_tasks(&graph);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].id, "deploy");
+    }
+
     // ========== Multiple blockers with mixed states ==========
 
     #[test]
@@ -1521,6 +2235,52 @@ mod tests {
         assert!(ready.is_empty(), "Failed tasks should not be ready");
     }
 
+    #[test]
+    fn test_ready_tasks_parent_waits_for_children() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("epic", "Epic")));
+        let mut child = make_task("child", "Child");
+        child.parent = Some("epic".to_string());
+        graph.add_node(Node::Task(child));
+
+        let ready: Vec<&str> = ready_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ready, vec!["child"]);
+
+        graph.get_task_mut("child").unwrap().status = Status::Done;
+        let ready: Vec<&str> = ready_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ready, vec!["epic"]);
+    }
+
+    #[test]
+    fn test_rollup_counts_descendants() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("epic", "Epic")));
+        let statuses = [
+            ("a", "epic", Status::Done, 2.0),
+            ("b", "epic", Status::Open, 3.0),
+            ("b1", "b", Status::Abandoned, 1.0),
+            ("b2", "b", Status::InProgress, 4.0),
+        ];
+        for (id, parent, status, hours) in statuses {
+            let mut task = make_task(id, id);
+            task.parent = Some(parent.to_string());
+            task.status = status;
+            task.estimate = Some(Estimate {
+                hours: Some(hours),
+                cost: None,
+            });
+            graph.add_node(Node::Task(task));
+        }
+
+        let r = rollup(&graph, "epic").unwrap();
+        assert_eq!(r.total, 4);
+        assert_eq!((r.done, r.open, r.in_progress, r.abandoned), (1, 1, 1, 1));
+        assert_eq!(r.hours, 10.0);
+        // 1 done out of 3 non-abandoned
+        assert!((r.progress - 100.0 / 3.0).abs() < 1e-9);
+        assert!(rollup(&graph, "a").is_none());
+    }
+
     #[test]
     fn test_ready_tasks_excludes_abandoned() {
         let mut graph = WorkGraph::new();
@@ -1623,4 +2383,312 @@ mod tests {
         // Remote ref without workgraph_dir → treated as blocked
         assert!(!is_blocker_satisfied("peer:task-id", &graph, None));
     }
+
+    // ---- dispatch ordering ----
+
+    #[test]
+    fn test_ready_tasks_sorted_by_priority() {
+        use crate::graph::Priority;
+        let mut graph = WorkGraph::new();
+        let mut low = make_task("a-low", "Low");
+        low.priority = Priority::Low;
+        let normal = make_task("b-normal", "Normal");
+        let mut critical = make_task("c-critical", "Critical");
+        critical.priority = Priority::Critical;
+        let mut high = make_task("d-high", "High");
+        high.priority = Priority::High;
+        for t in [low, normal, critical, high] {
+            graph.add_node(Node::Task(t));
+        }
+
+        let ids: Vec<&str> = ready_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["c-critical", "d-high", "b-normal", "a-low"]);
+    }
+
+    #[test]
+    fn test_ready_tasks_equal_priority_prefers_longer_critical_path() {
+        let mut graph = WorkGraph::new();
+        // "short" has no dependents; "long" unblocks a 5h chain
+        graph.add_node(Node::Task(make_task("a-short", "Short")));
+        graph.add_node(Node::Task(make_task("b-long", "Long")));
+        let mut downstream = make_task("downstream", "Downstream");
+        downstream.blocked_by = vec!["b-long".to_string()];
+        downstream.estimate = Some(Estimate {
+            hours: Some(5.0),
+            cost: None,
+        });
+        graph.add_node(Node::Task(downstream));
+
+        let ids: Vec<&str> = ready_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["b-long", "a-short"]);
+    }
+
+    #[test]
+    fn test_ready_tasks_priority_beats_critical_path() {
+        use crate::graph::Priority;
+        let mut graph = WorkGraph::new();
+        let mut urgent = make_task("urgent", "Urgent");
+        urgent.priority = Priority::High;
+        graph.add_node(Node::Task(urgent));
+        graph.add_node(Node::Task(make_task("long", "Long")));
+        let mut downstream = make_task("downstream", "Downstream");
+        downstream.blocked_by = vec!["long".to_string()];
+        graph.add_node(Node::Task(downstream));
+
+        let ready = ready_tasks(&graph);
+        assert_eq!(ready[0].id, "urgent");
+    }
+
+    #[test]
+    fn test_ready_tasks_stable_tiebreak_by_value_then_id() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("c", "C")));
+        graph.add_node(Node::Task(make_task("a", "A")));
+        let mut valuable = make_task("z", "Z");
+        valuable.value = Some(10.0);
+        graph.add_node(Node::Task(valuable));
+        graph.add_node(Node::Task(make_task("b", "B")));
+
+        for _ in 0..5 {
+            let ids: Vec<&str> = ready_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+            assert_eq!(ids, vec!["z", "a", "b", "c"]);
+        }
+    }
+
+    #[test]
+    fn test_critical_path_hours_chain_and_cycle() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("a", "A")));
+        let mut b = make_task("b", "B");
+        b.blocked_by = vec!["a".to_string()];
+        b.estimate = Some(Estimate {
+            hours: Some(3.0),
+            cost: None,
+        });
+        graph.add_node(Node::Task(b));
+        let mut done = make_task("done", "Done");
+        done.status = Status::Done;
+        done.blocked_by = vec!["b".to_string()];
+        graph.add_node(Node::Task(done));
+        // Cycle: x <-> y must not recurse forever
+        let mut x = make_task("x", "X");
+        x.blocked_by = vec!["y".to_string()];
+        let mut y = make_task("y", "Y");
+        y.blocked_by = vec!["x".to_string()];
+        graph.add_node(Node::Task(x));
+        graph.add_node(Node::Task(y));
+
+        let hours = critical_path_hours(&graph);
+        assert_eq!(hours["a"], 4.0);
+        assert_eq!(hours["b"], 3.0);
+        assert!(!hours.contains_key("done"));
+        assert_eq!(hours["x"], 2.0);
+        assert_eq!(hours["y"], 2.0);
+    }
+
+    #[test]
+    fn test_critical_path_hours_cycle_is_order_independent() {
+        let with_hours = |id: &str, hours: f64, blocked_by: &[&str]| {
+            let mut task = make_task(id, id);
+            task.blocked_by = blocked_by.iter().map(|b| b.to_string()).collect();
+            task.estimate = Some(Estimate {
+                hours: Some(hours),
+                cost: None,
+            });
+            task
+        };
+        // x <-> y, with z waiting on y and x waiting on w
+        let tasks = [
+            with_hours("w", 4.0, &[]),
+            with_hours("x", 1.0, &["y", "w"]),
+            with_hours("y", 5.0, &["x"]),
+            with_hours("z", 2.0, &["y"]),
+        ];
+
+        // Each graph hashes its nodes differently, so is walked in a different order
+        for _ in 0..10 {
+            let mut graph = WorkGraph::new();
+            for task in &tasks {
+                graph.add_node(Node::Task(task.clone()));
+            }
+            let hours = critical_path_hours(&graph);
+            assert_eq!(hours["z"], 2.0);
+            assert_eq!(hours["x"], 8.0);
+            assert_eq!(hours["y"], 8.0);
+            assert_eq!(hours["w"], 12.0);
+        }
+    }
+
+    #[test]
+    fn test_compute_slack_backward_schedules_deadlines() {
+        let now: DateTime<Utc> = "2026-10-01T00:00:00Z".parse().unwrap();
+        let hours = |h: f64| {
+            Some(Estimate {
+                hours: Some(h),
+                cost: None,
+            })
+        };
+        let mut graph = WorkGraph::new();
+        // design (2h) -> build (4h, due in 10h) -> ship (1h, due in 6h)
+        let mut design = make_task("design", "Design");
+        design.estimate = hours(2.0);
+        let mut build = make_task("build", "Build");
+        build.blocked_by = vec!["design".to_string()];
+        build.estimate = hours(4.0);
+        build.deadline = Some("2026-10-01T10:00:00Z".to_string());
+        let mut ship = make_task("ship", "Ship");
+        ship.blocked_by = vec!["build".to_string()];
+        ship.estimate = hours(1.0);
+        ship.deadline = Some("2026-10-01T06:00:00Z".to_string());
+        graph.add_node(Node::Task(design));
+        graph.add_node(Node::Task(build));
+        graph.add_node(Node::Task(ship));
+        graph.add_node(Node::Task(make_task("unrelated", "Unrelated")));
+
+        let slack = compute_slack(&graph, now);
+        assert!(!slack.contains_key("unrelated"));
+        // ship must finish by 06:00 but can't before 07:00
+        assert_eq!(slack["ship"].slack_hours, -1.0);
+        // build's own 10:00 deadline is looser than ship's latest start (05:00)
+        assert_eq!(slack["build"].deadline_task, "ship");
+        assert_eq!(
+            slack["build"].latest_finish,
+            now + chrono::Duration::hours(5)
+        );
+        assert_eq!(slack["build"].slack_hours, -1.0);
+        assert_eq!(
+            slack["design"].latest_start,
+            now + chrono::Duration::hours(-1)
+        );
+
+        // Finishing design recovers the hour
+        graph.get_task_mut("design").unwrap().status = Status::Done;
+        let slack = compute_slack(&graph, now);
+        assert_eq!(slack["ship"].slack_hours, 1.0);
+        assert!(!slack.contains_key("design"));
+    }
+
+    #[test]
+    fn test_ready_tasks_least_slack_first() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("a-no-deadline", "No deadline")));
+        let mut later = make_task("b-later", "Later");
+        later.deadline = Some((Utc::now() + chrono::Duration::days(7)).to_rfc3339());
+        graph.add_node(Node::Task(later));
+        let mut sooner = make_task("c-sooner", "Sooner");
+        sooner.deadline = Some((Utc::now() + chrono::Duration::days(1)).to_rfc3339());
+        graph.add_node(Node::Task(sooner));
+
+        let ids: Vec<&str> = ready_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["c-sooner", "b-later", "a-no-deadline"]);
+    }
+
+    fn make_resource(id: &str, available: f64) -> Node {
+        Node::Resource(crate::graph::Resource {
+            id: id.to_string(),
+            name: None,
+            resource_type: None,
+            available: Some(available),
+            unit: None,
+        })
+    }
+
+    #[test]
+    fn test_resource_acts_as_counting_semaphore() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(make_resource("gpu-box", 2.0));
+        for id in ["running-1", "running-2"] {
+            let mut t = make_task(id, id);
+            t.status = Status::InProgress;
+            t.requires = vec!["gpu-box".to_string()];
+            graph.add_node(Node::Task(t));
+        }
+        let mut waiting = make_task("waiting", "Waiting");
+        waiting.requires = vec!["gpu-box".to_string()];
+        graph.add_node(Node::Task(waiting));
+        graph.add_node(Node::Task(make_task("free", "No resources")));
+
+        let ready: Vec<&str> = ready_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ready, vec!["free"]);
+
+        let usage = resource_usage(&graph);
+        assert_eq!(usage["gpu-box"], 2.0);
+        let waits = resource_waits(&graph, graph.get_task("waiting").unwrap(), &usage);
+        assert_eq!(waits.len(), 1);
+        assert_eq!(waits[0].resource, "gpu-box");
+
+        // Freeing a slot makes the waiting task ready again
+        graph.get_task_mut("running-1").unwrap().status = Status::Done;
+        let ready: Vec<&str> = ready_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert!(ready.contains(&"waiting"));
+    }
+
+    #[test]
+    fn test_resource_shortfalls() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(make_resource("gpu-box", 2.0));
+        let mut fits = make_task("fits", "Fits");
+        fits.requires = vec!["gpu-box".to_string()];
+        fits.resource_amounts.insert("gpu-box".to_string(), 2.0);
+        let mut too_big = make_task("too-big", "Too big");
+        too_big.requires = vec!["gpu-box".to_string()];
+        too_big.resource_amounts.insert("gpu-box".to_string(), 3.0);
+        graph.add_node(Node::Task(fits));
+        graph.add_node(Node::Task(too_big));
+
+        assert!(resource_shortfalls(&graph, graph.get_task("fits").unwrap()).is_empty());
+        assert_eq!(
+            resource_shortfalls(&graph, graph.get_task("too-big").unwrap()),
+            vec![ResourceShortfall {
+                resource: "gpu-box".to_string(),
+                needed: 3.0,
+                available: 2.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_resource_amounts_and_reservation() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(make_resource("api-quota", 3.0));
+        let mut big = make_task("big", "Big");
+        big.requires = vec!["api-quota".to_string()];
+        big.resource_amounts.insert("api-quota".to_string(), 2.0);
+        graph.add_node(Node::Task(big));
+        let mut small = make_task("small", "Small");
+        small.requires = vec!["api-quota".to_string()];
+        graph.add_node(Node::Task(small));
+        let mut huge = make_task("huge", "Huge");
+        huge.requires = vec!["api-quota".to_string()];
+        huge.resource_amounts.insert("api-quota".to_string(), 4.0);
+        graph.add_node(Node::Task(huge));
+
+        // Individually, big and small fit; huge never does
+        let ready: Vec<&str> = ready_tasks(&graph).iter().map(|t| t.id.as_str()).collect();
+        assert!(ready.contains(&"big") && ready.contains(&"small"));
+        assert!(!ready.contains(&"huge"));
+
+        // After reserving big (2 of 3), small (1) still fits but another big would not
+        let mut usage = resource_usage(&graph);
+        reserve_resources(graph.get_task("big").unwrap(), &mut usage);
+        assert!(resource_waits(&graph, graph.get_task("small").unwrap(), &usage).is_empty());
+        assert!(!resource_waits(&graph, graph.get_task("big").unwrap(), &usage).is_empty());
+    }
+
+    #[test]
+    fn test_resource_without_capacity_is_not_enforced() {
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Resource(crate::graph::Resource {
+            id: "docs".to_string(),
+            name: None,
+            resource_type: None,
+            available: None,
+            unit: None,
+        }));
+        let mut t = make_task("t1", "Task 1");
+        t.requires = vec!["docs".to_string(), "missing".to_string()];
+        graph.add_node(Node::Task(t));
+
+        assert_eq!(ready_tasks(&graph).len(), 1);
+    }
 }
@@ -0,0 +1,773 @@
+//! Task filter expressions
+//!
+//! A small query language for selecting tasks, shared by `wg list`, `ready`,
+//! `viz`, `archive`, `gc`, `replay` and `graph-export` (`--filter`):
+//!
+//! ```text
+//! status:open and (tag:rust or skill:security) and hours>4 and created<7d
+//! blocked_by:design-* not assigned:*
+//! ```
+//!
+//! - A term is `key<op>value`, with `:` or `=` (match), `!=`, `<`, `<=`, `>`
+//!   or `>=`. Values may be double-quoted to include spaces.
+//! - Terms combine with `and`, `or`, `not` (or a leading `-`) and
+//!   parentheses. `and` binds tighter than `or`; adjacent terms are and-ed.
+//! - Text matching is case-insensitive and `*` is a wildcard. `title` and
+//!   `description` match substrings; a bare word matches the ID or title.
+//! - List keys (`tag`, `skill`, `blocked_by`, ...) match if any entry does.
+//! - Time keys take an age (`created<7d`: created in the last week) or a
+//!   date (`completed>=2026-01-01`).
+//! - `@name` expands a saved query from the `[queries]` table in config.toml.
+
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::graph::{Priority, Status, Task, WorkGraph, parse_delay};
+
+/// Saved queries may reference each other; stop runaway expansion here
+const MAX_EXPANSION_DEPTH: usize = 8;
+
+/// A parsed filter expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    key: Key,
+    op: Op,
+    operand: Operand,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Key {
+    /// Bare word: ID or title
+    Text,
+    Id,
+    Title,
+    Description,
+    Status,
+    Assigned,
+    Tag,
+    Skill,
+    BlockedBy,
+    Blocks,
+    Parent,
+    Requires,
+    Model,
+    Priority,
+    Is,
+    Hours,
+    Cost,
+    Value,
+    Retries,
+    Created,
+    Started,
+    Completed,
+    Field(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Match,
+    NotMatch,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    /// Lowercased pattern, `*` as wildcard
+    Pattern(String),
+    Number(f64),
+    Priority(Priority),
+    /// Age in seconds
+    Age(i64),
+    Date(DateTime<Utc>),
+}
+
+/// Graph-wide facts a filter may need, computed at most once per command
+pub struct FilterContext<'a> {
+    graph: &'a WorkGraph,
+    now: DateTime<Utc>,
+    ready: OnceCell<HashSet<&'a str>>,
+}
+
+impl<'a> FilterContext<'a> {
+    pub fn new(graph: &'a WorkGraph) -> Self {
+        Self {
+            graph,
+            now: Utc::now(),
+            ready: OnceCell::new(),
+        }
+    }
+
+    fn is_ready(&self, id: &str) -> bool {
+        self.ready
+            .get_or_init(|| {
+                super::ready_tasks(self.graph)
+                    .into_iter()
+                    .map(|t| t.id.as_str())
+                    .collect()
+            })
+            .contains(id)
+    }
+}
+
+impl Filter {
+    /// Parse an expression with no saved queries available.
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_with_saved(input, &BTreeMap::new())
+    }
+
+    /// Parse an expression, expanding `@name` from `saved`.
+    pub fn parse_with_saved(input: &str, saved: &BTreeMap<String, String>) -> Result<Self> {
+        let expr = parse_expr(input, saved, 0)
+            .with_context(|| format!("Invalid filter '{}'", input))?;
+        Ok(Self { expr })
+    }
+
+    /// True if `task` satisfies the filter.
+    pub fn matches(&self, task: &Task, ctx: &FilterContext) -> bool {
+        eval(&self.expr, task, ctx)
+    }
+
+    /// The tasks in `graph` that satisfy the filter.
+    pub fn select<'a>(&self, graph: &'a WorkGraph) -> Vec<&'a Task> {
+        let ctx = FilterContext::new(graph);
+        graph.tasks().filter(|t| self.matches(t, &ctx)).collect()
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+// --- Lexing ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    /// A term or bare word, quotes still in place
+    Word(String),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' {
+            chars.next();
+            tokens.push(Token::LParen);
+        } else if c == ')' {
+            chars.next();
+            tokens.push(Token::RParen);
+        } else {
+            let mut word = String::new();
+            let mut in_quotes = false;
+            while let Some(&c) = chars.peek() {
+                if !in_quotes && (c.is_whitespace() || c == '(' || c == ')') {
+                    break;
+                }
+                if c == '"' {
+                    in_quotes = !in_quotes;
+                }
+                word.push(c);
+                chars.next();
+            }
+            if in_quotes {
+                anyhow::bail!("unterminated quote in '{}'", word);
+            }
+            tokens.push(match word.to_lowercase().as_str() {
+                "and" | "&&" => Token::And,
+                "or" | "||" => Token::Or,
+                "not" | "!" => Token::Not,
+                _ => Token::Word(word),
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+// --- Parsing ---
+
+fn parse_expr(input: &str, saved: &BTreeMap<String, String>, depth: usize) -> Result<Expr> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        anyhow::bail!("empty expression");
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        saved,
+        depth,
+    };
+    let expr = parser.or()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        anyhow::bail!("unexpected {:?}", token);
+    }
+    Ok(expr)
+}
+
+struct Parser<'s> {
+    tokens: Vec<Token>,
+    pos: usize,
+    saved: &'s BTreeMap<String, String>,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let right = self.and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut left = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => self.pos += 1,
+                // Juxtaposed terms are and-ed
+                Some(Token::Word(_) | Token::Not | Token::LParen) => {}
+                _ => break,
+            }
+            let right = self.unary()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::LParen) => {
+                let expr = self.or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => anyhow::bail!("missing ')'"),
+                }
+            }
+            Some(Token::Word(word)) => self.word(&word),
+            Some(token) => anyhow::bail!("unexpected {:?}", token),
+            None => anyhow::bail!("unexpected end of expression"),
+        }
+    }
+
+    fn word(&self, word: &str) -> Result<Expr> {
+        if let Some(rest) = word.strip_prefix('-')
+            && !rest.is_empty()
+        {
+            return Ok(Expr::Not(Box::new(self.word(rest)?)));
+        }
+        if let Some(name) = word.strip_prefix('@') {
+            let query = self
+                .saved
+                .get(name)
+                .with_context(|| format!("no saved query named '{}'", name))?;
+            if self.depth >= MAX_EXPANSION_DEPTH {
+                anyhow::bail!("saved query '{}' expands too deeply (cycle?)", name);
+            }
+            return parse_expr(query, self.saved, self.depth + 1)
+                .with_context(|| format!("in saved query '{}'", name));
+        }
+        parse_term(word).map(Expr::Term)
+    }
+}
+
+fn parse_term(word: &str) -> Result<Term> {
+    // Split at the first operator outside quotes
+    let split = word
+        .char_indices()
+        .take_while(|&(_, c)| c != '"')
+        .find(|&(_, c)| matches!(c, ':' | '=' | '<' | '>' | '!'));
+    let Some((pos, _)) = split else {
+        return Ok(Term {
+            key: Key::Text,
+            op: Op::Match,
+            operand: Operand::Pattern(unquote(word).to_lowercase()),
+        });
+    };
+    let (key, rest) = word.split_at(pos);
+    let (op, value) = if let Some(v) = rest.strip_prefix("!=") {
+        (Op::NotMatch, v)
+    } else if let Some(v) = rest.strip_prefix("<=") {
+        (Op::Le, v)
+    } else if let Some(v) = rest.strip_prefix(">=") {
+        (Op::Ge, v)
+    } else if let Some(v) = rest.strip_prefix('<') {
+        (Op::Lt, v)
+    } else if let Some(v) = rest.strip_prefix('>') {
+        (Op::Gt, v)
+    } else if let Some(v) = rest.strip_prefix(':').or_else(|| rest.strip_prefix('=')) {
+        (Op::Match, v)
+    } else {
+        anyhow::bail!("bad operator in '{}'", word);
+    };
+    let value = unquote(value);
+    if value.is_empty() {
+        anyhow::bail!("missing value in '{}'", word);
+    }
+    let name = key;
+    let key = parse_key(name)?;
+    let operand = parse_operand(&key, name, op, &value)?;
+    Ok(Term { key, op, operand })
+}
+
+fn parse_key(key: &str) -> Result<Key> {
+    let lower = key.to_lowercase();
+    if let Some(name) = lower
+        .strip_prefix("field.")
+        .or_else(|| lower.strip_prefix("fields."))
+    {
+        return Ok(Key::Field(name.to_string()));
+    }
+    Ok(match lower.as_str() {
+        "id" => Key::Id,
+        "title" => Key::Title,
+        "description" | "desc" => Key::Description,
+        "status" => Key::Status,
+        "assigned" | "assignee" => Key::Assigned,
+        "tag" => Key::Tag,
+        "skill" => Key::Skill,
+        "blocked_by" | "blocked-by" | "after" => Key::BlockedBy,
+        "blocks" | "before" => Key::Blocks,
+        "parent" => Key::Parent,
+        "requires" | "resource" => Key::Requires,
+        "model" => Key::Model,
+        "priority" => Key::Priority,
+        "is" => Key::Is,
+        "hours" => Key::Hours,
+        "cost" => Key::Cost,
+        "value" => Key::Value,
+        "retries" => Key::Retries,
+        "created" => Key::Created,
+        "started" => Key::Started,
+        "completed" => Key::Completed,
+        _ => anyhow::bail!("unknown key '{}'", key),
+    })
+}
+
+fn parse_operand(key: &Key, name: &str, op: Op, value: &str) -> Result<Operand> {
+    let ordered = !matches!(op, Op::Match | Op::NotMatch);
+    match key {
+        Key::Hours | Key::Cost | Key::Value | Key::Retries => {
+            let n = value
+                .parse::<f64>()
+                .with_context(|| format!("'{}' needs a number, got '{}'", name, value))?;
+            Ok(Operand::Number(n))
+        }
+        Key::Priority => Ok(Operand::Priority(value.parse()?)),
+        Key::Created | Key::Started | Key::Completed => {
+            if let Some(secs) = parse_delay(value) {
+                if !ordered {
+                    anyhow::bail!(
+                        "use '<' or '>' with an age, e.g. {}<{}",
+                        name,
+                        value
+                    );
+                }
+                return Ok(Operand::Age(secs as i64));
+            }
+            parse_date(value)
+                .map(Operand::Date)
+                .with_context(|| format!("'{}' needs an age (7d) or date, got '{}'", name, value))
+        }
+        Key::Status if !value.contains('*') => {
+            let known = [
+                Status::Open,
+                Status::InProgress,
+                Status::Done,
+                Status::Blocked,
+                Status::Failed,
+                Status::Abandoned,
+                Status::InReview,
+            ];
+            if !known.iter().any(|s| s.to_string() == value.to_lowercase()) {
+                anyhow::bail!(
+                    "unknown status '{}'. Valid values: open, in-progress, done, blocked, failed, abandoned, in-review",
+                    value
+                );
+            }
+            pattern_operand(name, op, value)
+        }
+        Key::Is => {
+            const STATES: [&str; 6] = ["ready", "paused", "blocked", "parent", "child", "loop"];
+            if !STATES.contains(&value.to_lowercase().as_str()) {
+                anyhow::bail!(
+                    "unknown state 'is:{}'. Valid values: {}",
+                    value,
+                    STATES.join(", ")
+                );
+            }
+            pattern_operand(name, op, value)
+        }
+        Key::Field(_) if ordered => match value.parse::<f64>() {
+            Ok(n) => Ok(Operand::Number(n)),
+            Err(_) => Ok(Operand::Pattern(value.to_lowercase())),
+        },
+        _ => pattern_operand(name, op, value),
+    }
+}
+
+fn pattern_operand(name: &str, op: Op, value: &str) -> Result<Operand> {
+    if !matches!(op, Op::Match | Op::NotMatch) {
+        anyhow::bail!("'{}' only supports ':' and '!='", name);
+    }
+    Ok(Operand::Pattern(value.to_lowercase()))
+}
+
+fn unquote(s: &str) -> String {
+    s.replace('"', "")
+}
+
+fn parse_date(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+// --- Evaluation ---
+
+fn eval(expr: &Expr, task: &Task, ctx: &FilterContext) -> bool {
+    match expr {
+        Expr::And(a, b) => eval(a, task, ctx) && eval(b, task, ctx),
+        Expr::Or(a, b) => eval(a, task, ctx) || eval(b, task, ctx),
+        Expr::Not(e) => !eval(e, task, ctx),
+        Expr::Term(term) => eval_term(term, task, ctx),
+    }
+}
+
+fn eval_term(term: &Term, task: &Task, ctx: &FilterContext) -> bool {
+    let matched = match (&term.key, &term.operand) {
+        (Key::Field(name), operand) => match (task.fields.get(name), operand) {
+            (Some(v), Operand::Number(n)) => {
+                return v.as_f64().is_some_and(|v| compare(term.op, v, *n));
+            }
+            (Some(v), Operand::Pattern(p)) if matches!(term.op, Op::Match | Op::NotMatch) => {
+                glob(p, &crate::fields::display(v).to_lowercase())
+            }
+            (Some(v), Operand::Pattern(p)) => {
+                let v = crate::fields::display(v).to_lowercase();
+                return compare(term.op, v.as_str(), p.as_str());
+            }
+            _ => false,
+        },
+        (Key::Hours | Key::Cost | Key::Value | Key::Retries, Operand::Number(n)) => {
+            let value = match term.key {
+                Key::Hours => task.estimate.as_ref().and_then(|e| e.hours),
+                Key::Cost => task.estimate.as_ref().and_then(|e| e.cost),
+                Key::Value => task.value,
+                _ => Some(task.retry_count as f64),
+            };
+            return value.is_some_and(|v| match term.op {
+                Op::Match => v == *n,
+                Op::NotMatch => v != *n,
+                op => compare(op, v, *n),
+            });
+        }
+        (Key::Priority, Operand::Priority(p)) => {
+            return match term.op {
+                Op::Match => task.priority == *p,
+                Op::NotMatch => task.priority != *p,
+                op => compare(op, task.priority, *p),
+            };
+        }
+        (Key::Created | Key::Started | Key::Completed, operand) => {
+            let stamp = match term.key {
+                Key::Created => task.created_at.as_deref(),
+                Key::Started => task.started_at.as_deref(),
+                _ => task.completed_at.as_deref(),
+            };
+            let Some(ts) = stamp.and_then(|s| s.parse::<DateTime<Utc>>().ok()) else {
+                return false;
+            };
+            return match operand {
+                // An age compares how long ago the event was
+                Operand::Age(secs) => compare(term.op, (ctx.now - ts).num_seconds(), *secs),
+                Operand::Date(date) => match term.op {
+                    Op::Match => ts.date_naive() == date.date_naive(),
+                    Op::NotMatch => ts.date_naive() != date.date_naive(),
+                    op => compare(op, ts, *date),
+                },
+                _ => false,
+            };
+        }
+        (key, Operand::Pattern(p)) => match_pattern(key, p, task, ctx),
+        _ => false,
+    };
+    match term.op {
+        Op::NotMatch => !matched,
+        _ => matched,
+    }
+}
+
+fn match_pattern(key: &Key, p: &str, task: &Task, ctx: &FilterContext) -> bool {
+    let any = |values: &[String]| values.iter().any(|v| glob(p, &v.to_lowercase()));
+    let one = |value: Option<&str>| value.is_some_and(|v| glob(p, &v.to_lowercase()));
+    match key {
+        Key::Text => {
+            glob(p, &task.id.to_lowercase()) || contains(p, &task.title.to_lowercase())
+        }
+        Key::Id => one(Some(&task.id)),
+        Key::Title => contains(p, &task.title.to_lowercase()),
+        Key::Description => task
+            .description
+            .as_deref()
+            .is_some_and(|d| contains(p, &d.to_lowercase())),
+        Key::Status => one(Some(&task.status.to_string())),
+        Key::Assigned => one(task.assigned.as_deref()),
+        Key::Tag => any(&task.tags),
+        Key::Skill => any(&task.skills),
+        Key::BlockedBy => any(&task.blocked_by),
+        Key::Blocks => any(&task.blocks),
+        Key::Parent => one(task.parent.as_deref()),
+        Key::Requires => any(&task.requires),
+        Key::Model => one(task.model.as_deref()),
+        Key::Is => match p {
+            "ready" => ctx.is_ready(&task.id),
+            "paused" => task.paused,
+            "blocked" => {
+                !crate::query::dependencies_met(task, |b| ctx.graph.get_task(b).map(|t| t.status))
+            }
+            "parent" => ctx.graph.tasks().any(|t| t.parent.as_deref() == Some(&task.id)),
+            "child" => task.parent.is_some(),
+            "loop" => !task.loops_to.is_empty(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn compare<T: PartialOrd>(op: Op, left: T, right: T) -> bool {
+    match op {
+        Op::Lt => left < right,
+        Op::Le => left <= right,
+        Op::Gt => left > right,
+        Op::Ge => left >= right,
+        Op::Match => left == right,
+        Op::NotMatch => left != right,
+    }
+}
+
+/// Substring match, or a whole-string glob when the pattern has a wildcard
+fn contains(pattern: &str, text: &str) -> bool {
+    if pattern.contains('*') {
+        glob(pattern, text)
+    } else {
+        text.contains(pattern)
+    }
+}
+
+/// Whole-string match where `*` matches any run of characters
+fn glob(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || text.len() < first.len() + last.len() {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    text.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Estimate, Node};
+
+    fn task(id: &str, title: &str, status: Status) -> Task {
+        Task {
+            id: id.to_string(),
+            title: title.to_string(),
+            status,
+            ..Task::default()
+        }
+    }
+
+    fn graph() -> WorkGraph {
+        let mut graph = WorkGraph::new();
+        let mut design = task("design-api", "Design the API", Status::Done);
+        design.tags = vec!["rust".to_string()];
+        design.completed_at = Some("2026-01-10T12:00:00Z".to_string());
+        let mut build = task("build-api", "Build the API", Status::Open);
+        build.tags = vec!["rust".to_string(), "backend".to_string()];
+        build.blocked_by = vec!["design-api".to_string()];
+        build.estimate = Some(Estimate {
+            hours: Some(8.0),
+            cost: None,
+        });
+        build.created_at = Some(Utc::now().to_rfc3339());
+        build.priority = Priority::High;
+        let mut audit = task("audit", "Security audit", Status::Open);
+        audit.skills = vec!["security".to_string()];
+        audit.assigned = Some("agent-7".to_string());
+        audit.blocked_by = vec!["build-api".to_string()];
+        audit.created_at = Some("2025-01-01T00:00:00Z".to_string());
+        audit
+            .fields
+            .insert("customer".to_string(), serde_json::json!("acme"));
+        audit.fields.insert("points".to_string(), serde_json::json!(5));
+        for t in [design, build, audit] {
+            graph.add_node(Node::Task(t));
+        }
+        graph
+    }
+
+    fn ids(filter: &str) -> Vec<String> {
+        let graph = graph();
+        let filter = Filter::parse(filter).unwrap();
+        let mut ids: Vec<String> = filter
+            .select(&graph)
+            .into_iter()
+            .map(|t| t.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_boolean_structure_and_precedence() {
+        assert_eq!(
+            ids("status:open and (tag:rust or skill:security)"),
+            vec!["audit", "build-api"]
+        );
+        // and binds tighter than or
+        assert_eq!(
+            ids("status:done or tag:backend and hours>4"),
+            vec!["build-api", "design-api"]
+        );
+        // juxtaposition is and; '-' and 'not' negate
+        assert_eq!(ids("tag:rust -status:done"), vec!["build-api"]);
+        assert_eq!(ids("not assigned:*"), vec!["build-api", "design-api"]);
+    }
+
+    #[test]
+    fn test_globs_text_and_lists() {
+        assert_eq!(ids("blocked_by:design-*"), vec!["build-api"]);
+        assert_eq!(ids("id:*-api"), vec!["build-api", "design-api"]);
+        assert_eq!(ids("title:\"the api\""), vec!["build-api", "design-api"]);
+        assert_eq!(ids("audit"), vec!["audit"]);
+        assert_eq!(ids("status!=open"), vec!["design-api"]);
+    }
+
+    #[test]
+    fn test_numbers_priority_and_fields() {
+        assert_eq!(ids("hours>=8"), vec!["build-api"]);
+        assert!(ids("hours>8").is_empty());
+        assert_eq!(ids("priority>normal"), vec!["build-api"]);
+        assert_eq!(ids("field.customer:ac*"), vec!["audit"]);
+        assert_eq!(ids("field.points>3"), vec!["audit"]);
+    }
+
+    #[test]
+    fn test_time_terms() {
+        assert_eq!(ids("created<7d"), vec!["build-api"]);
+        assert_eq!(ids("created>7d"), vec!["audit"]);
+        assert_eq!(ids("completed:2026-01-10"), vec!["design-api"]);
+        assert_eq!(ids("completed<2026-01-01"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_is_states() {
+        assert_eq!(ids("is:ready"), vec!["build-api"]);
+        assert_eq!(ids("is:blocked"), vec!["audit"]);
+    }
+
+    #[test]
+    fn test_saved_queries_expand() {
+        let saved = BTreeMap::from([
+            ("rusty".to_string(), "tag:rust".to_string()),
+            ("todo".to_string(), "@rusty status:open".to_string()),
+            ("loop".to_string(), "@loop".to_string()),
+        ]);
+        let graph = graph();
+        let filter = Filter::parse_with_saved("@todo", &saved).unwrap();
+        let selected: Vec<&str> = filter
+            .select(&graph)
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(selected, vec!["build-api"]);
+        assert!(Filter::parse_with_saved("@missing", &saved).is_err());
+        assert!(Filter::parse_with_saved("@loop", &saved).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in [
+            "",
+            "status:opne",
+            "bogus:1",
+            "hours>many",
+            "created:7d",
+            "tag>rust",
+            "(status:open",
+            "status:open)",
+            "title:\"unterminated",
+            "is:sleepy",
+            "and",
+        ] {
+            assert!(Filter::parse(bad).is_err(), "expected error for {:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_glob() {
+        assert!(glob("a*c", "abc"));
+        assert!(glob("*", ""));
+        assert!(glob("a*b*c", "a-b-c"));
+        assert!(!glob("a*c", "abd"));
+        assert!(!glob("ab*ba", "aba"));
+    }
+}
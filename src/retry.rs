@@ -0,0 +1,534 @@
+//! Automatic retries with backoff
+//!
+//! A failed task is retried by the coordinator when a [`RetryPolicy`] covers
+//! it: the task's own `retry_policy`, or else the `[retry.tags.<tag>]` policy
+//! of the first of its tags that has one.
+//!
+//! Each failure is recorded in the task's `retry_history` as a
+//! [`FailureRecord`] holding what the failing path knew: the reason, the
+//! wrapper's exit code, the triage verdict. On its next tick the coordinator
+//! ([`apply_policies`]) sorts the failure into a class and, if the policy
+//! retries that class and attempts remain, reopens the task with `ready_after`
+//! set for an exponential backoff: `backoff * 2^(n-1)` after the n-th
+//! failure, capped at `backoff_cap` and never more than [`MAX_BACKOFF_SECS`].
+//!
+//! ```toml
+//! [retry.tags.llm]
+//! max_attempts = 5
+//! backoff = "30s"
+//! backoff_cap = "30m"
+//! retry_on = ["rate-limit", "timeout"]
+//!
+//! [[retry.classes]]
+//! name = "network"
+//! pattern = "(?i)connection (reset|refused)"
+//! exit_codes = [75]
+//! ```
+//!
+//! Classes from `[[retry.classes]]` are tried in order, then the built-in
+//! ones: `rate-limit` (reasons mentioning a rate limit, HTTP 429 or an
+//! overloaded API) and `timeout` (exit code 124 or 137, or a reason saying it
+//! timed out). A failure matching no class is a genuine [`TASK_FAILURE`],
+//! retried only by policies that list it.
+
+use crate::config::RetryConfig;
+use crate::graph::{LogEntry, Status, Task, WorkGraph, parse_delay, unskip_dependents};
+use anyhow::Result;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Class of a failure that matches no failure class
+pub const TASK_FAILURE: &str = "task";
+
+/// Longest backoff a policy may ask for (30 days)
+pub const MAX_BACKOFF_SECS: u64 = 30 * 86400;
+
+/// When and how often a failed task is retried
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry (e.g. "30s", "5m"); doubles each retry
+    #[serde(default = "default_backoff")]
+    pub backoff: String,
+    /// Longest delay between retries
+    #[serde(default = "default_backoff_cap")]
+    pub backoff_cap: String,
+    /// Failure classes worth retrying
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<String>,
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_backoff() -> String {
+    "1m".to_string()
+}
+
+fn default_backoff_cap() -> String {
+    "1h".to_string()
+}
+
+fn default_retry_on() -> Vec<String> {
+    vec!["rate-limit".to_string(), "timeout".to_string()]
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff: default_backoff(),
+            backoff_cap: default_backoff_cap(),
+            retry_on: default_retry_on(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Check the attempt count and durations
+    pub fn validate(&self) -> Result<()> {
+        if self.max_attempts == 0 {
+            anyhow::bail!("max_attempts must be at least 1");
+        }
+        for (name, value) in [("backoff", &self.backoff), ("cap", &self.backoff_cap)] {
+            match parse_delay(value) {
+                None => anyhow::bail!(
+                    "Invalid {} '{}'. Use a duration like 30s, 5m, 1h or 1d",
+                    name,
+                    value
+                ),
+                Some(secs) if secs > MAX_BACKOFF_SECS => anyhow::bail!(
+                    "{} '{}' is too long; the most a retry can wait is {}d",
+                    name,
+                    value,
+                    MAX_BACKOFF_SECS / 86400
+                ),
+                Some(_) => {}
+            }
+        }
+        if self.retry_on.is_empty() {
+            anyhow::bail!("retry_on must name at least one failure class");
+        }
+        Ok(())
+    }
+
+    /// Whether failures of `class` are retried
+    pub fn retries(&self, class: &str) -> bool {
+        self.retry_on.iter().any(|c| c == class)
+    }
+
+    /// Seconds to wait after the `failures`-th failure. Policies from
+    /// `config.toml` aren't validated, so this clamps to [`MAX_BACKOFF_SECS`].
+    pub fn backoff_secs(&self, failures: u32) -> u64 {
+        let base = parse_delay(&self.backoff).unwrap_or(0);
+        let cap = parse_delay(&self.backoff_cap).unwrap_or(MAX_BACKOFF_SECS);
+        let doublings = failures.saturating_sub(1).min(32);
+        base.saturating_mul(1u64 << doublings)
+            .min(cap)
+            .min(MAX_BACKOFF_SECS)
+    }
+}
+
+/// Compact form used on the command line:
+/// `max=5,backoff=30s,cap=30m,on=rate-limit+timeout`. A bare number sets
+/// `max`; omitted keys keep their defaults.
+impl std::str::FromStr for RetryPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut policy = RetryPolicy::default();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=').unwrap_or(("max", part));
+            match key.trim() {
+                "max" | "max_attempts" => {
+                    policy.max_attempts = value.trim().parse().map_err(|_| {
+                        anyhow::anyhow!("Invalid max attempts '{}' in retry policy", value)
+                    })?;
+                }
+                "backoff" => policy.backoff = value.trim().to_string(),
+                "cap" | "backoff_cap" => policy.backoff_cap = value.trim().to_string(),
+                "on" | "retry_on" => {
+                    policy.retry_on = value
+                        .split('+')
+                        .map(str::trim)
+                        .filter(|c| !c.is_empty())
+                        .map(String::from)
+                        .collect();
+                }
+                other => anyhow::bail!(
+                    "Unknown retry policy key '{}'. Use: max, backoff, cap, on",
+                    other
+                ),
+            }
+        }
+        policy.validate()?;
+        Ok(policy)
+    }
+}
+
+impl std::fmt::Display for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "max={},backoff={},cap={},on={}",
+            self.max_attempts,
+            self.backoff,
+            self.backoff_cap,
+            self.retry_on.join("+")
+        )
+    }
+}
+
+/// A named kind of failure. A failure is in the class if any of the
+/// configured matchers hits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureClass {
+    pub name: String,
+    /// Regular expression searched for in the failure reason
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Exit codes of the agent wrapper or exec command
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exit_codes: Vec<i32>,
+    /// Triage verdicts ("continue", "restart", ...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verdicts: Vec<String>,
+}
+
+impl FailureClass {
+    /// Whether `failure` is in this class. An invalid pattern matches nothing.
+    pub fn matches(&self, failure: &FailureRecord) -> bool {
+        if failure
+            .exit_code
+            .is_some_and(|code| self.exit_codes.contains(&code))
+        {
+            return true;
+        }
+        if let Some(verdict) = &failure.verdict
+            && self.verdicts.contains(verdict)
+        {
+            return true;
+        }
+        match (&self.pattern, &failure.reason) {
+            (Some(pattern), Some(reason)) => {
+                regex::Regex::new(pattern).is_ok_and(|re| re.is_match(reason))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Classes tried after the configured ones
+pub fn builtin_classes() -> Vec<FailureClass> {
+    vec![
+        FailureClass {
+            name: "rate-limit".to_string(),
+            pattern: Some(r"(?i)rate[ _-]?limit|too many requests|\b429\b|overloaded".to_string()),
+            exit_codes: vec![],
+            verdicts: vec![],
+        },
+        FailureClass {
+            name: "timeout".to_string(),
+            pattern: Some(r"(?i)timed out|\btimeout\b".to_string()),
+            exit_codes: vec![124, 137],
+            verdicts: vec![],
+        },
+    ]
+}
+
+/// One failed attempt of a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureRecord {
+    /// Which attempt failed (the task's `retry_count` after the failure)
+    pub attempt: u32,
+    pub failed_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<String>,
+    /// Failure class, set once the coordinator has judged the failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    /// When the automatic retry was scheduled for, if there was one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<String>,
+}
+
+/// Record a failure of `task`, which has just been marked failed (so its
+/// `failure_reason` and `retry_count` are current)
+pub fn record_failure(task: &mut Task, exit_code: Option<i32>, verdict: Option<&str>) {
+    task.retry_history.push(FailureRecord {
+        attempt: task.retry_count,
+        failed_at: Utc::now().to_rfc3339(),
+        reason: task.failure_reason.clone(),
+        exit_code,
+        verdict: verdict.map(String::from),
+        class: None,
+        retry_at: None,
+    });
+}
+
+/// The class of a failure: the first configured class that matches, then the
+/// first built-in one, else [`TASK_FAILURE`]
+pub fn classify(config: &RetryConfig, failure: &FailureRecord) -> String {
+    config
+        .classes
+        .iter()
+        .cloned()
+        .chain(builtin_classes())
+        .find(|class| class.matches(failure))
+        .map_or_else(|| TASK_FAILURE.to_string(), |class| class.name)
+}
+
+/// The policy covering `task`, and the tag it comes from (`None` for the
+/// task's own policy)
+pub fn policy_for<'a>(
+    task: &'a Task,
+    config: &'a RetryConfig,
+) -> Option<(&'a RetryPolicy, Option<&'a str>)> {
+    if let Some(policy) = &task.retry_policy {
+        return Some((policy, None));
+    }
+    task.tags
+        .iter()
+        .find_map(|tag| config.tags.get(tag).map(|p| (p, Some(tag.as_str()))))
+}
+
+/// A retry scheduled by [`apply_policies`]
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoRetry {
+    pub task: String,
+    pub class: String,
+    /// The attempt about to start
+    pub attempt: u32,
+    pub retry_at: String,
+}
+
+/// Judge every failed task covered by a retry policy whose latest failure
+/// hasn't been judged yet, and reopen those worth retrying. Tasks the failure
+/// skipped (see [`crate::graph::skip_unreachable`]) are reopened with it.
+pub fn apply_policies(
+    graph: &mut WorkGraph,
+    config: &RetryConfig,
+    now: DateTime<Utc>,
+) -> Vec<AutoRetry> {
+    let failed: Vec<(String, RetryPolicy)> = graph
+        .tasks()
+        .filter(|t| t.status == Status::Failed)
+        .filter_map(|t| Some((t.id.clone(), policy_for(t, config)?.0.clone())))
+        .collect();
+
+    let mut retried = Vec::new();
+    for (id, policy) in failed {
+        let Some(task) = graph.get_task_mut(&id) else {
+            continue;
+        };
+        // Failures from paths that don't record them
+        if task
+            .retry_history
+            .last()
+            .is_none_or(|r| r.attempt != task.retry_count)
+        {
+            record_failure(task, None, None);
+        }
+        let retry_count = task.retry_count;
+        let max_retries = task.max_retries;
+        let Some(record) = task.retry_history.last_mut() else {
+            continue;
+        };
+        if record.class.is_some() {
+            continue;
+        }
+        let class = classify(config, record);
+        record.class = Some(class.clone());
+        if !policy.retries(&class)
+            || retry_count >= policy.max_attempts
+            || max_retries.is_some_and(|max| retry_count >= max)
+        {
+            continue;
+        }
+
+        let retry_at = i64::try_from(policy.backoff_secs(retry_count))
+            .ok()
+            .and_then(TimeDelta::try_seconds)
+            .and_then(|delay| now.checked_add_signed(delay));
+        let Some(retry_at) = retry_at.map(|at| at.to_rfc3339()) else {
+            continue;
+        };
+        record.retry_at = Some(retry_at.clone());
+        task.status = Status::Open;
+        task.assigned = None;
+        task.failure_reason = None;
+        task.ready_after = Some(retry_at.clone());
+        task.tags.retain(|t| t != "converged");
+        task.log.push(LogEntry {
+            timestamp: now.to_rfc3339(),
+            actor: Some("coordinator".to_string()),
+            message: format!(
+                "Automatic retry (attempt #{}) after {} failure, ready after {}",
+                retry_count + 1,
+                class,
+                retry_at
+            ),
+        });
+        unskip_dependents(graph, &id);
+        retried.push(AutoRetry {
+            task: id,
+            class,
+            attempt: retry_count + 1,
+            retry_at,
+        });
+    }
+    retried
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Node;
+
+    fn failed_task(id: &str, reason: &str, tags: &[&str]) -> Task {
+        let mut task = Task {
+            id: id.to_string(),
+            title: id.to_string(),
+            status: Status::Failed,
+            retry_count: 1,
+            failure_reason: Some(reason.to_string()),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Task::default()
+        };
+        record_failure(&mut task, None, None);
+        task
+    }
+
+    #[test]
+    fn test_policy_spec_round_trip() {
+        let policy: RetryPolicy = "max=5,backoff=30s,cap=10m,on=rate-limit+task"
+            .parse()
+            .unwrap();
+        assert_eq!(policy.max_attempts, 5);
+        assert_eq!(policy.retry_on, vec!["rate-limit", "task"]);
+        assert_eq!(policy.to_string().parse::<RetryPolicy>().unwrap(), policy);
+
+        let policy: RetryPolicy = "4".parse().unwrap();
+        assert_eq!(policy.max_attempts, 4);
+        assert_eq!(policy.backoff, "1m");
+
+        assert!("max=0".parse::<RetryPolicy>().is_err());
+        assert!("backoff=soon".parse::<RetryPolicy>().is_err());
+        assert!("cap=365d".parse::<RetryPolicy>().is_err());
+        assert!("tries=3".parse::<RetryPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let policy: RetryPolicy = "backoff=30s,cap=2m".parse().unwrap();
+        assert_eq!(policy.backoff_secs(1), 30);
+        assert_eq!(policy.backoff_secs(2), 60);
+        assert_eq!(policy.backoff_secs(3), 120);
+        assert_eq!(policy.backoff_secs(10), 120);
+
+        // Unvalidated policies from config.toml are clamped
+        let huge = RetryPolicy {
+            backoff: format!("{}d", u64::MAX / 86400),
+            backoff_cap: format!("{}s", u64::MAX),
+            ..RetryPolicy::default()
+        };
+        assert_eq!(huge.backoff_secs(40), MAX_BACKOFF_SECS);
+    }
+
+    #[test]
+    fn test_classify() {
+        let config = RetryConfig {
+            classes: vec![FailureClass {
+                name: "network".to_string(),
+                pattern: Some("(?i)connection reset".to_string()),
+                exit_codes: vec![75],
+                verdicts: vec![],
+            }],
+            ..RetryConfig::default()
+        };
+        let failure = |reason: &str, exit_code| FailureRecord {
+            attempt: 1,
+            failed_at: Utc::now().to_rfc3339(),
+            reason: Some(reason.to_string()),
+            exit_code,
+            verdict: None,
+            class: None,
+            retry_at: None,
+        };
+        assert_eq!(
+            classify(&config, &failure("API error 429", None)),
+            "rate-limit"
+        );
+        assert_eq!(
+            classify(&config, &failure("Agent exited", Some(124))),
+            "timeout"
+        );
+        assert_eq!(classify(&config, &failure("exit", Some(75))), "network");
+        assert_eq!(
+            classify(&config, &failure("Connection reset by peer", None)),
+            "network"
+        );
+        assert_eq!(
+            classify(&config, &failure("tests fail", Some(1))),
+            TASK_FAILURE
+        );
+    }
+
+    #[test]
+    fn test_apply_policies() {
+        let mut config = RetryConfig::default();
+        config
+            .tags
+            .insert("llm".to_string(), "max=2,backoff=1m".parse().unwrap());
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(failed_task(
+            "limited",
+            "rate limit exceeded",
+            &["llm"],
+        )));
+        graph.add_node(Node::Task(failed_task(
+            "broken",
+            "assertion failed",
+            &["llm"],
+        )));
+        graph.add_node(Node::Task(failed_task(
+            "untagged",
+            "rate limit exceeded",
+            &[],
+        )));
+
+        let now = Utc::now();
+        let retried = apply_policies(&mut graph, &config, now);
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].task, "limited");
+        assert_eq!(retried[0].class, "rate-limit");
+
+        let limited = graph.get_task("limited").unwrap();
+        assert_eq!(limited.status, Status::Open);
+        assert_eq!(
+            limited.ready_after,
+            Some((now + TimeDelta::seconds(60)).to_rfc3339())
+        );
+        let broken = graph.get_task("broken").unwrap();
+        assert_eq!(broken.status, Status::Failed);
+        assert_eq!(broken.retry_history[0].class.as_deref(), Some(TASK_FAILURE));
+        // Judged once
+        assert!(apply_policies(&mut graph, &config, now).is_empty());
+
+        // The second failure uses up the attempts
+        let limited = graph.get_task_mut("limited").unwrap();
+        limited.status = Status::Failed;
+        limited.retry_count = 2;
+        limited.failure_reason = Some("429 Too Many Requests".to_string());
+        record_failure(limited, None, None);
+        assert!(apply_policies(&mut graph, &config, now).is_empty());
+        assert_eq!(graph.get_task("limited").unwrap().retry_history.len(), 2);
+    }
+}
@@ -67,12 +67,24 @@ pub fn snapshot(
     let dest = run_dir(workgraph_dir, run_id);
     fs::create_dir_all(&dest).context("Failed to create run directory")?;
 
-    // Copy graph.jsonl
+    // Copy graph.jsonl (and its journal, if the graph is journaled). A graph
+    // stored in SQLite is exported as plain JSONL. No lock is taken: replay
+    // snapshots from inside a graph transaction.
     let graph_src = workgraph_dir.join("graph.jsonl");
-    if graph_src.exists() {
+    if crate::parser::is_sqlite(&graph_src) {
+        let (graph, _) = crate::parser::read_graph_unlocked(&graph_src)
+            .context("Failed to read graph for snapshot")?;
+        crate::parser::write_snapshot_unlocked(&graph, &dest.join("graph.jsonl"))
+            .context("Failed to write graph.jsonl to snapshot")?;
+    } else if graph_src.exists() {
         fs::copy(&graph_src, dest.join("graph.jsonl"))
             .context("Failed to copy graph.jsonl to snapshot")?;
     }
+    let journal_src = crate::parser::journal_path(&graph_src);
+    if journal_src.exists() {
+        fs::copy(&journal_src, crate::parser::journal_path(dest.join("graph.jsonl")))
+            .context("Failed to copy graph journal to snapshot")?;
+    }
 
     // Copy config.toml
     let config_src = workgraph_dir.join("config.toml");
@@ -122,14 +134,32 @@ pub fn list_runs(workgraph_dir: &Path) -> Result<Vec<String>> {
 
 /// Restore graph.jsonl from a snapshot.
 ///
-/// Replaces the current graph.jsonl with the snapshot's copy.
+/// Replaces the current graph.jsonl (and journal, if any) with the snapshot's copy.
 pub fn restore_graph(workgraph_dir: &Path, run_id: &str) -> Result<()> {
     let src = run_dir(workgraph_dir, run_id).join("graph.jsonl");
     if !src.exists() {
         anyhow::bail!("Snapshot graph.jsonl not found for run '{}'", run_id);
     }
     let dest = workgraph_dir.join("graph.jsonl");
+    if crate::parser::is_sqlite(&dest) {
+        // Keep the SQLite backend; load the snapshot into the database
+        let (graph, _) = crate::parser::read_graph_unlocked(&src)
+            .context("Failed to read snapshot graph.jsonl")?;
+        crate::parser::write_graph_unlocked(&graph, &dest)
+            .context("Failed to restore graph from snapshot")?;
+        return Ok(());
+    }
     fs::copy(&src, &dest).context("Failed to restore graph.jsonl from snapshot")?;
+
+    // Keep the live journal consistent with the restored snapshot
+    let src_journal = crate::parser::journal_path(&src);
+    let dest_journal = crate::parser::journal_path(&dest);
+    if src_journal.exists() {
+        fs::copy(&src_journal, &dest_journal)
+            .context("Failed to restore graph journal from snapshot")?;
+    } else if dest_journal.exists() {
+        fs::write(&dest_journal, "").context("Failed to reset graph journal")?;
+    }
     Ok(())
 }
 
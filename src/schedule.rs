@@ -0,0 +1,676 @@
+//! Cron-style recurring tasks
+//!
+//! A task with a [`Schedule`] fires at each time its cron expression matches
+//! (evaluated in UTC). Firing either reopens the task, incrementing its
+//! `loop_iteration` like a loop edge does, or, in copy mode, adds a fresh copy
+//! of it and leaves the scheduled task itself paused as the template.
+//!
+//! The coordinator fires schedules on each tick ([`fire_due`]). Fire times
+//! that pass while the daemon is down, or while a reopened task is still
+//! running, are handled by the schedule's [`MissedFire`] policy.
+
+use crate::graph::{LogEntry, Node, Status, Task, WorkGraph};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Most fire times handled in one go by [`MissedFire::CatchUpAll`]
+pub const MAX_CATCH_UP: usize = 100;
+
+/// A task's recurrence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Cron expression: `min hour day-of-month month day-of-week`, or a macro
+    /// such as `@daily`
+    pub cron: String,
+    /// Add a copy of the task at each fire instead of reopening it
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub copy: bool,
+    /// Policy for fire times that passed unhandled (None = the
+    /// `coordinator.missed_fire` config default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub missed: Option<MissedFire>,
+    /// Earliest fire time not yet handled (ISO 8601 / RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_fire: Option<String>,
+}
+
+fn is_false(val: &bool) -> bool {
+    !*val
+}
+
+/// What to do when more than one fire time is due, or the only one is late
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum MissedFire {
+    /// Drop missed fires; only fire if the latest fire time is on time
+    Skip,
+    /// Fire once for all the missed fire times
+    #[default]
+    CatchUpOne,
+    /// Fire once per missed fire time (up to [`MAX_CATCH_UP`])
+    CatchUpAll,
+}
+
+impl std::fmt::Display for MissedFire {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MissedFire::Skip => write!(f, "skip"),
+            MissedFire::CatchUpOne => write!(f, "catch-up-one"),
+            MissedFire::CatchUpAll => write!(f, "catch-up-all"),
+        }
+    }
+}
+
+impl std::str::FromStr for MissedFire {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "skip" => Ok(MissedFire::Skip),
+            "catch-up-one" | "one" => Ok(MissedFire::CatchUpOne),
+            "catch-up-all" | "all" => Ok(MissedFire::CatchUpAll),
+            other => anyhow::bail!(
+                "Unknown missed-fire policy '{}'. Must be: skip, catch-up-one, catch-up-all",
+                other
+            ),
+        }
+    }
+}
+
+/// A parsed cron expression. Each field is a bitmask of the values it matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day-of-month and day-of-week were both restricted, so a day matches if
+    /// either does (as in standard cron)
+    either_day: bool,
+}
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// Years searched for a match before deciding an expression never fires
+/// (long enough to find Feb 29)
+const SEARCH_YEARS: i32 = 8;
+
+impl CronExpr {
+    /// Parse a five-field cron expression, or one of `@yearly`, `@annually`,
+    /// `@monthly`, `@weekly`, `@daily`, `@midnight`, `@hourly`.
+    ///
+    /// Fields accept `*`, values, ranges (`1-5`), lists (`1,15`) and steps
+    /// (`*/15`, `9-17/2`); months and weekdays also accept three-letter names.
+    /// Weekday 7 is Sunday, like 0.
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expanded = match expr.trim().to_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@hourly" => "0 * * * *".to_string(),
+            other => other.to_string(),
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            anyhow::bail!(
+                "Invalid cron expression '{}': expected 5 fields (min hour day month weekday)",
+                expr
+            );
+        };
+
+        let parsed = (|| -> Result<Self> {
+            let mut weekdays = parse_field(weekday, 0, 7, WEEKDAY_NAMES, 0)?;
+            if weekdays & (1 << 7) != 0 {
+                weekdays = (weekdays | 1) & !(1 << 7);
+            }
+            Ok(CronExpr {
+                minutes: parse_field(minute, 0, 59, &[], 0)?,
+                hours: parse_field(hour, 0, 23, &[], 0)?,
+                days: parse_field(day, 1, 31, &[], 0)?,
+                months: parse_field(month, 1, 12, MONTH_NAMES, 1)?,
+                weekdays,
+                either_day: !day.starts_with('*') && !weekday.starts_with('*'),
+            })
+        })()
+        .with_context(|| format!("Invalid cron expression '{}'", expr))?;
+
+        let probe = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+        if parsed.next_after(probe).is_none() {
+            anyhow::bail!("Cron expression '{}' never fires", expr);
+        }
+        Ok(parsed)
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.either_day {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// The first matching minute strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after.year() + SEARCH_YEARS;
+        while t.year() <= limit {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.day_matches(t.date_naive()) {
+                t = (t.date_naive() + Duration::days(1))
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + Duration::hours(1);
+            } else if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+/// Parse one cron field into a bitmask over `min..=max`. `names[i]` is an
+/// alias for `first_name + i`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str], first_name: u32) -> Result<u64> {
+    let value = |s: &str| -> Result<u32> {
+        if let Some(i) = names.iter().position(|n| *n == s) {
+            return Ok(first_name + i as u32);
+        }
+        let v: u32 = s
+            .parse()
+            .with_context(|| format!("'{}' is not a number", s))?;
+        if v < min || v > max {
+            anyhow::bail!("{} is out of range {}-{}", v, min, max);
+        }
+        Ok(v)
+    };
+
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .with_context(|| format!("invalid step '{}'", step))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (value(lo)?, value(hi)?)
+        } else if part.contains('/') {
+            // "a/n" runs from a to the end of the range
+            (value(range)?, max)
+        } else {
+            let v = value(range)?;
+            (v, v)
+        };
+        if lo > hi {
+            anyhow::bail!("range {}-{} is backwards", lo, hi);
+        }
+        for v in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+impl Schedule {
+    /// A schedule for `cron` with its first fire time after `now`
+    pub fn new(
+        cron: &str,
+        copy: bool,
+        missed: Option<MissedFire>,
+        now: DateTime<Utc>,
+    ) -> Result<Self> {
+        let expr = CronExpr::parse(cron)?;
+        Ok(Schedule {
+            cron: cron.trim().to_string(),
+            copy,
+            missed,
+            next_fire: expr.next_after(now).map(|t| t.to_rfc3339()),
+        })
+    }
+
+    /// Up to `count` fire times from the next unhandled one on
+    pub fn upcoming(&self, now: DateTime<Utc>, count: usize) -> Vec<DateTime<Utc>> {
+        let Ok(expr) = CronExpr::parse(&self.cron) else {
+            return vec![];
+        };
+        let first = self
+            .next_fire
+            .as_ref()
+            .and_then(|t| t.parse::<DateTime<Utc>>().ok())
+            .or_else(|| expr.next_after(now));
+        std::iter::successors(first, |t| expr.next_after(*t))
+            .take(count)
+            .collect()
+    }
+}
+
+/// Attach `schedule` to a task. A reopening schedule holds an open task back
+/// until the first fire; a copying one pauses the task, which becomes the
+/// template for its copies.
+pub fn apply(task: &mut Task, schedule: Schedule) {
+    if schedule.copy {
+        task.paused = true;
+    } else if task.status == Status::Open {
+        task.ready_after = schedule.next_fire.clone();
+    }
+    task.schedule = Some(schedule);
+}
+
+/// One fire handled by [`fire_due`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Fired {
+    /// The scheduled task
+    pub task: String,
+    /// Fire times acted on
+    pub fires: Vec<DateTime<Utc>>,
+    /// Copies added (copy mode)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub created: Vec<String>,
+    /// Due fire times dropped by the missed-fire policy
+    pub missed: usize,
+}
+
+/// Fire every schedule that is due at `now`.
+///
+/// A fire is on time if it is at most `grace` old; older ones count as missed
+/// and are handled per the schedule's policy (or `default_missed`). A
+/// reopening schedule only fires once its task is done or failed: while the
+/// task is still open it just releases it, and while it is in progress or
+/// awaiting review the fire waits. Abandoned tasks never fire, and paused
+/// ones wait.
+pub fn fire_due(
+    graph: &mut WorkGraph,
+    now: DateTime<Utc>,
+    default_missed: MissedFire,
+    grace: Duration,
+) -> Vec<Fired> {
+    let scheduled: Vec<String> = graph
+        .tasks()
+        .filter(|t| t.schedule.is_some() && t.status != Status::Abandoned)
+        .map(|t| t.id.clone())
+        .collect();
+
+    let mut fired = Vec::new();
+    for id in scheduled {
+        if let Some(f) = fire_task(graph, &id, now, default_missed, grace) {
+            fired.push(f);
+        }
+    }
+    fired
+}
+
+fn fire_task(
+    graph: &mut WorkGraph,
+    id: &str,
+    now: DateTime<Utc>,
+    default_missed: MissedFire,
+    grace: Duration,
+) -> Option<Fired> {
+    let task = graph.get_task_mut(id)?;
+    let schedule = task.schedule.as_mut()?;
+    let Ok(expr) = CronExpr::parse(&schedule.cron) else {
+        return None;
+    };
+    let Some(next) = schedule
+        .next_fire
+        .as_ref()
+        .and_then(|t| t.parse::<DateTime<Utc>>().ok())
+    else {
+        schedule.next_fire = expr.next_after(now).map(|t| t.to_rfc3339());
+        return None;
+    };
+    if next > now {
+        return None;
+    }
+    let copy = schedule.copy;
+    let busy = matches!(
+        task.status,
+        Status::InProgress | Status::Blocked | Status::InReview
+    );
+    if !copy && (task.paused || busy) {
+        return None;
+    }
+
+    let due: Vec<DateTime<Utc>> = std::iter::successors(Some(next), |t| expr.next_after(*t))
+        .take_while(|t| *t <= now)
+        .take(MAX_CATCH_UP)
+        .collect();
+    let latest = *due.last()?;
+    let mut fires = match schedule.missed.unwrap_or(default_missed) {
+        MissedFire::Skip if now - latest > grace => vec![],
+        MissedFire::Skip | MissedFire::CatchUpOne => vec![latest],
+        MissedFire::CatchUpAll => due.clone(),
+    };
+    // Reopening handles one fire at a time; the rest wait for the next run
+    let mut next_fire = expr.next_after(now);
+    if !copy && fires.len() > 1 {
+        fires.truncate(1);
+        next_fire = due.get(1).copied();
+    }
+    schedule.next_fire = next_fire.map(|t| t.to_rfc3339());
+    let pending = next_fire.map_or(0, |t| due.iter().filter(|d| **d >= t).count());
+    let missed = due.len() - fires.len() - pending;
+
+    let mut created = Vec::new();
+    if copy {
+        let template = task.clone();
+        for fire in &fires {
+            let copy_id = format!("{}-{}", template.id, fire.format("%Y%m%d-%H%M"));
+            if graph.get_node(&copy_id).is_some() {
+                continue;
+            }
+            graph.add_node(Node::Task(copy_of(&template, &copy_id, *fire, now)));
+            created.push(copy_id);
+        }
+    } else if let Some(fire) = fires.first() {
+        let reopen = task.status != Status::Open;
+        if reopen {
+            task.status = Status::Open;
+            task.assigned = None;
+            task.started_at = None;
+            task.completed_at = None;
+            task.failure_reason = None;
+            task.loop_iteration += 1;
+        }
+        task.ready_after = None;
+        task.log.push(LogEntry {
+            timestamp: now.to_rfc3339(),
+            actor: None,
+            message: if reopen {
+                format!(
+                    "Re-opened by schedule for {} (iteration {})",
+                    fire.format("%Y-%m-%d %H:%M"),
+                    task.loop_iteration
+                )
+            } else {
+                format!("Released by schedule for {}", fire.format("%Y-%m-%d %H:%M"))
+            },
+        });
+    }
+
+    let task = graph.get_task_mut(id)?;
+    if missed > 0 {
+        task.log.push(LogEntry {
+            timestamp: now.to_rfc3339(),
+            actor: None,
+            message: format!("Schedule skipped {} missed fire(s)", missed),
+        });
+    }
+    if !created.is_empty() {
+        task.log.push(LogEntry {
+            timestamp: now.to_rfc3339(),
+            actor: None,
+            message: format!("Schedule created {}", created.join(", ")),
+        });
+    }
+    Some(Fired {
+        task: id.to_string(),
+        fires,
+        created,
+        missed,
+    })
+}
+
+/// A fresh, unscheduled copy of `template` for the fire at `fire`
+fn copy_of(template: &Task, id: &str, fire: DateTime<Utc>, now: DateTime<Utc>) -> Task {
+    Task {
+        id: id.to_string(),
+        status: Status::Open,
+        schedule: None,
+        paused: false,
+        not_before: None,
+        deadline: None,
+        assigned: None,
+        blocks: vec![],
+        blocked_by: vec![],
+        dep_groups: vec![],
+        dep_conditions: Default::default(),
+        fan_out: None,
+        outputs: Default::default(),
+        loops_to: vec![],
+        loop_iteration: 0,
+        artifacts: vec![],
+        artifact_snapshots: Default::default(),
+        created_at: Some(now.to_rfc3339()),
+        started_at: None,
+        completed_at: None,
+        ready_after: None,
+        retry_count: 0,
+        retry_history: Vec::new(),
+        failure_reason: None,
+        log: vec![LogEntry {
+            timestamp: now.to_rfc3339(),
+            actor: None,
+            message: format!(
+                "Created by schedule on {} for {}",
+                template.id,
+                fire.format("%Y-%m-%d %H:%M")
+            ),
+        }],
+        ..template.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let weekdays_9 = CronExpr::parse("0 9 * * mon-fri").unwrap();
+        // 2026-10-02 is a Friday
+        assert_eq!(
+            weekdays_9.next_after(at("2026-10-02T08:59:30Z")),
+            Some(at("2026-10-02T09:00:00Z"))
+        );
+        assert_eq!(
+            weekdays_9.next_after(at("2026-10-02T09:00:00Z")),
+            Some(at("2026-10-05T09:00:00Z"))
+        );
+
+        let monthly = CronExpr::parse("@monthly").unwrap();
+        assert_eq!(
+            monthly.next_after(at("2026-12-15T12:00:00Z")),
+            Some(at("2027-01-01T00:00:00Z"))
+        );
+
+        let quarter_hours = CronExpr::parse("*/15 9-17/4 * * *").unwrap();
+        assert_eq!(
+            quarter_hours.next_after(at("2026-10-01T13:50:00Z")),
+            Some(at("2026-10-01T17:00:00Z"))
+        );
+
+        // Day-of-month OR day-of-week when both are restricted
+        let first_or_sunday = CronExpr::parse("0 0 1 * 7").unwrap();
+        assert_eq!(
+            first_or_sunday.next_after(at("2026-10-01T00:00:00Z")),
+            Some(at("2026-10-04T00:00:00Z"))
+        );
+
+        let leap = CronExpr::parse("0 0 29 feb *").unwrap();
+        assert_eq!(
+            leap.next_after(at("2026-03-01T00:00:00Z")),
+            Some(at("2028-02-29T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_cron_parse_errors() {
+        assert!(CronExpr::parse("0 9 * *").is_err());
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("0 0 30 feb *").is_err());
+        assert!(CronExpr::parse("0 0 * * */0").is_err());
+        assert!(CronExpr::parse("0 17-9 * * *").is_err());
+    }
+
+    fn scheduled_graph(copy: bool, missed: MissedFire, created: &str) -> WorkGraph {
+        let mut task = Task {
+            id: "audit".to_string(),
+            title: "Dependency audit".to_string(),
+            ..Task::default()
+        };
+        apply(
+            &mut task,
+            Schedule::new("0 9 * * *", copy, Some(missed), at(created)).unwrap(),
+        );
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(task));
+        graph
+    }
+
+    #[test]
+    fn test_fire_due_reopens() {
+        let grace = Duration::minutes(2);
+        let mut graph = scheduled_graph(false, MissedFire::CatchUpOne, "2026-10-01T08:00:00Z");
+        assert_eq!(
+            graph.get_task("audit").unwrap().ready_after,
+            Some(at("2026-10-01T09:00:00Z").to_rfc3339())
+        );
+        assert!(
+            fire_due(
+                &mut graph,
+                at("2026-10-01T08:59:00Z"),
+                MissedFire::Skip,
+                grace
+            )
+            .is_empty()
+        );
+
+        // First fire releases the open task
+        let fired = fire_due(
+            &mut graph,
+            at("2026-10-01T09:00:30Z"),
+            MissedFire::Skip,
+            grace,
+        );
+        assert_eq!(fired[0].fires, vec![at("2026-10-01T09:00:00Z")]);
+        let task = graph.get_task("audit").unwrap();
+        assert_eq!(task.ready_after, None);
+        assert_eq!(task.loop_iteration, 0);
+
+        // Still running at the next fire: it waits, then catches up once
+        graph.get_task_mut("audit").unwrap().status = Status::InProgress;
+        assert!(
+            fire_due(
+                &mut graph,
+                at("2026-10-02T09:00:00Z"),
+                MissedFire::Skip,
+                grace
+            )
+            .is_empty()
+        );
+        graph.get_task_mut("audit").unwrap().status = Status::Done;
+        let fired = fire_due(
+            &mut graph,
+            at("2026-10-03T12:00:00Z"),
+            MissedFire::Skip,
+            grace,
+        );
+        assert_eq!(fired[0].fires, vec![at("2026-10-03T09:00:00Z")]);
+        assert_eq!(fired[0].missed, 1);
+        let task = graph.get_task("audit").unwrap();
+        assert_eq!(task.status, Status::Open);
+        assert_eq!(task.loop_iteration, 1);
+        assert_eq!(
+            task.schedule.as_ref().unwrap().next_fire.as_deref(),
+            Some(at("2026-10-04T09:00:00Z").to_rfc3339().as_str())
+        );
+    }
+
+    #[test]
+    fn test_fire_due_waits_for_review() {
+        let grace = Duration::minutes(2);
+        let mut graph = scheduled_graph(false, MissedFire::CatchUpOne, "2026-10-01T08:00:00Z");
+        fire_due(
+            &mut graph,
+            at("2026-10-01T09:00:30Z"),
+            MissedFire::Skip,
+            grace,
+        );
+
+        // Work awaiting sign-off isn't reopened under the reviewer
+        graph.get_task_mut("audit").unwrap().status = Status::InReview;
+        assert!(
+            fire_due(
+                &mut graph,
+                at("2026-10-02T09:00:30Z"),
+                MissedFire::Skip,
+                grace
+            )
+            .is_empty()
+        );
+        assert_eq!(graph.get_task("audit").unwrap().status, Status::InReview);
+
+        graph.get_task_mut("audit").unwrap().status = Status::Done;
+        let fired = fire_due(
+            &mut graph,
+            at("2026-10-02T09:01:00Z"),
+            MissedFire::Skip,
+            grace,
+        );
+        assert_eq!(fired[0].fires, vec![at("2026-10-02T09:00:00Z")]);
+        assert_eq!(graph.get_task("audit").unwrap().status, Status::Open);
+    }
+
+    #[test]
+    fn test_fire_due_copies_and_missed_policies() {
+        let grace = Duration::minutes(2);
+        let now = at("2026-10-03T12:00:00Z");
+
+        let mut graph = scheduled_graph(true, MissedFire::CatchUpAll, "2026-10-01T08:00:00Z");
+        assert!(graph.get_task("audit").unwrap().paused);
+        let fired = fire_due(&mut graph, now, MissedFire::Skip, grace);
+        assert_eq!(
+            fired[0].created,
+            vec![
+                "audit-20261001-0900",
+                "audit-20261002-0900",
+                "audit-20261003-0900"
+            ]
+        );
+        let copy = graph.get_task("audit-20261002-0900").unwrap();
+        assert!(copy.schedule.is_none() && !copy.paused);
+        assert_eq!(copy.title, "Dependency audit");
+
+        // Skip: three fires were missed and the latest is late
+        let mut graph = scheduled_graph(true, MissedFire::Skip, "2026-10-01T08:00:00Z");
+        let fired = fire_due(&mut graph, now, MissedFire::CatchUpAll, grace);
+        assert!(fired[0].created.is_empty());
+        assert_eq!(fired[0].missed, 3);
+        // ...but an on-time fire goes ahead
+        let fired = fire_due(
+            &mut graph,
+            at("2026-10-04T09:01:00Z"),
+            MissedFire::CatchUpAll,
+            grace,
+        );
+        assert_eq!(fired[0].created, vec!["audit-20261004-0900"]);
+    }
+}
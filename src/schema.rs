@@ -0,0 +1,445 @@
+//! Graph schema versions and migrations
+//!
+//! `graph.jsonl` starts with a header line, `{"kind":"schema","version":N}`,
+//! naming the schema version its nodes were written in (SQLite graphs keep it
+//! in the database's `user_version`). A graph without a header predates
+//! versioning and is version 0.
+//!
+//! [`MIGRATIONS`] is the ordered registry of steps from one version to the
+//! next; the current version is the number of steps. Graphs written in an
+//! older version are upgraded in memory on load, and on disk on the next save
+//! or with `wg migrate --apply` (which keeps a backup). A graph written in a
+//! newer version is refused with [`crate::parser::ParseError::SchemaTooNew`],
+//! rather than loaded with the fields this binary doesn't know dropped.
+//!
+//! Steps work on the JSON encoding of one node at a time and must be
+//! idempotent: in journaled mode, records appended since the last compaction
+//! may already be in the current version.
+//!
+//! Any change to what a node serializes to needs a step, even one that
+//! leaves old nodes alone: the version bump is what stops an older `wg` from
+//! loading the graph and dropping the fields it doesn't know.
+
+use serde_json::Value;
+
+/// `kind` of the header line in `graph.jsonl`
+pub const HEADER_KIND: &str = "schema";
+
+/// Schema version written by this build
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// What a migration step did to a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Unchanged,
+    Changed,
+    /// The node is obsolete and must be dropped
+    Removed,
+}
+
+/// One step in the migration registry
+pub struct Migration {
+    /// Version the step migrates to (its position in [`MIGRATIONS`] + 1)
+    pub version: u32,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub apply: fn(&mut Value) -> Change,
+}
+
+/// Ordered migration steps. Append new steps; never edit or reorder old ones.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "legacy-nodes",
+        description: "drop actor nodes, replace inline task identity with its agent hash, and \
+                      map pending-review status to done",
+        apply: legacy_nodes,
+    },
+    Migration {
+        version: 2,
+        name: "task-deadline",
+        description: "tasks gain deadline (existing nodes are unchanged)",
+        apply: unchanged,
+    },
+    Migration {
+        version: 3,
+        name: "task-schedule",
+        description: "tasks gain schedule (existing nodes are unchanged)",
+        apply: unchanged,
+    },
+    Migration {
+        version: 4,
+        name: "loop-guards",
+        description: "loop edges gain reward, artifact and shell guards (existing nodes are \
+                      unchanged)",
+        apply: unchanged,
+    },
+    Migration {
+        version: 5,
+        name: "dep-groups",
+        description: "tasks gain dep_groups (existing nodes are unchanged)",
+        apply: unchanged,
+    },
+    Migration {
+        version: 6,
+        name: "dep-conditions",
+        description: "tasks gain dep_conditions (existing nodes are unchanged)",
+        apply: unchanged,
+    },
+    Migration {
+        version: 7,
+        name: "retry-policies",
+        description: "tasks gain retry_policy and retry_history (existing nodes are unchanged)",
+        apply: unchanged,
+    },
+    Migration {
+        version: 8,
+        name: "fan-out",
+        description: "tasks gain fan_out (existing nodes are unchanged)",
+        apply: unchanged,
+    },
+    Migration {
+        version: 9,
+        name: "task-outputs",
+        description: "tasks gain outputs (existing nodes are unchanged)",
+        apply: unchanged,
+    },
+    Migration {
+        version: 10,
+        name: "artifact-snapshots",
+        description: "tasks gain artifact_snapshots (existing nodes are unchanged)",
+        apply: unchanged,
+    },
+    Migration {
+        version: 11,
+        name: "in-review-status",
+        description: "tasks may have status in-review, awaiting 'wg approve' or 'wg reject' \
+                      (existing nodes are unchanged)",
+        apply: unchanged,
+    },
+];
+
+/// The header line for the current schema version (without newline)
+pub fn header_line() -> String {
+    serde_json::json!({ "kind": HEADER_KIND, "version": SCHEMA_VERSION }).to_string()
+}
+
+/// The version named by a header line, or `None` if `value` is not a header
+pub fn header_version(value: &Value) -> Option<u32> {
+    if value.get("kind").and_then(Value::as_str) != Some(HEADER_KIND) {
+        return None;
+    }
+    Some(
+        value
+            .get("version")
+            .and_then(Value::as_u64)
+            .map_or(0, |v| v.min(u32::MAX as u64) as u32),
+    )
+}
+
+/// Steps needed to bring a graph from `version` to [`SCHEMA_VERSION`]
+pub fn pending(version: u32) -> &'static [Migration] {
+    MIGRATIONS.get(version as usize..).unwrap_or_default()
+}
+
+/// Run the steps after `version` over one node. Returns `false` if the node
+/// must be dropped.
+pub fn migrate_node(version: u32, node: &mut Value) -> bool {
+    pending(version)
+        .iter()
+        .all(|step| (step.apply)(node) != Change::Removed)
+}
+
+/// A step that only adds optional fields or values: nodes written before
+/// it are already valid
+fn unchanged(_node: &mut Value) -> Change {
+    Change::Unchanged
+}
+
+/// v0 -> v1: the ad hoc upgrades `wg` used to do while parsing
+fn legacy_nodes(node: &mut Value) -> Change {
+    let Some(fields) = node.as_object_mut() else {
+        return Change::Unchanged;
+    };
+    match fields.get("kind").and_then(Value::as_str) {
+        // Actor nodes were removed with the actor system
+        Some("actor") => return Change::Removed,
+        Some("task") => {}
+        _ => return Change::Unchanged,
+    }
+
+    let mut change = Change::Unchanged;
+    if fields.get("status").and_then(Value::as_str) == Some("pending-review") {
+        fields.insert("status".to_string(), Value::from("done"));
+        change = Change::Changed;
+    }
+    if let Some(identity) = fields.remove("identity") {
+        change = Change::Changed;
+        let role = identity.get("role_id").and_then(Value::as_str);
+        let objective = identity.get("objective_id").and_then(Value::as_str);
+        if fields.get("agent").is_none_or(Value::is_null)
+            && let (Some(role), Some(objective)) = (role, objective)
+        {
+            let agent = crate::identity::content_hash_agent(role, objective);
+            fields.insert("agent".to_string(), Value::from(agent));
+        }
+    }
+    change
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Status;
+    use serde_json::json;
+
+    #[test]
+    fn test_registry_is_ordered() {
+        for (i, step) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(step.version as usize, i + 1, "{}", step.name);
+        }
+        assert_eq!(pending(0).len(), MIGRATIONS.len());
+        assert!(pending(SCHEMA_VERSION).is_empty());
+        assert!(pending(SCHEMA_VERSION + 1).is_empty());
+    }
+
+    #[test]
+    fn test_header_round_trip() {
+        let header: Value = serde_json::from_str(&header_line()).unwrap();
+        assert_eq!(header_version(&header), Some(SCHEMA_VERSION));
+        assert_eq!(header_version(&json!({ "kind": "task", "id": "t" })), None);
+    }
+
+    #[test]
+    fn test_legacy_nodes() {
+        let mut actor = json!({ "kind": "actor", "id": "erik" });
+        assert!(!migrate_node(0, &mut actor));
+
+        let mut task = json!({
+            "kind": "task",
+            "id": "t",
+            "status": "pending-review",
+            "identity": { "role_id": "r", "objective_id": "o" },
+        });
+        assert!(migrate_node(0, &mut task));
+        assert_eq!(task["status"], "done");
+        assert!(task.get("identity").is_none());
+        assert_eq!(task["agent"], crate::identity::content_hash_agent("r", "o"));
+
+        // Idempotent
+        let before = task.clone();
+        assert_eq!(legacy_nodes(&mut task), Change::Unchanged);
+        assert_eq!(task, before);
+    }
+
+    /// Top-level task keys, by the schema version that introduced them.
+    /// Append-only, like [`MIGRATIONS`]: a new key goes in a new entry for
+    /// the version its migration step introduces.
+    const TASK_KEYS: &[(u32, &[&str])] = &[
+        (
+            1,
+            &[
+                "kind",
+                "id",
+                "title",
+                "description",
+                "status",
+                "assigned",
+                "estimate",
+                "blocks",
+                "blocked_by",
+                "requires",
+                "resource_amounts",
+                "tags",
+                "skills",
+                "inputs",
+                "deliverables",
+                "artifacts",
+                "exec",
+                "not_before",
+                "created_at",
+                "started_at",
+                "completed_at",
+                "log",
+                "retry_count",
+                "max_retries",
+                "failure_reason",
+                "model",
+                "verify",
+                "agent",
+                "loops_to",
+                "loop_iteration",
+                "ready_after",
+                "paused",
+                "priority",
+                "value",
+                "parent",
+                "auto_complete",
+                "fields",
+            ],
+        ),
+        (2, &["deadline"]),
+        (3, &["schedule"]),
+        (5, &["dep_groups"]),
+        (6, &["dep_conditions"]),
+        (7, &["retry_policy", "retry_history"]),
+        (8, &["fan_out"]),
+        (9, &["outputs"]),
+        (10, &["artifact_snapshots"]),
+    ];
+
+    /// A task with every field set, so every key is serialized. Built
+    /// without `..Default::default()` so a new field fails to compile here.
+    fn full_task() -> crate::graph::Task {
+        use crate::graph::*;
+        use std::collections::BTreeMap;
+        let s = |v: &str| Some(v.to_string());
+        let list = |v: &str| vec![v.to_string()];
+        Task {
+            id: "t".to_string(),
+            title: "T".to_string(),
+            description: s("d"),
+            status: Status::Open,
+            assigned: s("a"),
+            estimate: Some(Estimate {
+                hours: Some(1.0),
+                cost: None,
+            }),
+            blocks: list("b"),
+            blocked_by: list("c"),
+            dep_groups: vec![DepGroup {
+                tasks: list("c"),
+                need: 1,
+                abandon_rest: false,
+            }],
+            dep_conditions: BTreeMap::from([("c".to_string(), DepCondition::OnSuccess)]),
+            requires: list("gpu"),
+            resource_amounts: BTreeMap::from([("gpu".to_string(), 1.0)]),
+            tags: list("x"),
+            skills: list("rust"),
+            inputs: list("in"),
+            deliverables: list("out"),
+            artifacts: list("out"),
+            artifact_snapshots: BTreeMap::from([(
+                "out".to_string(),
+                crate::artifacts::ArtifactSnapshot {
+                    sha256: "0".repeat(64),
+                    size: 0,
+                    mtime: None,
+                },
+            )]),
+            exec: s("true"),
+            not_before: s("2026-01-01T00:00:00Z"),
+            deadline: s("2026-01-02T00:00:00Z"),
+            created_at: s("2026-01-01T00:00:00Z"),
+            started_at: s("2026-01-01T00:00:00Z"),
+            completed_at: s("2026-01-01T00:00:00Z"),
+            log: vec![LogEntry {
+                timestamp: "2026-01-01T00:00:00Z".to_string(),
+                actor: None,
+                message: "m".to_string(),
+            }],
+            retry_count: 1,
+            max_retries: Some(2),
+            retry_policy: Some(crate::retry::RetryPolicy::default()),
+            retry_history: vec![crate::retry::FailureRecord {
+                attempt: 1,
+                failed_at: "2026-01-01T00:00:00Z".to_string(),
+                reason: None,
+                exit_code: None,
+                verdict: None,
+                class: None,
+                retry_at: None,
+            }],
+            fan_out: Some(crate::fan_out::FanOut::new("t", "T", None, None, "t-join")),
+            outputs: BTreeMap::from([("k".to_string(), Value::from(1))]),
+            failure_reason: s("f"),
+            model: s("m"),
+            verify: s("v"),
+            agent: s("g"),
+            loops_to: vec![LoopEdge {
+                target: "c".to_string(),
+                guard: None,
+                max_iterations: 1,
+                delay: None,
+            }],
+            loop_iteration: 1,
+            ready_after: s("2026-01-01T00:00:00Z"),
+            schedule: Some(crate::schedule::Schedule {
+                cron: "0 9 * * *".to_string(),
+                copy: false,
+                missed: None,
+                next_fire: None,
+            }),
+            paused: true,
+            priority: Priority::High,
+            value: Some(1.0),
+            parent: s("p"),
+            auto_complete: true,
+            fields: BTreeMap::from([("k".to_string(), Value::from("v"))]),
+        }
+    }
+
+    #[test]
+    fn test_new_task_keys_bump_the_schema_version() {
+        let node = serde_json::to_value(crate::graph::Node::Task(full_task())).unwrap();
+        let mut serialized: Vec<&str> = node
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut known: Vec<&str> = TASK_KEYS
+            .iter()
+            .flat_map(|(_, keys)| *keys)
+            .copied()
+            .collect();
+        serialized.sort_unstable();
+        known.sort_unstable();
+        assert_eq!(
+            serialized, known,
+            "task keys changed: add a migration step and list new keys under its version"
+        );
+        for pair in TASK_KEYS.windows(2) {
+            assert!(pair[0].0 < pair[1].0, "TASK_KEYS must be in version order");
+        }
+        assert!(
+            TASK_KEYS.last().is_some_and(|(v, _)| *v <= SCHEMA_VERSION),
+            "task keys were added without a schema version bump"
+        );
+    }
+
+    /// The schema version that introduced each status. There's no wildcard
+    /// arm, so a new status doesn't compile until it's given the version of
+    /// its migration step.
+    fn status_version(status: Status) -> u32 {
+        match status {
+            Status::Open
+            | Status::InProgress
+            | Status::Done
+            | Status::Blocked
+            | Status::Failed
+            | Status::Abandoned => 1,
+            Status::InReview => 11,
+        }
+    }
+
+    #[test]
+    fn test_new_statuses_bump_the_schema_version() {
+        for status in [
+            Status::Open,
+            Status::InProgress,
+            Status::Done,
+            Status::Blocked,
+            Status::Failed,
+            Status::Abandoned,
+            Status::InReview,
+        ] {
+            assert!(
+                status_version(status) <= SCHEMA_VERSION,
+                "status {} was added without a schema version bump",
+                status
+            );
+        }
+    }
+}
@@ -0,0 +1,573 @@
+//! Full-text task search
+//!
+//! `wg search` ranks tasks with BM25 over their title, description, log
+//! messages and failure reason, including tasks moved to `archive.jsonl`.
+//!
+//! The inverted index lives in `.workgraph/search-index.json`. Once built, it
+//! is brought up to date after every graph save ([`update_after_save`]), once
+//! the graph lock is released so other writers don't wait on indexing, and
+//! [`SearchIndex::open`] re-indexes anything that changed behind its back
+//! (e.g. writes made directly through the SQLite store), so searches never
+//! see stale text. Each task is stored with a fingerprint of its indexed
+//! text, so an update only re-tokenizes tasks that actually changed.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::graph::{Node, Task, WorkGraph};
+
+/// Index file name inside the workgraph directory
+pub const INDEX_FILE: &str = "search-index.json";
+
+/// Bump when the on-disk format or tokenizer changes; older indexes are rebuilt
+const INDEX_VERSION: u32 = 1;
+
+const ARCHIVE_FILE: &str = "archive.jsonl";
+
+/// BM25 term-frequency saturation
+const K1: f64 = 1.2;
+/// BM25 document-length normalization
+const B: f64 = 0.75;
+
+/// Bytes of context kept before the first match in a snippet
+const SNIPPET_CONTEXT: usize = 60;
+/// Maximum snippet length in bytes, excluding ellipses
+const SNIPPET_WIDTH: usize = 160;
+
+const STOPWORDS: &[&str] = &[
+    "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "is", "it", "of", "on", "or",
+    "that", "the", "this", "to", "was", "with",
+];
+
+/// Inverted index over task text
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    docs: BTreeMap<String, Doc>,
+    /// term -> task ID -> term frequency
+    postings: BTreeMap<String, BTreeMap<String, u32>>,
+    /// Bytes of `archive.jsonl` already indexed
+    archive_offset: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Doc {
+    archived: bool,
+    fingerprint: String,
+    /// Length in tokens
+    len: u32,
+}
+
+/// A ranked search result
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub id: String,
+    pub score: f64,
+    pub archived: bool,
+}
+
+/// An excerpt of task text around the query terms
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Snippet {
+    /// Which part of the task the excerpt comes from: `title`,
+    /// `description`, `failure_reason` or `log`
+    pub field: &'static str,
+    pub text: String,
+    /// Byte ranges of `text` that match a query term
+    pub highlights: Vec<(usize, usize)>,
+}
+
+impl SearchIndex {
+    /// Load the index for `dir`, building or updating it as needed, and save
+    /// it back if anything changed.
+    pub fn open(dir: &Path, graph: &WorkGraph) -> Result<Self> {
+        let existing = Self::load(dir)?;
+        let is_new = existing.is_none();
+        let mut index = existing.unwrap_or_else(Self::empty);
+        if index.sync(dir, graph)? || is_new {
+            index.save(dir)?;
+        }
+        Ok(index)
+    }
+
+    /// Discard any existing index and build a fresh one.
+    pub fn rebuild(dir: &Path, graph: &WorkGraph) -> Result<Self> {
+        let mut index = Self::empty();
+        index.sync(dir, graph)?;
+        index.save(dir)?;
+        Ok(index)
+    }
+
+    fn empty() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            ..Self::default()
+        }
+    }
+
+    /// Read the saved index. A missing, unreadable or outdated index yields
+    /// `None` so the caller rebuilds it.
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(serde_json::from_str::<Self>(&content)
+            .ok()
+            .filter(|index| index.version == INDEX_VERSION))
+    }
+
+    /// Write the index atomically.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(INDEX_FILE);
+        let tmp_path = dir.join(format!(".{}.tmp.{}", INDEX_FILE, std::process::id()));
+        let json = serde_json::to_string(self).context("Failed to serialize search index")?;
+        std::fs::write(&tmp_path, json)
+            .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, &path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Number of indexed tasks, and how many of them are archived.
+    pub fn counts(&self) -> (usize, usize) {
+        let archived = self.docs.values().filter(|d| d.archived).count();
+        (self.docs.len(), archived)
+    }
+
+    /// Bring the index in line with `graph` and any tasks appended to the
+    /// archive since the last sync. Returns whether anything changed.
+    pub fn sync(&mut self, dir: &Path, graph: &WorkGraph) -> Result<bool> {
+        // Task ID -> (archived, fingerprint, text); later entries win
+        let mut updates: BTreeMap<String, (bool, String, String)> = BTreeMap::new();
+        let mut removed: HashSet<String> = self
+            .docs
+            .iter()
+            .filter(|(id, doc)| !doc.archived && graph.get_task(id).is_none())
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for task in graph.tasks() {
+            let text = indexed_text(task);
+            let fingerprint = fingerprint(&text);
+            match self.docs.get(&task.id) {
+                Some(doc) if !doc.archived && doc.fingerprint == fingerprint => {}
+                _ => {
+                    updates.insert(task.id.clone(), (false, fingerprint, text));
+                }
+            }
+        }
+
+        let (archived, offset, reset) = read_archive(dir, self.archive_offset)?;
+        if reset {
+            removed.extend(
+                self.docs
+                    .iter()
+                    .filter(|(_, doc)| doc.archived)
+                    .map(|(id, _)| id.clone()),
+            );
+        }
+        let archive_changed = offset != self.archive_offset;
+        self.archive_offset = offset;
+        for task in archived {
+            // A live task with the same ID takes precedence
+            if graph.get_task(&task.id).is_some() {
+                continue;
+            }
+            let text = indexed_text(&task);
+            let fingerprint = fingerprint(&text);
+            updates.insert(task.id, (true, fingerprint, text));
+        }
+
+        if removed.is_empty() && updates.is_empty() {
+            return Ok(archive_changed);
+        }
+
+        removed.extend(updates.keys().cloned());
+        self.remove_docs(&removed);
+        for (id, (archived, fingerprint, text)) in updates {
+            self.insert_doc(id, archived, fingerprint, &text);
+        }
+        Ok(true)
+    }
+
+    fn remove_docs(&mut self, ids: &HashSet<String>) {
+        for id in ids {
+            self.docs.remove(id);
+        }
+        self.postings.retain(|_, docs| {
+            docs.retain(|id, _| !ids.contains(id));
+            !docs.is_empty()
+        });
+    }
+
+    fn insert_doc(&mut self, id: String, archived: bool, fingerprint: String, text: &str) {
+        let mut counts: BTreeMap<String, u32> = BTreeMap::new();
+        let mut len = 0;
+        for term in tokenize(text) {
+            *counts.entry(term).or_default() += 1;
+            len += 1;
+        }
+        for (term, count) in counts {
+            self.postings
+                .entry(term)
+                .or_default()
+                .insert(id.clone(), count);
+        }
+        self.docs.insert(
+            id,
+            Doc {
+                archived,
+                fingerprint,
+                len,
+            },
+        );
+    }
+
+    /// Rank tasks against `query` with BM25, best first (ties by ID).
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let terms: HashSet<String> = tokenize(query).collect();
+        if terms.is_empty() || self.docs.is_empty() {
+            return Vec::new();
+        }
+
+        let n = self.docs.len() as f64;
+        let avg_len = self.docs.values().map(|d| d.len as f64).sum::<f64>() / n;
+        let mut scores: BTreeMap<&str, f64> = BTreeMap::new();
+        for term in &terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let df = postings.len() as f64;
+            let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+            for (id, &tf) in postings {
+                let len = self.docs.get(id).map_or(avg_len, |d| d.len as f64);
+                let tf = tf as f64;
+                let norm = K1 * (1.0 - B + B * len / avg_len.max(1.0));
+                *scores.entry(id).or_default() += idf * tf * (K1 + 1.0) / (tf + norm);
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .map(|(id, score)| SearchHit {
+                id: id.to_string(),
+                score,
+                archived: self.docs.get(id).is_some_and(|d| d.archived),
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+        hits
+    }
+}
+
+/// Keep the index of the graph at `graph_path` current after a save. Called
+/// once the graph lock is released; does nothing until an index has been
+/// built, and never fails the save: a missed or overtaken update is caught up
+/// by the next [`SearchIndex::open`].
+pub fn update_after_save(graph_path: &Path, graph: &WorkGraph) {
+    let Some(dir) = graph_path.parent() else {
+        return;
+    };
+    if !dir.join(INDEX_FILE).exists() {
+        return;
+    }
+    if let Ok(Some(mut index)) = SearchIndex::load(dir)
+        && let Ok(true) = index.sync(dir, graph)
+    {
+        let _ = index.save(dir);
+    }
+}
+
+/// All tasks in `archive.jsonl`.
+pub fn archived_tasks(dir: &Path) -> Result<Vec<Task>> {
+    read_archive(dir, 0).map(|(tasks, _, _)| tasks)
+}
+
+/// Read archived tasks appended after byte `offset`. Returns the tasks, the
+/// offset just past the last complete line, and whether the archive was
+/// rewritten (shorter than `offset`) so it had to be read from the start.
+fn read_archive(dir: &Path, offset: u64) -> Result<(Vec<Task>, u64, bool)> {
+    let path = dir.join(ARCHIVE_FILE);
+    if !path.exists() {
+        return Ok((Vec::new(), 0, offset > 0));
+    }
+    let mut file =
+        File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let len = file.metadata()?.len();
+    let (offset, reset) = if len < offset {
+        (0, true)
+    } else {
+        (offset, false)
+    };
+    file.seek(SeekFrom::Start(offset))?;
+    let mut content = String::new();
+    file.read_to_string(&mut content)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    // Leave a partially written last line for the next sync
+    let complete = content.rfind('\n').map_or(0, |i| i + 1);
+    let mut tasks = Vec::new();
+    for line in content[..complete].lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let node: Node = serde_json::from_str(trimmed)
+            .with_context(|| format!("Failed to parse archived task: {}", trimmed))?;
+        if let Node::Task(task) = node {
+            tasks.push(task);
+        }
+    }
+    Ok((tasks, offset + complete as u64, reset))
+}
+
+/// The searchable parts of a task, in display-preference order after the title
+fn sources(task: &Task) -> Vec<(&'static str, &str)> {
+    let mut sources = vec![("title", task.title.as_str())];
+    if let Some(description) = &task.description {
+        sources.push(("description", description));
+    }
+    if let Some(reason) = &task.failure_reason {
+        sources.push(("failure_reason", reason));
+    }
+    sources.extend(task.log.iter().map(|entry| ("log", entry.message.as_str())));
+    sources
+}
+
+fn indexed_text(task: &Task) -> String {
+    sources(task)
+        .into_iter()
+        .map(|(_, text)| text)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn fingerprint(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    digest[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Split text into lowercase alphanumeric terms, dropping one-character
+/// tokens and common stopwords. Returns each term with its byte range.
+fn token_spans(text: &str) -> impl Iterator<Item = (usize, usize, String)> + '_ {
+    let mut chars = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        loop {
+            let (start, _) = chars.find(|(_, c)| c.is_alphanumeric())?;
+            let mut end = text.len();
+            while let Some(&(i, c)) = chars.peek() {
+                if !c.is_alphanumeric() {
+                    end = i;
+                    break;
+                }
+                chars.next();
+            }
+            let term = text[start..end].to_lowercase();
+            if term.chars().count() > 1 && !STOPWORDS.contains(&term.as_str()) {
+                return Some((start, end, term));
+            }
+        }
+    })
+}
+
+/// The index terms in `text`, in order
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    token_spans(text).map(|(_, _, term)| term)
+}
+
+/// Pick the excerpt of `task` that best matches `query`: the description,
+/// failure reason or log message with the most matching terms, or the title
+/// if nothing else matches.
+pub fn snippet(task: &Task, query: &str) -> Option<Snippet> {
+    let terms: HashSet<String> = tokenize(query).collect();
+    let matches = |text: &str| tokenize(text).filter(|t| terms.contains(t)).count();
+
+    let sources = sources(task);
+    let mut best: Option<(&'static str, &str, usize)> = None;
+    for &(field, text) in sources.iter().skip(1) {
+        let count = matches(text);
+        if count > 0 && best.is_none_or(|(_, _, c)| count > c) {
+            best = Some((field, text, count));
+        }
+    }
+    let (field, text) = match best {
+        Some((field, text, _)) => (field, text),
+        None if matches(&task.title) > 0 => ("title", task.title.as_str()),
+        None => return None,
+    };
+    Some(excerpt(field, text, &terms))
+}
+
+fn excerpt(field: &'static str, text: &str, terms: &HashSet<String>) -> Snippet {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let hits: Vec<(usize, usize)> = token_spans(&text)
+        .filter(|(_, _, term)| terms.contains(term))
+        .map(|(start, end, _)| (start, end))
+        .collect();
+    let (first_start, first_end) = hits.first().copied().unwrap_or((0, 0));
+
+    // Start a little before the first match, on a word boundary
+    let mut start = first_start.saturating_sub(SNIPPET_CONTEXT);
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    if start > 0
+        && let Some(space) = text[start..first_start].find(' ')
+    {
+        start += space + 1;
+    }
+
+    // End at the width limit, on a word boundary, keeping the first match
+    let mut end = (start + SNIPPET_WIDTH).min(text.len()).max(first_end);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    if end < text.len()
+        && let Some(space) = text[first_end..end].rfind(' ')
+    {
+        end = first_end + space;
+    }
+
+    let prefix = if start > 0 { "..." } else { "" };
+    let suffix = if end < text.len() { "..." } else { "" };
+    let highlights = hits
+        .into_iter()
+        .filter(|&(s, e)| s >= start && e <= end)
+        .map(|(s, e)| (s - start + prefix.len(), e - start + prefix.len()))
+        .collect();
+    Snippet {
+        field,
+        text: format!("{}{}{}", prefix, &text[start..end], suffix),
+        highlights,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{LogEntry, Status};
+    use tempfile::tempdir;
+
+    fn task(id: &str, title: &str, description: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            title: title.to_string(),
+            description: (!description.is_empty()).then(|| description.to_string()),
+            ..Task::default()
+        }
+    }
+
+    fn graph(tasks: Vec<Task>) -> WorkGraph {
+        let mut graph = WorkGraph::new();
+        for task in tasks {
+            graph.add_node(Node::Task(task));
+        }
+        graph
+    }
+
+    fn ids(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|h| h.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let terms: Vec<String> = tokenize("Fix the flaky DB-migration, a v2 of it").collect();
+        assert_eq!(terms, vec!["fix", "flaky", "db", "migration", "v2"]);
+    }
+
+    #[test]
+    fn test_bm25_ranking_and_logs() {
+        let dir = tempdir().unwrap();
+        let mut logged = task("deploy", "Deploy service", "");
+        logged.log.push(LogEntry {
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            actor: None,
+            message: "Retried twice: the flaky migration timed out".to_string(),
+        });
+        let g = graph(vec![
+            task("schema", "Write migration", "Add the migration for users"),
+            logged,
+            task("docs", "Update docs", "Nothing relevant"),
+        ]);
+        let index = SearchIndex::open(dir.path(), &g).unwrap();
+
+        assert_eq!(
+            ids(&index.search("flaky migration")),
+            vec!["deploy", "schema"]
+        );
+        assert_eq!(ids(&index.search("migration")), vec!["schema", "deploy"]);
+        assert!(index.search("the").is_empty());
+        assert!(index.search("kubernetes").is_empty());
+    }
+
+    #[test]
+    fn test_incremental_update_and_archive() {
+        let dir = tempdir().unwrap();
+        let mut g = graph(vec![task("a", "Alpha", ""), task("b", "Beta", "")]);
+        let index = SearchIndex::open(dir.path(), &g).unwrap();
+        assert_eq!(index.counts(), (2, 0));
+
+        // Edit one task, archive the other
+        g.get_task_mut("a").unwrap().title = "Gamma".to_string();
+        let mut archived = g.get_task("b").unwrap().clone();
+        archived.status = Status::Done;
+        g.remove_node("b");
+        std::fs::write(
+            dir.path().join(ARCHIVE_FILE),
+            format!(
+                "{}\n",
+                serde_json::to_string(&Node::Task(archived)).unwrap()
+            ),
+        )
+        .unwrap();
+        update_after_save(&dir.path().join("graph.jsonl"), &g);
+
+        let index = SearchIndex::load(dir.path()).unwrap().unwrap();
+        assert_eq!(index.counts(), (2, 1));
+        assert!(index.search("alpha").is_empty());
+        assert_eq!(ids(&index.search("gamma")), vec!["a"]);
+        let hits = index.search("beta");
+        assert_eq!(ids(&hits), vec!["b"]);
+        assert!(hits[0].archived);
+
+        // Nothing changed: sync is a no-op
+        let mut index = index;
+        assert!(!index.sync(dir.path(), &g).unwrap());
+    }
+
+    #[test]
+    fn test_snippet_highlights_best_field() {
+        let mut t = task(
+            "t",
+            "Migration",
+            &format!("{} the flaky migration broke", "x ".repeat(60)),
+        );
+        t.failure_reason = Some("unrelated".to_string());
+        let snippet = snippet(&t, "flaky migration").unwrap();
+        assert_eq!(snippet.field, "description");
+        assert!(snippet.text.starts_with("..."));
+        let marked: Vec<&str> = snippet
+            .highlights
+            .iter()
+            .map(|&(s, e)| &snippet.text[s..e])
+            .collect();
+        assert_eq!(marked, vec!["flaky", "migration"]);
+
+        let title_only = task("u", "Flaky tests", "");
+        assert_eq!(snippet_field(&title_only, "flaky"), Some("title"));
+        assert_eq!(snippet_field(&title_only, "nothing"), None);
+    }
+
+    fn snippet_field(task: &Task, query: &str) -> Option<&'static str> {
+        snippet(task, query).map(|s| s.field)
+    }
+}
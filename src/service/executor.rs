@@ -11,6 +11,7 @@ use std::path::{Path, PathBuf};
 
 use crate::identity;
 use crate::graph::Task;
+use crate::outputs::{self, DepOutputs};
 
 /// Template variables that can be used in executor configurations.
 #[derive(Debug, Clone)]
@@ -23,6 +24,8 @@ pub struct TemplateVars {
     pub working_dir: String,
     pub skills_preamble: String,
     pub model: String,
+    /// Outputs of the task's dependencies, substituted for `{{deps.<task>.<key>}}`
+    pub dep_outputs: DepOutputs,
 }
 
 impl TemplateVars {
@@ -55,6 +58,7 @@ impl TemplateVars {
             working_dir,
             skills_preamble,
             model: task.model.clone().unwrap_or_default(),
+            dep_outputs: DepOutputs::new(),
         }
     }
 
@@ -171,7 +175,7 @@ impl TemplateVars {
 
     /// Apply template substitution to a string.
     pub fn apply(&self, template: &str) -> String {
-        template
+        let result = template
             .replace("{{task_id}}", &self.task_id)
             .replace("{{task_title}}", &self.task_title)
             .replace("{{task_description}}", &self.task_description)
@@ -179,7 +183,9 @@ impl TemplateVars {
             .replace("{{task_identity}}", &self.task_identity)
             .replace("{{working_dir}}", &self.working_dir)
             .replace("{{skills_preamble}}", &self.skills_preamble)
-            .replace("{{model}}", &self.model)
+            .replace("{{model}}", &self.model);
+        // Last, so references in the inserted task description resolve too
+        outputs::substitute(&result, &self.dep_outputs)
     }
 }
 
@@ -564,6 +570,21 @@ mod tests {
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: crate::graph::Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         }
     }
 
@@ -0,0 +1,136 @@
+//! JSONL graph store - the default backend.
+//!
+//! Wraps the functions in [`crate::parser`]. Point reads and queries load the
+//! whole graph; point writes run as a [`with_graph_mut`] transaction.
+
+use std::path::{Path, PathBuf};
+
+use super::{GraphStore, sorted};
+use crate::graph::{LogEntry, Node, Status, Task, WorkGraph};
+use crate::parser::{ParseError, load_graph, save_graph, with_graph_mut};
+
+/// Graph stored as `graph.jsonl` (plus `graph.journal` when journaled)
+#[derive(Debug, Clone)]
+pub struct JsonlStore {
+    path: PathBuf,
+}
+
+impl JsonlStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    fn select(&self, pred: impl Fn(&Task) -> bool) -> Result<Vec<Task>, ParseError> {
+        let graph = self.load()?;
+        Ok(sorted(graph.tasks().filter(|t| pred(t)).cloned().collect()))
+    }
+}
+
+impl GraphStore for JsonlStore {
+    fn load(&self) -> Result<WorkGraph, ParseError> {
+        load_graph(&self.path)
+    }
+
+    fn save(&self, graph: &WorkGraph) -> Result<(), ParseError> {
+        save_graph(graph, &self.path)
+    }
+
+    fn load_unfinished(&self) -> Result<WorkGraph, ParseError> {
+        self.load()
+    }
+
+    fn get_task(&self, id: &str) -> Result<Option<Task>, ParseError> {
+        Ok(self.load()?.get_task(id).cloned())
+    }
+
+    fn put_task(&self, task: &Task) -> Result<(), ParseError> {
+        with_graph_mut(&self.path, |graph| {
+            graph.add_node(Node::Task(task.clone()));
+            Ok(())
+        })
+    }
+
+    fn tasks_with_status(&self, status: Status) -> Result<Vec<Task>, ParseError> {
+        self.select(|t| t.status == status)
+    }
+
+    fn tasks_with_tag(&self, tag: &str) -> Result<Vec<Task>, ParseError> {
+        self.select(|t| t.tags.iter().any(|t| t == tag))
+    }
+
+    fn append_log(&self, task_id: &str, entry: LogEntry) -> Result<(), ParseError> {
+        with_graph_mut(&self.path, |graph| {
+            let task = graph
+                .get_task_mut(task_id)
+                .ok_or_else(|| ParseError::TaskNotFound(task_id.to_string()))?;
+            task.log.push(entry);
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn make_task(id: &str, status: Status, tags: &[&str]) -> Task {
+        Task {
+            id: id.to_string(),
+            title: id.to_string(),
+            status,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Task::default()
+        }
+    }
+
+    #[test]
+    fn test_jsonl_store_queries_and_point_writes() {
+        let dir = TempDir::new().unwrap();
+        let store = JsonlStore::new(dir.path().join("graph.jsonl"));
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("a", Status::Open, &["x"])));
+        graph.add_node(Node::Task(make_task("b", Status::Done, &["x", "y"])));
+        store.save(&graph).unwrap();
+
+        let open: Vec<_> = store.tasks_with_status(Status::Open).unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].id, "a");
+        let tagged: Vec<String> = store
+            .tasks_with_tag("x")
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(tagged, vec!["a", "b"]);
+
+        store.put_task(&make_task("c", Status::Open, &[])).unwrap();
+        store
+            .append_log(
+                "c",
+                LogEntry {
+                    timestamp: "2026-01-01T00:00:00Z".to_string(),
+                    actor: None,
+                    message: "hello".to_string(),
+                },
+            )
+            .unwrap();
+        let c = store.get_task("c").unwrap().unwrap();
+        assert_eq!(c.log.len(), 1);
+        assert_eq!(store.load().unwrap().tasks().count(), 3);
+
+        assert!(matches!(
+            store.append_log(
+                "missing",
+                LogEntry {
+                    timestamp: String::new(),
+                    actor: None,
+                    message: String::new(),
+                },
+            ),
+            Err(ParseError::TaskNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+//! Pluggable graph storage.
+//!
+//! [`GraphStore`] is the persistence interface for a work graph: whole-graph
+//! load/save plus the point reads and writes that commands need. Two backends
+//! implement it:
+//!
+//! - [`jsonl::JsonlStore`] (default) - `graph.jsonl`, optionally journaled
+//! - [`sqlite::SqliteStore`] (cargo feature `sqlite`) - `graph.db`, with
+//!   indexed status and tag lookups
+//!
+//! [`open`] picks the backend the graph is currently stored in. The free
+//! functions in [`crate::parser`] dispatch the same way, so code that loads
+//! and saves whole graphs works unchanged on either backend.
+
+pub mod jsonl;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use std::path::Path;
+
+use crate::graph::{LogEntry, Status, Task, WorkGraph};
+use crate::parser::ParseError;
+
+/// Persistence backend for a work graph.
+///
+/// Every method takes the graph lock for its duration, so calls are safe
+/// against concurrent `wg` processes. Mutating methods are individually
+/// atomic; use [`crate::parser::with_graph_mut`] when a change spans
+/// several tasks.
+pub trait GraphStore {
+    /// Load the full graph.
+    fn load(&self) -> Result<WorkGraph, ParseError>;
+
+    /// Replace the stored graph with `graph`.
+    fn save(&self, graph: &WorkGraph) -> Result<(), ParseError>;
+
+    /// The part of the graph that decides which tasks are ready: every
+    /// unfinished task, the finished tasks they depend on, and all resources.
+    fn load_unfinished(&self) -> Result<WorkGraph, ParseError>;
+
+    /// Fetch a single task by ID.
+    fn get_task(&self, id: &str) -> Result<Option<Task>, ParseError>;
+
+    /// Insert or replace a single task.
+    fn put_task(&self, task: &Task) -> Result<(), ParseError>;
+
+    /// All tasks with the given status.
+    fn tasks_with_status(&self, status: Status) -> Result<Vec<Task>, ParseError>;
+
+    /// All tasks carrying `tag`.
+    fn tasks_with_tag(&self, tag: &str) -> Result<Vec<Task>, ParseError>;
+
+    /// Append a log entry to a task.
+    fn append_log(&self, task_id: &str, entry: LogEntry) -> Result<(), ParseError>;
+}
+
+/// Open the store backing the graph at `graph_path`.
+///
+/// Returns the SQLite store when `graph.db` exists (and the `sqlite` feature
+/// is compiled in), otherwise the JSONL store. Without the feature, a graph
+/// stored in SQLite opens as a JSONL store whose calls fail with
+/// [`ParseError::SqliteUnsupported`].
+pub fn open<P: AsRef<Path>>(graph_path: P) -> Box<dyn GraphStore> {
+    let path = graph_path.as_ref();
+    #[cfg(feature = "sqlite")]
+    if crate::parser::is_sqlite(path) {
+        return Box::new(sqlite::SqliteStore::new(path));
+    }
+    Box::new(jsonl::JsonlStore::new(path))
+}
+
+/// Sort tasks by ID so both backends return query results in the same order
+fn sorted(mut tasks: Vec<Task>) -> Vec<Task> {
+    tasks.sort_by(|a, b| a.id.cmp(&b.id));
+    tasks
+}
@@ -0,0 +1,434 @@
+//! SQLite graph store (cargo feature `sqlite`).
+//!
+//! Nodes live in `graph.db` next to `graph.jsonl`, one row per node holding
+//! its JSON encoding. Task status and tags are mirrored into indexed columns
+//! so status and tag queries don't deserialize the whole graph. Whole-graph
+//! saves only rewrite rows whose encoding changed, in a single transaction.
+//!
+//! Once a graph is migrated, `graph.jsonl` holds only [`SQLITE_MARKER`] so
+//! that "is this directory initialized" checks keep working.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+use super::{GraphStore, sorted};
+use crate::graph::{LogEntry, Node, Status, Task, WorkGraph};
+use crate::parser::{
+    FileLock, ParseError, check_schema_version, get_lock_path, journal_path, load_graph,
+    read_graph_unlocked, save_graph, sqlite_path, write_snapshot_unlocked,
+};
+use crate::schema::{self, SCHEMA_VERSION};
+
+/// Contents of `graph.jsonl` while the graph is stored in SQLite
+pub const SQLITE_MARKER: &str =
+    "# Graph stored in graph.db (SQLite). Convert back with: wg migrate --to jsonl\n";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS nodes (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    status TEXT,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS nodes_status ON nodes(status);
+CREATE TABLE IF NOT EXISTS node_tags (
+    id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (id, tag)
+);
+CREATE INDEX IF NOT EXISTS node_tags_tag ON node_tags(tag);
+";
+
+/// Open the database, creating the schema if needed. Databases written in a
+/// newer graph schema version are refused.
+fn connect(db: &Path) -> Result<Connection, ParseError> {
+    let conn = Connection::open(db)?;
+    conn.execute_batch(SCHEMA)?;
+    check_schema_version(stored_version(&conn)?)?;
+    Ok(conn)
+}
+
+/// Graph schema version of the nodes, kept in `PRAGMA user_version`
+fn stored_version(conn: &Connection) -> Result<u32, ParseError> {
+    Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+/// Graph schema version of the database (see [`crate::schema`])
+pub(crate) fn schema_version(db: &Path) -> Result<u32, ParseError> {
+    let conn = Connection::open(db)?;
+    stored_version(&conn)
+}
+
+fn encode(node: &Node) -> Result<String, ParseError> {
+    serde_json::to_string(node).map_err(|e| ParseError::Json { line: 0, source: e })
+}
+
+fn decode(data: &str) -> Result<Node, ParseError> {
+    serde_json::from_str(data).map_err(|e| ParseError::Json { line: 0, source: e })
+}
+
+/// Insert or replace a node row and its tag rows
+fn upsert(conn: &Connection, node: &Node, data: &str) -> Result<(), ParseError> {
+    let (kind, status, tags) = match node {
+        Node::Task(t) => ("task", Some(t.status.to_string()), t.tags.as_slice()),
+        Node::Resource(_) => ("resource", None, &[][..]),
+    };
+    conn.execute(
+        "INSERT INTO nodes (id, kind, status, data) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(id) DO UPDATE SET kind = excluded.kind, status = excluded.status, data = excluded.data",
+        params![node.id(), kind, status, data],
+    )?;
+    conn.execute("DELETE FROM node_tags WHERE id = ?1", params![node.id()])?;
+    for tag in tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO node_tags (id, tag) VALUES (?1, ?2)",
+            params![node.id(), tag],
+        )?;
+    }
+    Ok(())
+}
+
+/// Read every node from the database. Caller must hold the graph lock.
+pub(crate) fn read_graph(db: &Path) -> Result<WorkGraph, ParseError> {
+    let conn = connect(db)?;
+    let version = stored_version(&conn)?;
+    let mut stmt = conn.prepare("SELECT data FROM nodes ORDER BY id")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let mut graph = WorkGraph::new();
+    for data in rows {
+        let data = data?;
+        if version == SCHEMA_VERSION {
+            graph.add_node(decode(&data)?);
+            continue;
+        }
+        let mut value: serde_json::Value = serde_json::from_str(&data)
+            .map_err(|e| ParseError::Json { line: 0, source: e })?;
+        if schema::migrate_node(version, &mut value) {
+            let node = serde_json::from_value(value)
+                .map_err(|e| ParseError::Json { line: 0, source: e })?;
+            graph.add_node(node);
+        }
+    }
+    Ok(graph)
+}
+
+/// Make the database hold exactly the nodes of `graph`, touching only rows
+/// that changed. Caller must hold the graph lock.
+pub(crate) fn write_graph(db: &Path, graph: &WorkGraph) -> Result<(), ParseError> {
+    let mut conn = connect(db)?;
+    let tx = conn.transaction()?;
+
+    let stored: HashMap<String, String> = {
+        let mut stmt = tx.prepare("SELECT id, data FROM nodes")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<_, _>>()?
+    };
+
+    for node in graph.nodes() {
+        let data = encode(node)?;
+        if stored.get(node.id()) != Some(&data) {
+            upsert(&tx, node, &data)?;
+        }
+    }
+    for id in stored.keys().filter(|id| graph.get_node(id).is_none()) {
+        tx.execute("DELETE FROM nodes WHERE id = ?1", params![id])?;
+        tx.execute("DELETE FROM node_tags WHERE id = ?1", params![id])?;
+    }
+    tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Switch a graph between JSONL and SQLite storage.
+///
+/// Enabling copies the current graph (snapshot plus any journal) into
+/// `graph.db`, drops the journal and replaces `graph.jsonl` with
+/// [`SQLITE_MARKER`]. Disabling writes the database back out as a plain
+/// `graph.jsonl` and removes `graph.db`.
+pub fn set_sqlite<P: AsRef<Path>>(path: P, enabled: bool) -> Result<(), ParseError> {
+    let path = path.as_ref();
+    let _lock = FileLock::acquire(get_lock_path(path))?;
+
+    let db = sqlite_path(path);
+    if enabled {
+        if db.exists() {
+            return Ok(());
+        }
+        let (graph, _) = read_graph_unlocked(path)?;
+        // Build the database under a temporary name so a crash never leaves
+        // a partial graph.db that would shadow graph.jsonl
+        let tmp = db.with_extension("db.tmp");
+        let _ = std::fs::remove_file(&tmp);
+        write_graph(&tmp, &graph)?;
+        std::fs::rename(&tmp, &db)?;
+        std::fs::write(path, SQLITE_MARKER)?;
+        let journal = journal_path(path);
+        if journal.exists() {
+            std::fs::remove_file(&journal)?;
+        }
+    } else if db.exists() {
+        let graph = read_graph(&db)?;
+        write_snapshot_unlocked(&graph, path)?;
+        std::fs::remove_file(&db)?;
+    }
+    Ok(())
+}
+
+/// Graph stored in `graph.db`
+#[derive(Debug, Clone)]
+pub struct SqliteStore {
+    path: PathBuf,
+}
+
+impl SqliteStore {
+    /// Store for the graph at `path` (the `graph.jsonl` path; the database is
+    /// the sibling `graph.db`)
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Run `f` on a connection while holding the graph lock
+    fn locked<T>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let _lock = FileLock::acquire(get_lock_path(&self.path))?;
+        let mut conn = connect(&sqlite_path(&self.path))?;
+        f(&mut conn)
+    }
+
+    fn query_tasks(&self, sql: &str, arg: &str) -> Result<Vec<Task>, ParseError> {
+        self.locked(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map(params![arg], |row| row.get::<_, String>(0))?;
+            let mut tasks = Vec::new();
+            for data in rows {
+                if let Node::Task(task) = decode(&data?)? {
+                    tasks.push(task);
+                }
+            }
+            Ok(sorted(tasks))
+        })
+    }
+}
+
+fn get_node(conn: &Connection, id: &str) -> Result<Option<Node>, ParseError> {
+    let data: Option<String> = conn
+        .query_row("SELECT data FROM nodes WHERE id = ?1", params![id], |row| {
+            row.get(0)
+        })
+        .optional()?;
+    data.as_deref().map(decode).transpose()
+}
+
+impl GraphStore for SqliteStore {
+    fn load(&self) -> Result<WorkGraph, ParseError> {
+        load_graph(&self.path)
+    }
+
+    fn save(&self, graph: &WorkGraph) -> Result<(), ParseError> {
+        save_graph(graph, &self.path)
+    }
+
+    fn load_unfinished(&self) -> Result<WorkGraph, ParseError> {
+        let [done, failed, abandoned] =
+            [Status::Done, Status::Failed, Status::Abandoned].map(|s| s.to_string());
+        self.locked(|conn| {
+            let mut graph = WorkGraph::new();
+            let mut stmt = conn.prepare(
+                "SELECT data FROM nodes
+                 WHERE kind = 'resource' OR (kind = 'task' AND status NOT IN (?1, ?2, ?3))",
+            )?;
+            let rows = stmt.query_map(params![done, failed, abandoned], |row| {
+                row.get::<_, String>(0)
+            })?;
+            for data in rows {
+                graph.add_node(decode(&data?)?);
+            }
+            // Finished blockers still decide whether their dependents can start
+            let blockers: BTreeSet<&String> = graph
+                .tasks()
+                .flat_map(|t| &t.blocked_by)
+                .filter(|id| graph.get_node(id).is_none())
+                .collect();
+            let blockers: Vec<Node> = blockers
+                .into_iter()
+                .filter_map(|id| get_node(conn, id).transpose())
+                .collect::<Result<_, _>>()?;
+            for node in blockers {
+                graph.add_node(node);
+            }
+            Ok(graph)
+        })
+    }
+
+    fn get_task(&self, id: &str) -> Result<Option<Task>, ParseError> {
+        self.locked(|conn| match get_node(conn, id)? {
+            Some(Node::Task(task)) => Ok(Some(task)),
+            _ => Ok(None),
+        })
+    }
+
+    fn put_task(&self, task: &Task) -> Result<(), ParseError> {
+        let node = Node::Task(task.clone());
+        let data = encode(&node)?;
+        self.locked(|conn| {
+            let tx = conn.transaction()?;
+            upsert(&tx, &node, &data)?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn tasks_with_status(&self, status: Status) -> Result<Vec<Task>, ParseError> {
+        self.query_tasks(
+            "SELECT data FROM nodes WHERE kind = 'task' AND status = ?1",
+            &status.to_string(),
+        )
+    }
+
+    fn tasks_with_tag(&self, tag: &str) -> Result<Vec<Task>, ParseError> {
+        self.query_tasks(
+            "SELECT n.data FROM nodes n JOIN node_tags t ON t.id = n.id
+             WHERE n.kind = 'task' AND t.tag = ?1",
+            tag,
+        )
+    }
+
+    fn append_log(&self, task_id: &str, entry: LogEntry) -> Result<(), ParseError> {
+        self.locked(|conn| {
+            let tx = conn.transaction()?;
+            let Some(Node::Task(mut task)) = get_node(&tx, task_id)? else {
+                return Err(ParseError::TaskNotFound(task_id.to_string()));
+            };
+            task.log.push(entry);
+            let node = Node::Task(task);
+            upsert(&tx, &node, &encode(&node)?)?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{is_journaled, is_sqlite, set_journaled, with_graph_mut};
+    use tempfile::TempDir;
+
+    fn make_task(id: &str, status: Status, tags: &[&str]) -> Task {
+        Task {
+            id: id.to_string(),
+            title: id.to_string(),
+            status,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Task::default()
+        }
+    }
+
+    fn setup(dir: &Path) -> PathBuf {
+        let path = dir.join("graph.jsonl");
+        let mut graph = WorkGraph::new();
+        graph.add_node(Node::Task(make_task("a", Status::Open, &["x"])));
+        graph.add_node(Node::Task(make_task("b", Status::Done, &["x", "y"])));
+        save_graph(&graph, &path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_migrate_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = setup(dir.path());
+        set_journaled(&path, true).unwrap();
+
+        set_sqlite(&path, true).unwrap();
+        assert!(is_sqlite(&path));
+        assert!(!is_journaled(&path));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), SQLITE_MARKER);
+        assert_eq!(load_graph(&path).unwrap().tasks().count(), 2);
+
+        with_graph_mut(&path, |graph| {
+            graph.get_task_mut("a").unwrap().status = Status::Done;
+            Ok::<_, ParseError>(())
+        })
+        .unwrap();
+
+        set_sqlite(&path, false).unwrap();
+        assert!(!is_sqlite(&path));
+        let graph = load_graph(&path).unwrap();
+        assert_eq!(graph.get_task("a").unwrap().status, Status::Done);
+    }
+
+    #[test]
+    fn test_indexed_queries_follow_saves() {
+        let dir = TempDir::new().unwrap();
+        let path = setup(dir.path());
+        set_sqlite(&path, true).unwrap();
+        let store = SqliteStore::new(&path);
+
+        let ids = |tasks: Vec<Task>| tasks.into_iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(
+            ids(store.tasks_with_status(Status::Open).unwrap()),
+            vec!["a"]
+        );
+        assert_eq!(ids(store.tasks_with_tag("x").unwrap()), vec!["a", "b"]);
+
+        // Whole-graph saves keep the indexes in step
+        let mut graph = store.load().unwrap();
+        graph.get_task_mut("a").unwrap().tags = vec!["z".to_string()];
+        graph.remove_node("b");
+        store.save(&graph).unwrap();
+        assert!(store.tasks_with_tag("x").unwrap().is_empty());
+        assert_eq!(ids(store.tasks_with_tag("z").unwrap()), vec!["a"]);
+
+        store
+            .put_task(&make_task("c", Status::Open, &["z"]))
+            .unwrap();
+        assert_eq!(
+            ids(store.tasks_with_status(Status::Open).unwrap()),
+            vec!["a", "c"]
+        );
+        store
+            .append_log(
+                "c",
+                LogEntry {
+                    timestamp: "2026-01-01T00:00:00Z".to_string(),
+                    actor: None,
+                    message: "hello".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(store.get_task("c").unwrap().unwrap().log.len(), 1);
+        assert!(store.get_task("b").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_load_unfinished_keeps_blockers() {
+        let dir = TempDir::new().unwrap();
+        let path = setup(dir.path());
+        let mut c = make_task("c", Status::Open, &[]);
+        c.blocked_by = vec!["b".to_string()];
+        with_graph_mut(&path, |graph| {
+            graph.add_node(Node::Task(c));
+            graph.add_node(Node::Task(make_task("d", Status::Failed, &[])));
+            Ok::<_, ParseError>(())
+        })
+        .unwrap();
+        set_sqlite(&path, true).unwrap();
+
+        let graph = SqliteStore::new(&path).load_unfinished().unwrap();
+        let mut ids: Vec<&str> = graph.tasks().map(|t| t.id.as_str()).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        let ready: Vec<&str> = crate::query::ready_tasks(&graph)
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(ready, vec!["a", "c"]);
+    }
+}
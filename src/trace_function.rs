@@ -92,7 +92,7 @@ pub enum InputType {
     Json,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskTemplate {
     pub template_id: String,
     pub title: String,
@@ -113,7 +113,7 @@ pub struct TaskTemplate {
     pub tags: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LoopEdgeTemplate {
     pub target: String,
     pub max_iterations: u32,
@@ -412,6 +412,14 @@ pub fn substitute_task_template(
 // Struct validation (internal consistency of a TraceFunction)
 // ---------------------------------------------------------------------------
 
+/// Parse a loop edge template's guard: a guard expression (see
+/// [`crate::loop_guard`]) or, as older extractions wrote, a JSON-encoded guard.
+pub fn parse_template_guard(guard: &str) -> anyhow::Result<crate::graph::LoopGuard> {
+    guard
+        .parse()
+        .or_else(|e| serde_json::from_str(guard).map_err(|_| e))
+}
+
 /// Validate the internal consistency of a trace function definition.
 ///
 /// Checks:
@@ -452,6 +460,18 @@ pub fn validate_function(func: &TraceFunction) -> Result<(), TraceFunctionError>
                     task.template_id, loop_edge.target
                 )));
             }
+            if let Some(guard) = &loop_edge.guard {
+                let problem = match parse_template_guard(guard) {
+                    Ok(parsed) => crate::loop_guard::validate(&parsed),
+                    Err(e) => Some(e.to_string()),
+                };
+                if let Some(problem) = problem {
+                    return Err(TraceFunctionError::Validation(format!(
+                        "Task '{}' has an invalid loops_to guard '{}': {}",
+                        task.template_id, guard, problem
+                    )));
+                }
+            }
         }
     }
 
@@ -998,6 +1018,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_function_loop_guards() {
+        let mut func = sample_function();
+        func.tasks[3].loops_to[0].guard = Some("reward:review<0.8".to_string());
+        assert!(validate_function(&func).is_ok());
+
+        // Older extractions stored the guard as JSON
+        func.tasks[3].loops_to[0].guard =
+            Some(r#"{"TaskStatus":{"task":"review","status":"failed"}}"#.to_string());
+        assert!(validate_function(&func).is_ok());
+
+        for bad in ["sometimes", "artifact-exists:/abs/path"] {
+            func.tasks[3].loops_to[0].guard = Some(bad.to_string());
+            match validate_function(&func).unwrap_err() {
+                TraceFunctionError::Validation(msg) => assert!(msg.contains("guard"), "{}", msg),
+                _ => panic!("Expected Validation error"),
+            }
+        }
+    }
+
     #[test]
     fn validate_function_duplicate_template_ids() {
         let mut func = sample_function();
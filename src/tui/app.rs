@@ -6,6 +6,7 @@ use std::time::{Duration, Instant};
 
 use workgraph::graph::{Status, Task, WorkGraph};
 use workgraph::parser::load_graph;
+use workgraph::query::compute_slack;
 use workgraph::{AgentEntry, AgentRegistry, AgentStatus};
 
 use super::graph_layout::DagLayout;
@@ -27,19 +28,14 @@ pub struct TaskEntry {
     pub title: String,
     pub status: Status,
     pub assigned: Option<String>,
+    /// Deadline slack in hours, if a deadline applies (negative = at risk)
+    pub slack_hours: Option<f64>,
 }
 
 impl TaskEntry {
-    /// Sort key: in-progress=0, open(ready)=1, failed=2, blocked=3, done=4, abandoned=5
+    /// Sort key: in-progress=0, in-review=1, open(ready)=2, failed=3, blocked=4, done=5, abandoned=6
     fn sort_key(&self) -> u8 {
-        match self.status {
-            Status::InProgress => 0,
-            Status::Open => 1,
-            Status::Failed => 2,
-            Status::Blocked => 3,
-            Status::Done => 4,
-            Status::Abandoned => 5,
-        }
+        sort_key_for_status(&self.status)
     }
 }
 
@@ -241,6 +237,8 @@ pub struct GraphRow {
     pub active_agent_count: usize,
     /// IDs of active agents on this task (for display)
     pub active_agent_ids: Vec<String>,
+    /// Deadline slack in hours, if a deadline applies (negative = at risk)
+    pub slack_hours: Option<f64>,
 }
 
 /// Active agent info for a task in the graph explorer
@@ -349,7 +347,11 @@ impl GraphExplorer {
 
         let critical_ids = compute_critical_path(&graph);
         let agent_map = Self::load_agent_map(workgraph_dir);
-        let rows = build_graph_tree(&graph, &self.collapsed_ids, &critical_ids, &agent_map);
+        let mut rows = build_graph_tree(&graph, &self.collapsed_ids, &critical_ids, &agent_map);
+        let slack = compute_slack(&graph, chrono::Utc::now());
+        for row in &mut rows {
+            row.slack_hours = slack.get(&row.task_id).map(|s| s.slack_hours);
+        }
 
         // Preserve selection by task ID
         let prev_id = self.rows.get(self.selected).map(|r| r.task_id.clone());
@@ -683,6 +685,7 @@ fn build_graph_tree(
                 back_ref: None,
                 active_agent_count: agent_count,
                 active_agent_ids: agent_ids,
+                slack_hours: None,
             });
         }
     }
@@ -725,6 +728,7 @@ fn flatten_subtree(
             back_ref: Some(task.id.clone()),
             active_agent_count: agent_count,
             active_agent_ids: agent_ids,
+            slack_hours: None,
         });
         return;
     }
@@ -742,6 +746,7 @@ fn flatten_subtree(
         back_ref: None,
         active_agent_count: agent_count,
         active_agent_ids: agent_ids,
+        slack_hours: None,
     });
 
     if is_collapsed {
@@ -768,11 +773,12 @@ fn flatten_subtree(
 fn sort_key_for_status(status: &Status) -> u8 {
     match status {
         Status::InProgress => 0,
-        Status::Open => 1,
-        Status::Failed => 2,
-        Status::Blocked => 3,
-        Status::Done => 4,
-        Status::Abandoned => 5,
+        Status::InReview => 1,
+        Status::Open => 2,
+        Status::Failed => 3,
+        Status::Blocked => 4,
+        Status::Done => 5,
+        Status::Abandoned => 6,
     }
 }
 
@@ -896,6 +902,7 @@ pub struct TaskCounts {
     pub ready: usize,
     pub blocked: usize,
     pub failed: usize,
+    pub in_review: usize,
     pub total: usize,
 }
 
@@ -1086,6 +1093,7 @@ impl App {
             }
         };
 
+        let slack = compute_slack(&graph, chrono::Utc::now());
         let mut entries: Vec<TaskEntry> = graph
             .tasks()
             .map(|t: &Task| TaskEntry {
@@ -1093,6 +1101,7 @@ impl App {
                 title: t.title.clone(),
                 status: t.status,
                 assigned: t.assigned.clone(),
+                slack_hours: slack.get(&t.id).map(|s| s.slack_hours),
             })
             .collect();
 
@@ -1107,6 +1116,7 @@ impl App {
             match entry.status {
                 Status::Done => counts.done += 1,
                 Status::InProgress => counts.in_progress += 1,
+                Status::InReview => counts.in_review += 1,
                 Status::Open => counts.ready += 1,
                 Status::Blocked => counts.blocked += 1,
                 Status::Failed => counts.failed += 1,
@@ -1433,6 +1443,7 @@ mod tests {
             back_ref: None,
             active_agent_count: 0,
             active_agent_ids: Vec::new(),
+            slack_hours: None,
         }
     }
 
@@ -1492,18 +1503,21 @@ mod tests {
                 title: "Alpha".into(),
                 status: Status::InProgress,
                 assigned: None,
+                slack_hours: None,
             },
             TaskEntry {
                 id: "b".into(),
                 title: "Beta".into(),
                 status: Status::Open,
                 assigned: None,
+                slack_hours: None,
             },
             TaskEntry {
                 id: "c".into(),
                 title: "Gamma".into(),
                 status: Status::Done,
                 assigned: None,
+                slack_hours: None,
             },
         ]
     }
@@ -1537,11 +1551,12 @@ mod tests {
     fn task_entry_sort_key_ordering() {
         let statuses = [
             (Status::InProgress, 0u8),
-            (Status::Open, 1),
-            (Status::Failed, 2),
-            (Status::Blocked, 3),
-            (Status::Done, 4),
-            (Status::Abandoned, 5),
+            (Status::InReview, 1),
+            (Status::Open, 2),
+            (Status::Failed, 3),
+            (Status::Blocked, 4),
+            (Status::Done, 5),
+            (Status::Abandoned, 6),
         ];
         for (status, expected) in &statuses {
             let entry = TaskEntry {
@@ -1549,6 +1564,7 @@ mod tests {
                 title: "x".into(),
                 status: status.clone(),
                 assigned: None,
+                slack_hours: None,
             };
             assert_eq!(
                 entry.sort_key(),
@@ -1563,6 +1579,7 @@ mod tests {
     fn task_entry_sort_key_is_monotonic() {
         let ordered = [
             Status::InProgress,
+            Status::InReview,
             Status::Open,
             Status::Failed,
             Status::Blocked,
@@ -1575,12 +1592,14 @@ mod tests {
                 title: "x".into(),
                 status: w[0].clone(),
                 assigned: None,
+                slack_hours: None,
             };
             let b = TaskEntry {
                 id: "x".into(),
                 title: "x".into(),
                 status: w[1].clone(),
                 assigned: None,
+                slack_hours: None,
             };
             assert!(
                 a.sort_key() < b.sort_key(),
@@ -1614,6 +1633,7 @@ mod tests {
         assert_eq!(status_color(&Status::Failed), Color::Red);
         assert_eq!(status_color(&Status::Blocked), Color::DarkGray);
         assert_eq!(status_color(&Status::Abandoned), Color::DarkGray);
+        assert_eq!(status_color(&Status::InReview), Color::Magenta);
     }
 
     #[test]
@@ -1625,6 +1645,7 @@ mod tests {
         assert_eq!(status_indicator(&Status::Failed), "[!]");
         assert_eq!(status_indicator(&Status::Blocked), "[B]");
         assert_eq!(status_indicator(&Status::Abandoned), "[-]");
+        assert_eq!(status_indicator(&Status::InReview), "[?]");
     }
 
     #[test]
@@ -1335,6 +1335,7 @@ fn status_indicator_str(status: &Status) -> &'static str {
         Status::Failed => "!",
         Status::Blocked => "B",
         Status::Abandoned => "-",
+        Status::InReview => "?",
     }
 }
 
@@ -595,6 +595,10 @@ fn draw_graph_tree_view(frame: &mut Frame, app: &mut App) {
                     ));
                 }
 
+                if let Some(span) = slack_span(row.slack_hours) {
+                    spans.push(span);
+                }
+
                 if row.back_ref.is_some() {
                     spans.push(Span::styled(
                         " ↗".to_string(),
@@ -1054,6 +1058,26 @@ fn draw_graph_detail_overlay(frame: &mut Frame, explorer: &app::GraphExplorer) {
         lines.push(Line::from(""));
     }
 
+    // Custom fields
+    if !task.fields.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "Fields:",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for (key, value) in &task.fields {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {}: ", key), Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    workgraph::fields::display(value),
+                    Style::default().fg(Color::White),
+                ),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
     // Loop info
     if !task.loops_to.is_empty() || task.loop_iteration > 0 {
         lines.push(Line::from(Span::styled(
@@ -1064,13 +1088,7 @@ fn draw_graph_detail_overlay(frame: &mut Frame, explorer: &app::GraphExplorer) {
         )));
         for edge in &task.loops_to {
             let guard_str = match &edge.guard {
-                Some(workgraph::graph::LoopGuard::TaskStatus { task: t, status: s }) => {
-                    format!(", guard: {}={:?}", t, s)
-                }
-                Some(workgraph::graph::LoopGuard::IterationLessThan(n)) => {
-                    format!(", guard: iter<{}", n)
-                }
-                Some(workgraph::graph::LoopGuard::Always) => ", guard: always".to_string(),
+                Some(guard) => format!(", guard: {}", guard),
                 None => String::new(),
             };
             let delay_str = match &edge.delay {
@@ -1198,6 +1216,9 @@ fn draw_task_list(frame: &mut Frame, app: &mut App, area: Rect) {
                 };
                 spans.push(Span::styled(format!("  ({})", agent), assign_style));
             }
+            if let Some(span) = slack_span(entry.slack_hours) {
+                spans.push(span);
+            }
 
             ListItem::new(Line::from(spans))
         })
@@ -1347,9 +1368,19 @@ pub(crate) fn status_indicator(status: &Status) -> &'static str {
         Status::Failed => "[!]",
         Status::Blocked => "[B]",
         Status::Abandoned => "[-]",
+        Status::InReview => "[?]",
     }
 }
 
+/// Red "behind" marker for tasks whose deadline slack is negative
+fn slack_span(slack_hours: Option<f64>) -> Option<Span<'static>> {
+    let slack = slack_hours.filter(|h| *h < 0.0)?;
+    Some(Span::styled(
+        format!("  ⚠ {}h behind", workgraph::format_hours(-slack)),
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+    ))
+}
+
 /// Map task status to a display color
 pub(crate) fn status_color(status: &Status) -> Color {
     match status {
@@ -1359,6 +1390,7 @@ pub(crate) fn status_color(status: &Status) -> Color {
         Status::Failed => Color::Red,
         Status::Blocked => Color::DarkGray,
         Status::Abandoned => Color::DarkGray,
+        Status::InReview => Color::Magenta,
     }
 }
 
@@ -1379,8 +1411,15 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         ),
         Span::styled(
             format!(
-                " {} tasks ({} active, {} done) ",
-                c.total, c.in_progress, c.done
+                " {} tasks ({} active, {}{} done) ",
+                c.total,
+                c.in_progress,
+                if c.in_review > 0 {
+                    format!("{} in review, ", c.in_review)
+                } else {
+                    String::new()
+                },
+                c.done
             ),
             Style::default().fg(Color::White),
         ),
@@ -0,0 +1,480 @@
+//! Undo and redo of graph operations
+//!
+//! Commands that modify tasks store one [`TaskChange`] per affected task
+//! under `detail.changes` in their provenance entry. `wg undo` applies those
+//! changes backwards and logs an `undo` operation whose `detail.target` is the
+//! timestamp of the entry it reverted; `wg redo` re-applies the most recently
+//! undone operation, as long as nothing new has changed the graph since.
+//!
+//! Only changed fields are stored. Array fields that merely grew (such as
+//! `log` or `artifacts`) store just the appended items, so a log entry does not
+//! copy the whole task log into the operation log.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::graph::{Node, Task, WorkGraph};
+use crate::provenance::OperationEntry;
+
+/// Operation name logged by `wg undo`
+pub const UNDO_OP: &str = "undo";
+/// Operation name logged by `wg redo`
+pub const REDO_OP: &str = "redo";
+
+/// Task fields as they serialize in the graph file
+type Fields = Map<String, Value>;
+
+/// How one task changed in an operation.
+///
+/// `before` and `after` hold the fields that changed, as they serialize in
+/// the graph file; a field missing on one side was at its default there.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskChange {
+    pub id: String,
+    /// Changed fields before the operation, or `None` if it created the task
+    pub before: Option<Fields>,
+    /// Changed fields after the operation, or `None` if it removed the task
+    pub after: Option<Fields>,
+    /// Items appended to array fields
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub appended: Fields,
+}
+
+impl TaskChange {
+    /// The state the graph is expected to be in, and the state to move to
+    fn states(&self, forward: bool) -> (Option<&Fields>, Option<&Fields>) {
+        if forward {
+            (self.before.as_ref(), self.after.as_ref())
+        } else {
+            (self.after.as_ref(), self.before.as_ref())
+        }
+    }
+
+    fn keys(&self) -> BTreeSet<&String> {
+        self.before
+            .iter()
+            .chain(self.after.iter())
+            .flat_map(|m| m.keys())
+            .collect()
+    }
+}
+
+/// Like [`crate::parser::with_graph_mut`], also returning the task changes
+/// the closure made so they can be recorded with the operation.
+pub fn with_graph_changes<P, T, F>(path: P, f: F) -> Result<(T, Vec<TaskChange>)>
+where
+    P: AsRef<std::path::Path>,
+    F: FnOnce(&mut WorkGraph) -> Result<T>,
+{
+    crate::parser::with_graph_mut(path, |graph| {
+        let before = graph.clone();
+        let value = f(graph)?;
+        Ok((value, diff(&before, graph)))
+    })
+}
+
+/// Per-task changes between two versions of a graph, sorted by task ID.
+pub fn diff(before: &WorkGraph, after: &WorkGraph) -> Vec<TaskChange> {
+    let mut changes = Vec::new();
+    for old in before.tasks() {
+        match after.get_task(&old.id) {
+            None => changes.push(TaskChange {
+                id: old.id.clone(),
+                before: Some(task_fields(old)),
+                after: None,
+                appended: Map::new(),
+            }),
+            Some(new) if new != old => changes.push(field_diff(old, new)),
+            Some(_) => {}
+        }
+    }
+    for new in after.tasks() {
+        if before.get_task(&new.id).is_none() {
+            changes.push(TaskChange {
+                id: new.id.clone(),
+                before: None,
+                after: Some(task_fields(new)),
+                appended: Map::new(),
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.id.cmp(&b.id));
+    changes
+}
+
+fn task_fields(task: &Task) -> Fields {
+    match serde_json::to_value(task) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+fn field_diff(old: &Task, new: &Task) -> TaskChange {
+    let old_fields = task_fields(old);
+    let new_fields = task_fields(new);
+    let mut before = Map::new();
+    let mut after = Map::new();
+    let mut appended = Map::new();
+
+    let keys: BTreeSet<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+    for key in keys {
+        let (old_value, new_value) = (old_fields.get(key), new_fields.get(key));
+        if old_value == new_value {
+            continue;
+        }
+        let old_items = match old_value {
+            Some(Value::Array(items)) => Some(items.as_slice()),
+            None => Some(&[][..]),
+            Some(_) => None,
+        };
+        if let (Some(old_items), Some(Value::Array(new_items))) = (old_items, new_value)
+            && new_items.starts_with(old_items)
+        {
+            appended.insert(
+                key.clone(),
+                Value::Array(new_items[old_items.len()..].to_vec()),
+            );
+            continue;
+        }
+        if let Some(value) = old_value {
+            before.insert(key.clone(), value.clone());
+        }
+        if let Some(value) = new_value {
+            after.insert(key.clone(), value.clone());
+        }
+    }
+
+    TaskChange {
+        id: new.id.clone(),
+        before: Some(before),
+        after: Some(after),
+        appended,
+    }
+}
+
+/// Add `changes` to an operation's detail object (no-op when empty).
+pub fn with_changes(detail: Value, changes: &[TaskChange]) -> Value {
+    if changes.is_empty() {
+        return detail;
+    }
+    let mut detail = match detail {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        other => Map::from_iter([("value".to_string(), other)]),
+    };
+    detail.insert(
+        "changes".to_string(),
+        serde_json::to_value(changes).unwrap_or_default(),
+    );
+    Value::Object(detail)
+}
+
+/// The task changes recorded in an operation entry
+pub fn changes(entry: &OperationEntry) -> Vec<TaskChange> {
+    entry
+        .detail
+        .get("changes")
+        .and_then(|c| serde_json::from_value(c.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// IDs of tasks an operation touched: its task ID plus any recorded changes
+fn touched(entry: &OperationEntry) -> HashSet<String> {
+    let mut ids: HashSet<String> = changes(entry).into_iter().map(|c| c.id).collect();
+    ids.extend(entry.task_id.clone());
+    ids
+}
+
+fn target(entry: &OperationEntry) -> Option<&str> {
+    entry.detail.get("target").and_then(Value::as_str)
+}
+
+fn is_undo_redo(entry: &OperationEntry) -> bool {
+    entry.op == UNDO_OP || entry.op == REDO_OP
+}
+
+/// Timestamps of operations that are currently undone
+fn undone(ops: &[OperationEntry]) -> HashSet<&str> {
+    let mut undone = HashSet::new();
+    for entry in ops {
+        match (entry.op.as_str(), target(entry)) {
+            (UNDO_OP, Some(ts)) => {
+                undone.insert(ts);
+            }
+            (REDO_OP, Some(ts)) => {
+                undone.remove(ts);
+            }
+            _ => {}
+        }
+    }
+    undone
+}
+
+/// Index of the operation `wg undo` would revert next
+pub fn next_undo(ops: &[OperationEntry]) -> Option<usize> {
+    let undone = undone(ops);
+    ops.iter().rposition(|entry| {
+        !is_undo_redo(entry)
+            && !undone.contains(entry.timestamp.as_str())
+            && !changes(entry).is_empty()
+    })
+}
+
+/// Index of the operation `wg redo` would re-apply next: the target of the
+/// latest undo, unless a new change has been made since.
+pub fn next_redo(ops: &[OperationEntry]) -> Option<usize> {
+    let undone = undone(ops);
+    let by_timestamp: HashMap<&str, usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| !is_undo_redo(entry))
+        .map(|(i, entry)| (entry.timestamp.as_str(), i))
+        .collect();
+    for entry in ops.iter().rev() {
+        if entry.op == UNDO_OP {
+            if let Some(ts) = target(entry)
+                && undone.contains(ts)
+            {
+                return by_timestamp.get(ts).copied();
+            }
+        } else if entry.op != REDO_OP && !changes(entry).is_empty() {
+            return None;
+        }
+    }
+    None
+}
+
+/// Later operations (still in effect) that touched a task changed by
+/// `ops[index]`, as `(op, task ID)` pairs
+pub fn later_conflicts(ops: &[OperationEntry], index: usize) -> Vec<(String, String)> {
+    let undone = undone(ops);
+    let ids = touched(&ops[index]);
+    let mut conflicts = Vec::new();
+    for entry in &ops[index + 1..] {
+        if is_undo_redo(entry) || undone.contains(entry.timestamp.as_str()) {
+            continue;
+        }
+        let mut hit: Vec<String> = touched(entry).intersection(&ids).cloned().collect();
+        hit.sort();
+        conflicts.extend(hit.into_iter().map(|id| (entry.op.clone(), id)));
+    }
+    conflicts
+}
+
+/// Tasks whose current state differs from what applying `changes` in the
+/// given direction expects (`forward` = redo, otherwise undo)
+pub fn mismatches(graph: &WorkGraph, changes: &[TaskChange], forward: bool) -> Vec<String> {
+    let mut ids = Vec::new();
+    for change in changes {
+        let (expected, _) = change.states(forward);
+        let ok = match (expected, graph.get_task(&change.id)) {
+            (None, current) => current.is_none(),
+            (Some(_), None) => false,
+            (Some(expected), Some(task)) => {
+                let current = task_fields(task);
+                let fields_match = change
+                    .keys()
+                    .into_iter()
+                    .all(|key| current.get(key) == expected.get(key));
+                // Undo removes appended items, so they must still be the tail
+                let tails_match = forward
+                    || change.appended.iter().all(|(key, items)| {
+                        match (current.get(key), items) {
+                            (Some(Value::Array(have)), Value::Array(items)) => {
+                                have.ends_with(items)
+                            }
+                            (None, Value::Array(items)) => items.is_empty(),
+                            _ => false,
+                        }
+                    });
+                fields_match && tails_match
+            }
+        };
+        if !ok {
+            ids.push(change.id.clone());
+        }
+    }
+    ids
+}
+
+/// Apply `changes` to `graph`, forwards (redo) or backwards (undo). Fields
+/// the changes do not mention are left as they are.
+pub fn apply(graph: &mut WorkGraph, changes: &[TaskChange], forward: bool) -> Result<()> {
+    for change in changes {
+        let (expected, target) = change.states(forward);
+        let Some(target) = target else {
+            graph.remove_node(&change.id);
+            continue;
+        };
+        let mut fields = match (expected, graph.get_task(&change.id)) {
+            // The task is being recreated in full
+            (None, _) => Map::new(),
+            (Some(_), Some(task)) => task_fields(task),
+            (Some(_), None) => anyhow::bail!("Task '{}' no longer exists", change.id),
+        };
+        for key in change.keys() {
+            match target.get(key) {
+                Some(value) => fields.insert(key.clone(), value.clone()),
+                None => fields.remove(key),
+            };
+        }
+        for (key, items) in &change.appended {
+            let Value::Array(items) = items else {
+                continue;
+            };
+            let entry = fields
+                .entry(key.clone())
+                .or_insert_with(|| Value::Array(Vec::new()));
+            let Value::Array(have) = entry else {
+                continue;
+            };
+            if forward {
+                have.extend(items.iter().cloned());
+            } else if have.ends_with(items) {
+                have.truncate(have.len() - items.len());
+            } else {
+                // Forced undo over later changes: drop each item's last copy
+                for item in items.iter().rev() {
+                    if let Some(pos) = have.iter().rposition(|v| v == item) {
+                        have.remove(pos);
+                    }
+                }
+            }
+        }
+        let task: Task = serde_json::from_value(Value::Object(fields))
+            .with_context(|| format!("Failed to restore task '{}'", change.id))?;
+        graph.add_node(Node::Task(task));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{LogEntry, Status};
+
+    fn task(id: &str, status: Status) -> Task {
+        Task {
+            id: id.to_string(),
+            title: id.to_string(),
+            status,
+            ..Task::default()
+        }
+    }
+
+    fn graph(tasks: Vec<Task>) -> WorkGraph {
+        let mut graph = WorkGraph::new();
+        for task in tasks {
+            graph.add_node(Node::Task(task));
+        }
+        graph
+    }
+
+    fn tasks(graph: &WorkGraph) -> Vec<Task> {
+        let mut tasks: Vec<Task> = graph.tasks().cloned().collect();
+        tasks.sort_by(|a, b| a.id.cmp(&b.id));
+        tasks
+    }
+
+    fn entry(op: &str, ts: &str, detail: Value) -> OperationEntry {
+        OperationEntry {
+            timestamp: ts.to_string(),
+            op: op.to_string(),
+            task_id: None,
+            actor: None,
+            detail,
+        }
+    }
+
+    #[test]
+    fn test_diff_and_apply_round_trip() {
+        let before = graph(vec![task("a", Status::Open), task("gone", Status::Failed)]);
+        let mut after = before.clone();
+        {
+            let a = after.get_task_mut("a").unwrap();
+            a.status = Status::Done;
+            a.completed_at = Some("2026-01-01T00:00:00Z".to_string());
+            a.log.push(LogEntry {
+                timestamp: "2026-01-01T00:00:00Z".to_string(),
+                actor: None,
+                message: "done".to_string(),
+            });
+        }
+        after.remove_node("gone");
+        after.add_node(Node::Task(task("new", Status::Open)));
+
+        let changes = diff(&before, &after);
+        assert_eq!(
+            changes.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(),
+            vec!["a", "gone", "new"]
+        );
+        let a = &changes[0];
+        assert_eq!(a.before.as_ref().unwrap()["status"], "open");
+        assert!(!a.before.as_ref().unwrap().contains_key("completed_at"));
+        assert_eq!(a.after.as_ref().unwrap()["status"], "done");
+        assert_eq!(a.appended["log"].as_array().unwrap().len(), 1);
+        assert!(!a.after.as_ref().unwrap().contains_key("log"));
+
+        let mut undone = after.clone();
+        assert!(mismatches(&undone, &changes, false).is_empty());
+        apply(&mut undone, &changes, false).unwrap();
+        assert_eq!(tasks(&undone), tasks(&before));
+
+        assert!(mismatches(&undone, &changes, true).is_empty());
+        apply(&mut undone, &changes, true).unwrap();
+        assert_eq!(tasks(&undone), tasks(&after));
+    }
+
+    #[test]
+    fn test_mismatch_detected_after_later_edit() {
+        let before = graph(vec![task("a", Status::Open)]);
+        let mut after = before.clone();
+        after.get_task_mut("a").unwrap().status = Status::Done;
+        let changes = diff(&before, &after);
+
+        after.get_task_mut("a").unwrap().status = Status::Open;
+        assert_eq!(mismatches(&after, &changes, false), vec!["a"]);
+    }
+
+    #[test]
+    fn test_undo_redo_stacks() {
+        let change = |id: &str| {
+            with_changes(
+                Value::Null,
+                &[TaskChange {
+                    id: id.to_string(),
+                    before: Some(Map::new()),
+                    after: Some(Map::new()),
+                    appended: Map::new(),
+                }],
+            )
+        };
+        let target = |ts: &str| serde_json::json!({ "target": ts });
+        let mut ops = vec![
+            entry("add_task", "t1", change("a")),
+            entry("done", "t2", change("a")),
+            entry("archive", "t3", Value::Null),
+        ];
+        assert_eq!(next_undo(&ops), Some(1));
+        assert_eq!(next_redo(&ops), None);
+        assert_eq!(later_conflicts(&ops, 0), vec![("done".to_string(), "a".to_string())]);
+
+        ops.push(entry(UNDO_OP, "t4", target("t2")));
+        assert_eq!(next_undo(&ops), Some(0));
+        assert_eq!(next_redo(&ops), Some(1));
+        assert!(later_conflicts(&ops, 0).is_empty());
+
+        ops.push(entry(REDO_OP, "t5", target("t2")));
+        assert_eq!(next_undo(&ops), Some(1));
+        assert_eq!(next_redo(&ops), None);
+
+        // A new change after an undo clears the redo stack
+        ops.push(entry(UNDO_OP, "t6", target("t2")));
+        ops.push(entry("edit", "t7", change("b")));
+        assert_eq!(next_redo(&ops), None);
+        assert_eq!(next_undo(&ops), Some(6));
+    }
+}
@@ -0,0 +1,290 @@
+//! Executable verification checks
+//!
+//! A task's `verify` text is criteria for human reviewers, but any line of it
+//! that is a check below is also run by `wg done` (and by `wg exec` and
+//! `wg agent run` once the task's command succeeds), and a failing check
+//! keeps the task from completing:
+//!
+//! | Line                   | Passes when                                            |
+//! |------------------------|--------------------------------------------------------|
+//! | `shell:<cmd>`          | `<cmd>` exits 0 (run with `sh -c` in the project root) |
+//! | `exists:<path>`        | the file exists under the project root                 |
+//! | `deliverables`         | every one of the task's deliverables exists            |
+//! | `match:<path>:<regex>` | the file's contents match the regex                    |
+//!
+//! Other lines are left for humans. `wg done --skip-verify` bypasses the checks.
+
+use crate::graph::Task;
+use anyhow::Result;
+use regex::Regex;
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How long a `shell:` check may run before it is killed and counted as failed
+pub const SHELL_CHECK_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Lines of a failing command's output kept for the report
+const OUTPUT_TAIL_LINES: usize = 20;
+
+/// One machine check from a task's verify criteria
+#[derive(Debug, Clone)]
+pub enum Check {
+    Shell(String),
+    Exists(String),
+    Deliverables,
+    Match { path: String, regex: Regex },
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::Shell(cmd) => write!(f, "shell:{}", cmd),
+            Check::Exists(path) => write!(f, "exists:{}", path),
+            Check::Deliverables => write!(f, "deliverables"),
+            Check::Match { path, regex } => write!(f, "match:{}:{}", path, regex),
+        }
+    }
+}
+
+/// A check that did not pass
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub check: String,
+    /// Why it failed, e.g. "exit code 101"
+    pub reason: String,
+    /// Last lines of a failing command's output
+    pub output: String,
+}
+
+impl Failure {
+    fn new(check: &Check, reason: impl Into<String>) -> Self {
+        Failure {
+            check: check.to_string(),
+            reason: reason.into(),
+            output: String::new(),
+        }
+    }
+}
+
+/// The checks in a verify text; other lines are ignored. Fails on a check
+/// that can't be parsed, such as an invalid regex.
+pub fn parse(verify: &str) -> Result<Vec<Check>> {
+    let mut checks = Vec::new();
+    for line in verify.lines().map(str::trim) {
+        let check = if line == "deliverables" {
+            Check::Deliverables
+        } else if let Some(cmd) = line.strip_prefix("shell:") {
+            Check::Shell(non_empty(line, cmd)?)
+        } else if let Some(path) = line.strip_prefix("exists:") {
+            Check::Exists(non_empty(line, path)?)
+        } else if let Some(rest) = line.strip_prefix("match:") {
+            let (path, pattern) = rest.split_once(':').ok_or_else(|| {
+                anyhow::anyhow!("Invalid check '{}'. Use 'match:<path>:<regex>'", line)
+            })?;
+            let regex = Regex::new(pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid regex in check '{}': {}", line, e))?;
+            Check::Match {
+                path: non_empty(line, path)?,
+                regex,
+            }
+        } else {
+            continue;
+        };
+        checks.push(check);
+    }
+    Ok(checks)
+}
+
+fn non_empty(line: &str, value: &str) -> Result<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        anyhow::bail!("Invalid check '{}': nothing after the prefix", line);
+    }
+    Ok(value.to_string())
+}
+
+/// Run the checks in `task.verify` and return the ones that failed
+pub fn run(task: &Task, dir: &Path) -> Result<Vec<Failure>> {
+    let Some(ref verify) = task.verify else {
+        return Ok(vec![]);
+    };
+    let root = dir.parent().unwrap_or(dir);
+    Ok(parse(verify)?
+        .iter()
+        .filter_map(|check| run_check(check, task, root).err())
+        .collect())
+}
+
+/// The failed checks and why, on one line for a task log
+pub fn summary(failures: &[Failure]) -> String {
+    failures
+        .iter()
+        .map(|f| format!("{} ({})", f.check, f.reason))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn run_check(check: &Check, task: &Task, root: &Path) -> Result<(), Failure> {
+    match check {
+        Check::Shell(cmd) => run_shell(check, cmd, root),
+        Check::Exists(path) => {
+            if root.join(path).exists() {
+                Ok(())
+            } else {
+                Err(Failure::new(check, format!("{} does not exist", path)))
+            }
+        }
+        Check::Deliverables => {
+            let missing: Vec<&str> = task
+                .deliverables
+                .iter()
+                .filter(|d| !root.join(d).exists())
+                .map(String::as_str)
+                .collect();
+            if missing.is_empty() {
+                Ok(())
+            } else {
+                Err(Failure::new(
+                    check,
+                    format!("missing deliverable(s): {}", missing.join(", ")),
+                ))
+            }
+        }
+        Check::Match { path, regex } => match std::fs::read_to_string(root.join(path)) {
+            Ok(contents) if regex.is_match(&contents) => Ok(()),
+            Ok(_) => Err(Failure::new(check, format!("{} does not match", path))),
+            Err(e) => Err(Failure::new(check, format!("can't read {}: {}", path, e))),
+        },
+    }
+}
+
+fn run_shell(check: &Check, cmd: &str, root: &Path) -> Result<(), Failure> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Failure::new(check, format!("failed to start: {}", e)))?;
+
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let started = Instant::now();
+    let reason = loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => {
+                break match status.code() {
+                    Some(code) => format!("exit code {}", code),
+                    None => "killed by a signal".to_string(),
+                };
+            }
+            Ok(None) if started.elapsed() < SHELL_CHECK_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            // Don't wait for the output: a leftover grandchild may hold the pipes
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Failure::new(
+                    check,
+                    format!("timed out after {}s", SHELL_CHECK_TIMEOUT.as_secs()),
+                ));
+            }
+            Err(e) => return Err(Failure::new(check, format!("failed to wait: {}", e))),
+        }
+    };
+
+    let output = format!(
+        "{}{}",
+        stdout.join().unwrap_or_default(),
+        stderr.join().unwrap_or_default()
+    );
+    let lines: Vec<&str> = output.lines().collect();
+    let tail = lines[lines.len().saturating_sub(OUTPUT_TAIL_LINES)..].join("\n");
+    Err(Failure {
+        output: tail,
+        ..Failure::new(check, reason)
+    })
+}
+
+/// Read a pipe to the end on a thread, so a chatty command can't fill it and
+/// stall while we wait for it to exit
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buf = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut buf);
+        }
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_checks_and_skips_prose() {
+        let checks = parse(
+            "All findings documented\nshell: cargo build\nexists:docs/report.md\n\
+             deliverables\nmatch:docs/report.md:^# Report",
+        )
+        .unwrap();
+        let shown: Vec<String> = checks.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            shown,
+            vec![
+                "shell:cargo build",
+                "exists:docs/report.md",
+                "deliverables",
+                "match:docs/report.md:^# Report",
+            ]
+        );
+
+        assert!(parse("shell:").is_err());
+        assert!(parse("match:report.md").is_err());
+        assert!(parse("match:report.md:(").is_err());
+        assert!(parse("Reviewed by a human").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run_reports_failed_checks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join(".workgraph");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(tmp.path().join("report.md"), "# Report\nAll good").unwrap();
+
+        let task = Task {
+            id: "t".to_string(),
+            deliverables: vec!["report.md".to_string(), "summary.md".to_string()],
+            verify: Some(
+                "shell:test -f report.md\nshell:echo broken; exit 3\nexists:report.md\n\
+                 deliverables\nmatch:report.md:All good\nmatch:report.md:^TODO"
+                    .to_string(),
+            ),
+            ..Task::default()
+        };
+        let failures = run(&task, &dir).unwrap();
+        let failed: Vec<(&str, &str)> = failures
+            .iter()
+            .map(|f| (f.check.as_str(), f.reason.as_str()))
+            .collect();
+        assert_eq!(
+            failed,
+            vec![
+                ("shell:echo broken; exit 3", "exit code 3"),
+                ("deliverables", "missing deliverable(s): summary.md"),
+                ("match:report.md:^TODO", "report.md does not match"),
+            ]
+        );
+        assert_eq!(failures[0].output, "broken");
+    }
+}
@@ -17,7 +17,7 @@ use tempfile::TempDir;
 
 use workgraph::identity::{self, Agent, Lineage, RewardHistory, SkillRef};
 use workgraph::config::Config;
-use workgraph::graph::{Node, Status, Task, WorkGraph};
+use workgraph::graph::{Node, Priority, Status, Task, WorkGraph};
 use workgraph::parser::{load_graph, save_graph};
 use workgraph::query::ready_tasks;
 use workgraph::service::executor::TemplateVars;
@@ -241,6 +241,21 @@ fn build_assign_subgraph(dir: &Path) {
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         };
 
         mutable_graph.add_node(Node::Task(assign_task));
@@ -1136,6 +1151,21 @@ Begin working on the task now.
             loop_iteration: 0,
             ready_after: None,
             paused: false,
+            priority: Priority::Normal,
+            value: None,
+            resource_amounts: Default::default(),
+            parent: None,
+            auto_complete: false,
+            fields: Default::default(),
+            deadline: None,
+            schedule: None,
+            dep_groups: vec![],
+            dep_conditions: Default::default(),
+            retry_policy: None,
+            retry_history: Vec::new(),
+            fan_out: None,
+            outputs: Default::default(),
+            artifact_snapshots: Default::default(),
         };
 
         // Wire up: assign-rust-feature blocks rust-feature
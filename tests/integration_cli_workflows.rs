@@ -385,6 +385,62 @@ fn test_done_blocked_task_fails() {
     assert!(!output.status.success());
 }
 
+#[test]
+fn test_agent_done_on_verified_task_waits_for_review() {
+    let tmp = TempDir::new().unwrap();
+    let mut task = make_task("rv1", "Reviewed task", Status::InProgress);
+    task.verify = Some("Output reviewed by a human".to_string());
+    let mut dependent = make_task("rv2", "After review", Status::Open);
+    dependent.blocked_by.push("rv1".to_string());
+    let wg_dir = setup_workgraph(&tmp, vec![task, dependent]);
+
+    // An agent finishing the task submits it for review instead
+    let output = Command::new(wg_binary())
+        .arg("--dir")
+        .arg(&wg_dir)
+        .args(["done", "rv1"])
+        .env("WG_AGENT_ID", "agent-1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let graph = load_graph(wg_dir.join("graph.jsonl")).unwrap();
+    assert_eq!(graph.get_task("rv1").unwrap().status, Status::InReview);
+    assert!(!wg_ok(&wg_dir, &["ready"]).contains("rv2"));
+    assert!(wg_ok(&wg_dir, &["status"]).contains("awaiting review: rv1"));
+
+    wg_ok(&wg_dir, &["approve", "rv1", "--actor", "erik"]);
+    let graph = load_graph(wg_dir.join("graph.jsonl")).unwrap();
+    assert_eq!(graph.get_task("rv1").unwrap().status, Status::Done);
+    assert!(wg_ok(&wg_dir, &["ready"]).contains("rv2"));
+}
+
+#[test]
+fn test_agent_exec_and_agent_run_on_verified_task_wait_for_review() {
+    for args in [
+        &["exec", "rv1"][..],
+        &["agent", "run", "--actor", "agent-1", "--once"][..],
+    ] {
+        let tmp = TempDir::new().unwrap();
+        let mut task = make_task("rv1", "Reviewed task", Status::Open);
+        task.exec = Some("true".to_string());
+        task.verify = Some("Output reviewed by a human".to_string());
+        let wg_dir = setup_workgraph(&tmp, vec![task]);
+
+        let output = Command::new(wg_binary())
+            .arg("--dir")
+            .arg(&wg_dir)
+            .args(args)
+            .env("WG_AGENT_ID", "agent-1")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", args);
+        let graph = load_graph(wg_dir.join("graph.jsonl")).unwrap();
+        let task = graph.get_task("rv1").unwrap();
+        assert_eq!(task.status, Status::InReview, "{:?}", args);
+        assert!(task.completed_at.is_none());
+    }
+}
+
 // ===========================================================================
 // wg fail / retry lifecycle via CLI
 // ===========================================================================
@@ -818,6 +874,87 @@ fn test_cost_json_output() {
     assert_eq!(parsed["total_cost"], 250.0);
 }
 
+// ===========================================================================
+// wg service tick: dead-agent triage
+// ===========================================================================
+
+/// Run one coordinator tick with auto-triage on and a stand-in `claude` on
+/// PATH that gives every dead agent's task the verdict `verdict`.
+#[cfg(unix)]
+fn tick_with_triage(tmp: &TempDir, wg_dir: &Path, verdict: &str) -> std::process::Output {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin = tmp.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let claude = bin.join("claude");
+    fs::write(
+        &claude,
+        format!(
+            "#!/bin/sh\necho '{{\"verdict\":\"{}\",\"reason\":\"finished\"}}'\n",
+            verdict
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&claude, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::write(
+        wg_dir.join("config.toml"),
+        "[identity]\nauto_triage = true\n",
+    )
+    .unwrap();
+
+    // The task's agent has exited
+    let mut exited = Command::new("true").spawn().unwrap();
+    let pid = exited.id();
+    exited.wait().unwrap();
+    let mut registry = workgraph::service::registry::AgentRegistry::new();
+    registry.register_agent(pid, "split", "claude", "/nonexistent/output.log");
+    registry.save(wg_dir).unwrap();
+
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+    Command::new(wg_binary())
+        .arg("--dir")
+        .arg(wg_dir)
+        .args(["service", "tick", "--executor", "shell"])
+        .env("PATH", path)
+        .env("HOME", tmp.path())
+        .output()
+        .unwrap()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_service_tick_triage_expands_fan_out_before_its_join() {
+    let tmp = TempDir::new().unwrap();
+    let mut split = make_task("split", "Split", Status::InProgress);
+    split.fan_out = Some(workgraph::fan_out::FanOut::new(
+        "split",
+        "Handle {{input.item}}",
+        None,
+        Some("items.json"),
+        "split-join",
+    ));
+    let mut join = make_task("split-join", "Join", Status::Open);
+    join.blocked_by = vec!["split".to_string()];
+    let wg_dir = setup_workgraph(&tmp, vec![split, join]);
+    fs::write(tmp.path().join("items.json"), r#"["a", "b"]"#).unwrap();
+
+    let output = tick_with_triage(&tmp, &wg_dir, "done");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+
+    let graph = load_graph(wg_dir.join("graph.jsonl")).unwrap();
+    let split = graph.get_task("split").unwrap();
+    assert_eq!(split.status, Status::Done);
+    let children = &split.fan_out.as_ref().unwrap().children;
+    assert_eq!(children.len(), 2);
+    // Only the children were ready this tick; the join waits for them
+    assert!(stdout.contains("2 ready"), "{}", stdout);
+    let join = graph.get_task("split-join").unwrap();
+    assert_eq!(join.status, Status::Open);
+    assert!(join.assigned.is_none());
+    assert!(children.iter().all(|c| join.blocked_by.contains(c)));
+}
+
 // ===========================================================================
 // JSON output parsing tests — structured output for machine consumption
 // ===========================================================================